@@ -51,7 +51,9 @@ fn benchmark_files_without_partitions(c: &mut Criterion) {
                         path: output.try_into().unwrap(),
                         idle_timeout_secs: None,
                         encoding: sinks::file::Encoding::Text.into(),
-                        compression: sinks::file::Compression::None,
+                        compression: sinks::util::Compression::None,
+                        rotate: Default::default(),
+                        retention: Default::default(),
                     },
                 );
 
@@ -5,5 +5,20 @@ fn main() {
     prost_build
         .compile_protos(&["proto/event.proto"], &["proto/"])
         .unwrap();
+
+    #[cfg(feature = "sources-opentelemetry")]
+    {
+        println!("cargo:rerun-if-changed=proto/opentelemetry");
+        tonic_build::configure()
+            .compile(
+                &[
+                    "proto/opentelemetry/proto/collector/logs/v1/logs_service.proto",
+                    "proto/opentelemetry/proto/collector/metrics/v1/metrics_service.proto",
+                ],
+                &["proto/opentelemetry/"],
+            )
+            .unwrap();
+    }
+
     built::write_built_file().expect("Failed to acquire build-time information");
 }
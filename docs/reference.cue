@@ -160,9 +160,12 @@ _values: {
 //
 // * `none` - compression is not applied
 // * `gzip` - gzip compression applied
-#CompressionAlgorithm: "none" | "gzip"
+// * `zstd` - zstd compression applied
+// * `snappy` - framed snappy compression applied
+// * `lz4` - lz4 frame compression applied
+#CompressionAlgorithm: "none" | "gzip" | "zstd" | "snappy" | "lz4"
 
-#CompressionLevel: "none" | "fast" | "default" | "best" | >=0 & <=9
+#CompressionLevel: "none" | "fast" | "default" | "best" | >=0 & <=21
 
 #Date: =~"^\\d{4}-\\d{2}-\\d{2}"
 
@@ -35,12 +35,20 @@ components: {
 						type: string: {
 							default: "check_fields"
 							enum: {
-								check_fields: "Allows you to check individual fields against a list of conditions."
+								check_fields: "Allows you to check individual fields against a list of conditions. Deprecated in favor of `remap`."
 								is_log:       "Returns true if the event is a log."
 								is_metric:    "Returns true if the event is a metric."
+								remap:        "Returns the result of the boolean [remap](/docs/reference/remap) expression in `source`. The expression can also be given as a plain string instead of a table, e.g. `condition = '.status >= 500'`."
 							}
 						}
 					}
+					source: {
+						description:   "The remap boolean expression to evaluate for each event."
+						relevant_when: "type = \"remap\""
+						required:      true
+						warnings: []
+						type: string: examples: [#".status >= 500 && contains(.path, "/api")"#]
+					}
 					"*.eq": {
 						common:      true
 						description: "Check whether a field's contents exactly matches the value specified, case sensitive. This may be a single string or a list of strings, in which case this evaluates to true if any of the list matches."
@@ -291,10 +299,10 @@ components: {
 
 			if Kind != "source" {
 				inputs: {
-					description: "A list of upstream [source](\(urls.vector_sources)) or [transform](\(urls.vector_transforms)) IDs. See [configuration](\(urls.vector_configuration)) for more info."
+					description: "A list of upstream [source](\(urls.vector_sources)) or [transform](\(urls.vector_transforms)) IDs. Transforms with named outputs, such as `errors`, can be consumed with `<transform-id>.<output>`, and the log events a sink's destination rejected with `<sink-id>.dead_letter`. Consuming a dead-letter output keeps a copy of each log event in the sink until it is delivered. See [configuration](\(urls.vector_configuration)) for more info."
 					required:    true
 					sort:        -1
-					type: array: items: type: string: examples: ["my-source-or-transform-id"]
+					type: array: items: type: string: examples: ["my-source-or-transform-id", "my-transform-id.errors", "my-sink-id.dead_letter"]
 				}
 			}
 
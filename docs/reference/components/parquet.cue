@@ -0,0 +1,50 @@
+package metadata
+
+components: _parquet: {
+	configuration: parquet: {
+		common:      false
+		description: "Options for the `parquet` encoding."
+		required:    false
+		warnings: []
+		type: object: {
+			examples: []
+			options: {
+				schema: {
+					common:      false
+					description: "The columns of the written files, mapping field paths to one of the `string`, `integer`, `float`, `boolean` or `timestamp` column types. Fields missing from an event, or that can't be converted to their column's type, are written as nulls. When unset, the columns are inferred from each batch."
+					required:    false
+					warnings: []
+					type: object: {
+						examples: [
+							{
+								"timestamp":   "timestamp"
+								"message":     "string"
+								"http.status": "integer"
+							},
+						]
+						options: {}
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: parquet: {
+		title: "Parquet Encoding"
+		body: """
+			With the `parquet` encoding, each batch is written as a [Parquet](\(urls.apache_parquet))
+			file holding a single row group, so the size of the row groups is set by the `batch`
+			options. The objects get a `.parquet` extension, unless `filename_extension` is set,
+			and the `application/vnd.apache.parquet` content type.
+
+			The columns of the file are either declared by the `parquet.schema` option or inferred
+			from the top-level fields of the batch's events. Inferred columns get the type of
+			their values: integers mixed with floats become `float` columns, and any other mix of
+			types, as well as objects and arrays, become `string` columns holding their JSON
+			representation.
+
+			The `compression` option applies to the columns of the file rather than to the object
+			as a whole, which is left uncompressed.
+			"""
+	}
+}
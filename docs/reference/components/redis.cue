@@ -0,0 +1,52 @@
+package metadata
+
+components: _redis: {
+	description: "[Redis](\(urls.redis)) is an in-memory data structure store, used as a database, cache and message broker. Its lists, channels and streams make it a lightweight queue for passing logs between tiers of Vector."
+
+	features: {
+		service: {
+			name:     "Redis"
+			thing:    "a \(name) server"
+			url:      urls.redis
+			versions: ">= 5.0"
+
+			interface: {
+				socket: {
+					api: {
+						title: "Redis protocol"
+						url:   urls.redis_protocol
+					}
+					direction: "outgoing"
+					protocols: ["tcp"]
+					ssl: "disabled"
+				}
+			}
+		}
+	}
+
+	support: {
+		platforms: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		url: {
+			description: "The [URL](\(urls.redis_url)) of the Redis server, in the `redis://[<username>][:<password>@]<host>[:<port>][/<db>]` format."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["redis://127.0.0.1:6379/0"]
+			}
+		}
+	}
+}
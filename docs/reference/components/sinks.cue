@@ -72,15 +72,49 @@ components: sinks: [Name=string]: {
 								unit:    "events"
 							}
 						}
+						max_segment_size: {
+							common:        false
+							description:   "The maximum size of a single segment file of the buffer. Segments are deleted once all of their events have been acknowledged by the sink."
+							required:      false
+							relevant_when: "type = \"disk_v2\""
+							type: uint: {
+								default: 134217728
+								unit:    "bytes"
+							}
+						}
 						max_size: {
 							description:   "The maximum size of the buffer on the disk."
 							required:      true
-							relevant_when: "type = \"disk\""
+							relevant_when: "type = \"disk\" or type = \"disk_v2\""
 							type: uint: {
 								examples: [104900000]
 								unit: "bytes"
 							}
 						}
+						overflow: {
+							common:        false
+							description:   "The `disk_v2` buffer that events spill into once `max_events` is reached. Takes the same options as `buffer`. Spilled events are read back in order once the sink catches up, and survive restarts."
+							required:      false
+							relevant_when: "type = \"memory\" and when_full = \"overflow\""
+							type: object: {
+								examples: [{type: "disk_v2", max_size: 104900000}]
+								options: {}
+							}
+						}
+						sync: {
+							common:        false
+							description:   "When writes to the buffer are synced to stable storage."
+							required:      false
+							relevant_when: "type = \"disk_v2\""
+							type: string: {
+								default: "flush"
+								enum: {
+									never:  "Leaves syncing to the operating system. Data survives a Vector crash, but not necessarily a host crash."
+									flush:  "Syncs whenever buffered writes are flushed, at least every 100 events."
+									always: "Syncs after every event. This is the most durable, but least performant option."
+								}
+							}
+						}
 						type: {
 							common:      true
 							description: "The buffer's type and storage mechanism."
@@ -88,8 +122,9 @@ components: sinks: [Name=string]: {
 							type: string: {
 								default: "memory"
 								enum: {
-									memory: "Stores the sink's buffer in memory. This is more performant, but less durable. Data will be lost if Vector is restarted forcefully."
-									disk:   "Stores the sink's buffer on disk. This is less performant, but durable. Data will not be lost between restarts."
+									memory:  "Stores the sink's buffer in memory. This is more performant, but less durable. Data will be lost if Vector is restarted forcefully."
+									disk:    "Stores the sink's buffer on disk. This is less performant, but durable. Data will not be lost between restarts."
+									disk_v2: "Stores the sink's buffer on disk in append-only segment files, without depending on leveldb. Data will not be lost between restarts. An existing `disk` buffer for the sink is migrated on startup."
 								}
 							}
 						}
@@ -102,6 +137,7 @@ components: sinks: [Name=string]: {
 								enum: {
 									block:       "Applies back pressure when the buffer is full. This prevents data loss, but will cause data to pile up on the edge."
 									drop_newest: "Drops new data as it's received. This data is lost. This should be used when performance is the highest priority."
+									overflow:    "Writes new data to the `overflow` buffer until the sink has caught up. Only supported by memory buffers."
 								}
 							}
 						}
@@ -125,6 +161,15 @@ components: sinks: [Name=string]: {
 							if list.Contains(sinks[Name].features.send.compression.algorithms, "gzip") {
 								gzip: "[Gzip](\(urls.gzip)) standard DEFLATE compression."
 							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "zstd") {
+								zstd: "[Zstandard](\(urls.zstd)) compression, which takes levels from 1 to 21 and defaults to 3."
+							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "snappy") {
+								snappy: "[Snappy](\(urls.snappy)) compression, in its framing format."
+							}
+							if list.Contains(sinks[Name].features.send.compression.algorithms, "lz4") {
+								lz4: "[LZ4](\(urls.lz4)) compression, in its frame format."
+							}
 						}
 					}
 				}
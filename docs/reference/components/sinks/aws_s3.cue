@@ -26,7 +26,7 @@ components: sinks: aws_s3: {
 			compression: {
 				enabled: true
 				default: "gzip"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy", "lz4"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -34,7 +34,7 @@ components: sinks: aws_s3: {
 				codec: {
 					enabled: true
 					default: "text"
-					enum: ["ndjson", "text"]
+					enum: ["ndjson", "parquet", "text"]
 				}
 			}
 			request: {
@@ -215,6 +215,7 @@ components: sinks: aws_s3: {
 				templateable: true
 			}
 		}
+		parquet: components._parquet.configuration.parquet
 		server_side_encryption: {
 			category:    "Encryption"
 			common:      false
@@ -361,6 +362,8 @@ components: sinks: aws_s3: {
 				"""
 		}
 
+		parquet: components._parquet.how_it_works.parquet
+
 		server_side_encryption: {
 			title: "Server-side Encryption"
 			body: """
@@ -236,6 +236,12 @@ components: sinks: elasticsearch: {
 					mapping errors, where data keys are not consistently typed.
 					To change this behavior please refer to the Elasticsearch
 					[`ignore_malformed` setting](\(urls.elasticsearch_ignore_malformed)).
+
+					Vector checks the result of every item of a bulk request. Only the
+					events Elasticsearch rejected are marked as failed and sent to the
+					sink's `dead_letter` output, while the rest count as delivered. If
+					any item was rejected with a `429` or server error, the whole request
+					is retried, so set `id_key` to avoid indexing the other events twice.
 					"""
 		}
 	}
@@ -19,7 +19,7 @@ components: sinks: file: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy", "lz4"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -70,6 +70,77 @@ components: sinks: file: {
 				templateable: true
 			}
 		}
+		retention: {
+			common:      false
+			description: "Limits the rotated files kept for each file. Applied whenever a file is rotated."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					max_files: {
+						common:      true
+						description: "The number of rotated files to keep. The oldest are removed once there are more. All of them are kept when unset."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [7]
+							unit: null
+						}
+					}
+				}
+			}
+		}
+		rotate: {
+			common:      false
+			description: "Rotates files once they are too large or too old."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					compression: {
+						common:      false
+						description: "The compression applied to files once they are rotated. Can't be used along with the `compression` option."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "none"
+							enum: {
+								none:   "No compression."
+								gzip:   "[Gzip](\(urls.gzip)) standard DEFLATE compression."
+								zstd:   "[Zstandard](\(urls.zstd)) compression."
+								snappy: "[Snappy](\(urls.snappy)) compression, in its framing format."
+								lz4:    "[LZ4](\(urls.lz4)) compression, in its frame format."
+							}
+						}
+					}
+					max_age_secs: {
+						common:      true
+						description: "Rotates a file once it was created this long ago."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [86400]
+							unit: "seconds"
+						}
+					}
+					max_bytes: {
+						common:      true
+						description: "Rotates a file once it holds this many bytes."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [104857600]
+							unit: "bytes"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -87,5 +158,22 @@ components: sinks: file: {
 				to create and write to files in the specified directories.
 				"""
 		}
+		rotation: {
+			title: "Rotation"
+			body: """
+				With the `rotate` options set, a file that has reached `rotate.max_bytes`, or was
+				created more than `rotate.max_age_secs` ago, is rotated before the next event is
+				written to it. Rotating `app.log` renames it to `app.log.1`, after shifting the
+				files rotated before it up by one, so that `app.log.1` becomes `app.log.2` and so
+				on. A new `app.log` is then created for the event.
+
+				Rotated files are compressed by `rotate.compression`, which adds its extension to
+				their names, such as `app.log.1.gz`. Only the most recent `retention.max_files`
+				rotated files are kept.
+
+				The age of a file is taken from its creation time, or from when Vector opened it
+				on filesystems that don't record creation times.
+				"""
+		}
 	}
 }
@@ -34,7 +34,7 @@ components: sinks: gcp_cloud_storage: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["ndjson", "text"]
+					enum: ["ndjson", "parquet", "text"]
 				}
 			}
 			request: {
@@ -177,6 +177,7 @@ components: sinks: gcp_cloud_storage: {
 				examples: []
 			}
 		}
+		parquet: components._parquet.configuration.parquet
 		storage_class: {
 			category:    "Storage"
 			common:      false
@@ -262,6 +263,8 @@ components: sinks: gcp_cloud_storage: {
 				"""
 		}
 
+		parquet: components._parquet.how_it_works.parquet
+
 		storage_class: {
 			title: "Storage Class"
 			body:  """
@@ -46,11 +46,34 @@ components: sinks: kafka: {
 
 	configuration: {
 		bootstrap_servers: components._kafka.configuration.bootstrap_servers
+		headers_field: {
+			common:      false
+			description: "The log field name holding a map whose entries are sent as the message's headers. Metrics are sent without headers."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["headers"]
+			}
+		}
+		key: {
+			common:      true
+			description: "The template of the message key. Can't be used along with `key_field`. If unspecified, or if a field of the template does not exist on the event, a blank value will be used."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ tenant_id }}/{{ user_id }}", "{{ name }}"]
+				templateable: true
+			}
+		}
 		key_field: {
-			description: "The log field name to use for the topic key. If unspecified, the key will be randomly generated. If the field does not exist on the log, a blank value will be used."
-			required:    true
+			common:      false
+			description: "The log field name to use for the topic key. Can't be used along with `key`. If unspecified, the key will be randomly generated. If the field does not exist on the log, a blank value will be used."
+			required:    false
 			warnings: []
 			type: string: {
+				default: null
 				examples: ["user_id"]
 			}
 		}
@@ -66,6 +89,17 @@ components: sinks: kafka: {
 				unit: null
 			}
 		}
+		partition: {
+			common:      false
+			description: "The template of the partition to send messages to. Messages whose partition can't be rendered into a number are left to the producer's partitioner, as are all of them when unspecified."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ shard }}", "0"]
+				templateable: true
+			}
+		}
 		sasl: {
 			common:      false
 			description: "Options for SASL/SCRAM authentication support."
@@ -126,9 +160,37 @@ components: sinks: kafka: {
 	}
 
 	input: {
-		logs:    true
-		metrics: null
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
 	}
 
 	how_it_works: components._kafka.how_it_works
+
+	how_it_works: {
+		headers: {
+			title: "Headers"
+			body: """
+				With `headers_field` set, each entry of the map held by that field becomes a header
+				of the message, so consumers can route messages without parsing their payloads. The
+				field itself is still part of the encoded event unless it is excluded with
+				`encoding.except_fields`.
+				"""
+		}
+		metrics: {
+			title: "Metrics"
+			body: """
+				Metric events are always encoded as JSON, whatever the `encoding.codec`. Their
+				`key`, `partition` and `topic` templates may refer to the metric's `name`,
+				`namespace` and tags, such as `{{ tags.host }}`. `key_field` only looks up log
+				fields, so metrics get a blank key with it.
+				"""
+		}
+	}
 }
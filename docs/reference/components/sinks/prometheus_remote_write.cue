@@ -0,0 +1,212 @@
+package metadata
+
+components: sinks: prometheus_remote_write: {
+	title:       "Prometheus Remote Write"
+	description: "The [Prometheus remote write protocol](\(urls.prometheus_remote_write)) lets Prometheus servers forward their samples to long term storages such as Cortex and Thanos, which also accept it from other senders."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_events:   1000
+				max_bytes:    null
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			request: {
+				enabled:                    true
+				in_flight_limit:            5
+				rate_limit_duration_secs:   1
+				rate_limit_num:             5
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               60
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				name:     "Prometheus remote write"
+				thing:    "a \(name) receiver"
+				url:      urls.prometheus_remote_write
+				versions: null
+
+				interface: {
+					socket: {
+						api: {
+							title: "Prometheus Remote Write"
+							url:   urls.prometheus_remote_write
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		platforms: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: [
+			"""
+				High cardinality metric names and labels are discouraged by
+				Prometheus as they can provide performance and reliability
+				problems. You should consider alternative strategies to reduce
+				the cardinality. Vector offers a [`tag_cardinality_limit` transform][docs.transforms.tag_cardinality_limit]
+				as a way to protect against this.
+				""",
+		]
+		notices: []
+	}
+
+	configuration: {
+		auth: {
+			common:      false
+			description: "Options for the authentication strategy."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					password: {
+						description: "The basic authentication password."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_PASSWORD}", "password"]
+						}
+					}
+					strategy: {
+						description: "The authentication strategy to use."
+						required:    true
+						warnings: []
+						type: string: {
+							enum: {
+								basic:  "The [basic authentication strategy][urls.basic_auth]."
+								bearer: "The bearer token authentication strategy."
+							}
+						}
+					}
+					token: {
+						description: "The token to use for bearer authentication"
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${API_TOKEN}", "xyz123"]
+						}
+					}
+					user: {
+						description: "The basic authentication user name."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_USERNAME}", "username"]
+						}
+					}
+				}
+			}
+		}
+		buckets: {
+			common:      false
+			description: "Default buckets to use for aggregating [distribution][docs.data-model.metric#distribution] metrics into histograms."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+				items: type: float: examples: [0.005, 0.01]
+			}
+		}
+		default_namespace: {
+			common:      true
+			description: """
+				Used as a namespace for metrics that don't have it.
+				A namespace will be prefixed to a metric's name.
+				It should follow Prometheus [naming conventions](\(urls.prometheus_metric_naming)).
+				"""
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["service"]
+			}
+		}
+		endpoint: {
+			description: "The URL of the remote write receiver, including its path."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["http://localhost:9009/api/v1/push", "https://thanos-receive:19291/api/v1/receive"]
+			}
+		}
+		flush_period_secs: {
+			common:      false
+			description: "Time after which the running total of an incremental metric that has not been updated is discarded, starting over from zero."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		metric_types: {
+			title: "Metric types"
+			body: """
+				Prometheus only stores absolute values, so the sink keeps the running total of every
+				incremental metric it receives and sends that total instead, until the metric is not
+				updated for `flush_period_secs`. Metrics are sent as the
+				series the [`prometheus` sink][docs.sinks.prometheus] would expose:
+
+				* Counters and gauges become a single series.
+				* Histograms become `_bucket` series labeled with their upper bound `le`, and `_sum`
+				  and `_count` series. Distributions are aggregated into histograms with the configured
+				  `buckets` first.
+				* Summaries become series labeled with their `quantile`, and `_sum` and `_count`
+				  series.
+				* Sets become a gauge of their number of values.
+
+				Each request also carries the type of the metric families it contains.
+				"""
+		}
+	}
+}
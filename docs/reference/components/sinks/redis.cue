@@ -0,0 +1,129 @@
+package metadata
+
+components: sinks: redis: {
+	title:       "Redis"
+	description: components._redis.description
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+	}
+
+	features: {
+		buffer: enabled:      true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    null
+				max_events:   100
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					default: null
+					enum: ["json", "text"]
+				}
+			}
+			request: {
+				enabled:                    true
+				in_flight_limit:            1
+				rate_limit_duration_secs:   1
+				rate_limit_num:             18446744073709551615
+				retry_initial_backoff_secs: 1
+				retry_max_duration_secs:    10
+				timeout_secs:               30
+			}
+			tls: enabled: false
+			to: components._redis.features.service
+		}
+	}
+
+	support: components._redis.support
+
+	configuration: {
+		data_type: {
+			common:      true
+			description: "The Redis data type to send events to."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "list"
+				enum: {
+					list:    "Push events onto the list named by `key`, with the command set by `list.method`."
+					channel: "Publish events to the channel named by `key` with `PUBLISH`."
+					stream:  "Append events to the stream named by `key` with `XADD`."
+				}
+			}
+		}
+		key: {
+			description: "The template of the list, channel or stream key to send events to. Events missing a field of the template are dropped."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector", "logs:{{ application }}"]
+				templateable: true
+			}
+		}
+		list: {
+			common:      false
+			description: "Options for the `list` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					method: {
+						common:      false
+						description: "The command pushing events onto the list."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "rpush"
+							enum: {
+								rpush: "Push events onto the tail of the list with `RPUSH`, so `BLPOP` reads them in order."
+								lpush: "Push events onto the head of the list with `LPUSH`, so `BRPOP` reads them in order."
+							}
+						}
+					}
+				}
+			}
+		}
+		url: components._redis.configuration.url
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		batching: {
+			title: "Batching"
+			body: """
+				Each batch of events is sent as a single `MULTI`/`EXEC` transaction, so a connection
+				failing midway doesn't leave a batch partly sent before it is retried. Consecutive
+				events pushed onto the same list are sent with a single command.
+
+				Requests are sent one at a time by default, which keeps the events of a list or stream
+				in the order they were received. Raising `request.in_flight_limit` trades that order
+				for throughput.
+				"""
+		}
+		streams: {
+			title: "Streams"
+			body: """
+				With the `stream` data type, each event is appended to the [stream](\(urls.redis_streams))
+				as an entry holding the encoded event in its `message` field, under an ID generated
+				by Redis.
+				"""
+		}
+	}
+}
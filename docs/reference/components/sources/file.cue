@@ -65,6 +65,12 @@ components: sources: file: {
 	}
 
 	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Only advance a file's checkpoint once the events read from it have been delivered by every sink they were sent to."
+			required:    false
+			type: bool: default: false
+		}
 		exclude: {
 			common:      false
 			description: "Array of file patterns to exclude. [Globbing](#globbing) is supported.*Takes precedence over the [`include` option](#include).*"
@@ -258,19 +264,24 @@ components: sources: file: {
 			title: "Compressed Files"
 			body: """
 				Vector will transparently detect files which have been compressed
-				using Gzip and decompress them for reading. This detection process
-				looks for the unique sequence of bytes in the Gzip header and does
-				not rely on the compressed files adhering to any kind of naming
+				using [Gzip](\(urls.gzip)) or [Zstandard](\(urls.zstd)) and
+				decompress them for reading. This detection process looks for the
+				unique sequence of bytes in the header of each format and does not
+				rely on the compressed files adhering to any kind of naming
 				convention.
 
-				One caveat with reading compressed files is that Vector is not able
-				to efficiently seek into them. Rather than implement a
-				potentially-expensive full scan as a seek mechanism, Vector
-				currently will not attempt to make further reads from a file for
-				which it has already stored a checkpoint in a previous run. For
-				this reason, users should take care to allow Vector to fully
-				process anycompressed files before shutting the process down or moving the
-				files to another location on disk.
+				Checksum fingerprints are computed over the decompressed content,
+				so a rotated file that is compressed while Vector is reading it,
+				for example `app.log.1` becoming `app.log.1.gz`, keeps its identity.
+				Vector carries on reading the compressed file from where it left
+				off rather than losing the rest of it or reading it again. This
+				also makes it possible to backfill historical archives by including
+				them in the `include` option.
+
+				Compressed files can't be seeked into, so resuming one from a
+				checkpoint, whether after a restart or after compression, requires
+				decompressing and discarding everything before the checkpointed
+				position. Checkpoints of compressed files count decompressed bytes.
 				"""
 		}
 
@@ -352,7 +363,8 @@ components: sources: file: {
 			body: """
 				By default, Vector identifies files by creating a
 				[cyclic redundancy check](urls.crc) (CRC) on the first 256 bytes of
-				the file. This serves as a fingerprint to uniquely identify the file.
+				the file, after decompressing it if it is compressed. This serves as a
+				fingerprint to uniquely identify the file.
 				The amount of bytes read can be controlled via the `fingerprint_bytes`
 				and `ignored_header_bytes` options.
 
@@ -32,6 +32,12 @@ components: sources: kafka: {
 	support: components._kafka.support
 
 	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Only store the offset of a message once its event has been delivered by every sink it was sent to. Messages whose events could not be delivered are consumed again after a restart."
+			required:    false
+			type: bool: default: false
+		}
 		auto_offset_reset: {
 			common:      false
 			description: "If offsets for consumer group do not exist, set them using this strategy. [librdkafka documentation][urls.librdkafka_config] for `auto.offset.reset` option for explanation."
@@ -73,6 +79,16 @@ components: sources: kafka: {
 				examples: ["consumer-group-name"]
 			}
 		}
+		headers_field: {
+			common:      false
+			description: "The log field name to store the Kafka record headers in, as a map of header names to values. If unspecified, the headers are not added to the log event."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["headers"]
+			}
+		}
 		key_field: {
 			common:      true
 			description: "The log field name to use for the Kafka message key. If unspecified, the key would not be added to the log event. If the message has null key, then this field would not be added to the log event."
@@ -84,6 +100,42 @@ components: sources: kafka: {
 			}
 		}
 		librdkafka_options: components._kafka.configuration.librdkafka_options
+		partitions: {
+			common:      false
+			description: "The partitions to read events from, instead of subscribing to `topics`. The partitions are assigned to this consumer rather than balanced across the consumer group, whose offsets are still stored."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						partition: {
+							description: "The partition number."
+							required:    true
+							warnings: []
+							type: uint: {
+								examples: [0, 3]
+								unit: null
+							}
+						}
+						start_offset: {
+							common:      true
+							description: "Where to start reading the partition from: `\"stored\"` resumes from the consumer group's offset, falling back to `auto_offset_reset`, `\"earliest\"` and `\"latest\"` start from either end of the partition, `{ offset = <offset> }` from an explicit offset, and `{ timestamp = <milliseconds> }` from the first record at or after a time."
+							required:    false
+							warnings: []
+							type: "*": {}
+						}
+						topic: {
+							description: "The topic of the partition."
+							required:    true
+							warnings: []
+							type: string: examples: ["topic-1"]
+						}
+					}
+				}
+			}
+		}
 		sasl: {
 			common:      false
 			description: "Options for SASL/SCRAM authentication support."
@@ -144,11 +196,26 @@ components: sources: kafka: {
 			}
 		}
 		socket_timeout_ms: components._kafka.configuration.socket_timeout_ms
+		statistics_interval_ms: {
+			common:      false
+			description: "How often librdkafka reports the statistics of the consumer, from which the lag and offsets of each partition are exposed as internal metrics. `0` disables the statistics."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 0
+				examples: [10000]
+				unit: "milliseconds"
+			}
+		}
 		topics: {
-			description: "The Kafka topics names to read events from. Regex is supported if the topic begins with `^`.\n"
-			required:    true
+			common:      true
+			description: "The Kafka topics names to read events from. Regex is supported if the topic begins with `^`. Either `topics` or `partitions` must be set.\n"
+			required:    false
 			warnings: []
-			type: array: items: type: string: examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+			type: array: {
+				default: []
+				items: type: string: examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+			}
 		}
 	}
 
@@ -166,5 +233,23 @@ components: sources: kafka: {
 		}
 	}
 
-	how_it_works: components._kafka.how_it_works
+	how_it_works: components._kafka.how_it_works & {
+		consumer_telemetry: {
+			title: "Consumer telemetry"
+			body: """
+				When `statistics_interval_ms` is set, the lag, consumed offset and committed offset of
+				every partition the source consumes, along with the partition's high watermark, are
+				reported as the `consumer_lag`, `consumer_offset`, `consumer_committed_offset` and
+				`partition_high_watermark` internal metrics, tagged with `topic` and `partition`.
+
+				Partition assignments and revocations caused by consumer group rebalances are logged,
+				and counted by the `consumer_rebalances_total` internal metric.
+
+				With `acknowledgements` enabled, a partition with an undelivered event stops storing
+				offsets until the next rebalance. While stalled, the `consumer_partition_stalled`
+				internal metric is `1` for the partition, and the offsets held back are counted by
+				`consumer_offsets_held_back_total`.
+				"""
+		}
+	}
 }
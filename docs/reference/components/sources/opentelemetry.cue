@@ -0,0 +1,245 @@
+package metadata
+
+components: sources: opentelemetry: {
+	_grpc_port: 4317
+	_http_port: 4318
+
+	title:       "OpenTelemetry"
+	description: "[OpenTelemetry](\(urls.opentelemetry)) is a collection of tools, APIs, and SDKs used to instrument, generate, collect, and export telemetry data. Its applications and collectors export logs and metrics over the [OpenTelemetry protocol (OTLP)](\(urls.opentelemetry_protocol))."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				name:     "OpenTelemetry"
+				thing:    "an \(name) exporter"
+				url:      urls.opentelemetry
+				versions: null
+
+				interface: socket: {
+					api: {
+						title: "OTLP"
+						url:   urls.opentelemetry_protocol
+					}
+					direction: "incoming"
+					port:      _grpc_port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+
+			tls: enabled: false
+		}
+	}
+
+	support: {
+		platforms: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		grpc: {
+			description: "Configures the listener for OTLP/gRPC requests."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					address: {
+						description: "The address to accept OTLP/gRPC connections on. It _must_ include a port."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["0.0.0.0:\(_grpc_port)"]
+						}
+					}
+					tls: configuration._tls_accept & {_args: {
+						can_enable:      true
+						enabled_default: false
+					}}
+				}
+			}
+		}
+		http: {
+			description: "Configures the listener for OTLP/HTTP requests."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					address: {
+						description: "The address to accept OTLP/HTTP connections on. It _must_ include a port."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["0.0.0.0:\(_http_port)"]
+						}
+					}
+					tls: configuration._tls_accept & {_args: {
+						can_enable:      true
+						enabled_default: false
+					}}
+				}
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An OpenTelemetry log record."
+		fields: {
+			message: {
+				description: "The body of the log record."
+				required:    true
+				type: "*": {}
+			}
+			timestamp: {
+				description: "The time the event occurred, falling back to the time it was observed, and to the time it was received by Vector if neither is set."
+				required:    true
+				type: timestamp: {}
+			}
+			observed_timestamp: {
+				description: "The time the event was observed by the collection system."
+				required:    false
+				common:      false
+				type: timestamp: {}
+			}
+			attributes: {
+				description: "The attributes of the log record."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"http.method": "GET"}]
+					options: {}
+				}
+			}
+			resources: {
+				description: "The attributes of the resource that produced the log record."
+				required:    false
+				common:      true
+				type: object: {
+					examples: [{"service.name": "checkout"}]
+					options: {}
+				}
+			}
+			scope: {
+				description: "The `name`, `version` and `attributes` of the instrumentation scope that produced the log record."
+				required:    false
+				common:      false
+				type: object: {
+					examples: [{"name": "io.opentelemetry.contrib.mongodb", "version": "1.0.0"}]
+					options: {}
+				}
+			}
+			severity_text: {
+				description: "The severity of the log record, as known at its source."
+				required:    false
+				common:      true
+				type: string: {
+					examples: ["INFO", "ERROR"]
+				}
+			}
+			severity_number: {
+				description: "The normalized severity of the log record, from `1` (`TRACE`) to `24` (`FATAL4`)."
+				required:    false
+				common:      true
+				type: uint: {
+					examples: [9, 17]
+					unit: null
+				}
+			}
+			trace_id: {
+				description: "The hex encoded ID of the trace the log record is part of."
+				required:    false
+				common:      false
+				type: string: {
+					examples: ["5b8efff798038103d269b633813fc60c"]
+				}
+			}
+			span_id: {
+				description: "The hex encoded ID of the span the log record is part of."
+				required:    false
+				common:      false
+				type: string: {
+					examples: ["eee19b7ec3c1b174"]
+				}
+			}
+			flags: {
+				description: "The trace flags of the log record."
+				required:    false
+				common:      false
+				type: uint: {
+					examples: [1]
+					unit: null
+				}
+			}
+			dropped_attributes_count: {
+				description: "The number of attributes that were dropped from the log record by its source."
+				required:    false
+				common:      false
+				type: uint: {
+					examples: [2]
+					unit: null
+				}
+			}
+		}
+	}
+
+	output: metrics: {
+		counter:   output._passthrough_counter
+		gauge:     output._passthrough_gauge
+		histogram: output._passthrough_histogram
+		summary:   output._passthrough_summary
+	}
+
+	how_it_works: {
+		transports: {
+			title: "Transports"
+			body: """
+				The source accepts OTLP export requests both over gRPC, on the `grpc.address`, and as
+				protobuf encoded requests over HTTP, on the `http.address`. Logs are posted to
+				`/v1/logs` and metrics to `/v1/metrics`. HTTP requests must have a `Content-Type` of
+				`application/x-protobuf`, and may be compressed with `gzip`, as long as they are no
+				larger than 32 MiB once decompressed.
+
+				Requests are only answered once their events have been forwarded, so that exporters
+				retry requests Vector could not accept.
+				"""
+		}
+		metric_types: {
+			title: "Metric types"
+			body: """
+				OTLP metrics are converted to Vector metrics as follows:
+
+				* Gauges become gauges.
+				* Monotonic sums become counters, other sums become gauges. Sums with a delta
+				  aggregation temporality are incremental, cumulative ones are absolute.
+				* Histograms become aggregated histograms, with one cumulative bucket per explicit
+				  bound. Values above the highest bound are only part of the total count.
+				* Summaries become aggregated summaries.
+
+				Exponential histograms are not supported yet and are dropped. The attributes of the data
+				points become tags, as do the attributes of their resource, prefixed with `resource.`,
+				and the name, version and attributes of their instrumentation scope, prefixed with
+				`scope.`.
+				"""
+		}
+	}
+}
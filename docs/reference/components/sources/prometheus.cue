@@ -52,13 +52,157 @@ components: sources: prometheus: {
 
 	configuration: {
 		endpoints: {
-			description: "Endpoints to scrape metrics from."
-			required:    true
+			common:      true
+			description: "Endpoints to scrape metrics from. Either `endpoints` or `file_sd_configs` must be set."
+			required:    false
 			warnings: ["You must explicitly add the path to your endpoints. Vector will _not_ automatically add `/metics`."]
 			type: array: {
+				default: []
 				items: type: string: examples: ["http://localhost:9090/metrics"]
 			}
 		}
+		file_sd_configs: {
+			common:      false
+			description: "Files to discover targets from, in the format of Prometheus' [`file_sd_configs`](\(urls.prometheus_file_sd_config)). The files are re-read before every scrape."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						files: {
+							description: "Paths of the target files, which may contain glob patterns. Files must have a `.json`, `.yml` or `.yaml` extension."
+							required:    true
+							warnings: []
+							type: array: items: type: string: examples: ["/etc/vector/targets/*.json"]
+						}
+					}
+				}
+			}
+		}
+		honor_labels: {
+			common:      false
+			description: "If `true`, tags of the scraped metrics conflicting with the labels of their target are kept. Otherwise they are renamed to `exported_<name>`."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		job_name: {
+			common:      true
+			description: "The value of the `job` tag added to scraped metrics. Defaults to the name of the source."
+			required:    false
+			warnings: []
+			type: string: {
+				default:  null
+				examples: ["node"]
+			}
+		}
+		labels: {
+			common:      false
+			description: "Extra labels to attach to the targets of `endpoints`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [{"env": "production"}]
+				options: {}
+			}
+		}
+		metrics_path: {
+			common:      false
+			description: "The path to scrape discovered targets on."
+			required:    false
+			warnings: []
+			type: string: default: "/metrics"
+		}
+		relabel_configs: {
+			common:      false
+			description: "Rewrites the labels of targets before they are scraped, like Prometheus' [`relabel_configs`](\(urls.prometheus_relabel_config))."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						action: {
+							common:      true
+							description: "The relabeling action to perform."
+							required:    false
+							warnings: []
+							type: string: {
+								default: "replace"
+								enum: {
+									replace:   "Sets `target_label` to `replacement` if `regex` matches the concatenated `source_labels`."
+									keep:      "Drops targets for which `regex` doesn't match the concatenated `source_labels`."
+									drop:      "Drops targets for which `regex` matches the concatenated `source_labels`."
+									labelmap:  "Copies the labels whose name matches `regex` to the label named by `replacement`."
+									labeldrop: "Removes the labels whose name matches `regex`."
+									labelkeep: "Removes the labels whose name doesn't match `regex`."
+								}
+							}
+						}
+						regex: {
+							common:      true
+							description: "The regular expression matched against the concatenated `source_labels`, or against label names. It must match the whole value."
+							required:    false
+							warnings: []
+							type: string: {
+								default:  "(.*)"
+								examples: ["(.*):\\d+"]
+							}
+						}
+						replacement: {
+							common:      false
+							description: "The value of `target_label`, which may refer to the groups captured by `regex`."
+							required:    false
+							warnings: []
+							type: string: default: "$1"
+						}
+						separator: {
+							common:      false
+							description: "The separator placed between the values of `source_labels`."
+							required:    false
+							warnings: []
+							type: string: default: ";"
+						}
+						source_labels: {
+							common:      true
+							description: "The labels whose values are concatenated and matched against `regex`."
+							required:    false
+							warnings: []
+							type: array: {
+								default: []
+								items: type: string: examples: ["__address__"]
+							}
+						}
+						target_label: {
+							common:      true
+							description: "The label set by the `replace` action."
+							required:    false
+							warnings: []
+							type: string: {
+								default:  null
+								examples: ["host"]
+							}
+						}
+					}
+				}
+			}
+		}
+		scheme: {
+			common:      false
+			description: "The scheme to scrape discovered targets with."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "http"
+				enum: {
+					http:  "Scrape targets over HTTP."
+					https: "Scrape targets over HTTPS."
+				}
+			}
+		}
 		scrape_interval_secs: {
 			common:      true
 			description: "The interval between scrapes, in seconds."
@@ -69,6 +213,16 @@ components: sources: prometheus: {
 				unit:    "seconds"
 			}
 		}
+		scrape_timeout_secs: {
+			common:      false
+			description: "The time to wait for a scrape to complete, in seconds. Defaults to `scrape_interval_secs`, which it must not exceed."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
 	}
 
 	output: metrics: {
@@ -77,4 +231,29 @@ components: sources: prometheus: {
 		histogram: output._passthrough_histogram
 		summary:   output._passthrough_summary
 	}
+
+	how_it_works: {
+		targets: {
+			title: "Targets"
+			body: """
+				Targets are discovered from `endpoints` and `file_sd_configs` before every scrape. Each
+				target is described by labels: `__address__`, `__scheme__`, `__metrics_path__` and
+				`__param_<name>` make up the URL to scrape, targets from files are labeled with
+				`__meta_filepath`, and `job` is set to the `job_name`. The `relabel_configs` then
+				rewrite these labels, or drop the target.
+
+				Once relabeled, `instance` defaults to the `__address__` of the target, and labels
+				starting with `__` are removed. The remaining labels are added as tags to every metric
+				scraped from the target.
+				"""
+		}
+		up_metric: {
+			title: "The `up` metric"
+			body: """
+				After each scrape, an `up` gauge tagged with the labels of the target is emitted. It
+				is `1` if the target was scraped successfully and `0` if the scrape failed or timed
+				out.
+				"""
+		}
+	}
 }
@@ -0,0 +1,118 @@
+package metadata
+
+components: sources: prometheus_remote_write: {
+	_port: 9090
+
+	title:       "Prometheus Remote Write"
+	description: "[Prometheus](\(urls.prometheus)) is a pull-based monitoring system that scrapes metrics from configured endpoints, and can forward the samples it scraped to other systems with its [remote write protocol](\(urls.prometheus_remote_write))."
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+	}
+
+	features: {
+		multiline: enabled: false
+		receive: {
+			from: {
+				name:     "Prometheus"
+				thing:    "a \(name) server"
+				url:      urls.prometheus
+				versions: ">= 2.0"
+
+				interface: socket: {
+					api: {
+						title: "Prometheus Remote Write"
+						url:   urls.prometheus_remote_write
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		platforms: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address to accept connections on. The address _must_ include a port."
+			required:    true
+			type: string: examples: ["0.0.0.0:\(_port)", "localhost:\(_port)"]
+		}
+		auth: {
+			common:      false
+			description: "Options for HTTP Basic Authentication."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					username: {
+						description: "The basic authentication user name."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_USERNAME}", "username"]
+						}
+					}
+					password: {
+						description: "The basic authentication password."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["${PROMETHEUS_PASSWORD}", "password"]
+						}
+					}
+				}
+			}
+		}
+	}
+
+	output: metrics: {
+		counter: output._passthrough_counter
+		gauge:   output._passthrough_gauge
+	}
+
+	how_it_works: {
+		metric_types: {
+			title: "Metric types"
+			body: """
+				Write requests carry the individual samples of each series, and from time to time the
+				metadata Prometheus knows about their metric families. The source remembers the metadata
+				it received for later requests. Every sample becomes an absolute metric named after its
+				series, with the labels of the series as tags.
+
+				Samples are counters when the metadata describes their series as a counter, or as the
+				`_bucket`, `_sum` or `_count` series of a histogram or summary. Samples of series without
+				metadata are counters if their name ends with `_total`, `_bucket`, `_sum` or `_count`.
+				All other samples are gauges.
+				"""
+		}
+	}
+}
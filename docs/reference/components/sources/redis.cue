@@ -0,0 +1,172 @@
+package metadata
+
+components: sources: redis: {
+	title:       "Redis"
+	description: components._redis.description
+
+	features: {
+		collect: {
+			checkpoint: enabled: false
+			tls: enabled:        false
+			from: components._redis.features.service
+		}
+		multiline: enabled: false
+	}
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	support: components._redis.support
+
+	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Only acknowledge a stream entry once its event has been delivered by every sink it was sent to. Entries whose events could not be delivered are read again after a restart. Only applies to the `stream` data type."
+			required:    false
+			type: bool: default: false
+		}
+		data_type: {
+			common:      true
+			description: "The Redis data type to read events from."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "list"
+				enum: {
+					list:    "Pop events off the list named by `key`, with the command set by `list.method`."
+					channel: "Subscribe to the channel named by `key` with `SUBSCRIBE`."
+					stream:  "Read the stream named by `key` as part of the consumer group set by `stream.group`, acknowledging entries with `XACK`."
+				}
+			}
+		}
+		key: {
+			description: "The key of the list, channel or stream to read events from."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["vector"]
+			}
+		}
+		key_field: {
+			common:      false
+			description: "The log field name to store the key the event was read from in. If unspecified, the key is not added to the log event."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["redis_key"]
+			}
+		}
+		list: {
+			common:      false
+			description: "Options for the `list` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					method: {
+						common:      false
+						description: "The command popping events off the list."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "blpop"
+							enum: {
+								blpop: "Pop events off the head of the list with `BLPOP`."
+								brpop: "Pop events off the tail of the list with `BRPOP`."
+							}
+						}
+					}
+				}
+			}
+		}
+		stream: {
+			common:      false
+			description: "Options for the `stream` data type."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					consumer: {
+						common:      false
+						description: "The name of the consumer within the group. Defaults to the hostname."
+						required:    false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["aggregator-1"]
+						}
+					}
+					count: {
+						common:      false
+						description: "The maximum number of entries read at once."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 100
+							unit:    null
+						}
+					}
+					group: {
+						common:      true
+						description: "The consumer group reading the stream. It is created, along with the stream, if it doesn't exist yet."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "vector"
+							examples: ["aggregators"]
+						}
+					}
+				}
+			}
+		}
+		url: components._redis.configuration.url
+	}
+
+	output: logs: record: {
+		description: "An individual list element, channel message or stream entry."
+		fields: {
+			message: {
+				description: "The list element or channel message. Stream entries get a field for each of their own fields instead."
+				required:    true
+				type: string: examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+			}
+			timestamp: fields._current_timestamp & {
+				description: "The time the event was read, or for stream entries the time they were added, as recorded in their ID."
+			}
+		}
+	}
+
+	how_it_works: {
+		consumer_groups: {
+			title: "Consumer groups"
+			body: """
+				With the `stream` data type, the source reads the [stream](\(urls.redis_streams)) with
+				`XREADGROUP`, so that several instances of Vector sharing a `stream.group` split its
+				entries between them. A group created by the source starts from the first entry of
+				the stream.
+
+				Entries are acknowledged with `XACK` once their events are sent on, or delivered when
+				`acknowledgements` is enabled. Entries read by a consumer but never acknowledged, such
+				as when Vector stopped before delivering them, are read again when it restarts under
+				the same `stream.consumer` name, before any new entry.
+				"""
+		}
+		delivery: {
+			title: "Delivery"
+			body: """
+				Elements popped off a list are removed from Redis as soon as they are read, and
+				messages published to a channel while the source is disconnected are lost, as Redis
+				doesn't keep them. Use the `stream` data type for events that have to survive a
+				restart of Vector.
+				"""
+		}
+	}
+}
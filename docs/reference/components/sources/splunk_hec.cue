@@ -59,6 +59,12 @@ components: sources: splunk_hec: {
 	}
 
 	configuration: {
+		acknowledgements: {
+			common:      false
+			description: "Only respond to a request once its events have been delivered by every sink they were sent to. Requests whose events could not be delivered because of a transient error are answered with a `503` so that clients retry them, while requests whose events were rejected are answered with a `400`. Requests that are issued an ack ID by `indexer_acknowledgements` are answered right away instead."
+			required:    false
+			type: bool: default: false
+		}
 		address: {
 			common:      true
 			description: "The address to accept connections on."
@@ -68,6 +74,37 @@ components: sources: splunk_hec: {
 				default: "0.0.0.0:\(_port)"
 			}
 		}
+		indexer_acknowledgements: {
+			common:      false
+			description: "Issue an ack ID for each request sent on a channel, which clients can query on `/services/collector/ack` to find out whether the request's events have been delivered. See [indexer acknowledgements](#indexer-acknowledgements)."
+			required:    false
+			type: object: options: {
+				enabled: {
+					common:      false
+					description: "Enables indexer acknowledgements. Ack IDs are issued for requests that carry a channel, either in the `X-Splunk-Request-Channel` header or in the `channel` query parameter. `/services/collector/raw` requests must carry one."
+					required:    false
+					type: bool: default: false
+				}
+				max_channels: {
+					common:      false
+					description: "The maximum number of channels to track acks for. Once reached, the channel that has been idle for the longest time is forgotten."
+					required:    false
+					type: uint: {
+						default: 1000000
+						unit:    null
+					}
+				}
+				max_pending_acks_per_channel: {
+					common:      false
+					description: "The maximum number of acks that can be pending or unqueried on a channel. Further requests on the channel are answered with a `503` until the client queries its acks."
+					required:    false
+					type: uint: {
+						default: 1000000
+						unit:    null
+					}
+				}
+			}
+		}
 		token: {
 			common:      true
 			description: "If supplied, incoming requests must supply this token in the `Authorization` header, just as a client would if it was communicating with the Splunk HEC endpoint directly. If _not_ supplied, the `Authorization` header will be ignored and requests will not be authenticated."
@@ -80,6 +117,33 @@ components: sources: splunk_hec: {
 		}
 	}
 
+	how_it_works: {
+		indexer_acknowledgements: {
+			title: "Indexer acknowledgements"
+			body: """
+				With `indexer_acknowledgements` enabled, every request sent on a channel to
+				`/services/collector/event` or `/services/collector/raw` is answered right away with an ack ID.
+				IDs are never reused, even across channels:
+
+				```json
+				{"text": "Success", "code": 0, "ackId": 0}
+				```
+
+				Clients then query the delivery status of their requests by posting the ack IDs to
+				`/services/collector/ack` on the same channel:
+
+				```json
+				{"acks": [0, 1, 2]}
+				```
+
+				Vector responds with the IDs whose events have been delivered by every sink they were sent to,
+				for example `{"acks": {"0": true, "1": false, "2": false}}`. Like Splunk, an ID is only reported
+				as delivered once. Requests whose events fail to be delivered are never reported as delivered,
+				so clients resend them once they give up waiting.
+				"""
+		}
+	}
+
 	output: logs: event: {
 		description: "A single event"
 		fields: {
@@ -4,7 +4,7 @@ components: sources: statsd: {
 	_port: 8125
 
 	title:       "StatsD"
-	description: "[StatsD](\(urls.statsd)) is a standard and, by extension, a set of tools that can be used to send, collect, and aggregate custom metrics from any application. Originally, StatsD referred to a daemon written by [Etsy](\(urls.etsy)) in Node."
+	description: "[StatsD](\(urls.statsd)) is a standard and, by extension, a set of tools that can be used to send, collect, and aggregate custom metrics from any application. Originally, StatsD referred to a daemon written by [Etsy](\(urls.etsy)) in Node. [DogStatsD](\(urls.dogstatsd)) is Datadog's extension of the protocol, adding tags, events and service checks."
 
 	classes: {
 		commonly_used: false
@@ -63,22 +63,31 @@ components: sources: statsd: {
 				examples: ["0.0.0.0:\(_port)", "systemd", "systemd#3"]
 			}
 		}
+		dogstatsd_events: {
+			common:      false
+			description: "Whether to emit DogStatsD events (`_e{...}`) and service checks (`_sc|...`) as log events. When disabled, they are discarded and the source only emits metrics."
+			groups: ["tcp", "udp", "unix", "unix_datagram"]
+			required: false
+			warnings: []
+			type: bool: default: false
+		}
 		mode: {
 			description: "The type of socket to use."
-			groups: ["tcp", "udp", "unix"]
+			groups: ["tcp", "udp", "unix", "unix_datagram"]
 			required: true
 			warnings: []
 			type: string: {
 				enum: {
-					tcp:  "TCP Socket."
-					udp:  "UDP Socket."
-					unix: "Unix Domain Socket."
+					tcp:           "TCP Socket."
+					udp:           "UDP Socket."
+					unix:          "Unix Domain Socket."
+					unix_datagram: "Unix Domain Datagram Socket, as used by DogStatsD clients."
 				}
 			}
 		}
 		path: {
 			description: "The unix socket path. *This should be an absolute path*."
-			groups: ["unix"]
+			groups: ["unix", "unix_datagram"]
 			required: true
 			warnings: []
 			type: string: {
@@ -96,7 +105,112 @@ components: sources: statsd: {
 				unit:    "seconds"
 			}
 		}
+	}
 
+	output: logs: {
+		event: {
+			description: "A DogStatsD event."
+			fields: {
+				message: {
+					description: "The text of the event."
+					required:    true
+					type: string: examples: ["Version 1.2 rolled out"]
+				}
+				title: {
+					description: "The title of the event."
+					required:    true
+					type: string: examples: ["Deploy done"]
+				}
+				timestamp: {
+					description: "The time of the event, from its `d:` field, or the time it was received by Vector."
+					required:    true
+					type: timestamp: {}
+				}
+				host: {
+					description: "The host of the event, from its `h:` field."
+					required:    false
+					common:      true
+					type: string: examples: ["web-1"]
+				}
+				priority: {
+					description: "The priority of the event, from its `p:` field."
+					required:    false
+					common:      false
+					type: string: examples: ["normal", "low"]
+				}
+				alert_type: {
+					description: "The alert type of the event, from its `t:` field."
+					required:    false
+					common:      true
+					type: string: examples: ["error", "warning", "info", "success"]
+				}
+				aggregation_key: {
+					description: "The key grouping the event with others, from its `k:` field."
+					required:    false
+					common:      false
+					type: string: examples: ["deploys"]
+				}
+				source_type_name: {
+					description: "The type of source of the event, from its `s:` field."
+					required:    false
+					common:      false
+					type: string: examples: ["jenkins"]
+				}
+				tags: {
+					description: "The tags of the event."
+					required:    false
+					common:      true
+					type: object: {
+						examples: [{"env": "prod"}]
+						options: {}
+					}
+				}
+			}
+		}
+		service_check: {
+			description: "A DogStatsD service check."
+			fields: {
+				check: {
+					description: "The name of the service check."
+					required:    true
+					type: string: examples: ["db.replication"]
+				}
+				status: {
+					description: "The status of the service check: `0` for OK, `1` for warning, `2` for critical and `3` for unknown."
+					required:    true
+					type: uint: {
+						examples: [0, 2]
+						unit: null
+					}
+				}
+				message: {
+					description: "The message of the service check, from its `m:` field."
+					required:    false
+					common:      true
+					type: string: examples: ["Lagging by 30s"]
+				}
+				timestamp: {
+					description: "The time of the service check, from its `d:` field, or the time it was received by Vector."
+					required:    true
+					type: timestamp: {}
+				}
+				host: {
+					description: "The host of the service check, from its `h:` field."
+					required:    false
+					common:      true
+					type: string: examples: ["db-1"]
+				}
+				tags: {
+					description: "The tags of the service check."
+					required:    false
+					common:      true
+					type: object: {
+						examples: [{"env": "prod"}]
+						options: {}
+					}
+				}
+			}
+		}
 	}
 
 	output: metrics: {
@@ -107,6 +221,20 @@ components: sources: statsd: {
 	}
 
 	how_it_works: {
+		dogstatsd: {
+			title: "DogStatsD"
+			body: """
+				The source understands the [DogStatsD datagram format](\(urls.dogstatsd_datagram_format)).
+				The optional fields following the metric type may come in any order: tags (`#`)
+				become metric tags, the container ID (`c:`) becomes the `container_id` tag, and the
+				timestamp (`T`) becomes the metric timestamp. Lines packing several values, such as
+				`latency:10:20:30|ms`, become a single metric holding all of them.
+
+				With `dogstatsd_events` enabled, events (`_e{...}`) and service checks (`_sc|...`)
+				become log events. Otherwise they are discarded, so that the source only emits
+				metrics and can feed metric-only sinks.
+				"""
+		}
 		timestamps: {
 			title: "Timestamps"
 			body: """
@@ -117,6 +245,7 @@ components: sources: statsd: {
 				timestamps will be substituted by current time by downstream
 				sinks or 3rd party services during sending/ingestion. See the
 				[metric][docs.data-model.metric] data model page for more info.
+				DogStatsD metrics with a `T` field keep their timestamp.
 				"""
 		}
 	}
@@ -43,7 +43,7 @@ components: transforms: json_parser: {
 			type: bool: default: true
 		}
 		drop_invalid: {
-			description: "If `true` events with invalid JSON will be dropped, otherwise the event will be kept and passed through."
+			description: "If `true` events with invalid JSON will be dropped, otherwise they are sent to the `errors` output."
 			required:    true
 			warnings: []
 			type: bool: {}
@@ -89,8 +89,9 @@ components: transforms: json_parser: {
 				If the value for the specified `field` is not valid JSON you can control keeping
 				or discarding the event with the `drop_invalid` option. Setting it to `true` will
 				discard the event and drop it entirely. Setting it to `false` will keep the
-				event and pass it through. Note that passing through the event could cause
-				problems and violate assumptions about the structure of your event.
+				event unchanged and send it to the `errors` output of this transform instead of
+				its default output. Other components can consume it by using
+				`<transform-id>.errors` as an input.
 				"""
 		}
 
@@ -31,7 +31,7 @@ components: transforms: reduce: {
 	configuration: {
 		ends_when: {
 			common:      false
-			description: "A condition used to distinguish the final event of a transaction. If this condition resolves to true for an event the transaction it belongs to is immediately flushed."
+			description: "A condition used to distinguish the final event of a transaction. If this condition resolves to true for an event the transaction it belongs to is immediately flushed. Like any condition, it can be a remap boolean expression, e.g. `ends_when = '.status == \"done\"'`."
 			required:    false
 			warnings: []
 			type: object: configuration._conditions
@@ -123,7 +123,7 @@ components: transforms: regex_parser: {
 		failed_parsing: {
 			title: "Failed Parsing"
 			body: """
-				By default, if the input message text does not match any of the configured regular expression patterns, this transform will log an error message but leave the log event unchanged. If you instead wish to have this transform drop the event, set `drop_failed = true`.
+				By default, if the input message text does not match any of the configured regular expression patterns, this transform will log an error message and send the log event unchanged to the `errors` output of this transform instead of its default output. Other components can consume it by using `<transform-id>.errors` as an input. If you instead wish to have this transform drop the event, set `drop_failed = true`.
 				"""
 		}
 		flags: {
@@ -35,6 +35,20 @@ components: transforms: remap: {
 	}
 
 	configuration: {
+		drop_on_abort: {
+			common:      false
+			description: "If an event should be dropped when the program runs the `abort` statement. When disabled, the event is passed on with the changes made before aborting."
+			required:    false
+			warnings: []
+			type: bool: default: true
+		}
+		drop_on_err: {
+			common:      false
+			description: "If an event should be dropped when the program fails with an error that it doesn't handle itself."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
 		source: {
 			description: "The remap source/instruction set to execute for each event"
 			required:    true
@@ -51,8 +65,15 @@ components: transforms: remap: {
 	}
 
 	input: {
-		logs:    true
-		metrics: null
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
 	}
 
 	examples: [
@@ -111,6 +132,61 @@ components: transforms: remap: {
 				timestamp: "2020-10-01T02:22:11.223212Z"
 			}
 		},
+		{
+			title: "Handle Errors"
+			configuration: {
+				source: #"""
+					if .level == "debug" { abort }
+					.timestamp = parse_timestamp(.timestamp, "%s") ?? now()
+					.parsed, $err = parse_json(.message)
+					.parse_error = $err
+					"""#
+			}
+			input: log: {
+				level:     "info"
+				timestamp: "1601518931"
+				message:   "not json"
+			}
+			output: log: {
+				level:       "info"
+				timestamp:   "2020-10-01T02:22:11Z"
+				message:     "not json"
+				parsed:      null
+				parse_error: "function call error: unable to parse json expected ident at line 1 column 2"
+			}
+		},
+		{
+			title: "Rename Metrics and Rewrite Tags"
+			configuration: {
+				source: #"""
+					if .tags.env == "test" { abort }
+					.name = "http_" + .name
+					.tags.host = downcase(.tags.host)
+					del(".tags.env")
+					"""#
+			}
+			input: metric: {
+				kind: "incremental"
+				name: "requests"
+				counter: {
+					value: 2.0
+				}
+				tags: {
+					env:  "prod"
+					host: "LOCALHOST"
+				}
+			}
+			output: metric: {
+				kind: "incremental"
+				name: "http_requests"
+				counter: {
+					value: 2.0
+				}
+				tags: {
+					host: "localhost"
+				}
+			}
+		},
 	]
 
 	how_it_works: {
@@ -129,5 +205,68 @@ components: transforms: remap: {
 				[the docs](/docs/reference/remap).
 				"""#
 		}
+
+		error_handling: {
+			title: "Error Handling"
+			body: #"""
+				By default, a failing expression stops the program, and the event is
+				passed on as it was at that point, or dropped if `drop_on_err` is set.
+
+				Programs can handle errors themselves instead. The `??` operator
+				evaluates to its right-hand side if the left-hand side fails, e.g.
+				`.ts = parse_timestamp(.t, "%s") ?? now()`. An assignment with a
+				second target captures the error message instead of failing, e.g.
+				`.parsed, $err = parse_json(.message)`. On success the second target
+				is set to `null`, on failure the first one is.
+
+				The `abort` statement stops the program and drops the event, unless
+				`drop_on_abort` is disabled.
+				"""#
+		}
+
+		type_checking: {
+			title: "Type Checking"
+			body: #"""
+				Programs are type checked when Vector starts, and by `vector validate`.
+				The kinds of values assigned to variables and fields, and returned by
+				functions, are tracked through the program. Passing a value to a
+				function that can never accept it, such as `upcase(.count)` after
+				`.count = 1`, is reported as an error pointing at the offending
+				expression, instead of failing for every event at runtime.
+
+				Fields that the program doesn't assign itself can be of any kind, so
+				they are only checked at runtime.
+				"""#
+		}
+
+		metrics: {
+			title: "Metrics"
+			body: #"""
+				Metric events expose the `.name`, `.namespace`, `.timestamp`, `.kind`
+				and `.tags` fields, which can be read and changed. `.tags` is a map
+				of strings, so individual tags are available as `.tags.<name>`.
+
+				The `.type` of the metric, such as `"counter"`, and its `.value` can
+				only be read. The value of counters and gauges is a float, the value
+				of the other types is a map of their fields, e.g. `.value.buckets`
+				for histograms.
+				"""#
+		}
+
+		iteration: {
+			title: "Iteration"
+			body: #"""
+				Arrays and maps are iterated over by passing a closure to one of the
+				`map_values`, `map_keys`, `filter` or `for_each` functions, e.g.
+				`.labels = map_keys(.labels) -> |key, value| { "k8s_" + $key }`.
+				The closure is called with the key, or index for arrays, and the
+				value of each element, which are available as variables in its body.
+				These variables, and any other variables first assigned in the
+				closure, are not visible outside of it.
+
+				There are no other loops, and closures can't call themselves, so
+				programs always run to completion.
+				"""#
+		}
 	}
 }
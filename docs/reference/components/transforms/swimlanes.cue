@@ -56,10 +56,10 @@ components: transforms: swimlanes: {
 			title: "Split by log level"
 			configuration: {
 				lanes: {
-					debug: "level.eq": "debug"
-					info: "level.eq":  "info"
-					warn: "level.eq":  "warn"
-					error: "level.eq": "error"
+					debug: #".level == "debug""#
+					info:  #".level == "info""#
+					warn:  #".level == "warn""#
+					error: #".level == "error""#
 				}
 			}
 			input: log: {
@@ -5,6 +5,7 @@ urls: {
 	apache_extended_status:                                   "https://httpd.apache.org/docs/current/mod/core.html#extendedstatus"
 	apache_install:                                           "https://httpd.apache.org/docs/current/install.html"
 	apache_mod_status:                                        "http://httpd.apache.org/docs/current/mod/mod_status.html"
+	apache_parquet:                                           "https://parquet.apache.org/"
 	apt:                                                      "https://en.wikipedia.org/wiki/APT_(software)"
 	arm:                                                      "https://en.wikipedia.org/wiki/ARM_architecture"
 	aws_arm_g2_announcement:                                  "https://aws.amazon.com/about-aws/whats-new/2019/12/announcing-new-amazon-ec2-m6g-c6g-and-r6g-instances-powered-by-next-generation-arm-based-aws-graviton2-processors/"
@@ -97,6 +98,8 @@ urls: {
 	docker_object_labels:                                     "https://docs.docker.com/config/labels-custom-metadata/"
 	docker_setup:                                             "https://docs.docker.com/get-docker/"
 	dockerfile:                                               "https://github.com/timberio/vector/blob/master/Dockerfile"
+	dogstatsd:                                                "https://docs.datadoghq.com/developers/dogstatsd/"
+	dogstatsd_datagram_format:                                "https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/"
 	dpkg:                                                     "https://wiki.debian.org/dpkg"
 	dry_code:                                                 "https://en.wikipedia.org/wiki/Don%27t_repeat_yourself"
 	cidr:                                                     "https://en.wikipedia.org/wiki/Classless_Inter-Domain_Routing"
@@ -242,6 +245,8 @@ urls: {
 	nixos:                                                    "https://nixos.org/"
 	nixpkgs_9682:                                             "https://github.com/NixOS/nixpkgs/issues/9682"
 	openssl:                                                  "https://www.openssl.org/"
+	opentelemetry:                                            "https://opentelemetry.io/"
+	opentelemetry_protocol:                                   "https://opentelemetry.io/docs/specs/otlp/"
 	papertrail:                                               "https://www.papertrail.com/"
 	papertrail_syslog:                                        "https://help.papertrailapp.com/kb/how-it-works/http-api/#submitting-log-messages"
 	perl_windows:                                             "https://www.perl.org/get.html#win32"
@@ -249,6 +254,7 @@ urls: {
 	prometheus:                                               "https://prometheus.io/"
 	prometheus_client:                                        "https://prometheus.io/docs/instrumenting/clientlibs/"
 	prometheus_counter:                                       "https://prometheus.io/docs/concepts/metric_types/#counter"
+	prometheus_file_sd_config:                                "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#file_sd_config"
 	prometheus_gauge:                                         "https://prometheus.io/docs/concepts/metric_types/#gauge"
 	prometheus_high_cardinality:                              "https://prometheus.io/docs/practices/naming/#labels"
 	prometheus_histogram:                                     "https://prometheus.io/docs/concepts/metric_types/#histogram"
@@ -256,9 +262,15 @@ urls: {
 	prometheus_summary:                                       "https://prometheus.io/docs/concepts/metric_types/#summary"
 	prometheus_text_based_exposition_format:                  "https://github.com/prometheus/docs/blob/master/content/docs/instrumenting/exposition_formats.md#text-based-format"
 	prometheus_metric_naming:                                 "https://prometheus.io/docs/practices/naming/#metric-names"
+	prometheus_relabel_config:                                "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#relabel_config"
+	prometheus_remote_write:                                  "https://prometheus.io/docs/practices/remote_write/"
 	pulsar:                                                   "https://pulsar.apache.org/"
 	pulsar_protocol:                                          "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
 	rdkafka:                                                  "https://github.com/edenhill/librdkafka"
+	redis:                                                    "https://redis.io/"
+	redis_protocol:                                           "https://redis.io/topics/protocol"
+	redis_streams:                                            "https://redis.io/topics/streams-intro"
+	redis_url:                                                "https://www.iana.org/assignments/uri-schemes/prov/redis"
 	regex:                                                    "https://en.wikipedia.org/wiki/Regular_expression"
 	regex_grouping_and_flags:                                 "https://docs.rs/regex/1.3.9/regex/#grouping-and-flags"
 	regex_tester:                                             "https://rustexp.lpil.uk/"
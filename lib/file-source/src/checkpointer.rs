@@ -4,13 +4,38 @@ use std::{
     collections::HashMap,
     fs, io,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
     time,
 };
 
 pub struct Checkpointer {
     directory: PathBuf,
     glob_string: String,
-    checkpoints: HashMap<FileFingerprint, FilePosition>,
+    checkpoints: Arc<CheckpointsView>,
+}
+
+/// The in-memory checkpoints of a `Checkpointer`.
+///
+/// This can be shared with the consumer of a `FileServer` to update the
+/// checkpoints once the lines read have been acknowledged, instead of as soon
+/// as they are read.
+#[derive(Debug, Default)]
+pub struct CheckpointsView {
+    checkpoints: Mutex<HashMap<FileFingerprint, FilePosition>>,
+}
+
+impl CheckpointsView {
+    pub fn update(&self, fng: FileFingerprint, pos: FilePosition) {
+        self.lock().insert(fng, pos);
+    }
+
+    pub fn get(&self, fng: FileFingerprint) -> Option<FilePosition> {
+        self.lock().get(&fng).cloned()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<FileFingerprint, FilePosition>> {
+        self.checkpoints.lock().expect("poisoned lock")
+    }
 }
 
 impl Checkpointer {
@@ -20,10 +45,14 @@ impl Checkpointer {
         Checkpointer {
             directory,
             glob_string,
-            checkpoints: HashMap::new(),
+            checkpoints: Arc::new(CheckpointsView::default()),
         }
     }
 
+    pub fn view(&self) -> Arc<CheckpointsView> {
+        Arc::clone(&self.checkpoints)
+    }
+
     /// Encode a fingerprint to a file name, including legacy Unknown values
     ///
     /// For each of the non-legacy variants, prepend an identifier byte that falls outside of the
@@ -74,22 +103,20 @@ impl Checkpointer {
     }
 
     pub fn set_checkpoint(&mut self, fng: FileFingerprint, pos: FilePosition) {
-        self.checkpoints.insert(fng, pos);
+        self.checkpoints.update(fng, pos);
     }
 
     pub fn get_checkpoint(&self, fng: FileFingerprint) -> Option<FilePosition> {
-        self.checkpoints.get(&fng).cloned()
+        self.checkpoints.get(fng)
     }
 
     /// Scan through a given list of fresh fingerprints (i.e. not legacy Unknown) to see if any
     /// match an existing legacy fingerprint. If so, upgrade the existing fingerprint.
     pub fn maybe_upgrade(&mut self, fresh: impl Iterator<Item = FileFingerprint>) {
+        let mut checkpoints = self.checkpoints.lock();
         for fng in fresh {
-            if let Some(pos) = self
-                .checkpoints
-                .remove(&FileFingerprint::Unknown(fng.to_legacy()))
-            {
-                self.checkpoints.insert(fng, pos);
+            if let Some(pos) = checkpoints.remove(&FileFingerprint::Unknown(fng.to_legacy())) {
+                checkpoints.insert(fng, pos);
             }
         }
     }
@@ -97,10 +124,11 @@ impl Checkpointer {
     pub fn write_checkpoints(&mut self) -> Result<usize, io::Error> {
         fs::remove_dir_all(&self.directory).ok();
         fs::create_dir_all(&self.directory)?;
-        for (&fng, &pos) in self.checkpoints.iter() {
+        let checkpoints = self.checkpoints.lock();
+        for (&fng, &pos) in checkpoints.iter() {
             fs::File::create(self.encode(fng, pos))?;
         }
-        Ok(self.checkpoints.len())
+        Ok(checkpoints.len())
     }
 
     pub fn read_checkpoints(&mut self, ignore_before: Option<time::SystemTime>) {
@@ -114,7 +142,7 @@ impl Checkpointer {
                 }
             }
             let (fng, pos) = self.decode(&path);
-            self.checkpoints.insert(fng, pos);
+            self.checkpoints.update(fng, pos);
         }
     }
 }
@@ -138,6 +166,17 @@ mod test {
         assert_eq!(chkptr.get_checkpoint(fingerprint), Some(position));
     }
 
+    #[test]
+    fn test_checkpointer_view_updates() {
+        let fingerprint: FileFingerprint = 0x1234567890abcdef.into();
+        let data_dir = tempdir().unwrap();
+        let chkptr = Checkpointer::new(&data_dir.path());
+        let view = chkptr.view();
+        assert_eq!(chkptr.get_checkpoint(fingerprint), None);
+        view.update(fingerprint, 1234);
+        assert_eq!(chkptr.get_checkpoint(fingerprint), Some(1234));
+    }
+
     #[test]
     fn test_checkpointer_restart() {
         let fingerprint: FileFingerprint = 0x1234567890abcdef.into();
@@ -0,0 +1,123 @@
+use flate2::bufread::MultiGzDecoder;
+use std::io::{self, BufRead, Read};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// The compression of a file, as detected from its leading magic bytes
+/// rather than from its name.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    pub fn detect(r: &mut impl BufRead) -> io::Result<Option<Self>> {
+        let header_bytes = r.fill_buf()?;
+        if header_bytes.starts_with(GZIP_MAGIC) {
+            return Ok(Some(Compression::Gzip));
+        }
+        #[cfg(feature = "zstd")]
+        {
+            if header_bytes.starts_with(ZSTD_MAGIC) {
+                return Ok(Some(Compression::Zstd));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn decoder<R: BufRead + 'static>(self, r: R) -> io::Result<Box<dyn Read>> {
+        Ok(match self {
+            Compression::Gzip => Box::new(MultiGzDecoder::new(r)),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(r)?),
+        })
+    }
+}
+
+/// Wraps `r` in a decoder if its content is compressed, so it yields the
+/// decompressed content either way.
+pub fn decompressed<R: BufRead + 'static>(mut r: R) -> io::Result<Box<dyn BufRead>> {
+    Ok(match Compression::detect(&mut r)? {
+        Some(compression) => Box::new(io::BufReader::new(compression.decoder(r)?)),
+        None => Box::new(r),
+    })
+}
+
+/// Discards the first `count` bytes of `r`, returning how many were
+/// actually available. This is how compressed files, which can't be seeked
+/// into, are resumed.
+pub fn skip(r: &mut impl Read, count: u64) -> io::Result<u64> {
+    io::copy(&mut r.take(count), &mut io::sink())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression as GzCompression};
+    use std::io::{Cursor, Write};
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), GzCompression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn read_all(r: impl BufRead + 'static) -> Vec<u8> {
+        let mut buf = Vec::new();
+        decompressed(r).unwrap().read_to_end(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn detects_compression_from_magic_bytes() {
+        let mut plain = Cursor::new(b"plain text\n".to_vec());
+        assert_eq!(Compression::detect(&mut plain).unwrap(), None);
+
+        let mut gzipped = Cursor::new(gzip(b"compressed\n"));
+        assert_eq!(
+            Compression::detect(&mut gzipped).unwrap(),
+            Some(Compression::Gzip)
+        );
+
+        #[cfg(feature = "zstd")]
+        {
+            let mut zstded = Cursor::new(zstd::encode_all(&b"compressed\n"[..], 0).unwrap());
+            assert_eq!(
+                Compression::detect(&mut zstded).unwrap(),
+                Some(Compression::Zstd)
+            );
+        }
+    }
+
+    #[test]
+    fn reads_decompressed_content() {
+        assert_eq!(read_all(Cursor::new(b"plain\n".to_vec())), b"plain\n");
+
+        // Concatenated gzip members, as written by appending to a `.gz` file.
+        let mut members = gzip(b"first\n");
+        members.extend(gzip(b"second\n"));
+        assert_eq!(read_all(Cursor::new(members)), b"first\nsecond\n");
+
+        #[cfg(feature = "zstd")]
+        {
+            let zstded = zstd::encode_all(&b"zstd\n"[..], 0).unwrap();
+            assert_eq!(read_all(Cursor::new(zstded)), b"zstd\n");
+        }
+    }
+
+    #[test]
+    fn skips_decompressed_bytes() {
+        let mut reader = decompressed(Cursor::new(gzip(b"first\nsecond\n"))).unwrap();
+        assert_eq!(skip(&mut reader, 6).unwrap(), 6);
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "second\n");
+
+        let mut reader = decompressed(Cursor::new(gzip(b"short\n"))).unwrap();
+        assert_eq!(skip(&mut reader, 100).unwrap(), 6);
+    }
+}
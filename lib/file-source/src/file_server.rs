@@ -2,7 +2,7 @@ use crate::{
     checkpointer::Checkpointer,
     file_watcher::FileWatcher,
     fingerprinter::{FileFingerprint, Fingerprinter},
-    FileSourceInternalEvents,
+    FilePosition, FileSourceInternalEvents,
 };
 use bytes::Bytes;
 use futures::{
@@ -39,12 +39,25 @@ where
     pub start_at_beginning: bool,
     pub ignore_before: Option<time::SystemTime>,
     pub max_line_bytes: usize,
-    pub data_dir: PathBuf,
     pub glob_minimum_cooldown: Duration,
     pub fingerprinter: Fingerprinter,
     pub oldest_first: bool,
     pub remove_after: Option<Duration>,
     pub emitter: E,
+    /// When set, reading a line does not advance the file's checkpoint. The
+    /// consumer is then responsible for updating the checkpoints through
+    /// `Checkpointer::view` once it is done with the lines it received.
+    pub acknowledgements: bool,
+}
+
+/// A line read from a watched file.
+#[derive(Debug)]
+pub struct Line {
+    pub text: Bytes,
+    pub filename: String,
+    pub file_id: FileFingerprint,
+    /// The position in the file right after this line.
+    pub offset: FilePosition,
 }
 
 /// `FileServer` as Source
@@ -69,10 +82,11 @@ where
         self,
         mut chans: C,
         mut shutdown: impl Future + Unpin,
-    ) -> Result<Shutdown, <C as Sink<Vec<(Bytes, String)>>>::Error>
+        mut checkpointer: Checkpointer,
+    ) -> Result<Shutdown, <C as Sink<Vec<Line>>>::Error>
     where
-        C: Sink<Vec<(Bytes, String)>> + Unpin,
-        <C as Sink<Vec<(Bytes, String)>>>::Error: std::error::Error,
+        C: Sink<Vec<Line>> + Unpin,
+        <C as Sink<Vec<Line>>>::Error: std::error::Error,
     {
         let mut fingerprint_buffer = Vec::new();
 
@@ -81,7 +95,6 @@ where
         let mut backoff_cap: usize = 1;
         let mut lines = Vec::new();
 
-        let mut checkpointer = Checkpointer::new(&self.data_dir);
         checkpointer.read_checkpoints(self.ignore_before);
 
         let mut known_small_files = HashSet::new();
@@ -238,10 +251,12 @@ where
 
                     bytes_read += sz;
 
-                    lines.push((
-                        line,
-                        watcher.path.to_str().expect("not a valid path").to_owned(),
-                    ));
+                    lines.push(Line {
+                        text: line,
+                        filename: watcher.path.to_str().expect("not a valid path").to_owned(),
+                        file_id,
+                        offset: watcher.get_file_position(),
+                    });
 
                     if bytes_read > self.max_read_bytes {
                         maxed_out_reading_single_file = true;
@@ -252,7 +267,9 @@ where
 
                 if bytes_read > 0 {
                     global_bytes_read = global_bytes_read.saturating_add(bytes_read);
-                    checkpointer.set_checkpoint(file_id, watcher.get_file_position());
+                    if !self.acknowledgements {
+                        checkpointer.set_checkpoint(file_id, watcher.get_file_position());
+                    }
                 } else {
                     // Should the file be removed
                     if let Some(grace_period) = self.remove_after {
@@ -1,6 +1,8 @@
-use crate::FilePosition;
+use crate::{
+    compression::{self, Compression},
+    FilePosition,
+};
 use bytes::{Bytes, BytesMut};
-use flate2::bufread::MultiGzDecoder;
 use std::{
     fs::{self, File},
     io::{self, BufRead, Seek},
@@ -56,28 +58,28 @@ impl FileWatcher {
             false
         };
 
-        let (reader, file_position): (Box<dyn BufRead>, FilePosition) = if is_gzipped(&mut reader)?
-        {
-            if file_position != 0 || too_old {
-                // We can't accurately seek into gzipped files without manually scanning through
-                // the entire thing, so for now we simply refuse to read gzipped files for which we
-                // already have a stored file position from a previous run.
-                debug!(
-                    message = "Not re-reading gzipped file with existing stored offset.",
-                    ?path,
-                    %file_position
-                );
-                (Box::new(null_reader()), file_position)
+        let (reader, file_position): (Box<dyn BufRead>, FilePosition) =
+            if let Some(compression) = Compression::detect(&mut reader)? {
+                if too_old {
+                    debug!(
+                        message = "Not reading compressed file older than ignore_older.",
+                        ?path
+                    );
+                    (Box::new(null_reader()), file_position)
+                } else {
+                    // Compressed files can't be seeked into, so a stored position, which counts
+                    // decompressed bytes, is resumed by decompressing and discarding up to it.
+                    let mut reader = io::BufReader::new(compression.decoder(reader)?);
+                    let pos = compression::skip(&mut reader, file_position)?;
+                    (Box::new(reader), pos)
+                }
+            } else if too_old {
+                let pos = reader.seek(io::SeekFrom::End(0)).unwrap();
+                (Box::new(reader), pos)
             } else {
-                (Box::new(io::BufReader::new(MultiGzDecoder::new(reader))), 0)
-            }
-        } else if too_old {
-            let pos = reader.seek(io::SeekFrom::End(0)).unwrap();
-            (Box::new(reader), pos)
-        } else {
-            let pos = reader.seek(io::SeekFrom::Start(file_position)).unwrap();
-            (Box::new(reader), pos)
-        };
+                let pos = reader.seek(io::SeekFrom::Start(file_position)).unwrap();
+                (Box::new(reader), pos)
+            };
 
         let ts = metadata
             .modified()
@@ -105,16 +107,18 @@ impl FileWatcher {
         let file_handle = File::open(&path)?;
         if (file_handle.portable_dev()?, file_handle.portable_ino()?) != (self.devno, self.inode) {
             let mut reader = io::BufReader::new(fs::File::open(&path)?);
-            let gzipped = is_gzipped(&mut reader)?;
-            let new_reader: Box<dyn BufRead> = if gzipped {
-                if self.file_position != 0 {
-                    Box::new(null_reader())
-                } else {
-                    Box::new(io::BufReader::new(MultiGzDecoder::new(reader)))
+            // A file compressed after being rotated keeps the fingerprint of its decompressed
+            // content, so carry on reading it from the same decompressed position.
+            let new_reader: Box<dyn BufRead> = match Compression::detect(&mut reader)? {
+                Some(compression) => {
+                    let mut reader = io::BufReader::new(compression.decoder(reader)?);
+                    compression::skip(&mut reader, self.file_position)?;
+                    Box::new(reader)
+                }
+                None => {
+                    reader.seek(io::SeekFrom::Start(self.file_position))?;
+                    Box::new(reader)
                 }
-            } else {
-                reader.seek(io::SeekFrom::Start(self.file_position))?;
-                Box::new(reader)
             };
             self.reader = new_reader;
             self.devno = file_handle.portable_dev()?;
@@ -203,11 +207,6 @@ impl FileWatcher {
     }
 }
 
-fn is_gzipped(r: &mut io::BufReader<fs::File>) -> io::Result<bool> {
-    let header_bytes = r.fill_buf()?;
-    Ok(header_bytes.starts_with(&[0x1f, 0x8b]))
-}
-
 fn null_reader() -> impl BufRead {
     io::Cursor::new(Vec::new())
 }
@@ -279,9 +278,39 @@ fn read_until_with_max_size<R: BufRead + ?Sized>(
 
 #[cfg(test)]
 mod test {
-    use super::read_until_with_max_size;
+    use super::{read_until_with_max_size, FileWatcher};
     use bytes::BytesMut;
-    use std::io::Cursor;
+    use flate2::{write::GzEncoder, Compression};
+    use std::{
+        fs,
+        io::{Cursor, Write},
+    };
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_resume_compressed_file() {
+        let dir = tempdir().unwrap();
+        let plain_path = dir.path().join("app.log.1");
+        let gzipped_path = dir.path().join("app.log.1.gz");
+        let data = b"first\nsecond\nthird\n";
+        fs::write(&plain_path, &data[..]).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        fs::write(&gzipped_path, encoder.finish().unwrap()).unwrap();
+
+        // A stored position counts decompressed bytes.
+        let mut watcher = FileWatcher::new(gzipped_path.clone(), 6, None, 1000).unwrap();
+        assert_eq!(watcher.read_line().unwrap().unwrap(), "second");
+        assert_eq!(watcher.get_file_position(), 13);
+
+        // Compressing a file part way through reading it carries on from the same line.
+        let mut watcher = FileWatcher::new(plain_path, 0, None, 1000).unwrap();
+        assert_eq!(watcher.read_line().unwrap().unwrap(), "first");
+        watcher.update_path(gzipped_path).unwrap();
+        assert_eq!(watcher.read_line().unwrap().unwrap(), "second");
+        assert_eq!(watcher.read_line().unwrap().unwrap(), "third");
+        assert_eq!(watcher.read_line().unwrap(), None);
+    }
 
     #[test]
     fn test_read_until_with_max_size() {
@@ -1,8 +1,8 @@
-use crate::{metadata_ext::PortableFileExt, FileSourceInternalEvents};
+use crate::{compression, metadata_ext::PortableFileExt, FileSourceInternalEvents};
 use std::{
     collections::HashSet,
-    fs::{self, File},
-    io::{self, Read, Seek, SeekFrom, Write},
+    fs::File,
+    io::{self, BufReader, Read, Write},
     path::PathBuf,
 };
 
@@ -71,8 +71,7 @@ impl Fingerprinter {
                 bytes,
             } => {
                 buffer.resize(bytes, 0u8);
-                let mut fp = fs::File::open(path)?;
-                fp.seek(io::SeekFrom::Start(ignored_header_bytes as u64))?;
+                let mut fp = open_decompressed(path, ignored_header_bytes)?;
                 fp.read_exact(&mut buffer[..bytes])?;
                 let fingerprint = crc::crc64::checksum_ecma(&buffer[..]);
                 Ok(Checksum(fingerprint))
@@ -82,8 +81,7 @@ impl Fingerprinter {
                 ignored_header_bytes,
             } => {
                 buffer.resize(max_line_length, 0u8);
-                let mut fp = fs::File::open(path)?;
-                fp.seek(SeekFrom::Start(ignored_header_bytes as u64))?;
+                let fp = open_decompressed(path, ignored_header_bytes)?;
                 fingerprinter_read_until(fp, b'\n', buffer)?;
                 let fingerprint = crc::crc64::checksum_ecma(&buffer[..]);
                 Ok(FirstLineChecksum(fingerprint))
@@ -113,6 +111,19 @@ impl Fingerprinter {
     }
 }
 
+/// Opens the file at `path` positioned after its header, reading its
+/// decompressed content if it is compressed. Checksums are computed over
+/// that content so a file keeps its fingerprint once it is compressed, as
+/// happens to rotated logs.
+fn open_decompressed(path: &PathBuf, ignored_header_bytes: usize) -> io::Result<impl Read> {
+    let mut fp = compression::decompressed(BufReader::new(File::open(path)?))?;
+    let skipped = compression::skip(&mut fp, ignored_header_bytes as u64)?;
+    if skipped < ignored_header_bytes as u64 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "EOF reached"));
+    }
+    Ok(fp)
+}
+
 fn fingerprinter_read_until(mut r: impl Read, delim: u8, mut buf: &mut [u8]) -> io::Result<()> {
     while !buf.is_empty() {
         let read = match r.read(buf) {
@@ -137,7 +148,8 @@ fn fingerprinter_read_until(mut r: impl Read, delim: u8, mut buf: &mut [u8]) ->
 #[cfg(test)]
 mod test {
     use super::Fingerprinter;
-    use std::fs;
+    use flate2::{write::GzEncoder, Compression};
+    use std::{fs, io::Write};
     use tempfile::tempdir;
 
     #[test]
@@ -249,6 +261,33 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_compressed_file_fingerprint() {
+        let fingerprinter = Fingerprinter::Checksum {
+            bytes: 32,
+            ignored_header_bytes: 4,
+        };
+
+        let target_dir = tempdir().unwrap();
+        let data = b"head the first line of the file\nthe second line\n";
+        let plain_path = target_dir.path().join("app.log.1");
+        let gzipped_path = target_dir.path().join("app.log.1.gz");
+        fs::write(&plain_path, &data[..]).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        fs::write(&gzipped_path, encoder.finish().unwrap()).unwrap();
+
+        let mut buf = Vec::new();
+        assert_eq!(
+            fingerprinter
+                .get_fingerprint_of_file(&plain_path, &mut buf)
+                .unwrap(),
+            fingerprinter
+                .get_fingerprint_of_file(&gzipped_path, &mut buf)
+                .unwrap(),
+        );
+    }
+
     #[test]
     fn test_inode_fingerprint() {
         let fingerprinter = Fingerprinter::DevInode;
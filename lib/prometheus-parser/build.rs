@@ -0,0 +1,4 @@
+fn main() {
+    println!("cargo:rerun-if-changed=proto/prometheus-remote.proto");
+    prost_build::compile_protos(&["proto/prometheus-remote.proto"], &["proto/"]).unwrap();
+}
@@ -8,6 +8,11 @@ use line::Line;
 use line::Metric;
 use line::MetricKind;
 
+/// The Prometheus remote write protocol messages.
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
+}
+
 #[derive(Debug, snafu::Snafu, PartialEq)]
 pub enum ParserError {
     #[snafu(display("{}, line: `{}`", kind, line))]
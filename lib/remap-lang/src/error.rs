@@ -6,6 +6,9 @@ pub enum Error {
     #[error("parser error: {0}")]
     Parser(String),
 
+    #[error("type error: {0}")]
+    Type(String),
+
     #[error("unexpected token sequence")]
     Rule(#[from] Rule),
 
@@ -24,6 +27,9 @@ pub enum Error {
     #[error("function call error: {0}")]
     Call(String),
 
+    #[error("program aborted")]
+    Abort,
+
     #[error("unknown error")]
     Unknown,
 }
@@ -57,15 +63,18 @@ impl fmt::Display for Rule {
         }
 
         rules_str![
+            abort,
             addition,
             argument,
             arguments,
+            array,
             assignment,
             block,
             boolean,
             boolean_expr,
             call,
             char,
+            closure,
             comparison,
             EOI,
             equality,
@@ -75,6 +84,8 @@ impl fmt::Display for Rule {
             ident,
             if_statement,
             integer,
+            map,
+            map_entry,
             multiplication,
             not,
             null,
@@ -1,21 +1,27 @@
-use crate::{Object, Result, State, Value};
+use crate::{Kind, Object, Result, State, TypeState, Value};
 
+mod abort;
 pub(super) mod arithmetic;
+mod array;
 pub(super) mod assignment;
 mod block;
 pub(super) mod function;
 pub(super) mod if_statement;
 mod literal;
+mod map;
 mod noop;
 pub(super) mod not;
 pub(super) mod path;
 pub(super) mod variable;
 
+pub(super) use abort::Abort;
 pub(super) use arithmetic::Arithmetic;
+pub(super) use array::Array;
 pub(super) use assignment::{Assignment, Target};
 pub(super) use block::Block;
 pub(super) use function::Function;
 pub(super) use if_statement::IfStatement;
+pub(super) use map::Map;
 pub(super) use not::Not;
 pub(super) use variable::Variable;
 
@@ -49,6 +55,15 @@ pub enum Error {
 
 pub trait Expression: Send + Sync + std::fmt::Debug + dyn_clone::DynClone {
     fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>>;
+
+    /// Type check the expression when the program is compiled, returning the
+    /// kinds of values it can resolve to.
+    ///
+    /// Errors are recorded in the provided [`TypeState`]. Expressions that
+    /// can't tell ahead of time what they resolve to return [`Kind::ANY`].
+    fn type_check(&self, _: &mut TypeState) -> Kind {
+        Kind::ANY
+    }
 }
 
 dyn_clone::clone_trait_object!(Expression);
@@ -76,6 +91,12 @@ macro_rules! expression_dispatch {
                     $(Expr::$expr(expression) => expression.execute(state, object)),+
                 }
             }
+
+            fn type_check(&self, state: &mut TypeState) -> Kind {
+                match self {
+                    $(Expr::$expr(expression) => expression.type_check(state)),+
+                }
+            }
         }
 
         $(
@@ -89,12 +110,15 @@ macro_rules! expression_dispatch {
 }
 
 expression_dispatch![
+    Abort,
     Arithmetic,
+    Array,
     Assignment,
     Block,
     Function,
     IfStatement,
     Literal,
+    Map,
     Noop,
     Not,
     Path,
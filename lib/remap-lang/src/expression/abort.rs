@@ -0,0 +1,16 @@
+use crate::{Error, Expression, Kind, Object, Result, State, TypeState, Value};
+
+/// Stops the program, signaling to the caller that the object should be
+/// discarded.
+#[derive(Debug, Clone)]
+pub(crate) struct Abort;
+
+impl Expression for Abort {
+    fn execute(&self, _: &mut State, _: &mut dyn Object) -> Result<Option<Value>> {
+        Err(Error::Abort)
+    }
+
+    fn type_check(&self, _: &mut TypeState) -> Kind {
+        Kind::NONE
+    }
+}
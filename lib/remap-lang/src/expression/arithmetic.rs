@@ -1,5 +1,5 @@
-use super::{Expr, Expression, Object, Result, State, Value};
-use crate::Operator;
+use super::{Expr, Expression, Kind, Object, Result, State, TypeState, Value};
+use crate::{Error, Operator};
 
 #[derive(Debug, Clone)]
 pub struct Arithmetic {
@@ -16,6 +16,17 @@ impl Arithmetic {
 
 impl Expression for Arithmetic {
     fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        // The right-hand side of an error coalescing operation is only
+        // evaluated if the left-hand side fails. Aborting is not an error that
+        // can be recovered from.
+        if let Operator::ErrorOr = self.op {
+            return match self.lhs.execute(state, object) {
+                Err(Error::Abort) => Err(Error::Abort),
+                Err(_) => self.rhs.execute(state, object),
+                ok => ok,
+            };
+        }
+
         let lhs = self
             .lhs
             .execute(state, object)?
@@ -41,8 +52,27 @@ impl Expression for Arithmetic {
             GreaterOrEqual => lhs.try_ge(rhs),
             Less => lhs.try_lt(rhs),
             LessOrEqual => lhs.try_le(rhs),
+            ErrorOr => unreachable!("handled above"),
         };
 
         result.map(Some).map_err(Into::into)
     }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        let lhs = self.lhs.type_check(state);
+        let rhs = self.rhs.type_check(state);
+
+        use Operator::*;
+        match self.op {
+            // The result has the kind of the left-hand side, if the operation
+            // supports it.
+            Multiply => lhs & (Kind::STRING | Kind::INTEGER | Kind::FLOAT),
+            Add => lhs & (Kind::STRING | Kind::INTEGER | Kind::FLOAT),
+            Divide | Subtract | Remainder => lhs & (Kind::INTEGER | Kind::FLOAT),
+            Equal | NotEqual | Greater | GreaterOrEqual | Less | LessOrEqual | And => Kind::BOOLEAN,
+            Or if lhs.contains(Kind::NULL) => (lhs & Kind::BOOLEAN) | rhs,
+            Or => lhs & Kind::BOOLEAN,
+            ErrorOr => lhs | rhs,
+        }
+    }
 }
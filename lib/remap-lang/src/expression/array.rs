@@ -0,0 +1,70 @@
+use super::Error as E;
+use crate::{Expr, Expression, Kind, Object, Result, State, TypeState, Value};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Array {
+    expressions: Vec<Expr>,
+}
+
+impl Array {
+    pub fn new(expressions: Vec<Expr>) -> Self {
+        Self { expressions }
+    }
+}
+
+impl Expression for Array {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        self.expressions
+            .iter()
+            .map(|expr| -> Result<Value> { Ok(expr.execute(state, object)?.ok_or(E::Missing)?) })
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array)
+            .map(Some)
+    }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        for expr in &self.expressions {
+            expr.type_check(state);
+        }
+
+        Kind::ARRAY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Literal;
+
+    #[test]
+    fn array() {
+        let cases = vec![
+            (Ok(Some(Value::Array(vec![]))), Array::new(vec![])),
+            (
+                Ok(Some(vec![Value::from(1), Value::from("x")].into())),
+                Array::new(vec![Literal::from(1).into(), Literal::from("x").into()]),
+            ),
+            (
+                Ok(Some(vec![Value::Array(vec![Value::Null])].into())),
+                Array::new(vec![
+                    Array::new(vec![Literal::from(Value::Null).into()]).into()
+                ]),
+            ),
+            (
+                Err("path error".to_string()),
+                Array::new(vec![crate::Path::from("foo").into()]),
+            ),
+        ];
+
+        let mut state = State::default();
+        let mut object = std::collections::HashMap::default();
+
+        for (exp, expr) in cases {
+            let got = expr
+                .execute(&mut state, &mut object)
+                .map_err(|e| e.to_string());
+
+            assert_eq!(got, exp);
+        }
+    }
+}
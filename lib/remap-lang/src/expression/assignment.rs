@@ -1,5 +1,8 @@
 use super::Error as E;
-use crate::{Expr, Expression, Object, Result, State, Value};
+use crate::{
+    vec_path_to_string, Error as RuntimeError, Expr, Expression, Kind, Object, Result, State,
+    TypeState, Value,
+};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
@@ -13,36 +16,114 @@ pub(crate) enum Target {
     Variable(String),
 }
 
+impl Target {
+    fn insert(&self, state: &mut State, object: &mut dyn Object, value: Value) -> Result<()> {
+        match self {
+            Target::Variable(ident) => {
+                state.variables_mut().insert(ident.clone(), value);
+            }
+            Target::Path(path) => object
+                .insert(path, value)
+                .map_err(|e| E::Assignment(Error::PathInsertion(e)))?,
+        }
+
+        Ok(())
+    }
+
+    fn type_check(&self, state: &mut TypeState, kind: Kind) {
+        match self {
+            Target::Variable(ident) => state.set_variable(ident, kind),
+            // The kind of a coalesced path depends on which field exists.
+            Target::Path(path) if path.iter().any(|segment| segment.len() > 1) => {}
+            Target::Path(path) => state.set_path(&vec_path_to_string(path), kind),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Assignment {
     target: Target,
+    error: Option<Target>,
     value: Box<Expr>,
 }
 
 impl Assignment {
     pub fn new(target: Target, value: Box<Expr>) -> Self {
-        Self { target, value }
+        Self {
+            target,
+            error: None,
+            value,
+        }
+    }
+
+    /// An assignment that captures a failing expression, e.g.
+    /// `.parsed, $err = parse_json(.message)`.
+    ///
+    /// If the expression succeeds, its value is assigned to `target`, and
+    /// `null` to `error`. If it fails, `target` is set to `null`, and the
+    /// error message is assigned to `error`.
+    pub fn fallible(target: Target, error: Target, value: Box<Expr>) -> Self {
+        Self {
+            target,
+            error: Some(error),
+            value,
+        }
     }
 }
 
 impl Expression for Assignment {
     fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
-        let value = self.value.execute(state, object)?;
+        let value = match (self.value.execute(state, object), &self.error) {
+            (Ok(value), None) => value,
+            (Ok(value), Some(error)) => {
+                error.insert(state, object, Value::Null)?;
+                value
+            }
+            (Err(RuntimeError::Abort), _) => return Err(RuntimeError::Abort),
+            (Err(err), Some(error)) => {
+                error.insert(state, object, error_message(&err).into())?;
+                Some(Value::Null)
+            }
+            (Err(err), None) => return Err(err),
+        };
 
         match value {
             None => Ok(None),
             Some(value) => {
-                match &self.target {
-                    Target::Variable(ident) => {
-                        state.variables_mut().insert(ident.clone(), value.clone());
-                    }
-                    Target::Path(path) => object
-                        .insert(&path, value.clone())
-                        .map_err(|e| E::Assignment(Error::PathInsertion(e)))?,
-                }
+                self.target.insert(state, object, value.clone())?;
 
                 Ok(Some(value))
             }
         }
     }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        let kind = self.value.type_check(state);
+
+        match &self.error {
+            None => {
+                self.target.type_check(state, kind);
+                kind
+            }
+            Some(error) => {
+                self.target.type_check(state, kind | Kind::NULL);
+                error.type_check(state, Kind::STRING | Kind::NULL);
+                kind | Kind::NULL
+            }
+        }
+    }
+}
+
+/// Renders the error, including all of its sources.
+fn error_message(error: &RuntimeError) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+
+    message
 }
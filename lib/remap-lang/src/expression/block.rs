@@ -1,4 +1,4 @@
-use crate::{Expr, Expression, Object, Result, State, Value};
+use crate::{Expr, Expression, Kind, Object, Result, State, TypeState, Value};
 
 #[derive(Debug, Clone)]
 pub(crate) struct Block {
@@ -21,4 +21,10 @@ impl Expression for Block {
 
         Ok(value)
     }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        self.expressions
+            .iter()
+            .fold(Kind::ANY, |_, expr| expr.type_check(state))
+    }
 }
@@ -1,5 +1,8 @@
 use super::Error as E;
-use crate::{Argument, ArgumentList, Expression, Function as Fn, Object, Result, State, Value};
+use crate::{
+    Argument, ArgumentList, Closure, Expression, Function as Fn, Kind, Object, Result, Span, State,
+    TypeState, Value,
+};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
@@ -20,17 +23,34 @@ pub enum Error {
 
     #[error(r#"incorrect value type for argument "{0}" (got "{0}")"#)]
     Value(&'static str, &'static str),
+
+    #[error("unexpected closure")]
+    Closure,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Function {
     function: Box<dyn Expression>,
+    ident: &'static str,
+    arguments: Vec<TypedArgument>,
+    closure: Option<Closure>,
+    return_kind: Kind,
+}
+
+/// An expression argument, kept to type check the function call.
+#[derive(Debug, Clone)]
+struct TypedArgument {
+    keyword: &'static str,
+    kind: Kind,
+    expression: Box<dyn Expression>,
+    span: Span,
 }
 
 impl Function {
     pub(crate) fn new(
         ident: String,
-        arguments: Vec<(Option<String>, Argument)>,
+        arguments: Vec<(Option<String>, Argument, Span)>,
+        closure: Option<Closure>,
         definitions: &[Box<dyn Fn>],
     ) -> Result<Self> {
         let definition = definitions
@@ -50,6 +70,10 @@ impl Function {
             .into());
         }
 
+        if closure.is_some() && !definition.accepts_closure() {
+            return Err(E::Function(ident.to_owned(), Error::Closure).into());
+        }
+
         // Keeps track of positional argument indices.
         //
         // Used to map a positional argument to its keyword. Keyword arguments
@@ -57,8 +81,9 @@ impl Function {
         // positional arguments.
         let mut index = 0;
         let mut list = ArgumentList::default();
+        let mut typed_arguments = vec![];
 
-        for (keyword, argument) in arguments {
+        for (keyword, argument, span) in arguments {
             let param = match &keyword {
                 // positional argument
                 None => {
@@ -90,6 +115,13 @@ impl Function {
                 // Wrap expression argument to validate its value type at
                 // runtime.
                 Argument::Expression(expr) => {
+                    typed_arguments.push(TypedArgument {
+                        keyword: param.keyword,
+                        kind: param.kind(),
+                        expression: expr.clone(),
+                        span,
+                    });
+
                     Argument::Expression(Box::new(ArgumentValidator::new(
                         expr,
                         definition.identifier(),
@@ -114,8 +146,18 @@ impl Function {
             })
             .collect::<Result<_>>()?;
 
+        if let Some(closure) = &closure {
+            list.set_closure(closure.clone());
+        }
+
         let function = definition.compile(list)?;
-        Ok(Self { function })
+        Ok(Self {
+            function,
+            ident,
+            arguments: typed_arguments,
+            closure,
+            return_kind: definition.return_kind(),
+        })
     }
 }
 
@@ -123,6 +165,35 @@ impl Expression for Function {
     fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
         self.function.execute(state, object)
     }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        for argument in &self.arguments {
+            let kind = argument.expression.type_check(state);
+
+            state.expect(
+                argument.span,
+                argument.kind,
+                kind,
+                format!(
+                    r#"argument "{}" of function "{}" expects {}"#,
+                    argument.keyword, self.ident, argument.kind
+                ),
+            );
+        }
+
+        // The closure is called zero or more times.
+        if let Some(closure) = &self.closure {
+            let mut called = state.branch();
+            called.scoped(closure.parameters(), |state| {
+                closure.body().type_check(state);
+            });
+
+            let not_called = state.branch();
+            state.merge(vec![called, not_called]);
+        }
+
+        self.return_kind
+    }
 }
 
 #[derive(Clone)]
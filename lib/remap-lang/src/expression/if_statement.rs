@@ -1,5 +1,5 @@
 use super::Error as E;
-use crate::{value, Expr, Expression, Object, Result, State, Value};
+use crate::{value, Expr, Expression, Kind, Object, Result, Span, State, TypeState, Value};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
@@ -10,6 +10,7 @@ pub enum Error {
 #[derive(Debug, Clone)]
 pub(crate) struct IfStatement {
     conditional: Box<Expr>,
+    span: Span,
     true_expression: Box<Expr>,
     false_expression: Box<Expr>,
 }
@@ -17,11 +18,13 @@ pub(crate) struct IfStatement {
 impl IfStatement {
     pub fn new(
         conditional: Box<Expr>,
+        span: Span,
         true_expression: Box<Expr>,
         false_expression: Box<Expr>,
     ) -> Self {
         Self {
             conditional,
+            span,
             true_expression,
             false_expression,
         }
@@ -40,4 +43,23 @@ impl Expression for IfStatement {
             .into()),
         }
     }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        let conditional = self.conditional.type_check(state);
+        state.expect(
+            self.span,
+            Kind::BOOLEAN,
+            conditional,
+            "if-statement condition must be a boolean".to_owned(),
+        );
+
+        let mut true_state = state.branch();
+        let mut false_state = state.branch();
+        let kind = self.true_expression.type_check(&mut true_state)
+            | self.false_expression.type_check(&mut false_state);
+
+        state.merge(vec![true_state, false_state]);
+
+        kind
+    }
 }
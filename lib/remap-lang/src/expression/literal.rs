@@ -1,4 +1,4 @@
-use crate::{Expression, Object, Result, State, Value};
+use crate::{Expression, Kind, Object, Result, State, TypeState, Value};
 
 #[derive(Debug, Clone)]
 pub struct Literal(Value);
@@ -13,4 +13,8 @@ impl Expression for Literal {
     fn execute(&self, _: &mut State, _: &mut dyn Object) -> Result<Option<Value>> {
         Ok(Some(self.0.clone()))
     }
+
+    fn type_check(&self, _: &mut TypeState) -> Kind {
+        Kind::of(&self.0)
+    }
 }
@@ -0,0 +1,82 @@
+use super::Error as E;
+use crate::{Expr, Expression, Kind, Object, Result, State, TypeState, Value};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Map {
+    expressions: BTreeMap<String, Expr>,
+}
+
+impl Map {
+    pub fn new(expressions: BTreeMap<String, Expr>) -> Self {
+        Self { expressions }
+    }
+}
+
+impl Expression for Map {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        self.expressions
+            .iter()
+            .map(|(key, expr)| -> Result<(String, Value)> {
+                let value = expr.execute(state, object)?.ok_or(E::Missing)?;
+
+                Ok((key.to_owned(), value))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()
+            .map(Value::Map)
+            .map(Some)
+    }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        for expr in self.expressions.values() {
+            expr.type_check(state);
+        }
+
+        Kind::MAP
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Literal;
+
+    #[test]
+    fn map() {
+        let cases = vec![
+            (
+                Ok(Some(Value::Map(BTreeMap::new()))),
+                Map::new(BTreeMap::new()),
+            ),
+            (
+                Ok(Some(Value::Map(
+                    vec![("a".to_owned(), Value::from(1))].into_iter().collect(),
+                ))),
+                Map::new(
+                    vec![("a".to_owned(), Literal::from(1).into())]
+                        .into_iter()
+                        .collect(),
+                ),
+            ),
+            (
+                Err("path error".to_string()),
+                Map::new(
+                    vec![("a".to_owned(), crate::Path::from("foo").into())]
+                        .into_iter()
+                        .collect(),
+                ),
+            ),
+        ];
+
+        let mut state = State::default();
+        let mut object = std::collections::HashMap::default();
+
+        for (exp, expr) in cases {
+            let got = expr
+                .execute(&mut state, &mut object)
+                .map_err(|e| e.to_string());
+
+            assert_eq!(got, exp);
+        }
+    }
+}
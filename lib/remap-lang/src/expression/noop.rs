@@ -1,4 +1,4 @@
-use crate::{Expression, Object, Result, State, Value};
+use crate::{Expression, Kind, Object, Result, State, TypeState, Value};
 
 #[derive(Debug, Clone)]
 pub struct Noop;
@@ -7,4 +7,10 @@ impl Expression for Noop {
     fn execute(&self, _: &mut State, _: &mut dyn Object) -> Result<Option<Value>> {
         Ok(None)
     }
+
+    fn type_check(&self, _: &mut TypeState) -> Kind {
+        // An assignment of nothing leaves its target untouched, which could
+        // be of any kind.
+        Kind::ANY
+    }
 }
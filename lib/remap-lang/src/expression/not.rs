@@ -1,5 +1,5 @@
 use super::Error as E;
-use crate::{value, Expr, Expression, Object, Result, State, Value};
+use crate::{value, Expr, Expression, Kind, Object, Result, State, TypeState, Value};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
@@ -32,6 +32,12 @@ impl Expression for Not {
             .transpose()
         })
     }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        self.expression.type_check(state);
+
+        Kind::BOOLEAN
+    }
 }
 
 #[cfg(test)]
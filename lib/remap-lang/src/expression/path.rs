@@ -1,5 +1,5 @@
 use super::Error as E;
-use crate::{Expression, Object, Result, State, Value};
+use crate::{vec_path_to_string, Expression, Kind, Object, Result, State, TypeState, Value};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
@@ -38,6 +38,10 @@ impl Expression for Path {
             .ok_or_else(|| E::from(Error::Missing(segments_to_path(&self.segments))).into())
             .map(Some)
     }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        state.path(&vec_path_to_string(&self.segments))
+    }
 }
 
 fn segments_to_path(segments: &[Vec<String>]) -> String {
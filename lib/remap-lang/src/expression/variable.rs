@@ -1,5 +1,5 @@
 use super::Error as E;
-use crate::{Expression, Object, Result, State, Value};
+use crate::{Expression, Kind, Object, Result, State, TypeState, Value};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
@@ -26,4 +26,8 @@ impl Expression for Variable {
             .ok_or_else(|| E::from(Error::Undefined(self.ident.to_owned())).into())
             .map(Some)
     }
+
+    fn type_check(&self, state: &mut TypeState) -> Kind {
+        state.variable(&self.ident)
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Expression, Result, Value};
+use crate::{Expr, Expression, Kind, Object, Result, State, Value};
 use core::convert::TryInto;
 use std::collections::HashMap;
 
@@ -12,6 +12,12 @@ pub enum Error {
 
     #[error(r#"missing required argument "{0}""#)]
     Required(String),
+
+    #[error("missing required closure")]
+    RequiredClosure,
+
+    #[error("expected closure with {0} parameters, got {1}")]
+    ClosureArity(usize, usize),
 }
 
 #[derive(Copy, Clone)]
@@ -24,6 +30,9 @@ pub struct Parameter {
 
     /// The parser calls this method to determine if a given argument value is
     /// accepted by the parameter.
+    ///
+    /// It is also used to type check arguments when the program is compiled,
+    /// and should therefore only depend on the kind of the value.
     pub accepts: fn(&Value) -> bool,
 
     /// Whether or not this is a required parameter.
@@ -33,6 +42,13 @@ pub struct Parameter {
     pub required: bool,
 }
 
+impl Parameter {
+    /// The kinds of values accepted by the parameter.
+    pub fn kind(&self) -> Kind {
+        Kind::accepted_by(self.accepts)
+    }
+}
+
 impl std::fmt::Debug for Parameter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Parameter")
@@ -44,11 +60,14 @@ impl std::fmt::Debug for Parameter {
 }
 
 #[derive(Debug, Default)]
-pub struct ArgumentList(HashMap<&'static str, Argument>);
+pub struct ArgumentList {
+    arguments: HashMap<&'static str, Argument>,
+    closure: Option<Closure>,
+}
 
 impl ArgumentList {
     pub fn optional(&mut self, keyword: &str) -> Option<Argument> {
-        self.0.remove(keyword)
+        self.arguments.remove(keyword)
     }
 
     pub fn optional_expr(&mut self, keyword: &str) -> Result<Option<Box<dyn Expression>>> {
@@ -58,7 +77,7 @@ impl ArgumentList {
     }
 
     pub fn required(&mut self, keyword: &str) -> Result<Argument> {
-        self.0
+        self.arguments
             .remove(keyword)
             .ok_or_else(|| Error::Required(keyword.to_owned()).into())
     }
@@ -73,12 +92,74 @@ impl ArgumentList {
             .and_then(|v| v.try_into().map_err(Into::into))
     }
 
+    /// The closure passed to the function, which must take `parameters`
+    /// parameters.
+    pub fn required_closure(&mut self, parameters: usize) -> Result<Closure> {
+        let closure = self.closure.take().ok_or(Error::RequiredClosure)?;
+
+        if closure.parameters.len() != parameters {
+            return Err(Error::ClosureArity(parameters, closure.parameters.len()).into());
+        }
+
+        Ok(closure)
+    }
+
     pub fn keywords(&self) -> Vec<&'static str> {
-        self.0.keys().copied().collect::<Vec<_>>()
+        self.arguments.keys().copied().collect::<Vec<_>>()
     }
 
     pub fn insert(&mut self, k: &'static str, v: Argument) {
-        self.0.insert(k, v);
+        self.arguments.insert(k, v);
+    }
+
+    pub(crate) fn set_closure(&mut self, closure: Closure) {
+        self.closure = Some(closure);
+    }
+}
+
+/// A closure passed to a function, e.g. `for_each(.foo) -> |key, value| { ... }`.
+///
+/// Closures can only be called by the function they're passed to, and there
+/// are no other looping constructs, so programs always terminate: a function
+/// calls its closure at most once for each element of a collection that was
+/// resolved before iterating over it.
+#[derive(Debug, Clone)]
+pub struct Closure {
+    parameters: Vec<String>,
+    body: Box<Expr>,
+}
+
+impl Closure {
+    pub(crate) fn new(parameters: Vec<String>, body: Box<Expr>) -> Self {
+        Self { parameters, body }
+    }
+
+    pub(crate) fn parameters(&self) -> &[String] {
+        &self.parameters
+    }
+
+    pub(crate) fn body(&self) -> &Expr {
+        &self.body
+    }
+
+    /// Call the closure, binding the arguments to the closure parameters.
+    ///
+    /// The parameters, and any variables first assigned by the closure, are
+    /// only visible within the closure.
+    pub fn call(
+        &self,
+        state: &mut State,
+        object: &mut dyn Object,
+        arguments: Vec<Value>,
+    ) -> Result<Option<Value>> {
+        let bindings = self
+            .parameters
+            .iter()
+            .map(String::as_str)
+            .zip(arguments)
+            .collect();
+
+        state.scoped(bindings, |state| self.body.execute(state, object))
     }
 }
 
@@ -135,6 +216,23 @@ pub trait Function: std::fmt::Debug + Sync + CloneFunction {
     fn parameters(&self) -> &'static [Parameter] {
         &[]
     }
+
+    /// Whether the function takes a closure, e.g.
+    /// `for_each(.foo) -> |key, value| { ... }`.
+    ///
+    /// The closure is available through [`ArgumentList::required_closure`].
+    fn accepts_closure(&self) -> bool {
+        false
+    }
+
+    /// The kinds of values the function can return.
+    ///
+    /// This is used to type check the program when it is compiled, for
+    /// example to reject passing the result of the function to a parameter
+    /// that never accepts it.
+    fn return_kind(&self) -> Kind {
+        Kind::ANY
+    }
 }
 
 pub trait CloneFunction {
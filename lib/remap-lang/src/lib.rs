@@ -6,18 +6,21 @@ mod parser;
 mod program;
 mod runtime;
 mod state;
+mod type_check;
 mod value;
 
 use expression::Expr;
 use operator::Operator;
+use type_check::Span;
 
 pub mod prelude;
 pub use error::Error;
 pub use expression::{Expression, Literal, Noop, Path};
-pub use function::{Argument, ArgumentList, Function, Parameter};
+pub use function::{Argument, ArgumentList, Closure, Function, Parameter};
 pub use program::Program;
 pub use runtime::Runtime;
 pub use state::State;
+pub use type_check::{Kind, TypeState};
 pub use value::Value;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -129,7 +132,15 @@ fn vec_path_to_string(path: &[Vec<String>]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn map(fields: Vec<(&str, Value)>) -> Value {
+        fields
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v))
+            .collect::<BTreeMap<_, _>>()
+            .into()
+    }
 
     #[test]
     fn it_works() {
@@ -199,6 +210,54 @@ mod tests {
                 r#"if false { 1 } else if false { 2 } else if false { 3 } else { 4 }"#,
                 Ok(Some(4.into())),
             ),
+            (r#"[]"#, Ok(Some(Value::Array(vec![])))),
+            (
+                r#"[1, 2, "x"]"#,
+                Ok(Some(vec![Value::from(1), 2.into(), "x".into()].into())),
+            ),
+            (
+                "[\n  1,\n  [true, null],\n]",
+                Ok(Some(
+                    vec![
+                        Value::from(1),
+                        vec![Value::Boolean(true), Value::Null].into(),
+                    ]
+                    .into(),
+                )),
+            ),
+            (r#"{}"#, Ok(Some(Value::Map(BTreeMap::new())))),
+            (
+                "$bar = 2\n.foo = {\n  \"a\": 1 + 1,\n  \"b\": [$bar, {\"c\": \"d\"}]\n}",
+                Ok(Some(map(vec![
+                    ("a", 2.into()),
+                    (
+                        "b",
+                        vec![Value::from(2), map(vec![("c", "d".into())])].into(),
+                    ),
+                ]))),
+            ),
+            (
+                r#"if true { {"a": 1} }"#,
+                Ok(Some(map(vec![("a", 1.into())]))),
+            ),
+            (r#".foo ?? "default""#, Ok(Some("default".into()))),
+            ("$foo = 1\n$foo ?? \"default\"", Ok(Some(1.into()))),
+            (r#".foo ?? .bar ?? 1 + 1"#, Ok(Some(2.into()))),
+            (r#"(.foo == 1) ?? false"#, Ok(Some(false.into()))),
+            (
+                ".foo, $err = .bar\n$err",
+                Ok(Some("path error: missing path: bar".into())),
+            ),
+            (".foo, $err = .bar\n.foo", Ok(Some(Value::Null))),
+            (
+                "$foo, $err = 1 + 1\n[$foo, $err]",
+                Ok(Some(vec![Value::from(2), Value::Null].into())),
+            ),
+            ("abort", Err(Error::Abort)),
+            ("if true { abort }\n.foo = true", Err(Error::Abort)),
+            ("(abort) ?? true", Err(Error::Abort)),
+            (".foo, $err = (abort)", Err(Error::Abort)),
+            ("if false { abort }\ntrue", Ok(Some(true.into()))),
         ];
 
         for (script, result) in cases {
@@ -214,4 +273,135 @@ mod tests {
             assert_eq!(runtime.execute(&mut event, &program), result);
         }
     }
+
+    #[derive(Debug, Clone)]
+    struct Each;
+
+    impl Function for Each {
+        fn identifier(&self) -> &'static str {
+            "each"
+        }
+
+        fn parameters(&self) -> &'static [Parameter] {
+            &[Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            }]
+        }
+
+        fn accepts_closure(&self) -> bool {
+            true
+        }
+
+        fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+            let value = match arguments.required("value")? {
+                Argument::Expression(expr) => expr,
+                Argument::Regex(_) => unreachable!(),
+            };
+            let closure = arguments.required_closure(1)?;
+
+            Ok(Box::new(EachFn { value, closure }))
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct EachFn {
+        value: Box<dyn Expression>,
+        closure: Closure,
+    }
+
+    impl Expression for EachFn {
+        fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+            let values = match self.value.execute(state, object)? {
+                Some(Value::Array(values)) => values,
+                _ => unreachable!(),
+            };
+
+            values
+                .into_iter()
+                .map(|value| {
+                    self.closure
+                        .call(state, object, vec![value])
+                        .map(|value| value.unwrap_or(Value::Null))
+                })
+                .collect::<Result<Vec<_>>>()
+                .map(|values| Some(values.into()))
+        }
+    }
+
+    #[test]
+    fn closures() {
+        let cases: Vec<(&str, Result<Option<Value>>)> = vec![
+            (
+                "each([1, 2]) -> |x| { $x + 1 }",
+                Ok(Some(vec![Value::from(2), 3.into()].into())),
+            ),
+            (
+                "$total = 0\neach([1, 2]) -> |x| { $total = $total + $x }\n$total",
+                Ok(Some(3.into())),
+            ),
+            (
+                "$x = \"outer\"\neach([1]) -> |x| { $x }\n$x",
+                Ok(Some("outer".into())),
+            ),
+            (
+                "each([1]) -> |x| { $y = $x }\n$x",
+                Err(
+                    expression::Error::Variable(expression::variable::Error::Undefined(
+                        "x".to_owned(),
+                    ))
+                    .into(),
+                ),
+            ),
+            (
+                "each([1]) -> |x| { $y = $x }\n$y",
+                Err(
+                    expression::Error::Variable(expression::variable::Error::Undefined(
+                        "y".to_owned(),
+                    ))
+                    .into(),
+                ),
+            ),
+            (
+                "each([[1, 2], [3]]) -> |x| { each($x) -> |y| { $y * 2 } }",
+                Ok(Some(
+                    vec![
+                        Value::from(vec![Value::from(2), 4.into()]),
+                        vec![Value::from(6)].into(),
+                    ]
+                    .into(),
+                )),
+            ),
+        ];
+
+        for (script, result) in cases {
+            let program = Program::new(script, &[Box::new(Each)]).unwrap();
+            let mut runtime = Runtime::new(State::default());
+            let mut event = HashMap::default();
+
+            assert_eq!(runtime.execute(&mut event, &program), result, "{}", script);
+        }
+    }
+
+    #[test]
+    fn closure_errors() {
+        let cases: Vec<(&str, Error)> = vec![
+            ("each([1])", function::Error::RequiredClosure.into()),
+            (
+                "each([1]) -> |x, y| { $x }",
+                function::Error::ClosureArity(1, 2).into(),
+            ),
+            (
+                "each([1]) -> |x| { each([$x]) }",
+                function::Error::RequiredClosure.into(),
+            ),
+        ];
+
+        for (script, error) in cases {
+            let result = Program::new(script, &[Box::new(Each)]).map(|_| ());
+
+            assert_eq!(result, Err(error), "{}", script);
+        }
+    }
 }
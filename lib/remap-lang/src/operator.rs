@@ -16,6 +16,7 @@ pub(crate) enum Operator {
     LessOrEqual,
     And,
     Or,
+    ErrorOr,
 }
 
 impl FromStr for Operator {
@@ -38,6 +39,7 @@ impl FromStr for Operator {
             "<=" => LessOrEqual,
             "&&" => And,
             "||" => Or,
+            "??" => ErrorOr,
             _ => return Err("unknown operator"),
         })
     }
@@ -61,6 +63,7 @@ impl AsRef<str> for Operator {
             LessOrEqual => "<=",
             And => "&&",
             Or => "||",
+            ErrorOr => "??",
         }
     }
 }
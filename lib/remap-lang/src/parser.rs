@@ -2,13 +2,14 @@
 
 use crate::{
     expression::{
-        Arithmetic, Assignment, Block, Function, IfStatement, Literal, Noop, Not, Path, Target,
-        Variable,
+        Abort, Arithmetic, Array, Assignment, Block, Function, IfStatement, Literal, Map, Noop,
+        Not, Path, Target, Variable,
     },
-    Argument, Error, Expr, Function as Fn, Operator, Result, Value,
+    Argument, Closure, Error, Expr, Function as Fn, Operator, Result, Span, Value,
 };
 use pest::iterators::{Pair, Pairs};
 use regex::{Regex, RegexBuilder};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 #[derive(pest_derive::Parser)]
@@ -63,7 +64,7 @@ impl Parser<'_> {
 
         for pair in pairs {
             match pair.as_rule() {
-                R::assignment | R::boolean_expr | R::block | R::if_statement => {
+                R::assignment | R::abort | R::boolean_expr | R::block | R::if_statement => {
                     expressions.push(self.expression_from_pair(pair)?)
                 }
                 R::EOI => (),
@@ -80,11 +81,26 @@ impl Parser<'_> {
             R::assignment => {
                 let mut inner = pair.into_inner();
                 let target = self.target_from_pair(inner.next().ok_or(e(R::target))?)?;
-                let expression =
-                    self.expression_from_pair(inner.next().ok_or(e(R::expression))?)?;
+                let mut next = inner.next().ok_or(e(R::expression))?;
+
+                // A second target receives the error of a failing expression.
+                let error = match next.as_rule() {
+                    R::variable | R::path => {
+                        let error = self.target_from_pair(next)?;
+                        next = inner.next().ok_or(e(R::expression))?;
+                        Some(error)
+                    }
+                    _ => None,
+                };
+
+                let expression = Box::new(self.expression_from_pair(next)?);
 
-                Ok(Expr::from(Assignment::new(target, Box::new(expression))))
+                Ok(Expr::from(match error {
+                    Some(error) => Assignment::fallible(target, error, expression),
+                    None => Assignment::new(target, expression),
+                }))
             }
+            R::abort => Ok(Expr::from(Abort)),
             R::boolean_expr => self.boolean_expr_from_pairs(pair.into_inner()),
             R::block => self.block_from_pairs(pair.into_inner()),
             R::if_statement => self.if_statement_from_pairs(pair.into_inner()),
@@ -126,7 +142,9 @@ impl Parser<'_> {
     /// Parse if-statement expressions.
     fn if_statement_from_pairs(&self, mut pairs: Pairs<R>) -> Result<Expr> {
         // if condition
-        let conditional = self.expression_from_pair(pairs.next().ok_or(e(R::if_statement))?)?;
+        let pair = pairs.next().ok_or(e(R::if_statement))?;
+        let span = Span::from(pair.as_span());
+        let conditional = self.expression_from_pair(pair)?;
         let true_expression = self.expression_from_pair(pairs.next().ok_or(e(R::if_statement))?)?;
 
         // else condition
@@ -140,26 +158,29 @@ impl Parser<'_> {
 
         // optional if-else conditions
         while let Some(pair) = pairs.next() {
-            let (conditional, true_expression) = match pairs.peek().map(Pair::as_rule) {
+            let (conditional, span, true_expression) = match pairs.peek().map(Pair::as_rule) {
                 Some(R::block) | None => {
+                    let span = Span::from(pair.as_span());
                     let conditional = self.expression_from_pair(pair)?;
                     let true_expression = false_expression;
                     false_expression = Expr::from(Noop);
 
-                    (conditional, true_expression)
+                    (conditional, span, true_expression)
                 }
                 Some(R::boolean_expr) => {
                     let next_pair = pairs.next().ok_or(e(R::if_statement))?;
+                    let span = Span::from(next_pair.as_span());
                     let conditional = self.expression_from_pair(next_pair)?;
                     let true_expression = self.expression_from_pair(pair)?;
 
-                    (conditional, true_expression)
+                    (conditional, span, true_expression)
                 }
                 _ => return Err(e(R::if_statement)),
             };
 
             false_expression = Expr::from(IfStatement::new(
                 Box::new(conditional),
+                span,
                 Box::new(true_expression),
                 Box::new(false_expression),
             ));
@@ -167,6 +188,7 @@ impl Parser<'_> {
 
         Ok(Expr::from(IfStatement::new(
             Box::new(conditional),
+            span,
             Box::new(true_expression),
             Box::new(false_expression),
         )))
@@ -217,44 +239,106 @@ impl Parser<'_> {
             R::boolean => Expr::from(Literal::from(pair.as_str() == "true")),
             R::integer => Expr::from(Literal::from(pair.as_str().parse::<i64>().unwrap())),
             R::float => Expr::from(Literal::from(pair.as_str().parse::<f64>().unwrap())),
+            R::array => self.array_from_pair(pair)?,
+            R::map => self.map_from_pair(pair)?,
             _ => return Err(e(R::value)),
         })
     }
 
+    /// Parse an array literal, e.g. `[1, .foo, "bar"]`.
+    fn array_from_pair(&self, pair: Pair<R>) -> Result<Expr> {
+        let expressions = pair
+            .into_inner()
+            .map(|pair| self.expression_from_pair(pair))
+            .collect::<Result<_>>()?;
+
+        Ok(Expr::from(Array::new(expressions)))
+    }
+
+    /// Parse a map literal, e.g. `{"foo": .bar, "baz": [1, 2]}`.
+    ///
+    /// If a key is repeated, the last value wins.
+    fn map_from_pair(&self, pair: Pair<R>) -> Result<Expr> {
+        let mut expressions = BTreeMap::new();
+
+        for entry in pair.into_inner() {
+            let mut inner = entry.into_inner();
+            let key = inner
+                .next()
+                .ok_or(e(R::map_entry))?
+                .into_inner()
+                .next()
+                .ok_or(e(R::string))?;
+            let key = self.escaped_string_from_pair(key)?;
+            let expression = self.expression_from_pair(inner.next().ok_or(e(R::map_entry))?)?;
+
+            expressions.insert(key, expression);
+        }
+
+        Ok(Expr::from(Map::new(expressions)))
+    }
+
     /// Parse function call expressions.
     fn call_from_pair(&self, pair: Pair<R>) -> Result<Expr> {
         let mut inner = pair.into_inner();
 
         let ident = inner.next().ok_or(e(R::call))?.as_str().to_owned();
-        let arguments = inner
-            .next()
-            .map(|pair| self.arguments_from_pair(pair))
-            .transpose()?
-            .unwrap_or_default();
+        let mut arguments = vec![];
+        let mut closure = None;
+
+        for pair in inner {
+            match pair.as_rule() {
+                R::arguments => arguments = self.arguments_from_pair(pair)?,
+                R::closure => closure = Some(self.closure_from_pair(pair)?),
+                _ => return Err(e(R::call)),
+            }
+        }
 
-        Function::new(ident, arguments, &self.function_definitions).map(Expr::from)
+        Function::new(ident, arguments, closure, &self.function_definitions).map(Expr::from)
+    }
+
+    /// Parse a [`Closure`], passed to a function call.
+    fn closure_from_pair(&self, pair: Pair<R>) -> Result<Closure> {
+        let mut parameters = vec![];
+
+        for pair in pair.into_inner() {
+            match pair.as_rule() {
+                R::ident => parameters.push(pair.as_str().to_owned()),
+                R::block => {
+                    let body = self.block_from_pairs(pair.into_inner())?;
+                    return Ok(Closure::new(parameters, Box::new(body)));
+                }
+                _ => return Err(e(R::closure)),
+            }
+        }
+
+        Err(e(R::closure))
     }
 
     /// Parse into a vector of argument properties.
-    fn arguments_from_pair(&self, pair: Pair<R>) -> Result<Vec<(Option<String>, Argument)>> {
+    fn arguments_from_pair(&self, pair: Pair<R>) -> Result<Vec<(Option<String>, Argument, Span)>> {
         pair.into_inner()
             .map(|pair| self.argument_from_pair(pair))
             .collect::<Result<_>>()
     }
 
-    /// Parse optional argument keyword and [`Argument`] value.
-    fn argument_from_pair(&self, pair: Pair<R>) -> Result<(Option<String>, Argument)> {
+    /// Parse optional argument keyword, [`Argument`] value and the span of
+    /// the value.
+    fn argument_from_pair(&self, pair: Pair<R>) -> Result<(Option<String>, Argument, Span)> {
         let mut ident = None;
 
         for pair in pair.into_inner() {
+            let span = Span::from(pair.as_span());
+
             match pair.as_rule() {
                 // This matches first, if a keyword is provided.
                 R::ident => ident = Some(pair.as_str().to_owned()),
-                R::regex => return Ok((ident, Argument::Regex(self.regex_from_pair(pair)?))),
+                R::regex => return Ok((ident, Argument::Regex(self.regex_from_pair(pair)?), span)),
                 _ => {
                     return Ok((
                         ident,
                         Argument::Expression(Box::new(self.expression_from_pair(pair)?)),
+                        span,
                     ))
                 }
             }
@@ -430,18 +514,18 @@ mod tests {
             ),
             (
                 ".foo = to_string",
-                vec![" 1:8\n", "= expected assignment, if_statement, not, or block"],
+                vec![" 1:8\n", "= expected assignment, if_statement, abort, not, or block"],
             ),
             (
                 r#"foo = "bar""#,
                 vec![
                     " 1:1\n",
-                    "= expected EOI, assignment, if_statement, not, or block",
+                    "= expected EOI, assignment, if_statement, abort, not, or block",
                 ],
             ),
             (
                 r#".foo.bar = "baz" and this"#,
-                vec![" 1:18\n", "= expected EOI, assignment, if_statement, not, operator_boolean_expr, operator_equality, operator_comparison, operator_addition, operator_multiplication, or block"],
+                vec![" 1:18\n", "= expected EOI, assignment, if_statement, abort, not, operator_boolean_expr, operator_equality, operator_comparison, operator_addition, operator_multiplication, or block"],
             ),
             (r#".foo.bar = "baz" +"#, vec![" 1:19", "= expected not"]),
             (
@@ -456,22 +540,45 @@ mod tests {
                 "if .foo { }",
                 vec![
                     " 1:11\n",
-                    "= expected assignment, if_statement, not, or block",
+                    "= expected assignment, if_statement, abort, not, or block",
                 ],
             ),
             (
                 "if { del(.foo) } else { del(.bar) }",
                 vec![" 1:4\n", "= expected not"],
             ),
+            (
+                "[1, 2",
+                vec![" 1:6\n", "= expected operator_boolean_expr, operator_equality, operator_comparison, operator_addition, or operator_multiplication"],
+            ),
+            (
+                // Without a colon, this is parsed as a block.
+                r#"{"foo" 1}"#,
+                vec![" 1:8\n", "= expected operator_boolean_expr"],
+            ),
+            (
+                // Map keys must be quoted.
+                "{foo: 1}",
+                vec![
+                    " 1:2\n",
+                    "= expected assignment, if_statement, abort, not, or block",
+                ],
+            ),
             (
                 "if .foo > .bar { del(.foo) } else { .bar = .baz",
                 // This message isn't great, ideally I'd like "expected closing bracket"
                 vec![" 1:48\n", "= expected operator_boolean_expr, operator_equality, operator_comparison, operator_addition, operator_multiplication, or path_index"],
             ),
             (
+                // The comma could also start the error target of an assignment.
                 "only_fields(.foo,)",
-                vec![" 1:18\n", "= expected argument"],
+                vec![" 1:18\n", "= expected variable, argument, or path"],
+            ),
+            (
+                ".foo, = 1",
+                vec![" 1:7\n", "= expected variable or path"],
             ),
+
             (
                 "only_fields(,)",
                 vec![" 1:13\n", "= expected argument"],
@@ -480,7 +587,7 @@ mod tests {
                 // Due to the explicit list of allowed escape chars our grammar
                 // doesn't actually recognize this as a string literal.
                 r#".foo = "invalid escape \k sequence""#,
-                vec![" 1:8\n", "= expected assignment, if_statement, not, or block"],
+                vec![" 1:8\n", "= expected assignment, if_statement, abort, not, or block"],
             ),
             (
                 // Same here as above.
@@ -495,12 +602,12 @@ mod tests {
             (
                 // We cannot assign a regular expression to a field.
                 r#".foo = /ab/i"#,
-                vec![" 1:8\n", "= expected assignment, if_statement, not, or block"],
+                vec![" 1:8\n", "= expected assignment, if_statement, abort, not, or block"],
             ),
             (
                 // We cannot assign to a regular expression.
                 r#"/ab/ = .foo"#,
-                vec![" 1:1\n", "= expected EOI, assignment, if_statement, not, or block"],
+                vec![" 1:1\n", "= expected EOI, assignment, if_statement, abort, not, or block"],
             ),
         ];
 
@@ -1,4 +1,4 @@
 pub use crate::{
-    Argument, ArgumentList, Error, Expression, Function, Literal, Noop, Object, Parameter, Path,
-    Result, State, Value,
+    Argument, ArgumentList, Closure, Error, Expression, Function, Kind, Literal, Noop, Object,
+    Parameter, Path, Result, State, TypeState, Value,
 };
@@ -1,15 +1,16 @@
-use crate::{parser, Error, Expr, Function, Result};
+use crate::{parser, Error, Expr, Expression, Function, Kind, Result, TypeState};
 use pest::Parser;
 
 /// The program to execute.
 ///
 /// This object is passed to [`Runtime::execute`](crate::Runtime::execute).
 ///
-/// You can create a program using [`Program::from_str`]. The provided string
-/// will be parsed. If parsing fails, an [`Error`] is returned.
+/// You can create a program using [`Program::new`]. The provided string
+/// will be parsed and type checked. If either fails, an [`Error`] is returned.
 #[derive(Debug, Clone)]
 pub struct Program {
     pub(crate) expressions: Vec<Expr>,
+    kind: Kind,
 }
 
 impl Program {
@@ -22,6 +23,18 @@ impl Program {
         };
         let expressions = parser.pairs_to_expressions(pairs)?;
 
-        Ok(Self { expressions })
+        let mut state = TypeState::new(function_definitions);
+        let kind = expressions
+            .iter()
+            .fold(Kind::ANY, |_, expression| expression.type_check(&mut state));
+        state.into_result(source).map_err(Error::Type)?;
+
+        Ok(Self { expressions, kind })
+    }
+
+    /// The kind of value the program resolves to, as far as it is known
+    /// before running it.
+    pub fn kind(&self) -> Kind {
+        self.kind
     }
 }
@@ -12,6 +12,15 @@ impl Runtime {
 
     /// Given the provided [`Object`], run the provided [`Program`] to
     /// completion.
+    ///
+    /// The first failing expression that isn't handled by the program itself
+    /// stops execution. If the program runs an `abort` statement,
+    /// [`Error::Abort`](crate::Error::Abort) is returned, and the caller is
+    /// expected to discard the object.
+    ///
+    /// Execution always terminates: the language has no loops or recursion,
+    /// and a [`Closure`](crate::Closure) is only called by the function it's
+    /// passed to, once for each element of an already resolved collection.
     pub fn execute(
         &mut self,
         object: &mut impl Object,
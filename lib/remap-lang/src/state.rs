@@ -1,5 +1,5 @@
 use crate::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Default)]
 pub struct State {
@@ -14,4 +14,64 @@ impl State {
     pub fn variables_mut(&mut self) -> &mut HashMap<String, Value> {
         &mut self.variables
     }
+
+    /// Run `f` in a new variable scope, with the given variables bound.
+    ///
+    /// Variables bound by, or first assigned in, the scope are removed once it
+    /// ends, restoring any variables they shadowed. Changes to variables that
+    /// exist outside of the scope are kept.
+    pub(crate) fn scoped<T>(
+        &mut self,
+        bindings: Vec<(&str, Value)>,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let outer = self.variables.keys().cloned().collect::<HashSet<_>>();
+        let shadowed = bindings
+            .into_iter()
+            .map(|(ident, value)| {
+                (
+                    ident.to_owned(),
+                    self.variables.insert(ident.to_owned(), value),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let result = f(self);
+
+        self.variables.retain(|ident, _| outer.contains(ident));
+        for (ident, value) in shadowed {
+            if let Some(value) = value {
+                self.variables.insert(ident, value);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped() {
+        let mut state = State::default();
+        state.variables_mut().insert("outer".to_owned(), 1.into());
+        state
+            .variables_mut()
+            .insert("shadowed".to_owned(), 2.into());
+
+        state.scoped(vec![("shadowed", 3.into()), ("param", 4.into())], |state| {
+            assert_eq!(state.variable("shadowed"), Some(&3.into()));
+            assert_eq!(state.variable("param"), Some(&4.into()));
+
+            state.variables_mut().insert("outer".to_owned(), 5.into());
+            state.variables_mut().insert("local".to_owned(), 6.into());
+        });
+
+        assert_eq!(state.variable("outer"), Some(&5.into()));
+        assert_eq!(state.variable("shadowed"), Some(&2.into()));
+        assert_eq!(state.variable("param"), None);
+        assert_eq!(state.variable("local"), None);
+    }
 }
@@ -0,0 +1,527 @@
+//! Compile-time type checking of remap programs.
+//!
+//! Before a program runs, each expression reports the [`Kind`]s of values it
+//! can resolve to. Expressions that are guaranteed to fail at runtime, such as
+//! passing an integer to a function that only accepts strings, are reported as
+//! errors when the program is compiled, instead of failing for every event.
+//!
+//! Kinds that can't be known ahead of time, such as event fields that weren't
+//! assigned by the program itself, resolve to [`Kind::ANY`], and never cause
+//! an error.
+
+use crate::{Function, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::{BitAnd, BitOr};
+
+/// A set of [`Value`] kinds.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Kind(u8);
+
+impl Kind {
+    pub const STRING: Kind = Kind(1);
+    pub const INTEGER: Kind = Kind(1 << 1);
+    pub const FLOAT: Kind = Kind(1 << 2);
+    pub const BOOLEAN: Kind = Kind(1 << 3);
+    pub const MAP: Kind = Kind(1 << 4);
+    pub const ARRAY: Kind = Kind(1 << 5);
+    pub const TIMESTAMP: Kind = Kind(1 << 6);
+    pub const NULL: Kind = Kind(1 << 7);
+
+    /// Any kind of value.
+    pub const ANY: Kind = Kind(u8::MAX);
+
+    /// No value at all, e.g. for expressions that never return.
+    pub const NONE: Kind = Kind(0);
+
+    const NAMES: [(Kind, &'static str); 8] = [
+        (Kind::STRING, "string"),
+        (Kind::INTEGER, "integer"),
+        (Kind::FLOAT, "float"),
+        (Kind::BOOLEAN, "boolean"),
+        (Kind::MAP, "map"),
+        (Kind::ARRAY, "array"),
+        (Kind::TIMESTAMP, "timestamp"),
+        (Kind::NULL, "null"),
+    ];
+
+    /// The kind of the given value.
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::String(_) => Kind::STRING,
+            Value::Integer(_) => Kind::INTEGER,
+            Value::Float(_) => Kind::FLOAT,
+            Value::Boolean(_) => Kind::BOOLEAN,
+            Value::Map(_) => Kind::MAP,
+            Value::Array(_) => Kind::ARRAY,
+            Value::Timestamp(_) => Kind::TIMESTAMP,
+            Value::Null => Kind::NULL,
+        }
+    }
+
+    /// The kinds of values for which `accepts` returns `true`.
+    ///
+    /// `accepts` is called with an example value of each kind, so it should
+    /// only look at the kind of the value, not its contents.
+    pub fn accepted_by(accepts: fn(&Value) -> bool) -> Self {
+        use chrono::{TimeZone, Utc};
+
+        let examples = [
+            Value::from(""),
+            Value::Integer(0),
+            Value::Float(0.0),
+            Value::Boolean(false),
+            Value::Map(Default::default()),
+            Value::Array(vec![]),
+            Value::Timestamp(Utc.timestamp(0, 0)),
+            Value::Null,
+        ];
+
+        examples
+            .iter()
+            .filter(|value| accepts(value))
+            .fold(Kind::NONE, |kind, value| kind | Kind::of(value))
+    }
+
+    pub fn is_empty(self) -> bool {
+        self == Kind::NONE
+    }
+
+    pub fn contains(self, other: Kind) -> bool {
+        self & other == other
+    }
+
+    pub fn intersects(self, other: Kind) -> bool {
+        !(self & other).is_empty()
+    }
+}
+
+impl BitOr for Kind {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Kind(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for Kind {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Kind(self.0 & rhs.0)
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Kind::ANY {
+            return f.write_str("any");
+        }
+
+        let names = Kind::NAMES
+            .iter()
+            .filter(|(kind, _)| self.contains(*kind))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>();
+
+        match names.split_last() {
+            None => f.write_str("nothing"),
+            Some((last, [])) => f.write_str(last),
+            Some((last, rest)) => write!(f, "{} or {}", rest.join(", "), last),
+        }
+    }
+}
+
+impl fmt::Debug for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Kind({})", self)
+    }
+}
+
+/// A byte range in the program source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    start: usize,
+    end: usize,
+}
+
+impl From<pest::Span<'_>> for Span {
+    fn from(span: pest::Span<'_>) -> Self {
+        Self {
+            start: span.start(),
+            end: span.end(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TypeError {
+    span: Span,
+    message: String,
+    expected: Kind,
+}
+
+/// Conversion functions to suggest when a value of the wrong kind is used.
+const CONVERSIONS: [(Kind, &str); 5] = [
+    (Kind::STRING, "to_string"),
+    (Kind::INTEGER, "to_int"),
+    (Kind::FLOAT, "to_float"),
+    (Kind::BOOLEAN, "to_bool"),
+    (Kind::TIMESTAMP, "to_timestamp"),
+];
+
+/// The state kept while type checking a program.
+///
+/// It tracks the kinds of variables and paths assigned so far, and collects
+/// all errors found along the way.
+#[derive(Debug, Default)]
+pub struct TypeState {
+    variables: HashMap<String, Kind>,
+    paths: HashMap<String, Kind>,
+    functions: HashSet<&'static str>,
+    errors: Vec<TypeError>,
+}
+
+impl TypeState {
+    pub(crate) fn new(functions: &[Box<dyn Function>]) -> Self {
+        Self {
+            functions: functions.iter().map(|f| f.identifier()).collect(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn variable(&self, ident: &str) -> Kind {
+        self.variables.get(ident).copied().unwrap_or(Kind::ANY)
+    }
+
+    pub(crate) fn set_variable(&mut self, ident: &str, kind: Kind) {
+        self.variables.insert(ident.to_owned(), kind);
+    }
+
+    /// Type check a closure body, with its parameters bound to values of any
+    /// kind. Like [`State::scoped`](crate::State), variables bound in the
+    /// scope are forgotten once it ends.
+    pub(crate) fn scoped(&mut self, parameters: &[String], f: impl FnOnce(&mut Self)) {
+        let outer = self.variables.clone();
+        for parameter in parameters {
+            self.set_variable(parameter, Kind::ANY);
+        }
+
+        f(self);
+
+        self.variables.retain(|ident, _| outer.contains_key(ident));
+        for parameter in parameters {
+            if let Some(kind) = outer.get(parameter) {
+                self.set_variable(parameter, *kind);
+            }
+        }
+    }
+
+    pub(crate) fn path(&self, path: &str) -> Kind {
+        self.paths.get(path).copied().unwrap_or(Kind::ANY)
+    }
+
+    /// Assigning to a path also changes its parents and children, so any
+    /// kinds known for those are forgotten.
+    pub(crate) fn set_path(&mut self, path: &str, kind: Kind) {
+        let nested = |parent: &str, child: &str| {
+            child.len() > parent.len()
+                && child.starts_with(parent)
+                && matches!(child.as_bytes()[parent.len()], b'.' | b'[')
+        };
+
+        self.paths
+            .retain(|known, _| !nested(known, path) && !nested(path, known));
+        self.paths.insert(path.to_owned(), kind);
+    }
+
+    /// Records an error if a value of kind `got` can never be one of the
+    /// `expected` kinds.
+    pub(crate) fn expect(&mut self, span: Span, expected: Kind, got: Kind, message: String) {
+        // Expressions that never return a value can't be of the wrong kind.
+        if got.is_empty() || got.intersects(expected) {
+            return;
+        }
+
+        self.errors.push(TypeError {
+            span,
+            message: format!("{}, got {}", message, got),
+            expected,
+        });
+    }
+
+    /// Returns a copy of the known kinds, to type check one branch of a
+    /// conditional expression with.
+    pub(crate) fn branch(&self) -> Self {
+        Self {
+            variables: self.variables.clone(),
+            paths: self.paths.clone(),
+            functions: self.functions.clone(),
+            errors: vec![],
+        }
+    }
+
+    /// Combines the results of type checking all branches of a conditional
+    /// expression, of which exactly one is executed.
+    pub(crate) fn merge(&mut self, branches: Vec<TypeState>) {
+        fn union(maps: Vec<&HashMap<String, Kind>>) -> HashMap<String, Kind> {
+            let mut union = HashMap::new();
+            for map in maps {
+                for (key, kind) in map {
+                    let entry = union.entry(key.clone()).or_insert(Kind::NONE);
+                    *entry = *entry | *kind;
+                }
+            }
+            // Anything not assigned in every branch keeps the kind it had
+            // before, which is part of the union as every branch started out
+            // with it.
+            union
+        }
+
+        self.variables = union(branches.iter().map(|b| &b.variables).collect());
+
+        // A path that is unknown in any branch is unknown afterwards.
+        let paths = union(branches.iter().map(|b| &b.paths).collect());
+        self.paths = paths
+            .into_iter()
+            .filter(|(path, _)| branches.iter().all(|b| b.paths.contains_key(path)))
+            .collect();
+
+        for branch in branches {
+            self.errors.extend(branch.errors);
+        }
+    }
+
+    /// Renders all errors found, if any, in the same format the parser uses.
+    pub(crate) fn into_result(self, source: &str) -> std::result::Result<(), String> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+
+        let functions = &self.functions;
+        let errors = self
+            .errors
+            .iter()
+            .map(|error| {
+                let suggestion = CONVERSIONS
+                    .iter()
+                    .find(|(kind, function)| {
+                        error.expected.contains(*kind) && functions.contains(function)
+                    })
+                    .map(|(_, function)| function);
+
+                render(source, error, suggestion)
+            })
+            .collect::<Vec<_>>();
+
+        Err(errors.join("\n\n"))
+    }
+}
+
+fn render(source: &str, error: &TypeError, suggestion: Option<&&str>) -> String {
+    let start = error.span.start.min(source.len());
+    let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = source[start..]
+        .find('\n')
+        .map(|i| start + i)
+        .unwrap_or_else(|| source.len());
+
+    let line = &source[line_start..line_end];
+    let line_number = source[..start].matches('\n').count() + 1;
+    let column = source[line_start..start].chars().count() + 1;
+    // Spans can include trailing whitespace, which isn't worth pointing at.
+    let width = source[start..error.span.end.min(line_end)]
+        .trim_end()
+        .chars()
+        .count()
+        .max(1);
+
+    let number = line_number.to_string();
+    let pad = " ".repeat(number.len());
+
+    let mut rendered = format!(
+        "{}\n{}--> {}:{}\n{} |\n{} | {}\n{} | {}{}\n{} |",
+        error.message,
+        pad,
+        number,
+        column,
+        pad,
+        number,
+        line,
+        pad,
+        " ".repeat(column - 1),
+        "^".repeat(width),
+        pad,
+    );
+
+    if let Some(function) = suggestion {
+        rendered.push_str(&format!(
+            "\n{} = help: convert the value first, e.g. `{}(...)`",
+            pad, function
+        ));
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArgumentList, Expression, Noop, Parameter, Program, Result};
+
+    #[derive(Debug, Clone)]
+    struct Upcase;
+
+    impl Function for Upcase {
+        fn identifier(&self) -> &'static str {
+            "upcase"
+        }
+
+        fn parameters(&self) -> &'static [Parameter] {
+            &[Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            }]
+        }
+
+        fn compile(&self, _: ArgumentList) -> Result<Box<dyn Expression>> {
+            Ok(Box::new(Noop))
+        }
+
+        fn return_kind(&self) -> Kind {
+            Kind::STRING
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct ToString;
+
+    impl Function for ToString {
+        fn identifier(&self) -> &'static str {
+            "to_string"
+        }
+
+        fn parameters(&self) -> &'static [Parameter] {
+            &[Parameter {
+                keyword: "value",
+                accepts: |_| true,
+                required: true,
+            }]
+        }
+
+        fn compile(&self, _: ArgumentList) -> Result<Box<dyn Expression>> {
+            Ok(Box::new(Noop))
+        }
+
+        fn return_kind(&self) -> Kind {
+            Kind::STRING
+        }
+    }
+
+    #[test]
+    fn type_check_program() {
+        let cases: Vec<(&str, std::result::Result<(), &str>)> = vec![
+            // The kinds of event fields are unknown.
+            (".foo = upcase(.count)", Ok(())),
+            (
+                ".count = 1\n.foo = upcase(.count)",
+                Err(
+                    r#"type error: argument "value" of function "upcase" expects string, got integer
+ --> 2:15
+  |
+2 | .foo = upcase(.count)
+  |               ^^^^^^
+  |
+  = help: convert the value first, e.g. `to_string(...)`"#,
+                ),
+            ),
+            (
+                "$x = upcase(.foo)\nif $x { 1 }",
+                Err(
+                    r#"type error: if-statement condition must be a boolean, got string
+ --> 2:4
+  |
+2 | if $x { 1 }
+  |    ^^
+  |"#,
+                ),
+            ),
+            ("upcase(to_string(1))", Ok(())),
+            ("upcase(\"a\" + 1)", Ok(())),
+            ("upcase(.foo ?? 1)", Ok(())),
+            ("upcase(1 + 1)", Err(r#"expects string, got integer"#)),
+            ("upcase(.foo == 1)", Err(r#"expects string, got boolean"#)),
+            ("upcase(value = [1])", Err(r#"expects string, got array"#)),
+            ("if .a { $x = 1 } else { $x = \"a\" }\nupcase($x)", Ok(())),
+            (
+                "if .a { $x = 1 } else { $x = 2.0 }\nupcase($x)",
+                Err(r#"expects string, got integer or float"#),
+            ),
+            ("$x = 1\nif .a { $x = \"a\" }\nupcase($x)", Ok(())),
+            (".x, $err = upcase(.y)\nupcase($err)", Ok(())),
+            (".x = 1\n.x.y = 2\nupcase(.x)", Ok(())),
+            (
+                "upcase(1)\nupcase(true)",
+                Err(r#"got integer
+ --> 1:8
+  |
+1 | upcase(1)
+  |        ^
+  |
+  = help: convert the value first, e.g. `to_string(...)`
+
+argument "value" of function "upcase" expects string, got boolean"#),
+            ),
+        ];
+
+        for (source, expected) in cases {
+            let got = Program::new(source, &[Box::new(Upcase), Box::new(ToString)])
+                .map(|_| ())
+                .map_err(|e| e.to_string());
+
+            match expected {
+                Ok(()) => assert_eq!(got, Ok(()), "{}", source),
+                Err(expected) => {
+                    let got = got.expect_err(source);
+                    assert!(got.contains(expected), "{}\n\n{}", source, got);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn kind_display() {
+        assert_eq!(Kind::STRING.to_string(), "string");
+        assert_eq!((Kind::STRING | Kind::NULL).to_string(), "string or null");
+        assert_eq!(
+            (Kind::INTEGER | Kind::FLOAT | Kind::STRING).to_string(),
+            "string, integer or float"
+        );
+        assert_eq!(Kind::ANY.to_string(), "any");
+        assert_eq!(Kind::NONE.to_string(), "nothing");
+    }
+
+    #[test]
+    fn kind_accepted_by() {
+        assert_eq!(
+            Kind::accepted_by(|v| matches!(v, Value::Integer(_) | Value::Float(_))),
+            Kind::INTEGER | Kind::FLOAT
+        );
+        assert_eq!(Kind::accepted_by(|_| true), Kind::ANY);
+    }
+
+    #[test]
+    fn set_path_forgets_nested_paths() {
+        let mut state = TypeState::default();
+        state.set_path("foo.bar", Kind::INTEGER);
+        state.set_path("foo.bar[0]", Kind::STRING);
+        state.set_path("foobar", Kind::STRING);
+        state.set_path("foo", Kind::MAP);
+
+        assert_eq!(state.path("foo"), Kind::MAP);
+        assert_eq!(state.path("foo.bar"), Kind::ANY);
+        assert_eq!(state.path("foo.bar[0]"), Kind::ANY);
+        assert_eq!(state.path("foobar"), Kind::STRING);
+    }
+}
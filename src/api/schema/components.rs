@@ -78,7 +78,27 @@ pub struct InputsData {
 }
 
 #[derive(Clone)]
-pub struct Transform(InputsData);
+pub struct TransformData {
+    name: String,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl TransformData {
+    /// Whether `input` refers to this transform or one of its named outputs.
+    fn is_input(&self, input: &str) -> bool {
+        match input.strip_prefix(self.name.as_str()) {
+            Some("") => true,
+            Some(rest) => rest
+                .strip_prefix('.')
+                .map_or(false, |output| self.outputs.iter().any(|o| o == output)),
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Transform(TransformData);
 
 #[Object]
 impl Transform {
@@ -87,16 +107,18 @@ impl Transform {
         self.0.name.clone()
     }
 
+    /// Named outputs, consumed as `<transform-name>.<output>`
+    async fn outputs(&self) -> Vec<String> {
+        self.0.outputs.clone()
+    }
+
     /// Source inputs
     async fn sources(&self) -> Vec<Source> {
         self.0
             .inputs
             .iter()
-            .filter_map(|name| match COMPONENTS.read().expect(INVARIANT).get(name) {
-                Some(t) => match t {
-                    Component::Source(s) => Some(s.clone()),
-                    _ => None,
-                },
+            .filter_map(|name| match get_input(name) {
+                Some(Component::Source(s)) => Some(s),
                 _ => None,
             })
             .collect()
@@ -105,7 +127,7 @@ impl Transform {
     /// Sink outputs
     async fn sinks(&self) -> Vec<Sink> {
         filter_components(|(_name, components)| match components {
-            Component::Sink(s) if s.0.inputs.contains(&self.0.name) => Some(s.clone()),
+            Component::Sink(s) if s.0.inputs.iter().any(|i| self.0.is_input(i)) => Some(s.clone()),
             _ => None,
         })
     }
@@ -131,11 +153,8 @@ impl Sink {
         self.0
             .inputs
             .iter()
-            .filter_map(|name| match COMPONENTS.read().expect(INVARIANT).get(name) {
-                Some(components) => match components {
-                    Component::Source(s) => Some(s.clone()),
-                    _ => None,
-                },
+            .filter_map(|name| match get_input(name) {
+                Some(Component::Source(s)) => Some(s),
                 _ => None,
             })
             .collect()
@@ -146,11 +165,8 @@ impl Sink {
         self.0
             .inputs
             .iter()
-            .filter_map(|name| match COMPONENTS.read().expect(INVARIANT).get(name) {
-                Some(components) => match components {
-                    Component::Transform(t) => Some(t.clone()),
-                    _ => None,
-                },
+            .filter_map(|name| match get_input(name) {
+                Some(Component::Transform(t)) => Some(t),
                 _ => None,
             })
             .collect()
@@ -259,6 +275,17 @@ fn get_sinks() -> Vec<Sink> {
     })
 }
 
+/// Returns the component an input refers to, looking through the named outputs of transforms
+fn get_input(input: &str) -> Option<Component> {
+    let components = COMPONENTS.read().expect(INVARIANT);
+    components.get(input).cloned().or_else(|| {
+        components.values().find_map(|component| match component {
+            Component::Transform(t) if t.0.is_input(input) => Some(component.clone()),
+            _ => None,
+        })
+    })
+}
+
 /// Returns the current component names as a HashSet
 fn get_component_names() -> HashSet<String> {
     COMPONENTS
@@ -288,9 +315,10 @@ pub fn update_config(config: &Config) {
     for (name, transform) in config.transforms.iter() {
         new_components.insert(
             name.to_string(),
-            Component::Transform(Transform(InputsData {
+            Component::Transform(Transform(TransformData {
                 name: name.to_owned(),
                 inputs: transform.inputs.clone(),
+                outputs: transform.inner.named_outputs(),
             })),
         );
     }
@@ -5,7 +5,6 @@ use crate::{
     config, generate, heartbeat, list, metrics, signal, topology, trace, unit_test, validate,
 };
 use std::cmp::max;
-use std::path::PathBuf;
 
 use futures::{
     compat::{Future01CompatExt, Stream01CompatExt},
@@ -29,7 +28,7 @@ use tokio::runtime;
 use tokio::runtime::Runtime;
 
 pub struct ApplicationConfig {
-    pub config_paths: Vec<PathBuf>,
+    pub config_paths: Vec<config::ConfigPath>,
     pub topology: RunningTopology,
     pub graceful_crash: mpsc::UnboundedReceiver<()>,
     #[cfg(feature = "api")]
@@ -107,7 +106,7 @@ impl Application {
         };
 
         let config = {
-            let config_paths = root_opts.config_paths.clone();
+            let config_paths = root_opts.config_paths_with_formats();
             let watch_config = root_opts.watch_config;
             let require_healthy = root_opts.require_healthy;
 
@@ -219,7 +218,7 @@ impl Application {
                 Some(signal) = signals.next() => {
                     if signal == SignalTo::Reload {
                         // Reload paths
-                        config_paths = config::process_paths(&opts.config_paths).unwrap_or(config_paths);
+                        config_paths = config::process_paths(&opts.config_paths_with_formats()).unwrap_or(config_paths);
                         // Reload config
                         let new_config = config::load_from_paths(&config_paths).map_err(handle_config_errors).ok();
 
@@ -1,4 +1,7 @@
-use crate::event::{proto, Event};
+use crate::event::{
+    finalization::{EventFinalizers, EventStatus},
+    proto, Event,
+};
 use bytes::Bytes;
 use futures01::{
     task::{self, AtomicTask, Task},
@@ -16,15 +19,16 @@ use snafu::ResultExt;
 use std::{
     collections::VecDeque,
     convert::TryInto,
+    io,
     mem::size_of,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Mutex,
     },
 };
 
-use super::{DataDirOpenError, Error};
+use super::{BufferIoError, DataDirOpenError, Error};
 use crate::buffers::Acker;
 
 #[derive(Copy, Clone, Debug)]
@@ -52,6 +56,9 @@ pub struct Writer {
     batch_size: usize,
     max_size: usize,
     current_size: Arc<AtomicUsize>,
+    // Events are durable once their batch has been written, so their
+    // finalizers are held until then instead of being written to disk.
+    pending_finalizers: EventFinalizers,
 }
 
 // Writebatch isn't Send, but the leveldb docs explicitly say that it's okay to share across threads
@@ -68,6 +75,7 @@ impl Clone for Writer {
             batch_size: 0,
             max_size: self.max_size,
             current_size: Arc::clone(&self.current_size),
+            pending_finalizers: EventFinalizers::default(),
         }
     }
 }
@@ -78,8 +86,9 @@ impl Sink for Writer {
 
     fn start_send(
         &mut self,
-        event: Self::SinkItem,
+        mut event: Self::SinkItem,
     ) -> Result<AsyncSink<Self::SinkItem>, Self::SinkError> {
+        let finalizers = event.take_finalizers();
         let mut value = vec![];
         proto::EventWrapper::from(event).encode(&mut value).unwrap(); // This will not error when writing to a Vec
         let event_size = value.len();
@@ -97,7 +106,10 @@ impl Sink for Writer {
             self.poll_complete()?;
 
             let buf = Bytes::from(value);
-            let event = proto::EventWrapper::decode(buf).unwrap().into();
+            let mut event: Event = proto::EventWrapper::decode(buf).unwrap().into();
+            if let Event::Log(log) = &mut event {
+                log.merge_finalizers(finalizers);
+            }
             return Ok(AsyncSink::NotReady(event));
         }
 
@@ -105,6 +117,7 @@ impl Sink for Writer {
 
         self.writebatch.put(Key(key), &value);
         self.batch_size += 1;
+        self.pending_finalizers.merge(finalizers);
 
         if self.batch_size >= 100 {
             self.poll_complete()?;
@@ -132,6 +145,7 @@ impl Writer {
             .unwrap();
         self.writebatch = Writebatch::new();
         self.batch_size = 0;
+        std::mem::take(&mut self.pending_finalizers).update_status(EventStatus::Delivered);
         self.write_notifier.notify();
     }
 }
@@ -251,6 +265,27 @@ impl Reader {
     }
 }
 
+/// Hands the encoded form of every event left in the buffer at `path` to
+/// `f`, in order, returning how many there were. The buffer itself is left
+/// untouched.
+pub fn drain<F>(path: &Path, mut f: F) -> Result<usize, Error>
+where
+    F: FnMut(Vec<u8>) -> io::Result<()>,
+{
+    let db: Database<Key> =
+        Database::open(path, Options::new()).with_context(|| DataDirOpenError {
+            data_dir: path.parent().expect("always a parent"),
+        })?;
+
+    let mut count = 0;
+    for value in db.value_iter(ReadOptions::new()) {
+        f(value).with_context(|| BufferIoError { path })?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
 pub struct Buffer;
 
 impl super::DiskBuffer for Buffer {
@@ -295,6 +330,7 @@ impl super::DiskBuffer for Buffer {
             batch_size: 0,
             max_size,
             current_size: Arc::clone(&current_size),
+            pending_finalizers: EventFinalizers::default(),
         };
 
         let reader = Reader {
@@ -1,12 +1,15 @@
-#![cfg(feature = "leveldb")]
-
 use crate::event::Event;
 use futures01::{Async, AsyncSink, Poll, Sink, Stream};
+#[cfg(feature = "leveldb")]
+use snafu::ResultExt;
 use snafu::Snafu;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::{atomic::AtomicUsize, Arc};
 
+#[cfg(feature = "leveldb")]
 pub mod leveldb_buffer;
+pub mod segmented_buffer;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -19,11 +22,14 @@ pub enum Error {
         data_dir: PathBuf,
         source: std::io::Error,
     },
+    #[cfg(feature = "leveldb")]
     #[snafu(display("Unable to open data_dir {:?}", data_dir))]
     DataDirOpenError {
         data_dir: PathBuf,
         source: leveldb::database::error::Error,
     },
+    #[snafu(display("Unable to access disk buffer {:?}: {}", path, source))]
+    BufferIoError { path: PathBuf, source: io::Error },
 }
 
 pub trait DiskBuffer {
@@ -38,7 +44,26 @@ pub trait DiskBuffer {
 
 #[derive(Clone)]
 pub struct Writer {
-    inner: leveldb_buffer::Writer,
+    inner: WriterInner,
+}
+
+#[derive(Clone)]
+enum WriterInner {
+    #[cfg(feature = "leveldb")]
+    LevelDb(leveldb_buffer::Writer),
+    Segmented(segmented_buffer::Writer),
+}
+
+impl Writer {
+    /// Counts the events that were written but not read yet, for buffers
+    /// that keep track of it.
+    pub fn unread_counter(&self) -> Option<Arc<AtomicUsize>> {
+        match &self.inner {
+            #[cfg(feature = "leveldb")]
+            WriterInner::LevelDb(_) => None,
+            WriterInner::Segmented(writer) => Some(writer.unread_counter()),
+        }
+    }
 }
 
 impl Sink for Writer {
@@ -49,33 +74,105 @@ impl Sink for Writer {
         &mut self,
         event: Self::SinkItem,
     ) -> Result<AsyncSink<Self::SinkItem>, Self::SinkError> {
-        self.inner.start_send(event)
+        match &mut self.inner {
+            #[cfg(feature = "leveldb")]
+            WriterInner::LevelDb(writer) => writer.start_send(event),
+            WriterInner::Segmented(writer) => writer.start_send(event),
+        }
     }
 
     fn poll_complete(&mut self) -> Result<Async<()>, Self::SinkError> {
-        self.inner.poll_complete()
+        match &mut self.inner {
+            #[cfg(feature = "leveldb")]
+            WriterInner::LevelDb(writer) => writer.poll_complete(),
+            WriterInner::Segmented(writer) => writer.poll_complete(),
+        }
     }
 
     fn close(&mut self) -> Poll<(), Self::SinkError> {
-        self.inner.close()
+        match &mut self.inner {
+            #[cfg(feature = "leveldb")]
+            WriterInner::LevelDb(writer) => writer.close(),
+            WriterInner::Segmented(writer) => writer.close(),
+        }
     }
 }
 
-pub fn open(
+type Opened = (
+    Writer,
+    Box<dyn Stream<Item = Event, Error = ()> + Send>,
+    super::Acker,
+);
+
+#[cfg(feature = "leveldb")]
+pub fn open(data_dir: &Path, name: &str, max_size: usize) -> Result<Opened, Error> {
+    use leveldb_buffer::Buffer;
+
+    let path = data_dir.join(name);
+
+    check_data_dir(data_dir)?;
+
+    let (writer, reader, acker) = Buffer::build(path, max_size)?;
+    let writer = Writer {
+        inner: WriterInner::LevelDb(writer),
+    };
+    Ok((writer, Box::new(reader), acker))
+}
+
+/// Opens a segmented disk buffer, first draining any leveldb buffer left in
+/// `legacy_name` into it.
+pub fn open_segmented(
     data_dir: &Path,
     name: &str,
-    max_size: usize,
-) -> Result<
-    (
-        Writer,
-        Box<dyn Stream<Item = Event, Error = ()> + Send>,
-        super::Acker,
-    ),
-    Error,
-> {
+    legacy_name: &str,
+    options: segmented_buffer::Options,
+) -> Result<Opened, Error> {
     let path = data_dir.join(name);
 
-    // Check data dir
+    check_data_dir(data_dir)?;
+
+    let (mut writer, reader, acker) = segmented_buffer::Buffer::build(path, options)?;
+    migrate_leveldb(&data_dir.join(legacy_name), &mut writer)?;
+
+    let writer = Writer {
+        inner: WriterInner::Segmented(writer),
+    };
+    Ok((writer, Box::new(reader), acker))
+}
+
+#[cfg(feature = "leveldb")]
+fn migrate_leveldb(legacy_path: &Path, writer: &mut segmented_buffer::Writer) -> Result<(), Error> {
+    if !legacy_path.exists() {
+        return Ok(());
+    }
+
+    info!(message = "Migrating leveldb disk buffer.", path = ?legacy_path);
+    let count = leveldb_buffer::drain(legacy_path, |value| writer.append_encoded(&value))?;
+    // The leveldb buffer is only removed once its events are safely on disk.
+    writer
+        .sync()
+        .with_context(|| BufferIoError { path: legacy_path })?;
+    std::fs::remove_dir_all(legacy_path).with_context(|| BufferIoError { path: legacy_path })?;
+    info!(message = "Migrated leveldb disk buffer.", path = ?legacy_path, events = count);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "leveldb"))]
+fn migrate_leveldb(
+    legacy_path: &Path,
+    _writer: &mut segmented_buffer::Writer,
+) -> Result<(), Error> {
+    if legacy_path.exists() {
+        warn!(
+            message = "Found a leveldb disk buffer, but leveldb support is not enabled; its events will not be migrated.",
+            path = ?legacy_path
+        );
+    }
+    Ok(())
+}
+
+fn check_data_dir(data_dir: &Path) -> Result<(), Error> {
     std::fs::metadata(&data_dir)
         .map_err(|e| match e.kind() {
             io::ErrorKind::PermissionDenied => Error::DataDirNotWritable {
@@ -97,8 +194,5 @@ pub fn open(
             } else {
                 Ok(())
             }
-        })?;
-
-    let (writer, reader, acker) = leveldb_buffer::Buffer::build(path, max_size)?;
-    Ok((Writer { inner: writer }, Box::new(reader), acker))
+        })
 }
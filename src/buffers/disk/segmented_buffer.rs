@@ -0,0 +1,883 @@
+//! A disk buffer built on append-only segment files.
+//!
+//! Events are appended to numbered segment files as length-prefixed records,
+//! each carrying a CRC32 checksum of its payload. Once the active segment
+//! reaches `max_segment_size` the writer moves on to a new one. The reader
+//! follows the segments in order, and the position just past the last
+//! acknowledged record is persisted in a cursor file so that acknowledged
+//! events are not read again after a restart. Segments that lie entirely
+//! before the cursor are deleted.
+//!
+//! On startup every remaining segment is validated. Corrupted records are
+//! skipped, and a segment is truncated at its first torn record, which is what
+//! a crash in the middle of a write leaves behind.
+
+use super::{BufferIoError, Error};
+use crate::{
+    buffers::Acker,
+    event::{
+        finalization::{EventFinalizers, EventStatus},
+        proto, Event,
+    },
+};
+use bytes::Bytes;
+use futures01::{
+    task::{self, AtomicTask, Task},
+    Async, AsyncSink, Poll, Sink, Stream,
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use std::{
+    collections::VecDeque,
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Length and checksum of the payload, both little endian `u32`s.
+const HEADER_LEN: usize = 8;
+/// Largest payload a record may have. A header claiming more than that is
+/// corrupted, and is never trusted to size an allocation.
+const MAX_RECORD_LEN: usize = 128 << 20;
+const SEGMENT_EXTENSION: &str = "seg";
+const CURSOR_FILE: &str = "cursor";
+const CURSOR_TMP_FILE: &str = "cursor.tmp";
+/// Number of records written before the writer flushes on its own.
+const FLUSH_BATCH_SIZE: usize = 100;
+
+/// When writes to the active segment are synced to stable storage.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPolicy {
+    /// Leave syncing to the operating system. Buffered events survive a
+    /// process crash, but not necessarily a host crash.
+    Never,
+    /// Sync whenever the writer flushes, which happens at least every 100
+    /// events.
+    Flush,
+    /// Sync after every event.
+    Always,
+}
+
+impl Default for SyncPolicy {
+    fn default() -> Self {
+        SyncPolicy::Flush
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub max_size: usize,
+    pub max_segment_size: usize,
+    pub sync: SyncPolicy,
+}
+
+/// A position in the buffer: a segment id and a byte offset within it.
+type Position = (u64, u64);
+
+struct ActiveSegment {
+    id: u64,
+    file: BufWriter<File>,
+    size: usize,
+}
+
+struct Shared {
+    dir: PathBuf,
+    options: Options,
+    segment: Mutex<ActiveSegment>,
+    // Id of the segment being written. Every segment before it is complete
+    // and fully flushed.
+    head: AtomicU64,
+    current_size: Arc<AtomicUsize>,
+    // Number of records appended that the reader hasn't reached yet,
+    // including ones that haven't been flushed.
+    unread: Arc<AtomicUsize>,
+    write_notifier: Arc<AtomicTask>,
+    blocked_write_tasks: Arc<Mutex<Vec<Task>>>,
+}
+
+impl Shared {
+    fn append(&self, payload: &[u8]) -> io::Result<()> {
+        let mut segment = self.segment.lock().unwrap();
+
+        let record_len = HEADER_LEN + payload.len();
+        if segment.size > 0 && segment.size + record_len > self.options.max_segment_size {
+            self.roll(&mut segment)?;
+        }
+
+        segment.file.write_all(&encode_header(payload))?;
+        segment.file.write_all(payload)?;
+        segment.size += record_len;
+        self.unread.fetch_add(1, Ordering::Release);
+
+        if self.options.sync == SyncPolicy::Always {
+            segment.file.flush()?;
+            segment.file.get_ref().sync_data()?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        let mut segment = self.segment.lock().unwrap();
+        segment.file.flush()?;
+        if self.options.sync == SyncPolicy::Flush {
+            segment.file.get_ref().sync_data()?;
+        }
+        drop(segment);
+
+        self.write_notifier.notify();
+        Ok(())
+    }
+
+    /// Completes the active segment and starts the next one.
+    fn roll(&self, segment: &mut ActiveSegment) -> io::Result<()> {
+        segment.file.flush()?;
+        if self.options.sync != SyncPolicy::Never {
+            segment.file.get_ref().sync_data()?;
+        }
+
+        let id = segment.id + 1;
+        *segment = ActiveSegment {
+            id,
+            file: BufWriter::new(create_segment(&self.dir, id)?),
+            size: 0,
+        };
+        // Only published once the previous segment is flushed, so the reader
+        // can treat the end of any earlier segment as final.
+        self.head.store(id, Ordering::Release);
+        self.write_notifier.notify();
+
+        Ok(())
+    }
+}
+
+pub struct Writer {
+    shared: Arc<Shared>,
+    unflushed: usize,
+    // Events are durable once they have been flushed, so their finalizers
+    // are held until then instead of being written to disk.
+    pending_finalizers: EventFinalizers,
+}
+
+impl Clone for Writer {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+            unflushed: 0,
+            pending_finalizers: EventFinalizers::default(),
+        }
+    }
+}
+
+impl Sink for Writer {
+    type SinkItem = Event;
+    type SinkError = ();
+
+    fn start_send(
+        &mut self,
+        mut event: Self::SinkItem,
+    ) -> Result<AsyncSink<Self::SinkItem>, Self::SinkError> {
+        let finalizers = event.take_finalizers();
+        let mut value = vec![];
+        proto::EventWrapper::from(event).encode(&mut value).unwrap(); // This will not error when writing to a Vec
+        if value.len() > MAX_RECORD_LEN {
+            error!(
+                message = "Event is too large for the disk buffer; dropping it.",
+                size = value.len()
+            );
+            finalizers.update_status(EventStatus::Failed);
+            return Ok(AsyncSink::Ready);
+        }
+        let record_len = HEADER_LEN + value.len();
+
+        let current_size = &self.shared.current_size;
+        if current_size.fetch_add(record_len, Ordering::Relaxed) + (record_len / 2)
+            > self.shared.options.max_size
+        {
+            self.shared
+                .blocked_write_tasks
+                .lock()
+                .unwrap()
+                .push(task::current());
+
+            current_size.fetch_sub(record_len, Ordering::Relaxed);
+
+            self.poll_complete()?;
+
+            let buf = Bytes::from(value);
+            let mut event: Event = proto::EventWrapper::decode(buf).unwrap().into();
+            if let Event::Log(log) = &mut event {
+                log.merge_finalizers(finalizers);
+            }
+            return Ok(AsyncSink::NotReady(event));
+        }
+
+        self.shared.append(&value).map_err(|error| {
+            error!(message = "Error writing to disk buffer.", %error);
+        })?;
+        self.unflushed += 1;
+        self.pending_finalizers.merge(finalizers);
+
+        if self.unflushed >= FLUSH_BATCH_SIZE {
+            self.poll_complete()?;
+        }
+
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Result<Async<()>, Self::SinkError> {
+        if self.unflushed > 0 {
+            self.flush().map_err(|error| {
+                error!(message = "Error flushing disk buffer.", %error);
+            })?;
+        }
+
+        Ok(Async::Ready(()))
+    }
+}
+
+impl Writer {
+    fn flush(&mut self) -> io::Result<()> {
+        self.shared.flush()?;
+        self.unflushed = 0;
+        std::mem::take(&mut self.pending_finalizers).update_status(EventStatus::Delivered);
+        Ok(())
+    }
+
+    /// Appends an already encoded event, such as one migrated from another
+    /// buffer, bypassing the size limit.
+    pub(super) fn append_encoded(&mut self, value: &[u8]) -> io::Result<()> {
+        if value.len() > MAX_RECORD_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "event is too large for the disk buffer",
+            ));
+        }
+        self.shared
+            .current_size
+            .fetch_add(HEADER_LEN + value.len(), Ordering::Relaxed);
+        self.shared.append(value)?;
+        self.unflushed += 1;
+        Ok(())
+    }
+
+    /// Flushes and syncs everything written so far, regardless of the
+    /// configured sync policy.
+    pub(super) fn sync(&mut self) -> io::Result<()> {
+        self.flush()?;
+        let segment = self.shared.segment.lock().unwrap();
+        segment.file.get_ref().sync_data()
+    }
+
+    /// Counts the events that were written but not yet read. Events are
+    /// counted as soon as they are written, before they are flushed.
+    pub(super) fn unread_counter(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.shared.unread)
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        if self.unflushed > 0 {
+            if let Err(error) = self.flush() {
+                error!(message = "Error flushing disk buffer.", %error);
+            }
+        }
+
+        // We need to wake up the reader so it can return None if there are no more writers
+        self.shared.write_notifier.notify();
+    }
+}
+
+pub struct Reader {
+    shared: Arc<Shared>,
+    segment_id: u64,
+    offset: u64,
+    file: Option<BufReader<File>>,
+    cursor: Position,
+    ack_counter: Arc<AtomicUsize>,
+    // The position just past each record that was read but not acked yet,
+    // along with its size.
+    unacked: VecDeque<(Position, usize)>,
+}
+
+impl Stream for Reader {
+    type Item = Event;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.delete_acked();
+
+        // If there's no complete record to read, we return NotReady and rely on
+        // Writer using write_notifier to wake this task up after the next flush.
+        self.shared.write_notifier.register();
+
+        loop {
+            // Checked before reading, so a record flushed by the last writer
+            // on its way out is still read.
+            let writers_gone = Arc::strong_count(&self.shared) == 1;
+
+            let value = tokio::task::block_in_place(|| self.read_record()).map_err(|error| {
+                error!(message = "Error reading from disk buffer.", %error);
+            })?;
+
+            match value {
+                Some(value) => match proto::EventWrapper::decode(Bytes::from(value)) {
+                    Ok(event) => return Ok(Async::Ready(Some(Event::from(event)))),
+                    Err(error) => {
+                        error!(message = "Error deserializing proto.", %error);
+                        debug_assert!(false);
+                    }
+                },
+                None if writers_gone => return Ok(Async::Ready(None)),
+                None => return Ok(Async::NotReady),
+            }
+        }
+    }
+}
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        self.delete_acked();
+    }
+}
+
+impl Reader {
+    /// Reads the next record, moving on to the following segment once the
+    /// current one is exhausted. Returns `None` if no complete record has
+    /// been flushed yet.
+    fn read_record(&mut self) -> io::Result<Option<Vec<u8>>> {
+        loop {
+            let head = self.shared.head.load(Ordering::Acquire);
+
+            if self.file.is_none() {
+                match File::open(segment_path(&self.shared.dir, self.segment_id)) {
+                    Ok(file) => {
+                        let mut file = BufReader::new(file);
+                        file.seek(SeekFrom::Start(self.offset))?;
+                        self.file = Some(file);
+                    }
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                        if self.segment_id < head {
+                            self.next_segment();
+                            continue;
+                        }
+                        return Ok(None);
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+
+            let file = self.file.as_mut().expect("segment file is open");
+            match read_record(file)? {
+                ReadRecord::Complete(payload) => {
+                    let record_len = HEADER_LEN + payload.len();
+                    self.offset += record_len as u64;
+                    self.unacked
+                        .push_back(((self.segment_id, self.offset), record_len));
+                    self.shared.unread.fetch_sub(1, Ordering::Release);
+                    return Ok(Some(payload));
+                }
+                ReadRecord::Corrupted(len) => {
+                    error!(
+                        message = "Skipping corrupted disk buffer record.",
+                        segment = self.segment_id,
+                        offset = self.offset
+                    );
+                    // The cursor moves past the skipped record once the next
+                    // record is acked.
+                    let record_len = HEADER_LEN + len;
+                    self.offset += record_len as u64;
+                    self.shared
+                        .current_size
+                        .fetch_sub(record_len, Ordering::Relaxed);
+                    self.shared.unread.fetch_sub(1, Ordering::Release);
+                }
+                ReadRecord::Incomplete if self.segment_id < head => {
+                    // The segment is complete, so there is nothing more in it.
+                    self.next_segment();
+                }
+                ReadRecord::Incomplete => {
+                    file.seek(SeekFrom::Start(self.offset))?;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn next_segment(&mut self) {
+        self.segment_id += 1;
+        self.offset = 0;
+        self.file = None;
+    }
+
+    fn delete_acked(&mut self) {
+        let num_to_delete = self.ack_counter.swap(0, Ordering::Relaxed);
+
+        if num_to_delete > 0 {
+            assert!(
+                num_to_delete <= self.unacked.len(),
+                "Tried to ack beyond read offset"
+            );
+
+            let mut cursor = self.cursor;
+            let mut size_deleted = 0;
+            for (position, size) in self.unacked.drain(..num_to_delete) {
+                cursor = position;
+                size_deleted += size;
+            }
+
+            if let Err(error) = self.advance_cursor(cursor) {
+                error!(message = "Error updating disk buffer cursor.", %error);
+            }
+
+            self.shared
+                .current_size
+                .fetch_sub(size_deleted, Ordering::Relaxed);
+        }
+
+        for task in self.shared.blocked_write_tasks.lock().unwrap().drain(..) {
+            task.notify();
+        }
+    }
+
+    fn advance_cursor(&mut self, cursor: Position) -> io::Result<()> {
+        write_cursor(&self.shared.dir, cursor)?;
+
+        for id in self.cursor.0..cursor.0 {
+            match fs::remove_file(segment_path(&self.shared.dir, id)) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                _ => {}
+            }
+        }
+        self.cursor = cursor;
+
+        Ok(())
+    }
+}
+
+pub struct Buffer;
+
+impl Buffer {
+    pub fn build(path: PathBuf, options: Options) -> Result<(Writer, Reader, Acker), Error> {
+        fs::create_dir_all(&path).with_context(|| BufferIoError { path: path.clone() })?;
+
+        let (cursor, current_size, unread, head) =
+            recover(&path).with_context(|| BufferIoError { path: path.clone() })?;
+        let file =
+            create_segment(&path, head).with_context(|| BufferIoError { path: path.clone() })?;
+
+        let write_notifier = Arc::new(AtomicTask::new());
+        let ack_counter = Arc::new(AtomicUsize::new(0));
+        let acker = Acker::Disk(Arc::clone(&ack_counter), Arc::clone(&write_notifier));
+
+        let shared = Arc::new(Shared {
+            dir: path,
+            options,
+            segment: Mutex::new(ActiveSegment {
+                id: head,
+                file: BufWriter::new(file),
+                size: 0,
+            }),
+            head: AtomicU64::new(head),
+            current_size: Arc::new(AtomicUsize::new(current_size)),
+            unread: Arc::new(AtomicUsize::new(unread)),
+            write_notifier,
+            blocked_write_tasks: Arc::new(Mutex::new(Vec::new())),
+        });
+
+        let writer = Writer {
+            shared: Arc::clone(&shared),
+            unflushed: 0,
+            pending_finalizers: EventFinalizers::default(),
+        };
+
+        let reader = Reader {
+            shared,
+            segment_id: cursor.0,
+            offset: cursor.1,
+            file: None,
+            cursor,
+            ack_counter,
+            unacked: VecDeque::new(),
+        };
+
+        Ok((writer, reader, acker))
+    }
+}
+
+/// Validates the segments left by a previous run, returning the read cursor,
+/// the size and number of the unacknowledged records and the id of the next
+/// segment.
+fn recover(dir: &Path) -> io::Result<(Position, usize, usize, u64)> {
+    let segments = list_segments(dir)?;
+    let cursor = read_cursor(dir)?.unwrap_or_else(|| (segments.first().copied().unwrap_or(0), 0));
+
+    let mut current_size = 0;
+    let mut records = 0;
+    for &id in &segments {
+        if id < cursor.0 {
+            // Fully acknowledged before the segment could be deleted.
+            fs::remove_file(segment_path(dir, id))?;
+        } else {
+            let start = if id == cursor.0 { cursor.1 } else { 0 };
+            let (size, count) = recover_segment(dir, id, start)?;
+            current_size += size;
+            records += count;
+        }
+    }
+
+    // The next run always writes to a fresh segment, after both the last
+    // segment and the one the cursor points into.
+    let head = segments.last().copied().unwrap_or(cursor.0).max(cursor.0) + 1;
+
+    Ok((cursor, current_size, records, head))
+}
+
+/// Truncates a segment at its first torn record, returning the size and
+/// number of the records starting at `start`. Corrupted records are counted
+/// too, since the reader skips them the same way it reads valid ones.
+fn recover_segment(dir: &Path, id: u64, start: u64) -> io::Result<(usize, usize)> {
+    let path = segment_path(dir, id);
+    let mut file = BufReader::new(File::open(&path)?);
+
+    let mut offset = 0u64;
+    let mut size = 0;
+    let mut records = 0;
+    loop {
+        let record_len = match read_record(&mut file)? {
+            ReadRecord::Complete(payload) => HEADER_LEN + payload.len(),
+            ReadRecord::Corrupted(len) => HEADER_LEN + len,
+            ReadRecord::Incomplete => break,
+        };
+        if offset >= start {
+            size += record_len;
+            records += 1;
+        }
+        offset += record_len as u64;
+    }
+
+    let len = file.get_ref().metadata()?.len();
+    if offset < len {
+        warn!(
+            message = "Truncating torn disk buffer segment.",
+            path = ?path,
+            valid_bytes = offset,
+            dropped_bytes = len - offset
+        );
+        OpenOptions::new()
+            .write(true)
+            .open(&path)?
+            .set_len(offset)?;
+    }
+
+    Ok((size, records))
+}
+
+enum ReadRecord {
+    Complete(Vec<u8>),
+    /// A record whose checksum doesn't match, with the length of its payload.
+    Corrupted(usize),
+    /// The end of the data available so far, possibly with part of a record.
+    Incomplete,
+}
+
+fn read_record(input: &mut impl Read) -> io::Result<ReadRecord> {
+    let mut header = [0; HEADER_LEN];
+    if !read_full(input, &mut header)? {
+        return Ok(ReadRecord::Incomplete);
+    }
+
+    let len = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+    let checksum = u32::from_le_bytes(header[4..].try_into().unwrap());
+
+    if len > MAX_RECORD_LEN {
+        // Skipped without being read into memory.
+        let skipped = io::copy(&mut input.take(len as u64), &mut io::sink())?;
+        return Ok(if skipped < len as u64 {
+            ReadRecord::Incomplete
+        } else {
+            ReadRecord::Corrupted(len)
+        });
+    }
+
+    let mut payload = vec![0; len];
+    if !read_full(input, &mut payload)? {
+        return Ok(ReadRecord::Incomplete);
+    }
+
+    if crc32fast::hash(&payload) == checksum {
+        Ok(ReadRecord::Complete(payload))
+    } else {
+        Ok(ReadRecord::Corrupted(len))
+    }
+}
+
+/// Like `read_exact`, but returns `false` instead of failing at end of file.
+fn read_full(input: &mut impl Read, mut buf: &mut [u8]) -> io::Result<bool> {
+    while !buf.is_empty() {
+        match input.read(buf) {
+            Ok(0) => return Ok(false),
+            Ok(n) => buf = &mut buf[n..],
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(true)
+}
+
+fn encode_header(payload: &[u8]) -> [u8; HEADER_LEN] {
+    let mut header = [0; HEADER_LEN];
+    header[..4].copy_from_slice(&(payload.len() as u32).to_le_bytes());
+    header[4..].copy_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    header
+}
+
+fn segment_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", id, SEGMENT_EXTENSION))
+}
+
+fn create_segment(dir: &Path, id: u64) -> io::Result<File> {
+    OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(segment_path(dir, id))
+}
+
+fn list_segments(dir: &Path) -> io::Result<Vec<u64>> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some(SEGMENT_EXTENSION) {
+            if let Some(id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse().ok())
+            {
+                segments.push(id);
+            }
+        }
+    }
+    segments.sort_unstable();
+    Ok(segments)
+}
+
+/// Reads the persisted cursor, ignoring it if it is missing or damaged, in
+/// which case reading starts over from the oldest segment.
+fn read_cursor(dir: &Path) -> io::Result<Option<Position>> {
+    let data = match fs::read(dir.join(CURSOR_FILE)) {
+        Ok(data) => data,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    match read_record(&mut data.as_slice())? {
+        ReadRecord::Complete(payload) if payload.len() == 16 => {
+            let segment = u64::from_le_bytes(payload[..8].try_into().unwrap());
+            let offset = u64::from_le_bytes(payload[8..].try_into().unwrap());
+            Ok(Some((segment, offset)))
+        }
+        _ => {
+            warn!(message = "Ignoring damaged disk buffer cursor.", dir = ?dir);
+            Ok(None)
+        }
+    }
+}
+
+/// Atomically replaces the persisted cursor.
+fn write_cursor(dir: &Path, (segment, offset): Position) -> io::Result<()> {
+    let mut payload = Vec::with_capacity(16);
+    payload.extend_from_slice(&segment.to_le_bytes());
+    payload.extend_from_slice(&offset.to_le_bytes());
+
+    let tmp_path = dir.join(CURSOR_TMP_FILE);
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(&encode_header(&payload))?;
+    file.write_all(&payload)?;
+    file.sync_data()?;
+    fs::rename(tmp_path, dir.join(CURSOR_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, test_util::temp_dir};
+    use tokio01_test::task::MockTask;
+
+    fn options(max_segment_size: usize) -> Options {
+        Options {
+            max_size: 1 << 20,
+            max_segment_size,
+            sync: SyncPolicy::Flush,
+        }
+    }
+
+    fn write(writer: &mut Writer, messages: &[&str]) {
+        for message in messages {
+            assert!(writer.start_send(Event::from(*message)).unwrap().is_ready());
+        }
+        writer.poll_complete().unwrap();
+    }
+
+    fn read(reader: &mut Reader, count: usize) -> Vec<String> {
+        let mut mock = MockTask::new();
+        (0..count)
+            .map(|_| match mock.enter(|| reader.poll()) {
+                Ok(Async::Ready(Some(event))) => {
+                    event.as_log()[log_schema().message_key()].to_string_lossy()
+                }
+                other => panic!("unexpected poll result: {:?}", other),
+            })
+            .collect()
+    }
+
+    fn segment_count(dir: &Path) -> usize {
+        list_segments(dir).unwrap().len()
+    }
+
+    #[test]
+    fn reads_across_segments() {
+        let dir = temp_dir();
+        let (mut writer, mut reader, acker) = Buffer::build(dir.clone(), options(16)).unwrap();
+
+        write(&mut writer, &["one", "two", "three", "four"]);
+        assert!(segment_count(&dir) > 1);
+        assert_eq!(read(&mut reader, 4), vec!["one", "two", "three", "four"]);
+
+        let mut mock = MockTask::new();
+        assert!(mock.enter(|| reader.poll()).unwrap().is_not_ready());
+
+        acker.ack(4);
+        drop(writer);
+        assert_eq!(mock.enter(|| reader.poll()), Ok(Async::Ready(None)));
+        assert_eq!(segment_count(&dir), 1);
+    }
+
+    #[test]
+    fn resumes_after_acked_events() {
+        let dir = temp_dir();
+        let (mut writer, mut reader, acker) = Buffer::build(dir.clone(), options(64)).unwrap();
+        write(&mut writer, &["one", "two", "three"]);
+        assert_eq!(read(&mut reader, 2), vec!["one", "two"]);
+        acker.ack(1);
+        drop((writer, reader));
+
+        let (_writer, mut reader, _acker) = Buffer::build(dir, options(64)).unwrap();
+        assert_eq!(read(&mut reader, 2), vec!["two", "three"]);
+    }
+
+    #[test]
+    fn recovers_from_torn_write() {
+        let dir = temp_dir();
+        let (mut writer, reader, _acker) = Buffer::build(dir.clone(), options(1 << 20)).unwrap();
+        write(&mut writer, &["one", "two"]);
+        drop((writer, reader));
+
+        let segment = segment_path(&dir, list_segments(&dir).unwrap()[0]);
+        let mut file = OpenOptions::new().append(true).open(&segment).unwrap();
+        file.write_all(&[42, 0, 0, 0, 1, 2]).unwrap();
+        drop(file);
+
+        let (mut writer, mut reader, _acker) = Buffer::build(dir, options(1 << 20)).unwrap();
+        write(&mut writer, &["three"]);
+        assert_eq!(read(&mut reader, 3), vec!["one", "two", "three"]);
+    }
+
+    #[test]
+    fn skips_corrupted_records() {
+        let dir = temp_dir();
+        let (mut writer, mut reader, _acker) =
+            Buffer::build(dir.clone(), options(1 << 20)).unwrap();
+        write(&mut writer, &["one"]);
+
+        let segment = segment_path(&dir, list_segments(&dir).unwrap()[0]);
+        let mut data = fs::read(&segment).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        fs::write(&segment, data).unwrap();
+
+        write(&mut writer, &["two"]);
+        assert_eq!(read(&mut reader, 1), vec!["two"]);
+    }
+
+    #[test]
+    fn recovers_past_corrupted_records() {
+        let dir = temp_dir();
+        let (mut writer, reader, _acker) = Buffer::build(dir.clone(), options(1 << 20)).unwrap();
+        write(&mut writer, &["one"]);
+        let segment = segment_path(&dir, list_segments(&dir).unwrap()[0]);
+        let first_len = fs::metadata(&segment).unwrap().len() as usize;
+        write(&mut writer, &["two", "three"]);
+        drop((writer, reader));
+
+        let mut data = fs::read(&segment).unwrap();
+        data[first_len + HEADER_LEN] ^= 0xff;
+        fs::write(&segment, data).unwrap();
+
+        let (mut writer, mut reader, _acker) = Buffer::build(dir, options(1 << 20)).unwrap();
+        write(&mut writer, &["four"]);
+        assert_eq!(read(&mut reader, 3), vec!["one", "three", "four"]);
+    }
+
+    #[test]
+    fn recovers_from_oversized_record_length() {
+        let dir = temp_dir();
+        let (mut writer, reader, _acker) = Buffer::build(dir.clone(), options(1 << 20)).unwrap();
+        write(&mut writer, &["one"]);
+        drop((writer, reader));
+
+        let segment = segment_path(&dir, list_segments(&dir).unwrap()[0]);
+        let mut file = OpenOptions::new().append(true).open(&segment).unwrap();
+        file.write_all(&[0xff, 0xff, 0xff, 0xff, 1, 2, 3, 4, 5, 6])
+            .unwrap();
+        drop(file);
+
+        let (mut writer, mut reader, _acker) = Buffer::build(dir, options(1 << 20)).unwrap();
+        write(&mut writer, &["two"]);
+        assert_eq!(read(&mut reader, 2), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn blocks_when_full() {
+        let dir = temp_dir();
+        let options = Options {
+            max_size: 100,
+            ..options(1 << 20)
+        };
+        let (mut writer, mut reader, acker) = Buffer::build(dir, options).unwrap();
+
+        let mut mock = MockTask::new();
+        let mut sent = 0;
+        while mock
+            .enter(|| writer.start_send(Event::from("some message")))
+            .unwrap()
+            .is_ready()
+        {
+            sent += 1;
+        }
+        writer.poll_complete().unwrap();
+        assert!(sent > 0);
+
+        // Reading doesn't free up any space until the events are acked.
+        read(&mut reader, sent);
+        assert!(mock
+            .enter(|| writer.start_send(Event::from("some message")))
+            .unwrap()
+            .is_not_ready());
+
+        acker.ack(sent);
+        mock.enter(|| reader.poll()).unwrap();
+        assert!(mock.is_notified());
+        assert!(writer
+            .start_send(Event::from("some message"))
+            .unwrap()
+            .is_ready());
+    }
+}
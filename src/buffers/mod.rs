@@ -1,14 +1,15 @@
-use crate::Event;
+use crate::{event::LogEvent, Event};
 use futures01::{sync::mpsc, task::AtomicTask, AsyncSink, Poll, Sink, StartSend, Stream};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
 };
 
-#[cfg(feature = "leveldb")]
 pub mod disk;
+pub mod overflow;
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type")]
@@ -19,6 +20,9 @@ pub enum BufferConfig {
         max_events: usize,
         #[serde(default)]
         when_full: WhenFull,
+        /// Where events go once `max_events` is reached, with `when_full = "overflow"`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        overflow: Option<Box<BufferConfig>>,
     },
     #[cfg(feature = "leveldb")]
     Disk {
@@ -26,6 +30,15 @@ pub enum BufferConfig {
         #[serde(default)]
         when_full: WhenFull,
     },
+    DiskV2 {
+        max_size: usize,
+        #[serde(default = "BufferConfig::disk_v2_max_segment_size")]
+        max_segment_size: usize,
+        #[serde(default)]
+        sync: disk::segmented_buffer::SyncPolicy,
+        #[serde(default)]
+        when_full: WhenFull,
+    },
 }
 
 impl Default for BufferConfig {
@@ -33,6 +46,7 @@ impl Default for BufferConfig {
         BufferConfig::Memory {
             max_events: BufferConfig::memory_max_events(),
             when_full: Default::default(),
+            overflow: None,
         }
     }
 }
@@ -42,6 +56,7 @@ impl Default for BufferConfig {
 pub enum WhenFull {
     Block,
     DropNewest,
+    Overflow,
 }
 
 impl Default for WhenFull {
@@ -52,8 +67,8 @@ impl Default for WhenFull {
 
 pub enum BufferInputCloner {
     Memory(mpsc::Sender<Event>, WhenFull),
-    #[cfg(feature = "leveldb")]
     Disk(disk::Writer, WhenFull),
+    Overflow(overflow::Writer, WhenFull),
 }
 
 impl BufferInputCloner {
@@ -70,7 +85,6 @@ impl BufferInputCloner {
                 }
             }
 
-            #[cfg(feature = "leveldb")]
             BufferInputCloner::Disk(writer, when_full) => {
                 if when_full == &WhenFull::DropNewest {
                     Box::new(DropWhenFull {
@@ -80,6 +94,16 @@ impl BufferInputCloner {
                     Box::new(writer.clone())
                 }
             }
+
+            BufferInputCloner::Overflow(writer, when_full) => {
+                if when_full == &WhenFull::DropNewest {
+                    Box::new(DropWhenFull {
+                        inner: writer.clone(),
+                    })
+                } else {
+                    Box::new(writer.clone())
+                }
+            }
         }
     }
 }
@@ -90,7 +114,11 @@ impl BufferConfig {
         500
     }
 
-    #[cfg_attr(not(feature = "leveldb"), allow(unused))]
+    #[inline]
+    const fn disk_v2_max_segment_size() -> usize {
+        128 * 1024 * 1024
+    }
+
     pub fn build(
         &self,
         data_dir: &Option<PathBuf>,
@@ -104,9 +132,43 @@ impl BufferConfig {
         String,
     > {
         match &self {
+            BufferConfig::Memory {
+                max_events,
+                when_full: WhenFull::Overflow,
+                overflow,
+            } => {
+                let overflow = match overflow.as_deref() {
+                    Some(overflow @ BufferConfig::DiskV2 { .. }) => overflow,
+                    Some(_) => return Err("The overflow buffer must be a disk_v2 buffer.".into()),
+                    None => {
+                        return Err(
+                            "Must set an overflow buffer to use when_full = \"overflow\".".into(),
+                        )
+                    }
+                };
+
+                let (overflow_tx, overflow_rx, overflow_acker) =
+                    overflow.build(data_dir, sink_name)?;
+                let (overflow_tx, when_full) = match overflow_tx {
+                    BufferInputCloner::Disk(writer, when_full) => (writer, when_full),
+                    _ => unreachable!("disk_v2 buffers are written through disk::Writer"),
+                };
+
+                let (tx, rx, acker) =
+                    overflow::build(*max_events, overflow_tx, overflow_rx, overflow_acker)
+                        .expect("disk_v2 buffers count unread events");
+                let tx = BufferInputCloner::Overflow(tx, when_full);
+                Ok((tx, Box::new(rx), acker))
+            }
+
+            BufferConfig::Memory {
+                overflow: Some(_), ..
+            } => Err("The overflow buffer is only used with when_full = \"overflow\".".into()),
+
             BufferConfig::Memory {
                 max_events,
                 when_full,
+                overflow: None,
             } => {
                 let (tx, rx) = mpsc::channel(*max_events);
                 let tx = BufferInputCloner::Memory(tx, *when_full);
@@ -119,6 +181,7 @@ impl BufferConfig {
                 max_size,
                 when_full,
             } => {
+                check_disk_when_full(*when_full)?;
                 let data_dir = data_dir
                     .as_ref()
                     .ok_or_else(|| "Must set data_dir to use on-disk buffering.".to_string())?;
@@ -130,13 +193,48 @@ impl BufferConfig {
                 let rx = Box::new(rx);
                 Ok((tx, rx, acker))
             }
+
+            BufferConfig::DiskV2 {
+                max_size,
+                max_segment_size,
+                sync,
+                when_full,
+            } => {
+                check_disk_when_full(*when_full)?;
+                let data_dir = data_dir
+                    .as_ref()
+                    .ok_or_else(|| "Must set data_dir to use on-disk buffering.".to_string())?;
+                let buffer_dir = format!("{}_buffer_v2", sink_name);
+                // Where the leveldb based `disk` buffer keeps its data.
+                let legacy_dir = format!("{}_buffer", sink_name);
+                let options = disk::segmented_buffer::Options {
+                    max_size: *max_size,
+                    max_segment_size: *max_segment_size,
+                    sync: *sync,
+                };
+
+                let (tx, rx, acker) =
+                    disk::open_segmented(&data_dir, &buffer_dir, &legacy_dir, options)
+                        .map_err(|error| error.to_string())?;
+                let tx = BufferInputCloner::Disk(tx, *when_full);
+                Ok((tx, rx, acker))
+            }
         }
     }
 }
 
+fn check_disk_when_full(when_full: WhenFull) -> Result<(), String> {
+    if when_full == WhenFull::Overflow {
+        Err("Only memory buffers can overflow, as disk buffers have nowhere to overflow to.".into())
+    } else {
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Acker {
     Disk(Arc<AtomicUsize>, Arc<AtomicTask>),
+    Overflow(Arc<overflow::OverflowAcks>, Box<Acker>),
     Null,
 }
 
@@ -156,6 +254,10 @@ impl Acker {
                     counter.fetch_add(num, Ordering::Relaxed);
                     notifier.notify();
                 }
+                Acker::Overflow(acks, inner) => {
+                    // Only events read from disk are tracked by the disk buffer.
+                    inner.ack(acks.ack(num));
+                }
             }
         }
     }
@@ -169,6 +271,54 @@ impl Acker {
     }
 }
 
+/// Why a sink's destination rejected a request, as reported by the sink's
+/// retry logic when it decides not to retry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rejection {
+    pub reason: String,
+    /// The status of the response, such as an HTTP status code.
+    pub status: Option<u16>,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Request rejected: {}", self.reason)
+    }
+}
+
+impl std::error::Error for Rejection {}
+
+/// The dead-letter output of a sink. Log events the sink's destination rejects
+/// are handed back by their finalizers and sent here, annotated with the
+/// rejection.
+#[derive(Debug)]
+pub struct DeadLetter {
+    sink: String,
+    tx: mpsc::UnboundedSender<Event>,
+}
+
+impl DeadLetter {
+    pub fn new(sink: &str, tx: mpsc::UnboundedSender<Event>) -> Self {
+        Self {
+            sink: sink.into(),
+            tx,
+        }
+    }
+
+    pub fn send(&self, mut log: LogEvent, rejection: Option<Rejection>) {
+        log.insert("dead_letter.sink", self.sink.clone());
+        if let Some(rejection) = rejection {
+            log.insert("dead_letter.reason", rejection.reason);
+            if let Some(status) = rejection.status {
+                log.insert("dead_letter.status", status as i64);
+            }
+        }
+        // Fails only once the dead-letter output is gone, in which case
+        // nothing is left to route the event to.
+        let _ = self.tx.unbounded_send(log.into());
+    }
+}
+
 pub struct DropWhenFull<S> {
     inner: S,
 }
@@ -197,7 +347,7 @@ impl<S: Sink> Sink for DropWhenFull<S> {
 
 #[cfg(test)]
 mod test {
-    use super::{Acker, BufferConfig, DropWhenFull, WhenFull};
+    use super::{disk::segmented_buffer::SyncPolicy, Acker, BufferConfig, DropWhenFull, WhenFull};
     use futures::compat::Future01CompatExt;
     use futures01::{future, sync::mpsc, task::AtomicTask, Async, AsyncSink, Sink, Stream};
     use std::sync::{atomic::AtomicUsize, Arc};
@@ -258,6 +408,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 500,
                 when_full: WhenFull::Block,
+                overflow: None,
             },
         );
 
@@ -269,6 +420,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 100,
                 when_full: WhenFull::Block,
+                overflow: None,
             },
         );
 
@@ -280,6 +432,7 @@ mod test {
             BufferConfig::Memory {
                 max_events: 500,
                 when_full: WhenFull::DropNewest,
+                overflow: None,
             },
         );
 
@@ -294,5 +447,102 @@ mod test {
                 when_full: WhenFull::Block,
             },
         );
+
+        check(
+            r#"
+          type = "disk_v2"
+          max_size = 1024
+          "#,
+            BufferConfig::DiskV2 {
+                max_size: 1024,
+                max_segment_size: 128 * 1024 * 1024,
+                sync: SyncPolicy::Flush,
+                when_full: WhenFull::Block,
+            },
+        );
+
+        check(
+            r#"
+          type = "disk_v2"
+          max_size = 1024
+          max_segment_size = 256
+          sync = "always"
+          "#,
+            BufferConfig::DiskV2 {
+                max_size: 1024,
+                max_segment_size: 256,
+                sync: SyncPolicy::Always,
+                when_full: WhenFull::Block,
+            },
+        );
+
+        check(
+            r#"
+          type = "memory"
+          when_full = "overflow"
+
+          [overflow]
+          type = "disk_v2"
+          max_size = 1024
+          "#,
+            BufferConfig::Memory {
+                max_events: 500,
+                when_full: WhenFull::Overflow,
+                overflow: Some(Box::new(BufferConfig::DiskV2 {
+                    max_size: 1024,
+                    max_segment_size: 128 * 1024 * 1024,
+                    sync: SyncPolicy::Flush,
+                    when_full: WhenFull::Block,
+                })),
+            },
+        );
+    }
+
+    #[test]
+    fn overflow_requires_disk_v2_buffer() {
+        fn build_error(source: &str) -> String {
+            let data_dir = Some(std::env::temp_dir());
+            let conf: BufferConfig = toml::from_str(source).unwrap();
+            conf.build(&data_dir, "out").err().unwrap()
+        }
+
+        assert!(build_error(
+            r#"
+          type = "memory"
+          when_full = "overflow"
+          "#
+        )
+        .starts_with("Must set an overflow buffer"));
+
+        assert!(build_error(
+            r#"
+          type = "memory"
+          when_full = "overflow"
+
+          [overflow]
+          type = "memory"
+          "#
+        )
+        .contains("must be a disk_v2 buffer"));
+
+        assert!(build_error(
+            r#"
+          type = "memory"
+
+          [overflow]
+          type = "disk_v2"
+          max_size = 1024
+          "#
+        )
+        .contains("only used with when_full"));
+
+        assert!(build_error(
+            r#"
+          type = "disk_v2"
+          max_size = 1024
+          when_full = "overflow"
+          "#
+        )
+        .starts_with("Only memory buffers can overflow"));
     }
 }
@@ -0,0 +1,295 @@
+//! A buffer that keeps events in memory and spills them into a disk buffer
+//! while the in-memory channel is full.
+//!
+//! Once an event has spilled, every following event is written to disk as
+//! well until the reader has caught up with the disk buffer. Everything on
+//! disk is therefore newer than what is left in memory, and reading memory
+//! first preserves the order the events were written in.
+
+use super::{disk, Acker};
+use crate::Event;
+use futures01::{sync::mpsc, Async, AsyncSink, Poll, Sink, StartSend, Stream};
+use std::collections::VecDeque;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+struct Shared {
+    // Whether writers go straight to disk. Held while writing, so that the
+    // reader can't switch back to memory in the middle of a write.
+    spilled: Mutex<bool>,
+    // Events written to the disk buffer that haven't been read yet.
+    unread: Arc<AtomicUsize>,
+}
+
+#[derive(Clone)]
+pub struct Writer {
+    memory: mpsc::Sender<Event>,
+    overflow: disk::Writer,
+    shared: Arc<Shared>,
+}
+
+impl Sink for Writer {
+    type SinkItem = Event;
+    type SinkError = ();
+
+    fn start_send(&mut self, event: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        let mut spilled = self.shared.spilled.lock().expect("poisoned lock");
+
+        let event = if *spilled {
+            event
+        } else {
+            match self.memory.start_send(event) {
+                Ok(AsyncSink::Ready) => return Ok(AsyncSink::Ready),
+                Ok(AsyncSink::NotReady(event)) => {
+                    debug!(
+                        message = "In-memory buffer is full; spilling events to disk.",
+                        rate_limit_secs = 10
+                    );
+                    *spilled = true;
+                    event
+                }
+                Err(error) => {
+                    error!(message = "Sender error.", %error);
+                    return Err(());
+                }
+            }
+        };
+
+        self.overflow.start_send(event)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.memory
+            .poll_complete()
+            .map_err(|error| error!(message = "Sender error.", %error))?;
+        self.overflow.poll_complete()
+    }
+}
+
+pub struct Reader {
+    memory: mpsc::Receiver<Event>,
+    overflow: Box<dyn Stream<Item = Event, Error = ()> + Send>,
+    shared: Arc<Shared>,
+    acks: Arc<OverflowAcks>,
+}
+
+impl Stream for Reader {
+    type Item = Event;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            let memory_closed = match self.memory.poll()? {
+                Async::Ready(Some(event)) => {
+                    self.acks.push(false);
+                    return Ok(Async::Ready(Some(event)));
+                }
+                Async::Ready(None) => true,
+                Async::NotReady => false,
+            };
+
+            if memory_closed || self.shared.unread.load(Ordering::Acquire) > 0 {
+                // Returns `NotReady` until spilled events are flushed, and
+                // `None` once the writers are gone and the disk is drained.
+                return match self.overflow.poll()? {
+                    Async::Ready(Some(event)) => {
+                        self.acks.push(true);
+                        Ok(Async::Ready(Some(event)))
+                    }
+                    other => Ok(other),
+                };
+            }
+
+            let mut spilled = self.shared.spilled.lock().expect("poisoned lock");
+            if self.shared.unread.load(Ordering::Acquire) == 0 {
+                // Both tiers are empty, so new events can go to memory again.
+                *spilled = false;
+                return Ok(Async::NotReady);
+            }
+            // An event spilled since we checked, so go read it.
+        }
+    }
+}
+
+/// Remembers which of the events a sink has received were read from disk, so
+/// that acks can be passed on to the disk buffer for just those events.
+#[derive(Debug, Default)]
+pub struct OverflowAcks {
+    // Runs of consecutive events, and whether they were read from disk.
+    queue: Mutex<VecDeque<(bool, usize)>>,
+}
+
+impl OverflowAcks {
+    fn push(&self, from_disk: bool) {
+        let mut queue = self.queue.lock().expect("poisoned lock");
+        match queue.back_mut() {
+            Some((last, count)) if *last == from_disk => *count += 1,
+            _ => queue.push_back((from_disk, 1)),
+        }
+    }
+
+    /// Acks the next `num` events, returning how many of them were read from
+    /// disk.
+    pub(super) fn ack(&self, mut num: usize) -> usize {
+        let mut queue = self.queue.lock().expect("poisoned lock");
+        let mut from_disk = 0;
+        while num > 0 {
+            let (on_disk, count) = match queue.front_mut() {
+                Some(entry) => entry,
+                None => break,
+            };
+            let acked = num.min(*count);
+            if *on_disk {
+                from_disk += acked;
+            }
+            num -= acked;
+            *count -= acked;
+            if *count == 0 {
+                queue.pop_front();
+            }
+        }
+        from_disk
+    }
+}
+
+/// Puts an in-memory channel holding up to `max_events` events in front of
+/// `overflow`, which must be able to count its unread events.
+pub fn build(
+    max_events: usize,
+    overflow: disk::Writer,
+    overflow_rx: Box<dyn Stream<Item = Event, Error = ()> + Send>,
+    overflow_acker: Acker,
+) -> Option<(Writer, Reader, Acker)> {
+    let unread = overflow.unread_counter()?;
+    // Events left on disk by a previous run are older than anything new.
+    let spilled = unread.load(Ordering::Acquire) > 0;
+    let shared = Arc::new(Shared {
+        spilled: Mutex::new(spilled),
+        unread,
+    });
+
+    let (tx, rx) = mpsc::channel(max_events);
+    let acks = Arc::new(OverflowAcks::default());
+
+    let writer = Writer {
+        memory: tx,
+        overflow,
+        shared: Arc::clone(&shared),
+    };
+    let reader = Reader {
+        memory: rx,
+        overflow: overflow_rx,
+        shared,
+        acks: Arc::clone(&acks),
+    };
+    let acker = Acker::Overflow(acks, Box::new(overflow_acker));
+
+    Some((writer, reader, acker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffers::disk::segmented_buffer::{Options, SyncPolicy};
+    use crate::config::log_schema;
+    use futures::compat::Future01CompatExt;
+    use futures01::future;
+
+    fn open(dir: &std::path::Path, max_events: usize) -> (Writer, Reader, Acker) {
+        let options = Options {
+            max_size: 1024 * 1024,
+            max_segment_size: 1024,
+            sync: SyncPolicy::Never,
+        };
+        let (tx, rx, acker) = disk::open_segmented(dir, "overflow", "legacy", options).unwrap();
+        build(max_events, tx, rx, acker).unwrap()
+    }
+
+    fn message(event: Event) -> String {
+        event.as_log()[log_schema().message_key()].to_string_lossy()
+    }
+
+    fn read_all(reader: &mut Reader) -> Vec<String> {
+        let mut messages = vec![];
+        while let Async::Ready(Some(event)) = reader.poll().unwrap() {
+            messages.push(message(event));
+        }
+        messages
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn spills_to_disk_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+
+        future::lazy(|| {
+            let (mut writer, mut reader, acker) = open(dir.path(), 2);
+
+            for i in 0..10 {
+                let event = Event::from(format!("{}", i));
+                assert_eq!(writer.start_send(event), Ok(AsyncSink::Ready));
+            }
+            writer.poll_complete().unwrap();
+
+            let expected: Vec<_> = (0..10).map(|i| i.to_string()).collect();
+            assert_eq!(read_all(&mut reader), expected);
+
+            // Once the disk is drained, writes go to memory again.
+            acker.ack(10);
+            assert_eq!(
+                writer.start_send(Event::from("memory")),
+                Ok(AsyncSink::Ready)
+            );
+            assert!(!*writer.shared.spilled.lock().unwrap());
+            assert_eq!(read_all(&mut reader), vec!["memory".to_string()]);
+
+            future::ok::<(), ()>(())
+        })
+        .compat()
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn resumes_from_disk_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+
+        future::lazy(|| {
+            let (mut writer, reader, _acker) = open(dir.path(), 1);
+            for i in 0..5 {
+                writer.start_send(Event::from(format!("{}", i))).unwrap();
+            }
+            writer.poll_complete().unwrap();
+            drop((writer, reader));
+
+            // Whatever was still in memory is gone, but the spilled events
+            // come before anything written after the restart.
+            let (mut writer, mut reader, _acker) = open(dir.path(), 10);
+            writer.start_send(Event::from("new")).unwrap();
+            writer.poll_complete().unwrap();
+
+            let messages = read_all(&mut reader);
+            assert_eq!(messages.last().map(String::as_str), Some("new"));
+            assert!(messages.len() > 1);
+
+            future::ok::<(), ()>(())
+        })
+        .compat()
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn acks_only_events_read_from_disk() {
+        let acks = OverflowAcks::default();
+        for &from_disk in &[false, false, true, true, true, false, true] {
+            acks.push(from_disk);
+        }
+
+        assert_eq!(acks.ack(1), 0);
+        assert_eq!(acks.ack(3), 2);
+        assert_eq!(acks.ack(3), 2);
+        assert_eq!(acks.ack(1), 0);
+    }
+}
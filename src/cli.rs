@@ -1,6 +1,9 @@
 #[cfg(feature = "api-client")]
 use crate::top;
-use crate::{generate, get_version, list, unit_test, validate};
+use crate::{
+    config::{self, ConfigPath, Format},
+    generate, get_version, list, unit_test, validate,
+};
 use std::path::PathBuf;
 use structopt::{clap::AppSettings, StructOpt};
 
@@ -58,11 +61,31 @@ impl Opts {
 #[structopt(rename_all = "kebab-case")]
 pub struct RootOpts {
     /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name extension, defaulting to TOML.
     /// If zero files are specified the default config path
     /// `/etc/vector/vector.toml` will be targeted.
     #[structopt(name = "config", short, long, env = "VECTOR_CONFIG")]
     pub config_paths: Vec<PathBuf>,
 
+    /// Read configuration from one or more files in TOML format. Wildcard paths are supported.
+    #[structopt(name = "config-toml", long, env = "VECTOR_CONFIG_TOML")]
+    pub config_paths_toml: Vec<PathBuf>,
+
+    /// Read configuration from one or more files in YAML format. Wildcard paths are supported.
+    #[structopt(name = "config-yaml", long, env = "VECTOR_CONFIG_YAML")]
+    pub config_paths_yaml: Vec<PathBuf>,
+
+    /// Read configuration from one or more files in JSON format. Wildcard paths are supported.
+    #[structopt(name = "config-json", long, env = "VECTOR_CONFIG_JSON")]
+    pub config_paths_json: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories.
+    /// Files at the top level of a directory are loaded as complete configs,
+    /// while each file in its `sources`, `transforms`, `sinks` and `tests`
+    /// subdirectories defines a single component named after the file.
+    #[structopt(name = "config-dir", short = "C", long, env = "VECTOR_CONFIG_DIR")]
+    pub config_dirs: Vec<PathBuf>,
+
     /// Exit on startup if any sinks fail healthchecks
     #[structopt(short, long, env = "VECTOR_REQUIRE_HEALTHY")]
     pub require_healthy: bool,
@@ -98,6 +121,22 @@ pub struct RootOpts {
     pub watch_config: bool,
 }
 
+impl RootOpts {
+    /// Return all the config files, along with their explicitly requested
+    /// formats, followed by the config directories.
+    pub fn config_paths_with_formats(&self) -> Vec<ConfigPath> {
+        config::merge_path_lists(vec![
+            (&self.config_paths[..], None),
+            (&self.config_paths_toml[..], Some(Format::Toml)),
+            (&self.config_paths_yaml[..], Some(Format::Yaml)),
+            (&self.config_paths_json[..], Some(Format::Json)),
+        ])
+        .into_iter()
+        .chain(self.config_dirs.iter().cloned().map(ConfigPath::Dir))
+        .collect()
+    }
+}
+
 #[derive(StructOpt, Debug)]
 #[structopt(rename_all = "kebab-case")]
 pub enum SubCommand {
@@ -525,6 +525,10 @@ impl_generate_config_from_default!(CheckFieldsConfig);
 #[typetag::serde(name = "check_fields")]
 impl ConditionConfig for CheckFieldsConfig {
     fn build(&self) -> crate::Result<Box<dyn Condition>> {
+        warn!(
+            message = "The `check_fields` condition is deprecated, use a remap boolean expression instead, e.g. `condition = '.status == 200'`.",
+            rate_limit_secs = 30,
+        );
         build_predicates(&self.predicates)
             .map(|preds| -> Box<dyn Condition> { Box::new(CheckFields { predicates: preds }) })
             .map_err(|errs| {
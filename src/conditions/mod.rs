@@ -36,9 +36,15 @@ pub type ConditionDescription = ComponentDescription<Box<dyn ConditionConfig>>;
 
 inventory::collect!(ConditionDescription);
 
+/// A condition as written in a component's configuration.
+///
+/// A plain string is a remap boolean expression, e.g.
+/// `condition = '.status >= 500'`. Tables are conditions of the given `type`,
+/// and `check_fields` conditions if no type is given.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(untagged)]
 pub enum AnyCondition {
+    Remap(String),
     FromType(Box<dyn ConditionConfig>),
     NoTypeCondition(CheckFieldsConfig),
 }
@@ -46,8 +52,45 @@ pub enum AnyCondition {
 impl AnyCondition {
     pub fn build(&self) -> crate::Result<Box<dyn Condition>> {
         match self {
+            Self::Remap(source) => remap::RemapConfig::new(source.clone()).build(),
             Self::FromType(c) => c.build(),
             Self::NoTypeCondition(c) => c.build(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::log_event;
+
+    #[derive(Deserialize)]
+    struct Config {
+        condition: AnyCondition,
+    }
+
+    fn build(config: &str) -> crate::Result<Box<dyn Condition>> {
+        toml::from_str::<Config>(config).unwrap().condition.build()
+    }
+
+    #[test]
+    fn any_condition() {
+        let event = log_event!["status" => 503, "path" => "/api/v1"];
+
+        let condition =
+            build(r#"condition = '.status >= 500 && contains(.path, "/api")'"#).unwrap();
+        assert!(condition.check(&event));
+
+        let condition = build(
+            r#"condition.type = "remap"
+            condition.source = '.status < 500'"#,
+        )
+        .unwrap();
+        assert!(!condition.check(&event));
+
+        let condition = build(r#"condition."path.starts_with" = "/api""#).unwrap();
+        assert!(condition.check(&event));
+
+        assert!(build(r#"condition = '.status >='"#).is_err());
+    }
+}
@@ -17,11 +17,22 @@ inventory::submit! {
 
 impl_generate_config_from_default!(RemapConfig);
 
+impl RemapConfig {
+    pub fn new(source: String) -> Self {
+        Self { source }
+    }
+}
+
 #[typetag::serde(name = "remap")]
 impl ConditionConfig for RemapConfig {
     fn build(&self) -> crate::Result<Box<dyn Condition>> {
         let program = remap::Program::new(&self.source, &crate::remap::FUNCTIONS)?;
 
+        let kind = program.kind();
+        if !kind.is_empty() && !kind.intersects(remap::Kind::BOOLEAN) {
+            return Err(format!("source must resolve to a boolean, got {}", kind).into());
+        }
+
         Ok(Box::new(Remap { program }))
     }
 }
@@ -134,6 +145,20 @@ mod test {
                 ),
                 Ok(()),
             ),
+            (
+                log_event![],
+                r#"if upcase("foo") { true }"#,
+                Err(
+                    "type error: if-statement condition must be a boolean, got string\n --> 1:4\n  |\n1 | if upcase(\"foo\") { true }\n  |    ^^^^^^^^^^^^^\n  |\n  = help: convert the value first, e.g. `to_bool(...)`",
+                ),
+                Ok(()),
+            ),
+            (
+                log_event![],
+                r#"upcase("foo")"#,
+                Err("source must resolve to a boolean, got string"),
+                Ok(()),
+            ),
         ];
 
         for (event, source, build, check) in checks {
@@ -14,10 +14,22 @@ impl ConfigDiff {
     }
 
     pub fn new(old: &Config, new: &Config) -> Self {
+        let mut sinks = Difference::new(&old.sinks, &new.sinks);
+        // Sinks only keep rejected events around for their dead-letter output
+        // while something consumes it, so they are rebuilt when that changes.
+        let dead_letter_changed = old
+            .sinks
+            .keys()
+            .filter(|name| new.sinks.contains_key(*name))
+            .filter(|name| old.dead_letter_consumed(name) != new.dead_letter_consumed(name))
+            .cloned()
+            .collect::<Vec<_>>();
+        sinks.to_change.extend(dead_letter_changed);
+
         ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources),
             transforms: Difference::new(&old.transforms, &new.transforms),
-            sinks: Difference::new(&old.sinks, &new.sinks),
+            sinks,
         }
     }
 
@@ -0,0 +1,118 @@
+//! Support for loading configs from multiple formats.
+
+use serde::de::DeserializeOwned;
+use std::{fmt, path::Path, str::FromStr};
+
+/// The format used to deserialize a config file.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Format {
+    Toml,
+    Yaml,
+    Json,
+}
+
+/// An explicitly requested format, or `None` to detect it from the path.
+pub type FormatHint = Option<Format>;
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Toml
+    }
+}
+
+impl Format {
+    /// Obtain the format from the file extension, if it is a known one.
+    pub fn from_path<T: AsRef<Path>>(path: T) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "toml" => Some(Format::Toml),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    /// Resolve a hint against a path, falling back to TOML when neither the
+    /// hint nor the file extension determine the format.
+    pub fn resolve<T: AsRef<Path>>(hint: FormatHint, path: T) -> Self {
+        hint.or_else(|| Self::from_path(path)).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Format::Toml => "TOML",
+            Format::Yaml => "YAML",
+            Format::Json => "JSON",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(Format::Toml),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            "json" => Ok(Format::Json),
+            s => Err(format!("Unknown config format {:?}", s)),
+        }
+    }
+}
+
+/// Parse the string represented in the specified format.
+pub fn deserialize<T: DeserializeOwned>(content: &str, format: Format) -> Result<T, Vec<String>> {
+    match format {
+        Format::Toml => toml::from_str(content).map_err(|e| vec![e.to_string()]),
+        Format::Yaml => serde_yaml::from_str(content).map_err(|e| vec![e.to_string()]),
+        Format::Json => serde_json::from_str(content).map_err(|e| vec![e.to_string()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_format_from_extension() {
+        assert_eq!(Format::from_path("vector.toml"), Some(Format::Toml));
+        assert_eq!(Format::from_path("vector.yaml"), Some(Format::Yaml));
+        assert_eq!(Format::from_path("vector.yml"), Some(Format::Yaml));
+        assert_eq!(Format::from_path("vector.json"), Some(Format::Json));
+        assert_eq!(Format::from_path("vector.conf"), None);
+        assert_eq!(Format::from_path("vector"), None);
+    }
+
+    #[test]
+    fn hint_overrides_extension() {
+        assert_eq!(
+            Format::resolve(Some(Format::Json), "vector.toml"),
+            Format::Json
+        );
+        assert_eq!(Format::resolve(None, "vector.yml"), Format::Yaml);
+        assert_eq!(Format::resolve(None, "vector.conf"), Format::Toml);
+    }
+
+    #[test]
+    fn deserializes_equivalent_documents() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Doc {
+            name: String,
+            items: Vec<u32>,
+        }
+
+        let expected = Doc {
+            name: "foo".into(),
+            items: vec![1, 2],
+        };
+        let toml = "name = \"foo\"\nitems = [1, 2]\n";
+        let yaml = "name: foo\nitems:\n  - 1\n  - 2\n";
+        let json = r#"{"name": "foo", "items": [1, 2]}"#;
+
+        assert_eq!(deserialize::<Doc>(toml, Format::Toml).unwrap(), expected);
+        assert_eq!(deserialize::<Doc>(yaml, Format::Yaml).unwrap(), expected);
+        assert_eq!(deserialize::<Doc>(json, Format::Json).unwrap(), expected);
+    }
+}
@@ -1,9 +1,15 @@
-use super::{builder::ConfigBuilder, vars, Config};
+use super::{
+    builder::ConfigBuilder,
+    format::{self, Format, FormatHint},
+    vars, Config, ConfigPath, SinkOuter, SourceConfig, TestDefinition, TransformOuter,
+};
 use glob::glob;
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
+use serde::de::DeserializeOwned;
 use std::{
     collections::HashMap,
-    fs::File,
+    fs::{self, File},
     path::{Path, PathBuf},
     sync::Mutex,
 };
@@ -19,9 +25,24 @@ lazy_static! {
     pub static ref CONFIG_PATHS: Mutex<Vec<PathBuf>> = Mutex::default();
 }
 
+/// Merge lists of paths that share a format hint into a single list of
+/// config file paths tagged with that hint.
+pub fn merge_path_lists(path_lists: Vec<(&[PathBuf], FormatHint)>) -> Vec<ConfigPath> {
+    path_lists
+        .into_iter()
+        .flat_map(|(paths, format)| {
+            paths
+                .iter()
+                .cloned()
+                .map(move |path| ConfigPath::File(path, format))
+        })
+        .collect()
+}
+
 /// Expand a list of paths (potentially containing glob patterns) into real
-/// config paths, replacing it with the default paths when empty.
-pub fn process_paths(config_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
+/// config paths, replacing it with the default paths when empty. Each
+/// expanded file keeps the format hint of the pattern it was matched by.
+pub fn process_paths(config_paths: &[ConfigPath]) -> Option<Vec<ConfigPath>> {
     let default_paths = if cfg!(unix) {
         DEFAULT_UNIX_CONFIG_PATHS.clone()
     } else if cfg!(windows) {
@@ -29,6 +50,10 @@ pub fn process_paths(config_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
     } else {
         DEFAULT_UNIX_CONFIG_PATHS.clone()
     };
+    let default_paths = default_paths
+        .into_iter()
+        .map(|path| ConfigPath::File(path, None))
+        .collect::<Vec<_>>();
 
     let starting_paths = if !config_paths.is_empty() {
         config_paths
@@ -38,7 +63,19 @@ pub fn process_paths(config_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
 
     let mut paths = Vec::new();
 
-    for config_pattern in starting_paths {
+    for config_path in starting_paths {
+        let (config_pattern, format) = match config_path {
+            ConfigPath::File(path, format) => (path, format),
+            ConfigPath::Dir(path) => {
+                if !path.is_dir() {
+                    error!(message = "Config directory not found in path.", path = ?path);
+                    std::process::exit(exitcode::CONFIG);
+                }
+                paths.push(config_path.clone());
+                continue;
+            }
+        };
+
         let matches: Vec<PathBuf> = match glob(config_pattern.to_str().expect("No ability to glob"))
         {
             Ok(glob_paths) => glob_paths.filter_map(Result::ok).collect(),
@@ -54,56 +91,194 @@ pub fn process_paths(config_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
         }
 
         for path in matches {
-            paths.push(path);
+            paths.push(ConfigPath::File(path, *format));
         }
     }
 
-    paths.sort();
-    paths.dedup();
+    // A file matched by several patterns, possibly with different format
+    // hints, is only loaded once, with the hint it was first given.
+    let mut paths = paths
+        .into_iter()
+        .map(|path| {
+            let canonical =
+                fs::canonicalize(path.as_path()).unwrap_or_else(|_| path.as_path().clone());
+            (canonical, path)
+        })
+        .collect::<Vec<_>>();
+    paths.sort_by(|(a, _), (b, _)| a.cmp(b));
+    paths.dedup_by(|(a, _), (b, _)| a == b);
+    let paths = paths.into_iter().map(|(_, path)| path).collect::<Vec<_>>();
     // Ignore poison error and let the current main thread continue running to do the cleanup.
-    std::mem::drop(CONFIG_PATHS.lock().map(|mut guard| *guard = paths.clone()));
+    std::mem::drop(
+        CONFIG_PATHS
+            .lock()
+            .map(|mut guard| *guard = paths.iter().map(|path| path.as_path().clone()).collect()),
+    );
 
     Some(paths)
 }
 
-pub fn load_from_paths(config_paths: &[PathBuf]) -> Result<Config, Vec<String>> {
+pub fn load_from_paths(config_paths: &[ConfigPath]) -> Result<Config, Vec<String>> {
     load_builder_from_paths(config_paths).and_then(|builder| builder.build())
 }
 
 pub(super) fn load_builder_from_paths(
-    config_paths: &[PathBuf],
+    config_paths: &[ConfigPath],
 ) -> Result<ConfigBuilder, Vec<String>> {
-    let mut inputs = Vec::new();
+    let mut config = Config::builder();
     let mut errors = Vec::new();
 
-    for path in config_paths {
-        if let Some(file) = open_config(&path) {
-            inputs.push(file);
-        } else {
-            errors.push(format!("Config file not found in path: {:?}.", path));
+    for config_path in config_paths {
+        let loaded = match config_path {
+            ConfigPath::File(path, format) => load_file(path, Format::resolve(*format, path)),
+            ConfigPath::Dir(path) => load_builder_from_dir(path),
         };
+        let appended = loaded.and_then(|builder| {
+            config.append(builder).map_err(|errs| {
+                errs.into_iter()
+                    .map(|error| format!("{:?}: {}", config_path.as_path(), error))
+                    .collect()
+            })
+        });
+        if let Err(errs) = appended {
+            errors.extend(errs);
+        }
     }
 
     if errors.is_empty() {
-        load_from_inputs(inputs)
+        Ok(config)
     } else {
         Err(errors)
     }
 }
 
-pub fn load_from_str(input: &str) -> Result<Config, Vec<String>> {
-    load_from_inputs(std::iter::once(input.as_bytes())).and_then(|builder| builder.build())
+/// Load a config directory. Files at its top level are complete configs,
+/// while each file in the `sources`, `transforms` and `sinks` subdirectories
+/// holds a single component named after the file stem. Files in `tests` hold
+/// a single test, whose name also defaults to the file stem.
+fn load_builder_from_dir(dir: &Path) -> Result<ConfigBuilder, Vec<String>> {
+    let mut config = Config::builder();
+    let mut errors = Vec::new();
+
+    for (path, format) in config_files(dir)? {
+        if let Err(errs) = load_file(&path, format).and_then(|builder| config.append(builder)) {
+            errors.extend(errs);
+        }
+    }
+
+    let mut components = Config::builder();
+    components.sources = load_components::<Box<dyn SourceConfig>>(dir, "sources", &mut errors);
+    components.transforms = load_components::<TransformOuter>(dir, "transforms", &mut errors);
+    components.sinks = load_components::<SinkOuter>(dir, "sinks", &mut errors);
+    components.tests = load_components::<serde_json::Value>(dir, "tests", &mut errors)
+        .into_iter()
+        .filter_map(|(name, mut test)| {
+            if let Some(fields) = test.as_object_mut() {
+                fields.entry("name").or_insert_with(|| name.into());
+            }
+            serde_json::from_value::<TestDefinition>(test)
+                .map_err(|error| errors.push(format!("{:?}: {}", dir.join("tests"), error)))
+                .ok()
+        })
+        .collect();
+
+    if let Err(errs) = config.append(components) {
+        errors.extend(
+            errs.into_iter()
+                .map(|error| format!("{:?}: {}", dir, error)),
+        );
+    }
+
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Load every file in `dir/kind` as a single component, keyed by file stem.
+fn load_components<T: DeserializeOwned>(
+    dir: &Path,
+    kind: &str,
+    errors: &mut Vec<String>,
+) -> IndexMap<String, T> {
+    let mut components = IndexMap::new();
+
+    let dir = dir.join(kind);
+    if !dir.is_dir() {
+        return components;
+    }
+
+    let files = match config_files(&dir) {
+        Ok(files) => files,
+        Err(errs) => {
+            errors.extend(errs);
+            return components;
+        }
+    };
+
+    for (path, format) in files {
+        let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(name) => name.to_owned(),
+            None => {
+                errors.push(format!("Invalid component file name: {:?}.", path));
+                continue;
+            }
+        };
+        match load_file(&path, format) {
+            Ok(component) => {
+                if components.insert(name.clone(), component).is_some() {
+                    errors.push(format!(
+                        "duplicate {} name found: {} ({:?})",
+                        kind.trim_end_matches('s'),
+                        name,
+                        path
+                    ));
+                }
+            }
+            Err(errs) => errors.extend(errs),
+        }
+    }
+
+    components
+}
+
+/// List the files in `dir` with a known config format, in a stable order.
+fn config_files(dir: &Path) -> Result<Vec<(PathBuf, Format)>, Vec<String>> {
+    let entries = fs::read_dir(dir).map_err(|error| {
+        vec![format!(
+            "Could not read config directory {:?}: {}",
+            dir, error
+        )]
+    })?;
+
+    let mut files = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| Format::from_path(&path).map(|format| (path, format)))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    Ok(files)
+}
+
+pub fn load_from_str(input: &str, format: FormatHint) -> Result<Config, Vec<String>> {
+    load_from_inputs(std::iter::once((
+        input.as_bytes(),
+        format.unwrap_or_default(),
+    )))
+    .and_then(|builder| builder.build())
 }
 
 fn load_from_inputs(
-    inputs: impl IntoIterator<Item = impl std::io::Read>,
+    inputs: impl IntoIterator<Item = (impl std::io::Read, Format)>,
 ) -> Result<ConfigBuilder, Vec<String>> {
     let mut config = Config::builder();
     let mut errors = Vec::new();
 
-    for input in inputs {
-        if let Err(errs) = load(input).and_then(|n| config.append(n)) {
-            // TODO: add back paths
+    for (input, format) in inputs {
+        if let Err(errs) = load(input, format).and_then(|n| config.append(n)) {
             errors.extend(errs.iter().map(|e| e.to_string()));
         }
     }
@@ -115,6 +290,19 @@ fn load_from_inputs(
     }
 }
 
+/// Load a single file, prefixing any errors with its path and format so they
+/// can be traced back to it.
+fn load_file<T: DeserializeOwned>(path: &Path, format: Format) -> Result<T, Vec<String>> {
+    let file = open_config(path)
+        .ok_or_else(|| vec![format!("Config file not found in path: {:?}.", path)])?;
+
+    load(file, format).map_err(|errs| {
+        errs.into_iter()
+            .map(|error| format!("{:?} ({}): {}", path, format, error))
+            .collect()
+    })
+}
+
 fn open_config(path: &Path) -> Option<File> {
     match File::open(path) {
         Ok(f) => Some(f),
@@ -130,7 +318,10 @@ fn open_config(path: &Path) -> Option<File> {
     }
 }
 
-fn load(mut input: impl std::io::Read) -> Result<ConfigBuilder, Vec<String>> {
+fn load<T: DeserializeOwned>(
+    mut input: impl std::io::Read,
+    format: Format,
+) -> Result<T, Vec<String>> {
     let mut source_string = String::new();
     input
         .read_to_string(&mut source_string)
@@ -144,5 +335,28 @@ fn load(mut input: impl std::io::Read) -> Result<ConfigBuilder, Vec<String>> {
     }
     let with_vars = vars::interpolate(&source_string, &vars);
 
-    toml::from_str(&with_vars).map_err(|e| vec![e.to_string()])
+    format::deserialize(&with_vars, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::format::Format, test_util::temp_dir};
+
+    #[test]
+    fn process_paths_dedupes_files_by_path() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("vector.toml");
+        fs::write(&path, "").unwrap();
+
+        let paths = process_paths(&[
+            ConfigPath::File(path.clone(), Some(Format::Toml)),
+            ConfigPath::File(dir.join("*.toml"), None),
+            ConfigPath::File(dir.join(".").join("vector.toml"), Some(Format::Json)),
+        ])
+        .unwrap();
+
+        assert_eq!(paths, vec![ConfigPath::File(path, Some(Format::Toml))]);
+    }
 }
@@ -17,6 +17,7 @@ mod builder;
 mod compiler;
 pub mod component;
 mod diff;
+mod format;
 mod loading;
 mod log_schema;
 mod unit_test;
@@ -26,7 +27,8 @@ pub mod watcher;
 
 pub use builder::ConfigBuilder;
 pub use diff::ConfigDiff;
-pub use loading::{load_from_paths, load_from_str, process_paths, CONFIG_PATHS};
+pub use format::{Format, FormatHint};
+pub use loading::{load_from_paths, load_from_str, merge_path_lists, process_paths, CONFIG_PATHS};
 pub use log_schema::{log_schema, LogSchema, LOG_SCHEMA};
 pub use unit_test::build_unit_tests_main as build_unit_tests;
 pub use validation::warnings;
@@ -58,6 +60,24 @@ pub fn default_data_dir() -> Option<PathBuf> {
     Some(PathBuf::from("/var/lib/vector/"))
 }
 
+/// A location to load configuration from.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ConfigPath {
+    /// A single config file, optionally with an explicitly requested format.
+    File(PathBuf, FormatHint),
+    /// A directory with one file per component, see `--config-dir`.
+    Dir(PathBuf),
+}
+
+impl ConfigPath {
+    pub fn as_path(&self) -> &PathBuf {
+        match self {
+            ConfigPath::File(path, _) => path,
+            ConfigPath::Dir(path) => path,
+        }
+    }
+}
+
 #[derive(Debug, Snafu)]
 pub enum DataDirError {
     #[snafu(display("data_dir option required, but not given here or globally"))]
@@ -236,6 +256,13 @@ pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone
 
     fn transform_type(&self) -> &'static str;
 
+    /// The names of the outputs this transform can send events to besides its default one.
+    /// Other components consume them as `<transform-id>.<output>`, and they carry events of
+    /// the transform's `output_type`.
+    fn named_outputs(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     /// Allows a transform configuration to expand itself into multiple "child"
     /// transformations to replace it. This allows a transform to act as a macro
     /// for various patterns.
@@ -328,6 +355,7 @@ pub struct TestOutput {
 pub enum TestCondition {
     Embedded(Box<dyn conditions::ConditionConfig>),
     NoTypeEmbedded(conditions::CheckFieldsConfig),
+    /// A remap boolean expression.
     String(String),
 }
 
@@ -345,6 +373,49 @@ impl Config {
             .cloned()
             .unwrap_or_else(|| vec![String::from(identifier)])
     }
+
+    /// The sink whose dead-letter output is referred to by `input`, if any.
+    pub fn dead_letter_sink<'a>(&self, input: &'a str) -> Option<&'a str> {
+        input
+            .strip_suffix(DEAD_LETTER_SUFFIX)
+            .filter(|sink| self.sinks.contains_key(*sink))
+    }
+
+    /// The transform and the name of its output referred to by `input`, if `input` is one of
+    /// the named outputs of a transform.
+    pub fn transform_output<'a>(&self, input: &'a str) -> Option<(&'a str, &'a str)> {
+        let mut parts = input.rsplitn(2, '.');
+        let output = parts.next()?;
+        let transform = parts.next()?;
+        self.transforms
+            .get(transform)
+            .filter(|config| config.inner.named_outputs().iter().any(|o| o == output))
+            .map(|_| (transform, output))
+    }
+
+    /// Whether any component consumes the dead-letter output of `sink`.
+    pub fn dead_letter_consumed(&self, sink: &str) -> bool {
+        let output = dead_letter_output(sink);
+        self.transforms
+            .values()
+            .map(|transform| &transform.inputs)
+            .chain(self.sinks.values().map(|sink| &sink.inputs))
+            .any(|inputs| inputs.contains(&output))
+    }
+}
+
+const DEAD_LETTER_SUFFIX: &str = ".dead_letter";
+
+/// The name other components use as input to consume the events that `sink`
+/// had rejected by its destination.
+pub fn dead_letter_output(sink: &str) -> String {
+    format!("{}{}", sink, DEAD_LETTER_SUFFIX)
+}
+
+/// The name other components use as input to consume the named output `output`
+/// of `transform`.
+pub fn named_output(transform: &str, output: &str) -> String {
+    format!("{}.{}", transform, output)
 }
 
 fn healthcheck_default() -> bool {
@@ -358,7 +429,7 @@ fn healthcheck_default() -> bool {
     feature = "transforms-json_parser"
 ))]
 mod test {
-    use super::{builder::ConfigBuilder, load_from_str};
+    use super::{builder::ConfigBuilder, load_from_paths, load_from_str, ConfigPath, Format};
     use std::path::PathBuf;
 
     #[test]
@@ -374,6 +445,7 @@ mod test {
       inputs = ["in"]
       encoding = "json"
       "#,
+            Some(Format::Toml),
         )
         .unwrap();
 
@@ -396,6 +468,7 @@ mod test {
       inputs = ["in"]
       encoding = "json"
       "#,
+            Some(Format::Toml),
         )
         .unwrap();
 
@@ -428,6 +501,7 @@ mod test {
       inputs = ["in"]
       encoding = "json"
       "#,
+            Some(Format::Toml),
         )
         .unwrap();
 
@@ -529,6 +603,206 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn transform_named_outputs() {
+        let config = r#"
+      [sources.in]
+      type = "file"
+      include = ["/var/log/messages"]
+
+      [transforms.parse]
+      type = "json_parser"
+      inputs = ["in"]
+
+      [sinks.out]
+      type = "console"
+      inputs = ["parse"]
+      encoding = "json"
+
+      [sinks.errors]
+      type = "console"
+      inputs = ["parse.OUTPUT"]
+      encoding = "json"
+      "#;
+
+        let loaded = load_from_str(&config.replace("OUTPUT", "errors"), Some(Format::Toml));
+        let loaded = loaded.unwrap();
+        assert_eq!(
+            loaded.transform_output("parse.errors"),
+            Some(("parse", "errors"))
+        );
+        assert_eq!(loaded.transform_output("parse"), None);
+
+        let errors = load_from_str(&config.replace("OUTPUT", "nope"), Some(Format::Toml));
+        assert_eq!(
+            errors.unwrap_err(),
+            vec!["Input \"parse.nope\" for sink \"errors\" doesn't exist.".to_owned()]
+        );
+    }
+
+    #[test]
+    fn load_yaml() {
+        let config = load_from_str(
+            r#"
+      sources:
+        in:
+          type: file
+          include: ["/var/log/messages"]
+      sinks:
+        out:
+          type: console
+          inputs: ["in"]
+          encoding: json
+      "#,
+            Some(Format::Yaml),
+        )
+        .unwrap();
+
+        assert!(config.sources.contains_key("in"));
+        assert_eq!(config.sinks["out"].inputs, vec!["in".to_owned()]);
+    }
+
+    #[test]
+    fn load_json() {
+        let config = load_from_str(
+            r#"{
+        "data_dir": "/foobar",
+        "sources": {
+          "in": { "type": "file", "include": ["/var/log/messages"] }
+        },
+        "sinks": {
+          "out": { "type": "console", "inputs": ["in"], "encoding": "json" }
+        }
+      }"#,
+            Some(Format::Json),
+        )
+        .unwrap();
+
+        assert_eq!(Some(PathBuf::from("/foobar")), config.global.data_dir);
+        assert!(config.sources.contains_key("in"));
+        assert!(config.sinks.contains_key("out"));
+    }
+
+    #[test]
+    fn load_mixed_formats_from_paths() {
+        let dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.yml");
+        let sink = dir.join("sink.json");
+        std::fs::write(
+            &source,
+            "sources:\n  in:\n    type: file\n    include: [\"/var/log/messages\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &sink,
+            r#"{"sinks": {"out": {"type": "console", "inputs": ["in"], "encoding": "json"}}}"#,
+        )
+        .unwrap();
+
+        let config =
+            load_from_paths(&[ConfigPath::File(source, None), ConfigPath::File(sink, None)])
+                .unwrap();
+
+        assert!(config.sources.contains_key("in"));
+        assert!(config.sinks.contains_key("out"));
+    }
+
+    #[test]
+    fn load_errors_include_path_and_format() {
+        let dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("vector.conf");
+        std::fs::write(&path, "sources: [").unwrap();
+
+        let errors =
+            load_from_paths(&[ConfigPath::File(path.clone(), Some(Format::Yaml))]).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with(&format!("{:?} (YAML): ", path)));
+    }
+
+    fn write_files(dir: &std::path::Path, files: &[(&str, &str)]) {
+        for (name, content) in files {
+            let path = dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+    }
+
+    #[test]
+    fn load_dir() {
+        let dir = crate::test_util::temp_dir();
+        write_files(
+            &dir,
+            &[
+                ("global.toml", "data_dir = \"/foobar\"\n"),
+                (
+                    "sources/in.toml",
+                    "type = \"file\"\ninclude = [\"/var/log/messages\"]\n",
+                ),
+                (
+                    "transforms/parse.yaml",
+                    "type: json_parser\ninputs: [\"in\"]\n",
+                ),
+                (
+                    "sinks/out.json",
+                    r#"{"type": "console", "inputs": ["parse"], "encoding": "json"}"#,
+                ),
+                (
+                    "tests/parse.toml",
+                    r#"
+        [input]
+        insert_at = "parse"
+        type = "raw"
+        value = "{}"
+        [[outputs]]
+        extract_from = "parse"
+        [[outputs.conditions]]
+        type = "check_fields"
+        "message.exists" = false
+        "#,
+                ),
+                ("sinks/README.md", "not a component"),
+            ],
+        );
+
+        let config = load_from_paths(&[ConfigPath::Dir(dir)]).unwrap();
+
+        assert_eq!(Some(PathBuf::from("/foobar")), config.global.data_dir);
+        assert!(config.sources.contains_key("in"));
+        assert_eq!(config.transforms["parse"].inputs, vec!["in".to_owned()]);
+        assert_eq!(config.sinks["out"].inputs, vec!["parse".to_owned()]);
+        assert_eq!(config.sinks.len(), 1);
+        assert_eq!(config.tests.len(), 1);
+        assert_eq!(config.tests[0].name, "parse");
+    }
+
+    #[test]
+    fn load_dir_duplicate_component() {
+        let dir = crate::test_util::temp_dir();
+        write_files(
+            &dir,
+            &[
+                (
+                    "vector.toml",
+                    "[sources.in]\ntype = \"file\"\ninclude = [\"/var/log/messages\"]\n",
+                ),
+                (
+                    "sources/in.toml",
+                    "type = \"file\"\ninclude = [\"/var/log/messages\"]\n",
+                ),
+            ],
+        );
+
+        let errors = load_from_paths(&[ConfigPath::Dir(dir.clone())]).unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![format!("{:?}: duplicate source name found: in", dir)]
+        );
+    }
 }
 
 #[cfg(test)]
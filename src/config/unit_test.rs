@@ -1,14 +1,16 @@
-use super::{Config, ConfigBuilder, TestCondition, TestDefinition, TestInput, TestInputValue};
+use super::{
+    Config, ConfigBuilder, ConfigPath, TestCondition, TestDefinition, TestInput, TestInputValue,
+};
 use crate::config::TransformConfig;
 use crate::{
-    conditions::{Condition, ConditionConfig},
+    conditions::{remap::RemapConfig, Condition, ConditionConfig},
     event::{Event, Value},
     transforms::Transform,
 };
 use indexmap::IndexMap;
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
-pub async fn build_unit_tests_main(path: PathBuf) -> Result<Vec<UnitTest>, Vec<String>> {
+pub async fn build_unit_tests_main(path: ConfigPath) -> Result<Vec<UnitTest>, Vec<String>> {
     let config = super::loading::load_builder_from_paths(&[path])?;
 
     // Ignore failures on calls other than the first
@@ -507,33 +509,20 @@ async fn build_unit_test(
                 .iter()
                 .enumerate()
             {
-                match cond_conf {
-                    TestCondition::Embedded(b) => match b.build() {
-                        Ok(c) => {
-                            conditions.push(c);
-                        }
-                        Err(e) => {
-                            errors.push(format!(
-                                "failed to create test condition '{}': {}",
-                                index, e,
-                            ));
-                        }
-                    },
-                    TestCondition::NoTypeEmbedded(n) => match n.build() {
-                        Ok(c) => {
-                            conditions.push(c);
-                        }
-                        Err(e) => {
-                            errors.push(format!(
-                                "failed to create test condition '{}': {}",
-                                index, e,
-                            ));
-                        }
-                    },
-                    TestCondition::String(_s) => {
+                let condition = match cond_conf {
+                    TestCondition::Embedded(b) => b.build(),
+                    TestCondition::NoTypeEmbedded(n) => n.build(),
+                    TestCondition::String(source) => RemapConfig::new(source.clone()).build(),
+                };
+
+                match condition {
+                    Ok(c) => {
+                        conditions.push(c);
+                    }
+                    Err(e) => {
                         errors.push(format!(
-                            "failed to create test condition '{}': condition references are not yet supported",
-                            index
+                            "failed to create test condition '{}': {}",
+                            index, e,
                         ));
                     }
                 }
@@ -989,6 +978,79 @@ mod tests {
         assert_eq!(tests[0].run().1, Vec::<String>::new());
     }
 
+    #[tokio::test]
+    async fn test_remap_conditions() {
+        let config: ConfigBuilder = toml::from_str(
+            r#"
+[transforms.foo]
+  inputs = ["ignored"]
+  type = "add_fields"
+  [transforms.foo.fields]
+    new_field = "string value"
+
+[[tests]]
+  name = "successful test"
+
+  [tests.input]
+    insert_at = "foo"
+    value = "nah this doesnt matter"
+
+  [[tests.outputs]]
+    extract_from = "foo"
+    conditions = [
+      '.new_field == "string value"',
+      'starts_with(.message, "nah")',
+    ]
+
+[[tests]]
+  name = "failing test"
+
+  [tests.input]
+    insert_at = "foo"
+    value = "nah this doesnt matter"
+
+  [[tests.outputs]]
+    extract_from = "foo"
+    conditions = ['.new_field == "something else"']
+      "#,
+        )
+        .unwrap();
+
+        let mut tests = build_unit_tests(config).await.unwrap();
+        assert_eq!(tests[0].run().1, Vec::<String>::new());
+        assert_ne!(tests[1].run().1, Vec::<String>::new());
+
+        let config: ConfigBuilder = toml::from_str(
+            r#"
+[transforms.foo]
+  inputs = ["ignored"]
+  type = "add_fields"
+  [transforms.foo.fields]
+    new_field = "string value"
+
+[[tests]]
+  name = "broken test"
+
+  [tests.input]
+    insert_at = "foo"
+    value = "nah this doesnt matter"
+
+  [[tests.outputs]]
+    extract_from = "foo"
+    conditions = ['upcase(.new_field)']
+      "#,
+        )
+        .unwrap();
+
+        let errs = build_unit_tests(config).await.err().unwrap();
+        assert_eq!(
+            errs,
+            vec![r#"Failed to build test 'broken test':
+  failed to create test condition '0': source must resolve to a boolean, got string"#
+                .to_owned(),]
+        );
+    }
+
     #[tokio::test]
     async fn test_swimlanes() {
         let config: ConfigBuilder = toml::from_str(
@@ -1,5 +1,5 @@
-use super::{Config, DataType, Resource};
-use std::collections::HashMap;
+use super::{named_output, Config, DataType, Resource};
+use std::collections::{HashMap, HashSet};
 
 pub fn check_shape(config: &Config) -> Result<(), Vec<String>> {
     let mut errors = vec![];
@@ -30,7 +30,11 @@ pub fn check_shape(config: &Config) -> Result<(), Vec<String>> {
         }
 
         for input in inputs {
-            if !config.sources.contains_key(&input) && !config.transforms.contains_key(&input) {
+            if !config.sources.contains_key(&input)
+                && !config.transforms.contains_key(&input)
+                && config.dead_letter_sink(&input).is_none()
+                && config.transform_output(&input).is_none()
+            {
                 errors.push(format!(
                     "Input {:?} for {} {:?} doesn't exist.",
                     input, output_type, name
@@ -39,6 +43,21 @@ pub fn check_shape(config: &Config) -> Result<(), Vec<String>> {
         }
     }
 
+    for (name, transform) in config.transforms.iter() {
+        for output in transform.inner.named_outputs() {
+            let output = named_output(name, &output);
+            if config.sources.contains_key(&output)
+                || config.transforms.contains_key(&output)
+                || config.sinks.contains_key(&output)
+            {
+                errors.push(format!(
+                    "Output {:?} of transform {:?} has the same name as another component.",
+                    output, name
+                ));
+            }
+        }
+    }
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -71,21 +90,26 @@ pub fn check_resources(config: &Config) -> Result<(), Vec<String>> {
 pub fn warnings(config: &Config) -> Option<Vec<String>> {
     let mut warnings = vec![];
 
+    // Consuming any output of a transform counts as consuming the transform.
+    let consumed = config
+        .transforms
+        .values()
+        .map(|transform| &transform.inputs)
+        .chain(config.sinks.values().map(|sink| &sink.inputs))
+        .flatten()
+        .map(|input| match config.transform_output(input) {
+            Some((transform, _)) => transform,
+            None => input.as_str(),
+        })
+        .collect::<HashSet<_>>();
+
     let source_names = config.sources.keys().map(|name| ("source", name.clone()));
     let transform_names = config
         .transforms
         .keys()
         .map(|name| ("transform", name.clone()));
     for (input_type, name) in transform_names.chain(source_names) {
-        if !config
-            .transforms
-            .iter()
-            .any(|(_, transform)| transform.inputs.contains(&name))
-            && !config
-                .sinks
-                .iter()
-                .any(|(_, sink)| sink.inputs.contains(&name))
-        {
+        if !consumed.contains(name.as_str()) {
             warnings.push(format!(
                 "{} {:?} has no consumers",
                 capitalize(input_type),
@@ -94,6 +118,15 @@ pub fn warnings(config: &Config) -> Option<Vec<String>> {
         }
     }
 
+    for (name, sink) in config.sinks.iter() {
+        if sink.inner.input_type() == DataType::Metric && config.dead_letter_consumed(name) {
+            warnings.push(format!(
+                "Sink {:?} only accepts metrics, which are never sent to its dead-letter output",
+                name
+            ));
+        }
+    }
+
     if warnings.is_empty() {
         None
     } else {
@@ -238,10 +271,27 @@ impl From<&Config> for Graph {
                 config.inner.output_type(),
                 config.inputs.clone(),
             );
+            // Named outputs carry the transform's output type.
+            for output in config.inner.named_outputs() {
+                graph.add_transform(
+                    &named_output(name, &output),
+                    config.inner.output_type(),
+                    config.inner.output_type(),
+                    vec![name.as_str()],
+                );
+            }
         }
 
         for (name, config) in config.sinks.iter() {
             graph.add_sink(name, config.inner.input_type(), config.inputs.clone());
+            // A dead-letter output passes on events of the types its sink
+            // accepts, coming from the sink's inputs.
+            graph.add_transform(
+                &super::dead_letter_output(name),
+                config.inner.input_type(),
+                config.inner.input_type(),
+                config.inputs.clone(),
+            );
         }
 
         graph
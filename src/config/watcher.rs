@@ -1,7 +1,7 @@
-use crate::Error;
+use crate::{config::ConfigPath, Error};
 #[cfg(unix)]
 use notify::{raw_watcher, Op, RawEvent, RecommendedWatcher, RecursiveMode, Watcher};
-use std::{path::PathBuf, time::Duration};
+use std::time::Duration;
 #[cfg(unix)]
 use std::{
     sync::mpsc::{channel, Receiver},
@@ -21,13 +21,14 @@ const CONFIG_WATCH_DELAY: std::time::Duration = std::time::Duration::from_secs(1
 #[cfg(unix)]
 const RETRY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
-/// Triggers SIGHUP when file on config_path changes.
+/// Triggers SIGHUP when file on config_path changes. Config directories are
+/// watched recursively, so adding or removing a component file also triggers it.
 /// Accumulates file changes until no change for given duration has occurred.
 /// Has best effort guarantee of detecting all file changes from the end of
 /// this function until the main thread stops.
 #[cfg(unix)]
 pub fn spawn_thread(
-    config_paths: &[PathBuf],
+    config_paths: &[ConfigPath],
     delay: impl Into<Option<Duration>>,
 ) -> Result<(), Error> {
     let config_paths = config_paths.to_vec();
@@ -84,7 +85,7 @@ pub fn spawn_thread(
 #[cfg(windows)]
 /// Errors on Windows.
 pub fn spawn_thread(
-    _config_paths: &[PathBuf],
+    _config_paths: &[ConfigPath],
     _delay: impl Into<Option<Duration>>,
 ) -> Result<(), Error> {
     Err("Reloading config on Windows isn't currently supported. Related issue https://github.com/timberio/vector/issues/938 .".into())
@@ -100,7 +101,7 @@ fn raise_sighup() {
 
 #[cfg(unix)]
 fn create_watcher(
-    config_paths: &[PathBuf],
+    config_paths: &[ConfigPath],
 ) -> Result<(RecommendedWatcher, Receiver<RawEvent>), Error> {
     info!("Creating configuration file watcher.");
     let (sender, receiver) = channel();
@@ -110,9 +111,12 @@ fn create_watcher(
 }
 
 #[cfg(unix)]
-fn add_paths(watcher: &mut RecommendedWatcher, config_paths: &[PathBuf]) -> Result<(), Error> {
-    for path in config_paths {
-        watcher.watch(path, RecursiveMode::NonRecursive)?;
+fn add_paths(watcher: &mut RecommendedWatcher, config_paths: &[ConfigPath]) -> Result<(), Error> {
+    for config_path in config_paths {
+        match config_path {
+            ConfigPath::File(path, _) => watcher.watch(path, RecursiveMode::NonRecursive)?,
+            ConfigPath::Dir(path) => watcher.watch(path, RecursiveMode::Recursive)?,
+        }
     }
     Ok(())
 }
@@ -121,7 +125,7 @@ fn add_paths(watcher: &mut RecommendedWatcher, config_paths: &[PathBuf]) -> Resu
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::{temp_file, trace_init};
+    use crate::test_util::{temp_dir, temp_file, trace_init};
     use std::time::Duration;
     use std::{fs::File, io::Write};
     #[cfg(unix)]
@@ -144,8 +148,24 @@ mod tests {
         let file_path = temp_file();
         let mut file = File::create(&file_path).unwrap();
 
-        let _ = spawn_thread(&[file_path], delay).unwrap();
+        let _ = spawn_thread(&[ConfigPath::File(file_path, None)], delay).unwrap();
+
+        if !test(&mut file, delay * 5).await {
+            panic!("Test timed out");
+        }
+    }
+
+    #[tokio::test]
+    async fn dir_file_create() {
+        trace_init();
+
+        let delay = Duration::from_secs(3);
+        let dir = temp_dir().join("sources");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let _ = spawn_thread(&[ConfigPath::Dir(dir.parent().unwrap().into())], delay).unwrap();
 
+        let mut file = File::create(dir.join("in.toml")).unwrap();
         if !test(&mut file, delay * 5).await {
             panic!("Test timed out");
         }
@@ -161,7 +181,7 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         std::os::unix::fs::symlink(&file_path, &sym_file).unwrap();
 
-        let _ = spawn_thread(&[sym_file], delay).unwrap();
+        let _ = spawn_thread(&[ConfigPath::File(sym_file, None)], delay).unwrap();
 
         if !test(&mut file, delay * 5).await {
             panic!("Test timed out");
@@ -0,0 +1,357 @@
+//! End-to-end acknowledgement of events.
+//!
+//! A source that wants to know when its events have been delivered creates a
+//! `BatchNotifier` and attaches it to each event it emits. Every event then
+//! carries an `EventFinalizer` pointing back at that batch. Finalizers are
+//! shared between all clones of an event, so an event fanned out to several
+//! sinks is only finalized once the last copy has been dropped. When the last
+//! finalizer referencing a batch goes away, the batch's combined status is
+//! sent to the `BatchStatusReceiver` held by the source.
+//!
+//! A sink with a consumed dead-letter output attaches a finalizer holding a
+//! copy of each event it receives instead, which hands the copy to the
+//! dead-letter output if the sink's destination rejects the event.
+
+use super::LogEvent;
+use crate::buffers::{DeadLetter, Rejection};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+};
+use tokio::sync::oneshot;
+
+/// The delivery status of a single event.
+///
+/// The variants are ordered by precedence: updating a status always keeps the
+/// most severe of the two, so an event delivered by one sink and errored by
+/// another ends up `Errored`.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[repr(u8)]
+pub enum EventStatus {
+    /// No sink has reported on the event, e.g. it was dropped by a transform.
+    Dropped,
+    /// The event was durably delivered.
+    Delivered,
+    /// A transient error prevented delivery of the event.
+    Errored,
+    /// The event was permanently rejected by the downstream service.
+    Failed,
+}
+
+impl EventStatus {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Dropped,
+            1 => Self::Delivered,
+            2 => Self::Errored,
+            _ => Self::Failed,
+        }
+    }
+
+    pub fn update(self, status: Self) -> Self {
+        self.max(status)
+    }
+}
+
+/// The combined delivery status of a batch of events.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BatchStatus {
+    /// All events in the batch were delivered or intentionally dropped.
+    Delivered,
+    /// At least one event in the batch had a transient error.
+    Errored,
+    /// At least one event in the batch was permanently rejected.
+    Failed,
+}
+
+impl From<EventStatus> for BatchStatus {
+    fn from(status: EventStatus) -> Self {
+        match status {
+            EventStatus::Dropped | EventStatus::Delivered => Self::Delivered,
+            EventStatus::Errored => Self::Errored,
+            EventStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AtomicEventStatus(AtomicU8);
+
+impl AtomicEventStatus {
+    fn new() -> Self {
+        Self(AtomicU8::new(EventStatus::Dropped as u8))
+    }
+
+    fn update(&self, status: EventStatus) {
+        self.0.fetch_max(status as u8, Ordering::AcqRel);
+    }
+
+    fn load(&self) -> EventStatus {
+        EventStatus::from_u8(self.0.load(Ordering::Acquire))
+    }
+}
+
+/// Notifies a source once all events of a batch have been finalized.
+#[derive(Debug)]
+pub struct BatchNotifier {
+    status: AtomicEventStatus,
+    notifier: Option<oneshot::Sender<BatchStatus>>,
+}
+
+impl BatchNotifier {
+    /// Creates a new notifier along with the receiver that resolves once the
+    /// notifier and every finalizer referencing it have been dropped.
+    pub fn new_with_receiver() -> (Arc<Self>, BatchStatusReceiver) {
+        let (sender, receiver) = oneshot::channel();
+        let notifier = Self {
+            status: AtomicEventStatus::new(),
+            notifier: Some(sender),
+        };
+        (Arc::new(notifier), BatchStatusReceiver(receiver))
+    }
+
+    fn update_status(&self, status: EventStatus) {
+        self.status.update(status);
+    }
+}
+
+impl Drop for BatchNotifier {
+    fn drop(&mut self) {
+        if let Some(notifier) = self.notifier.take() {
+            // The source may have stopped waiting for the batch, which is fine.
+            let _ = notifier.send(self.status.load().into());
+        }
+    }
+}
+
+/// Resolves to the status of a batch once it has been finalized.
+#[derive(Debug)]
+pub struct BatchStatusReceiver(oneshot::Receiver<BatchStatus>);
+
+impl Future for BatchStatusReceiver {
+    type Output = BatchStatus;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.0).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(status)) => Poll::Ready(status),
+            Poll::Ready(Err(_)) => Poll::Ready(BatchStatus::Errored),
+        }
+    }
+}
+
+/// Tracks the status of one event and reports it to its batch when dropped.
+#[derive(Debug)]
+pub struct EventFinalizer {
+    status: AtomicEventStatus,
+    target: FinalizerTarget,
+}
+
+#[derive(Debug)]
+enum FinalizerTarget {
+    Batch(Arc<BatchNotifier>),
+    DeadLetter {
+        event: Option<Box<LogEvent>>,
+        dead_letter: Arc<DeadLetter>,
+        rejection: Mutex<Option<Rejection>>,
+    },
+}
+
+impl EventFinalizer {
+    pub fn new(batch: Arc<BatchNotifier>) -> Self {
+        Self::with_target(FinalizerTarget::Batch(batch))
+    }
+
+    /// A finalizer that sends `event` to `dead_letter` if the event fails.
+    pub fn dead_letter(event: LogEvent, dead_letter: Arc<DeadLetter>) -> Self {
+        Self::with_target(FinalizerTarget::DeadLetter {
+            event: Some(Box::new(event)),
+            dead_letter,
+            rejection: Mutex::new(None),
+        })
+    }
+
+    fn with_target(target: FinalizerTarget) -> Self {
+        Self {
+            status: AtomicEventStatus::new(),
+            target,
+        }
+    }
+
+    pub fn update_status(&self, status: EventStatus) {
+        self.status.update(status);
+    }
+
+    fn reject(&self, rejection: &Rejection) {
+        if let FinalizerTarget::DeadLetter {
+            rejection: slot, ..
+        } = &self.target
+        {
+            slot.lock()
+                .expect("poisoned lock")
+                .get_or_insert_with(|| rejection.clone());
+        }
+        self.update_status(EventStatus::Failed);
+    }
+}
+
+impl Drop for EventFinalizer {
+    fn drop(&mut self) {
+        let status = self.status.load();
+        match &mut self.target {
+            FinalizerTarget::Batch(batch) => batch.update_status(status),
+            FinalizerTarget::DeadLetter {
+                event,
+                dead_letter,
+                rejection,
+            } => {
+                if status == EventStatus::Failed {
+                    if let Some(event) = event.take() {
+                        let rejection = rejection.get_mut().expect("poisoned lock").take();
+                        dead_letter.send(*event, rejection);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The set of finalizers carried by an event.
+///
+/// Cloning shares the underlying finalizers, which is what makes fanned out
+/// copies of an event resolve their batch only once all of them are done.
+#[derive(Clone, Debug, Default)]
+pub struct EventFinalizers(Vec<Arc<EventFinalizer>>);
+
+impl EventFinalizers {
+    pub fn new(finalizer: EventFinalizer) -> Self {
+        Self(vec![Arc::new(finalizer)])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn add(&mut self, finalizer: EventFinalizer) {
+        self.0.push(Arc::new(finalizer));
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub fn update_status(&self, status: EventStatus) {
+        for finalizer in &self.0 {
+            finalizer.update_status(status);
+        }
+    }
+
+    /// Marks the events as failed because the sink's destination rejected them.
+    pub fn reject(&self, rejection: &Rejection) {
+        for finalizer in &self.0 {
+            finalizer.reject(rejection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, event::Event};
+    use futures::{compat::Future01CompatExt, FutureExt};
+    use futures01::{sync::mpsc, Stream};
+
+    fn finalizer(batch: &Arc<BatchNotifier>) -> EventFinalizers {
+        EventFinalizers::new(EventFinalizer::new(Arc::clone(batch)))
+    }
+
+    #[tokio::test]
+    async fn batch_resolves_after_all_finalizers_drop() {
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let first = finalizer(&batch);
+        let second = finalizer(&batch);
+        drop(batch);
+
+        first.update_status(EventStatus::Delivered);
+        drop(first);
+        assert!((&mut receiver).now_or_never().is_none());
+
+        second.update_status(EventStatus::Delivered);
+        drop(second);
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+    }
+
+    #[tokio::test]
+    async fn dropped_events_do_not_fail_batch() {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        drop(finalizer(&batch));
+        drop(batch);
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+    }
+
+    #[tokio::test]
+    async fn shared_finalizer_keeps_most_severe_status() {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let original = finalizer(&batch);
+        let copy = original.clone();
+        drop(batch);
+
+        original.update_status(EventStatus::Delivered);
+        copy.update_status(EventStatus::Errored);
+        drop(original);
+        drop(copy);
+        assert_eq!(receiver.await, BatchStatus::Errored);
+    }
+
+    #[tokio::test]
+    async fn rejected_events_go_to_dead_letter() {
+        let (tx, rx) = mpsc::unbounded();
+        let dead_letter = Arc::new(DeadLetter::new("out", tx));
+        let finalizers = ["one", "two", "three"]
+            .iter()
+            .map(|message| {
+                let finalizer = EventFinalizer::dead_letter(
+                    Event::from(*message).into_log(),
+                    Arc::clone(&dead_letter),
+                );
+                EventFinalizers::new(finalizer)
+            })
+            .collect::<Vec<_>>();
+        drop(dead_letter);
+
+        finalizers[0].update_status(EventStatus::Delivered);
+        let rejection = Rejection {
+            reason: "mapping error".into(),
+            status: Some(400),
+        };
+        finalizers[1].reject(&rejection);
+        finalizers[2].reject(&rejection);
+        drop(finalizers);
+
+        let events = rx.collect().compat().await.unwrap();
+        assert_eq!(events.len(), 2);
+        let log = events[1].as_log();
+        assert_eq!(log[log_schema().message_key()], "three".into());
+        assert_eq!(log["dead_letter.sink"], "out".into());
+        assert_eq!(log["dead_letter.reason"], "mapping error".into());
+        assert_eq!(log["dead_letter.status"], 400.into());
+    }
+
+    #[test]
+    fn status_update_precedence() {
+        use EventStatus::*;
+        assert_eq!(Dropped.update(Delivered), Delivered);
+        assert_eq!(Delivered.update(Dropped), Delivered);
+        assert_eq!(Delivered.update(Errored), Errored);
+        assert_eq!(Failed.update(Delivered), Failed);
+    }
+}
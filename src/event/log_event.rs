@@ -1,18 +1,52 @@
-use crate::event::{lookup::Segment, util, Lookup, PathComponent, Value};
+use crate::event::{
+    finalization::{BatchNotifier, EventFinalizer, EventFinalizers},
+    lookup::Segment,
+    util, Lookup, PathComponent, Value,
+};
 use serde::{Serialize, Serializer};
 use std::{
     collections::{btree_map::Entry, BTreeMap, HashMap},
     convert::{TryFrom, TryInto},
     fmt::{Debug, Display},
     iter::FromIterator,
+    sync::Arc,
 };
 
-#[derive(PartialEq, Debug, Clone, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct LogEvent {
     fields: BTreeMap<String, Value>,
+    finalizers: EventFinalizers,
+}
+
+impl PartialEq for LogEvent {
+    // Finalizers only track delivery and are not part of the event's content.
+    fn eq(&self, other: &Self) -> bool {
+        self.fields == other.fields
+    }
 }
 
 impl LogEvent {
+    pub fn finalizers(&self) -> &EventFinalizers {
+        &self.finalizers
+    }
+
+    pub fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+
+    pub fn merge_finalizers(&mut self, finalizers: EventFinalizers) {
+        self.finalizers.merge(finalizers);
+    }
+
+    pub fn add_batch_notifier(&mut self, batch: &Arc<BatchNotifier>) {
+        self.finalizers.add(EventFinalizer::new(Arc::clone(batch)));
+    }
+
+    pub fn with_batch_notifier(mut self, batch: &Arc<BatchNotifier>) -> Self {
+        self.add_batch_notifier(batch);
+        self
+    }
+
     #[instrument(level = "trace", skip(self, key), fields(key = %key.as_ref()))]
     pub fn get(&self, key: impl AsRef<str>) -> Option<&Value> {
         util::log::get(&self.fields, key.as_ref())
@@ -92,6 +126,10 @@ impl LogEvent {
         self.fields.is_empty()
     }
 
+    pub fn as_map(&self) -> &BTreeMap<String, Value> {
+        &self.fields
+    }
+
     #[instrument(level = "trace", skip(self, lookup), fields(lookup = %lookup), err)]
     fn entry(&mut self, lookup: Lookup) -> crate::Result<Entry<String, Value>> {
         trace!("Seeking to entry.");
@@ -134,14 +172,16 @@ impl LogEvent {
 
 impl From<BTreeMap<String, Value>> for LogEvent {
     fn from(map: BTreeMap<String, Value>) -> Self {
-        LogEvent { fields: map }
+        LogEvent {
+            fields: map,
+            finalizers: Default::default(),
+        }
     }
 }
 
 impl Into<BTreeMap<String, Value>> for LogEvent {
     fn into(self) -> BTreeMap<String, Value> {
-        let Self { fields } = self;
-        fields
+        self.fields
     }
 }
 
@@ -149,6 +189,7 @@ impl From<HashMap<String, Value>> for LogEvent {
     fn from(map: HashMap<String, Value>) -> Self {
         LogEvent {
             fields: map.into_iter().collect(),
+            finalizers: Default::default(),
         }
     }
 }
@@ -181,8 +222,7 @@ impl TryInto<serde_json::Value> for LogEvent {
     type Error = crate::Error;
 
     fn try_into(self) -> Result<serde_json::Value, Self::Error> {
-        let Self { fields } = self;
-        Ok(serde_json::to_value(fields)?)
+        Ok(serde_json::to_value(self.fields)?)
     }
 }
 
@@ -2,7 +2,11 @@ use crate::event::{LogEvent, Value};
 use bytes::BytesMut;
 
 /// Merges all fields specified at `fields` from `incoming` to `current`.
+///
+/// The finalizers of `incoming` are carried over, so the merged event is only
+/// acknowledged once it has been delivered.
 pub fn merge_log_event(current: &mut LogEvent, mut incoming: LogEvent, fields: &[impl AsRef<str>]) {
+    current.merge_finalizers(incoming.take_finalizers());
     for field in fields {
         let incoming_val = match incoming.remove(field) {
             None => continue,
@@ -1,3 +1,4 @@
+use super::Value;
 use chrono::{DateTime, Utc};
 use derive_is_enum_variant::is_enum_variant;
 use serde::{Deserialize, Serialize};
@@ -355,6 +356,196 @@ impl Display for Metric {
     }
 }
 
+/// Metrics are exposed to remap programs as `.name`, `.namespace`,
+/// `.timestamp`, `.kind` and `.tags`, which can all be changed, and the
+/// read-only `.type` and `.value`.
+impl remap::Object for Metric {
+    fn insert(&mut self, path: &[Vec<String>], value: remap::Value) -> Result<(), String> {
+        // Coalesced segments insert into the right-most field.
+        let path = path
+            .iter()
+            .filter_map(|segment| segment.last().map(String::as_str))
+            .collect::<Vec<_>>();
+
+        match path.as_slice() {
+            ["name"] => self.name = remap_string(value, "name")?,
+            ["namespace"] => {
+                self.namespace = match value {
+                    remap::Value::Null => None,
+                    value => Some(remap_string(value, "namespace")?),
+                }
+            }
+            ["timestamp"] => {
+                self.timestamp = match value {
+                    remap::Value::Timestamp(timestamp) => Some(timestamp),
+                    remap::Value::Null => None,
+                    value => {
+                        return Err(format!(
+                            "metric timestamp must be a timestamp, got {}",
+                            value.kind()
+                        ))
+                    }
+                }
+            }
+            ["kind"] => {
+                self.kind = match remap_string(value, "kind")?.as_str() {
+                    "incremental" => MetricKind::Incremental,
+                    "absolute" => MetricKind::Absolute,
+                    kind => {
+                        return Err(format!(
+                            r#"metric kind must be "incremental" or "absolute", got "{}""#,
+                            kind
+                        ))
+                    }
+                }
+            }
+            ["tags"] => {
+                self.tags = match value {
+                    remap::Value::Map(map) => Some(
+                        map.into_iter()
+                            .map(|(tag, value)| {
+                                let value = remap_string(value, &format!("tags.{}", tag))?;
+                                Ok((tag, value))
+                            })
+                            .collect::<Result<_, String>>()?,
+                    ),
+                    remap::Value::Null => None,
+                    value => {
+                        return Err(format!("metric tags must be a map, got {}", value.kind()))
+                    }
+                }
+            }
+            ["tags", tag] => {
+                let value = remap_string(value, &format!("tags.{}", tag))?;
+                self.tags
+                    .get_or_insert_with(BTreeMap::new)
+                    .insert((*tag).to_owned(), value);
+            }
+            ["type"] | ["value", ..] => {
+                return Err(format!("metric field .{} is read-only", path.join(".")))
+            }
+            _ => return Err(format!("unknown metric field .{}", path.join("."))),
+        }
+
+        Ok(())
+    }
+
+    fn find(&self, path: &[Vec<String>]) -> Result<Option<remap::Value>, String> {
+        // Try every combination of coalesced segments, in order.
+        let mut paths = vec![vec![]];
+        for segment in path {
+            paths = paths
+                .into_iter()
+                .flat_map(|path: Vec<&str>| {
+                    segment.iter().map(move |field| {
+                        let mut path = path.clone();
+                        path.push(field.as_str());
+                        path
+                    })
+                })
+                .collect();
+        }
+
+        Ok(paths.iter().find_map(|path| self.remap_field(path)))
+    }
+
+    fn paths(&self) -> Vec<String> {
+        let mut paths = vec!["name".to_owned()];
+        if self.namespace.is_some() {
+            paths.push("namespace".to_owned());
+        }
+        if self.timestamp.is_some() {
+            paths.push("timestamp".to_owned());
+        }
+        paths.push("kind".to_owned());
+        if let Some(tags) = &self.tags {
+            paths.extend(tags.keys().map(|tag| format!("tags.{}", tag)));
+        }
+        paths.push("type".to_owned());
+        paths.push("value".to_owned());
+        paths
+    }
+
+    fn remove(&mut self, path: &str, compact: bool) {
+        match path {
+            "namespace" => self.namespace = None,
+            "timestamp" => self.timestamp = None,
+            "tags" => self.tags = None,
+            path if path.starts_with("tags.") => {
+                if let Some(tags) = &mut self.tags {
+                    tags.remove(&path["tags.".len()..]);
+                    if compact && tags.is_empty() {
+                        self.tags = None;
+                    }
+                }
+            }
+            // The name, kind and value are required.
+            _ => {}
+        }
+    }
+}
+
+impl Metric {
+    fn remap_field(&self, path: &[&str]) -> Option<remap::Value> {
+        match path {
+            ["name"] => Some(self.name.as_str().into()),
+            ["namespace"] => self.namespace.as_deref().map(Into::into),
+            ["timestamp"] => self.timestamp.map(Into::into),
+            ["kind"] => Some(
+                match self.kind {
+                    MetricKind::Incremental => "incremental",
+                    MetricKind::Absolute => "absolute",
+                }
+                .into(),
+            ),
+            ["tags"] => self.tags.as_ref().map(|tags| {
+                tags.iter()
+                    .map(|(tag, value)| (tag.clone(), value.as_str().into()))
+                    .collect::<BTreeMap<_, remap::Value>>()
+                    .into()
+            }),
+            ["tags", tag] => self
+                .tags
+                .as_ref()
+                .and_then(|tags| tags.get(*tag))
+                .map(|value| value.as_str().into()),
+            ["type"] => Some(self.remap_value().0.into()),
+            ["value"] => Some(self.remap_value().1),
+            _ => None,
+        }
+    }
+
+    /// The type of the metric value and its fields. Counters and gauges are a
+    /// single float, the other types a map of their fields.
+    fn remap_value(&self) -> (String, remap::Value) {
+        let value = serde_json::to_value(&self.value).expect("metric values serialize");
+        let (kind, fields) = match value {
+            serde_json::Value::Object(map) => map.into_iter().next().expect("externally tagged"),
+            _ => unreachable!("metric values are externally tagged"),
+        };
+
+        let value = match fields {
+            serde_json::Value::Object(mut map) if map.len() == 1 && map.contains_key("value") => {
+                map.remove("value").expect("value exists")
+            }
+            fields => fields,
+        };
+
+        (kind, Value::from(value).into())
+    }
+}
+
+fn remap_string(value: remap::Value, field: &str) -> Result<String, String> {
+    match value {
+        remap::Value::String(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+        value => Err(format!(
+            "metric field {} must be a string, got {}",
+            field,
+            value.kind()
+        )),
+    }
+}
+
 fn write_list<I, T, W>(
     fmt: &mut Formatter<'_>,
     sep: &str,
@@ -696,4 +887,86 @@ mod test {
             r#"six{} = count=2 sum=127 1@63 2@64"#
         );
     }
+
+    #[test]
+    fn remap_object() {
+        use remap::Object;
+
+        let path = |path: &str| {
+            path.split('.')
+                .map(|segment| vec![segment.to_owned()])
+                .collect::<Vec<_>>()
+        };
+
+        let mut metric = Metric {
+            name: "zub".into(),
+            namespace: Some("zoob".into()),
+            timestamp: Some(ts()),
+            tags: Some(tags()),
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.23 },
+        };
+
+        assert_eq!(metric.find(&path("name")), Ok(Some("zub".into())));
+        assert_eq!(metric.find(&path("timestamp")), Ok(Some(ts().into())));
+        assert_eq!(metric.find(&path("kind")), Ok(Some("incremental".into())));
+        assert_eq!(
+            metric.find(&path("tags.normal_tag")),
+            Ok(Some("value".into()))
+        );
+        assert_eq!(metric.find(&path("tags.missing")), Ok(None));
+        assert_eq!(metric.find(&path("type")), Ok(Some("counter".into())));
+        assert_eq!(metric.find(&path("value")), Ok(Some(1.23.into())));
+        assert_eq!(
+            metric.find(&[
+                vec!["tags".to_owned()],
+                vec!["missing".to_owned(), "true_tag".to_owned()]
+            ]),
+            Ok(Some("true".into()))
+        );
+
+        metric.insert(&path("name"), "baz".into()).unwrap();
+        metric
+            .insert(&path("namespace"), remap::Value::Null)
+            .unwrap();
+        metric.insert(&path("kind"), "absolute".into()).unwrap();
+        metric.insert(&path("tags.new_tag"), "new".into()).unwrap();
+        assert_eq!(metric.name, "baz");
+        assert_eq!(metric.namespace, None);
+        assert_eq!(metric.kind, MetricKind::Absolute);
+        assert_eq!(metric.tags.as_ref().unwrap()["new_tag"], "new");
+
+        assert_eq!(
+            metric.insert(&path("kind"), "gauge".into()),
+            Err(r#"metric kind must be "incremental" or "absolute", got "gauge""#.to_owned())
+        );
+        assert_eq!(
+            metric.insert(&path("tags.count"), 1.into()),
+            Err("metric field tags.count must be a string, got integer".to_owned())
+        );
+        assert_eq!(
+            metric.insert(&path("value"), 1.into()),
+            Err("metric field .value is read-only".to_owned())
+        );
+
+        assert_eq!(
+            metric.paths(),
+            vec![
+                "name",
+                "timestamp",
+                "kind",
+                "tags.empty_tag",
+                "tags.new_tag",
+                "tags.normal_tag",
+                "tags.true_tag",
+                "type",
+                "value"
+            ]
+        );
+
+        metric.remove("timestamp", false);
+        metric.remove("tags.new_tag", false);
+        assert_eq!(metric.timestamp, None);
+        assert_eq!(metric.tags, Some(tags()));
+    }
 }
@@ -5,6 +5,7 @@ use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
 use std::collections::{BTreeMap, HashMap};
 
 pub mod discriminant;
+pub mod finalization;
 pub mod merge;
 pub mod merge_state;
 pub mod metric;
@@ -14,10 +15,14 @@ mod log_event;
 mod lookup;
 mod value;
 
+use finalization::{BatchNotifier, EventFinalizers};
 pub use log_event::LogEvent;
 pub use lookup::Lookup;
 pub use metric::{Metric, MetricKind, MetricValue, StatisticKind};
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    sync::Arc,
+};
 pub(crate) use util::log::PathComponent;
 pub(crate) use util::log::PathIter;
 pub use value::Value;
@@ -80,6 +85,26 @@ impl Event {
             _ => panic!("Failed type coercion, {:?} is not a metric", self),
         }
     }
+
+    /// Attaches a finalizer for `batch` to this event. Only log events carry
+    /// finalizers; metrics are considered delivered as soon as they are sent.
+    pub fn add_batch_notifier(&mut self, batch: &Arc<BatchNotifier>) {
+        if let Event::Log(log) = self {
+            log.add_batch_notifier(batch);
+        }
+    }
+
+    pub fn with_batch_notifier(mut self, batch: &Arc<BatchNotifier>) -> Self {
+        self.add_batch_notifier(batch);
+        self
+    }
+
+    pub fn take_finalizers(&mut self) -> EventFinalizers {
+        match self {
+            Event::Log(log) => log.take_finalizers(),
+            Event::Metric(_) => EventFinalizers::default(),
+        }
+    }
 }
 
 fn timestamp_to_string(timestamp: &DateTime<Utc>) -> String {
@@ -423,8 +448,7 @@ impl From<Metric> for Event {
     }
 }
 
-// TODO(jean): add tests
-impl remap::Object for Event {
+impl remap::Object for LogEvent {
     // TODO(jean): replace this with `Lookup`, once that lands.
     fn insert(&mut self, path: &[Vec<String>], value: remap::Value) -> Result<(), String> {
         let path_str = path
@@ -438,7 +462,18 @@ impl remap::Object for Event {
             .collect::<Vec<_>>()
             .join(".");
 
-        self.as_mut_log().insert(path_str, value);
+        if path
+            .iter()
+            .flatten()
+            .any(|p| split_indices(p).1.iter().any(|index| *index < 0))
+        {
+            return Err(format!(
+                "unable to assign to negative index in path .{}",
+                path_str
+            ));
+        }
+
+        self.insert(path_str, value);
         Ok(())
     }
 
@@ -449,16 +484,19 @@ impl remap::Object for Event {
             .map(|c| c.iter().map(|p| p.replace(".", "\\.")).collect::<Vec<_>>())
             .collect::<Vec<_>>();
 
-        // Event.as_log returns a LogEvent struct rather than a naked
-        // IndexMap<_, Value>, which means specifically for the first item in
-        // the path we need to manually call .get.
+        // A LogEvent is a struct rather than a naked IndexMap<_, Value>, which
+        // means specifically for the first item in the path we need to
+        // manually call .get.
         //
         // If we could simply pull either an IndexMap or Value out of a LogEvent
         // then we wouldn't need this duplicate code as we'd jump straight into
         // the path walker.
         let mut value = path[0]
             .iter()
-            .find_map(|p| self.as_log().get(p))
+            .find_map(|p| {
+                let (field, indices) = split_indices(p);
+                index_value(self.get(field)?, &indices)
+            })
             .ok_or_else(|| format!("path .{} not found in event", path[0].first().unwrap()))?;
 
         // Walk remaining (if any) path segments. Our parse is already capable
@@ -476,7 +514,10 @@ impl remap::Object for Event {
         for (i, segments) in path.iter().enumerate().skip(1) {
             value = segments
                 .iter()
-                .find_map(|p| util::log::get_value(value, PathIter::new(p)))
+                .find_map(|p| {
+                    let (field, indices) = split_indices(p);
+                    index_value(util::log::get_value(value, PathIter::new(field))?, &indices)
+                })
                 .ok_or_else(|| {
                     format!(
                         "path {} not found in event",
@@ -495,12 +536,86 @@ impl remap::Object for Event {
     }
 
     fn paths(&self) -> Vec<String> {
-        self.as_log().keys().collect()
+        self.keys().collect()
+    }
+
+    fn remove(&mut self, path: &str, compact: bool) {
+        self.remove_prune(path, compact);
+    }
+}
+
+impl remap::Object for Event {
+    fn insert(&mut self, path: &[Vec<String>], value: remap::Value) -> Result<(), String> {
+        match self {
+            Event::Log(log) => remap::Object::insert(log, path, value),
+            Event::Metric(metric) => remap::Object::insert(metric, path, value),
+        }
+    }
+
+    fn find(&self, path: &[Vec<String>]) -> Result<Option<remap::Value>, String> {
+        match self {
+            Event::Log(log) => remap::Object::find(log, path),
+            Event::Metric(metric) => remap::Object::find(metric, path),
+        }
+    }
+
+    fn paths(&self) -> Vec<String> {
+        match self {
+            Event::Log(log) => remap::Object::paths(log),
+            Event::Metric(metric) => remap::Object::paths(metric),
+        }
     }
 
     fn remove(&mut self, path: &str, compact: bool) {
-        self.as_mut_log().remove_prune(path, compact);
+        match self {
+            Event::Log(log) => remap::Object::remove(log, path, compact),
+            Event::Metric(metric) => remap::Object::remove(metric, path, compact),
+        }
+    }
+}
+
+/// Splits the trailing indices off a remap path segment, e.g. `foo[1][-1]` into
+/// `foo` and `[1, -1]`, so that negative indices can be resolved against the
+/// length of the array they index into.
+fn split_indices(segment: &str) -> (&str, Vec<isize>) {
+    let mut field = segment;
+    let mut indices = vec![];
+
+    while field.ends_with(']') {
+        let start = match field.rfind('[') {
+            Some(start) => start,
+            None => break,
+        };
+        match field[start + 1..field.len() - 1].parse() {
+            Ok(index) => indices.push(index),
+            Err(_) => break,
+        }
+        field = &field[..start];
     }
+
+    indices.reverse();
+    (field, indices)
+}
+
+/// Looks up `indices` in nested arrays, counting negative indices from the end.
+fn index_value<'a>(mut value: &'a Value, indices: &[isize]) -> Option<&'a Value> {
+    for &index in indices {
+        let array = match value {
+            Value::Array(array) => array,
+            _ => return None,
+        };
+        let index = if index < 0 {
+            array.len() as isize + index
+        } else {
+            index
+        };
+        if index < 0 {
+            return None;
+        }
+        value = array.get(index as usize)?;
+    }
+
+    Some(value)
 }
 
 #[cfg(test)]
@@ -579,6 +694,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn remap_find_array_indices() {
+        use remap::Object;
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("foo[0]", 1);
+        event.as_mut_log().insert("foo[1]", 2);
+        event.as_mut_log().insert("bar.baz[0][1]", "x");
+
+        let find = |path: &[&str]| {
+            let path = path.iter().map(|p| vec![p.to_string()]).collect::<Vec<_>>();
+            event.find(&path)
+        };
+
+        assert_eq!(find(&["foo[0]"]), Ok(Some(1.into())));
+        assert_eq!(find(&["foo[-1]"]), Ok(Some(2.into())));
+        assert_eq!(find(&["foo[-2]"]), Ok(Some(1.into())));
+        assert!(find(&["foo[-3]"]).is_err());
+        assert_eq!(find(&["bar", "baz[-1][-1]"]), Ok(Some("x".into())));
+
+        assert!(event
+            .insert(&[vec!["foo[-1]".to_owned()]], remap::Value::Integer(3))
+            .is_err());
+    }
+
     #[test]
     fn event_iteration_order() {
         let mut event = Event::new_empty_log();
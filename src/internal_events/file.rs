@@ -1,4 +1,5 @@
 use super::InternalEvent;
+use crate::event::finalization::BatchStatus;
 use file_source::FileSourceInternalEvents;
 use metrics::counter;
 use std::io::Error;
@@ -31,6 +32,30 @@ impl InternalEvent for FileEventReceived<'_> {
     }
 }
 
+#[derive(Debug)]
+pub struct FileEventDeliveryFailed<'a> {
+    pub file: &'a str,
+    pub status: BatchStatus,
+}
+
+impl InternalEvent for FileEventDeliveryFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Event was not delivered; not advancing checkpoint.",
+            file = %self.file,
+            status = ?self.status,
+            rate_limit_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "events_delivery_failed_total", 1,
+            "file" => self.file.to_owned(),
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct FileChecksumFailed<'a> {
     pub path: &'a Path,
@@ -1,5 +1,7 @@
 use super::InternalEvent;
-use metrics::counter;
+use crate::event::finalization::BatchStatus;
+use metrics::{counter, gauge};
+use rdkafka::consumer::Rebalance;
 
 #[derive(Debug)]
 pub struct KafkaEventReceived {
@@ -32,6 +34,90 @@ impl InternalEvent for KafkaOffsetUpdateFailed {
     }
 }
 
+#[derive(Debug)]
+pub struct KafkaEventDeliveryFailed<'a> {
+    pub topic: &'a str,
+    pub partition: i32,
+    pub offset: i64,
+    pub status: BatchStatus,
+}
+
+impl InternalEvent for KafkaEventDeliveryFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Event was not delivered; not storing its offset.",
+            topic = %self.topic,
+            partition = %self.partition,
+            offset = %self.offset,
+            status = ?self.status,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_delivery_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaPartitionStalled<'a> {
+    pub topic: &'a str,
+    pub partition: i32,
+    pub stalled: bool,
+}
+
+impl InternalEvent for KafkaPartitionStalled<'_> {
+    fn emit_logs(&self) {
+        if self.stalled {
+            warn!(
+                message = "Partition stalled on an undelivered event; not storing its offsets until the next rebalance.",
+                topic = %self.topic,
+                partition = %self.partition,
+            );
+        } else {
+            info!(
+                message = "Partition no longer stalled.",
+                topic = %self.topic,
+                partition = %self.partition,
+            );
+        }
+    }
+
+    fn emit_metrics(&self) {
+        gauge!(
+            "consumer_partition_stalled", if self.stalled { 1.0 } else { 0.0 },
+            "topic" => self.topic.to_owned(),
+            "partition" => self.partition.to_string(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaOffsetHeldBack<'a> {
+    pub topic: &'a str,
+    pub partition: i32,
+    pub offset: i64,
+}
+
+impl InternalEvent for KafkaOffsetHeldBack<'_> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Partition is stalled; not storing offset.",
+            topic = %self.topic,
+            partition = %self.partition,
+            offset = %self.offset,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "consumer_offsets_held_back_total", 1,
+            "topic" => self.topic.to_owned(),
+            "partition" => self.partition.to_string(),
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct KafkaEventFailed {
     pub error: rdkafka::error::KafkaError,
@@ -57,3 +143,75 @@ impl InternalEvent for KafkaKeyExtractionFailed<'_> {
         error!(message = "Failed to extract key.", key_field = %self.key_field);
     }
 }
+
+#[derive(Debug)]
+pub struct KafkaPartitionStatistics<'a> {
+    pub topic: &'a str,
+    pub partition: i32,
+    pub consumer_lag: i64,
+    pub consumed_offset: i64,
+    pub committed_offset: i64,
+    pub high_watermark: i64,
+}
+
+impl InternalEvent for KafkaPartitionStatistics<'_> {
+    fn emit_metrics(&self) {
+        // librdkafka reports unknown offsets and lags as negative values.
+        let topic = self.topic.to_owned();
+        let partition = self.partition.to_string();
+        if self.consumer_lag >= 0 {
+            gauge!(
+                "consumer_lag", self.consumer_lag as f64,
+                "topic" => topic.clone(),
+                "partition" => partition.clone(),
+            );
+        }
+        if self.consumed_offset >= 0 {
+            gauge!(
+                "consumer_offset", self.consumed_offset as f64,
+                "topic" => topic.clone(),
+                "partition" => partition.clone(),
+            );
+        }
+        if self.committed_offset >= 0 {
+            gauge!(
+                "consumer_committed_offset", self.committed_offset as f64,
+                "topic" => topic.clone(),
+                "partition" => partition.clone(),
+            );
+        }
+        if self.high_watermark >= 0 {
+            gauge!(
+                "partition_high_watermark", self.high_watermark as f64,
+                "topic" => topic,
+                "partition" => partition,
+            );
+        }
+    }
+}
+
+pub struct KafkaRebalance<'a, 'b> {
+    pub rebalance: &'a Rebalance<'b>,
+}
+
+impl InternalEvent for KafkaRebalance<'_, '_> {
+    fn emit_logs(&self) {
+        match self.rebalance {
+            Rebalance::Assign(partitions) => {
+                let partitions = partitions
+                    .elements()
+                    .iter()
+                    .map(|element| format!("{}:{}", element.topic(), element.partition()))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                info!(message = "Partitions assigned.", %partitions);
+            }
+            Rebalance::Revoke => info!(message = "Partitions revoked."),
+            Rebalance::Error(error) => error!(message = "Rebalance failed.", %error),
+        }
+    }
+
+    fn emit_metrics(&self) {
+        counter!("consumer_rebalances_total", 1);
+    }
+}
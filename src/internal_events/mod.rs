@@ -56,9 +56,13 @@ mod metric_to_log;
 #[cfg(feature = "sources-mongodb_metrics")]
 mod mongodb_metrics;
 mod open;
+#[cfg(feature = "sources-opentelemetry")]
+mod opentelemetry;
 mod process;
 #[cfg(feature = "sources-prometheus")]
 mod prometheus;
+#[cfg(any(feature = "sources-redis", feature = "sinks-redis"))]
+mod redis;
 #[cfg(feature = "transforms-reduce")]
 mod reduce;
 #[cfg(feature = "transforms-regex_parser")]
@@ -154,9 +158,13 @@ pub use self::lua::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
 pub use self::open::*;
+#[cfg(feature = "sources-opentelemetry")]
+pub use self::opentelemetry::*;
 pub use self::process::*;
 #[cfg(feature = "sources-prometheus")]
 pub use self::prometheus::*;
+#[cfg(any(feature = "sources-redis", feature = "sinks-redis"))]
+pub use self::redis::*;
 #[cfg(feature = "transforms-reduce")]
 pub(crate) use self::reduce::*;
 #[cfg(feature = "transforms-regex_parser")]
@@ -0,0 +1,35 @@
+use super::InternalEvent;
+use metrics::counter;
+use prost::DecodeError;
+
+#[derive(Debug)]
+pub struct OpenTelemetryEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for OpenTelemetryEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received events.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_processed_total", self.count as u64);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct OpenTelemetryProtoDecodeError {
+    pub error: DecodeError,
+}
+
+impl InternalEvent for OpenTelemetryProtoDecodeError {
+    fn emit_logs(&self) {
+        error!(message = "Failed to decode protobuf message.", error = ?self.error, rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("protobuf_decode_errors_total", 1);
+    }
+}
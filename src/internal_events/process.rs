@@ -1,6 +1,6 @@
 use super::InternalEvent;
+use crate::config::ConfigPath;
 use metrics::counter;
-use std::path::PathBuf;
 
 #[derive(Debug)]
 pub struct VectorStarted;
@@ -24,7 +24,7 @@ impl InternalEvent for VectorStarted {
 
 #[derive(Debug)]
 pub struct VectorReloaded<'a> {
-    pub config_paths: &'a [PathBuf],
+    pub config_paths: &'a [ConfigPath],
 }
 
 impl InternalEvent for VectorReloaded<'_> {
@@ -1,8 +1,9 @@
 use super::InternalEvent;
-use crate::sources::prometheus::parser::ParserError;
+use crate::sources::prometheus::{parser::ParserError, TargetsFileError};
 use metrics::{counter, histogram};
 use std::borrow::Cow;
-use std::time::Instant;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct PrometheusEventReceived {
@@ -91,3 +92,61 @@ impl InternalEvent for PrometheusHttpError {
         counter!("http_request_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct PrometheusScrapeTimeout {
+    pub url: http::Uri,
+    pub timeout: Duration,
+}
+
+impl InternalEvent for PrometheusScrapeTimeout {
+    fn emit_logs(&self) {
+        error!(message = "Scrape timed out.", url = %self.url, timeout = ?self.timeout);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("scrape_timeouts_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct PrometheusTargetsFileError<'a> {
+    pub path: &'a Path,
+    pub error: TargetsFileError,
+}
+
+impl<'a> InternalEvent for PrometheusTargetsFileError<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed reading targets file.",
+            path = ?self.path,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("targets_file_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct PrometheusInvalidTarget {
+    pub url: String,
+    pub error: http::uri::InvalidUri,
+}
+
+impl InternalEvent for PrometheusInvalidTarget {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Dropping target with an invalid URL.",
+            url = %self.url,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("invalid_targets_total", 1);
+    }
+}
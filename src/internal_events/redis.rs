@@ -0,0 +1,110 @@
+use super::InternalEvent;
+use crate::event::finalization::BatchStatus;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct RedisEventReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for RedisEventReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", rate_limit_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_processed_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisReceiveFailed {
+    pub error: redis::RedisError,
+}
+
+impl InternalEvent for RedisReceiveFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to read from Redis.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisAckFailed {
+    pub count: usize,
+    pub error: redis::RedisError,
+}
+
+impl InternalEvent for RedisAckFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Unable to acknowledge stream entries.",
+            count = %self.count,
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("stream_acks_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisEventDeliveryFailed {
+    pub count: usize,
+    pub status: BatchStatus,
+}
+
+impl InternalEvent for RedisEventDeliveryFailed {
+    fn emit_logs(&self) {
+        error!(
+            message = "Events were not delivered; not acknowledging their stream entries.",
+            count = %self.count,
+            status = ?self.status,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_delivery_failed_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisEventSent {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for RedisEventSent {
+    fn emit_metrics(&self) {
+        counter!("events_processed_total", 1);
+        counter!("processed_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisMissingKeys<'a> {
+    pub keys: &'a [String],
+}
+
+impl<'a> InternalEvent for RedisMissingKeys<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Keys do not exist on the event; dropping event.",
+            missing_keys = ?self.keys,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_keys_total", 1);
+    }
+}
@@ -10,6 +10,29 @@ impl InternalEvent for RemapEventProcessed {
     }
 }
 
+#[derive(Debug)]
+pub struct RemapEventAborted {
+    /// If set to true, the remap transform has dropped the aborted event.
+    pub event_dropped: bool,
+}
+
+impl InternalEvent for RemapEventAborted {
+    fn emit_logs(&self) {
+        if self.event_dropped {
+            debug!(
+                message = "Mapping aborted; discarding event.",
+                rate_limit_secs = 30
+            );
+        }
+    }
+
+    fn emit_metrics(&self) {
+        if self.event_dropped {
+            counter!("events_discarded_total", 1);
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RemapFailedMapping {
     /// If set to true, the remap transform has dropped the event after a failed
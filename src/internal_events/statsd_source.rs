@@ -34,6 +34,25 @@ impl InternalEvent for StatsdInvalidRecord<'_> {
     }
 }
 
+#[derive(Debug)]
+pub struct StatsdLogEventDiscarded<'a> {
+    pub text: &'a str,
+}
+
+impl InternalEvent for StatsdLogEventDiscarded<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Discarding DogStatsD event or service check, as `dogstatsd_events` is disabled.",
+            text = %self.text,
+            rate_limit_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1);
+    }
+}
+
 #[derive(Debug)]
 enum StatsdSocketErrorType {
     Bind,
@@ -63,8 +82,8 @@ impl<T> StatsdSocketError<T> {
 impl<T: std::fmt::Debug + std::fmt::Display> InternalEvent for StatsdSocketError<T> {
     fn emit_logs(&self) {
         let message = match self.r#type {
-            StatsdSocketErrorType::Bind => "Failed to bind to UDP listener socket.",
-            StatsdSocketErrorType::Read => "Failed to read UDP datagram.",
+            StatsdSocketErrorType::Bind => "Failed to bind to listener socket.",
+            StatsdSocketErrorType::Read => "Failed to read datagram.",
         };
         error!(message, error = ?self.error);
     }
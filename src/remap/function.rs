@@ -28,16 +28,26 @@ macro_rules! optional {
     }
 }
 
+mod append;
 mod ceil;
+mod compact;
 mod contains;
 mod del;
 mod downcase;
 mod ends_with;
+mod filter;
+mod flatten;
 mod floor;
+mod for_each;
 mod format_number;
 mod format_timestamp;
+mod keys;
+mod length;
+mod map_keys;
+mod map_values;
 mod r#match;
 mod md5;
+mod merge;
 mod now;
 mod only_fields;
 mod parse_duration;
@@ -45,6 +55,7 @@ mod parse_json;
 mod parse_syslog;
 mod parse_timestamp;
 mod parse_url;
+mod push;
 mod round;
 mod sha1;
 mod sha2;
@@ -63,19 +74,30 @@ mod tokenize;
 mod truncate;
 mod upcase;
 mod uuid_v4;
+mod values;
 
 pub use self::md5::Md5;
 pub use self::sha1::Sha1;
 pub use self::sha2::Sha2;
 pub use self::sha3::Sha3;
+pub use append::Append;
 pub use ceil::Ceil;
+pub use compact::Compact;
 pub use contains::Contains;
 pub use del::Del;
 pub use downcase::Downcase;
 pub use ends_with::EndsWith;
+pub use filter::Filter;
+pub use flatten::Flatten;
 pub use floor::Floor;
+pub use for_each::ForEach;
 pub use format_number::FormatNumber;
 pub use format_timestamp::FormatTimestamp;
+pub use keys::Keys;
+pub use length::Length;
+pub use map_keys::MapKeys;
+pub use map_values::MapValues;
+pub use merge::Merge;
 pub use now::Now;
 pub use only_fields::OnlyFields;
 pub use parse_duration::ParseDuration;
@@ -83,6 +105,7 @@ pub use parse_json::ParseJson;
 pub use parse_syslog::ParseSyslog;
 pub use parse_timestamp::ParseTimestamp;
 pub use parse_url::ParseUrl;
+pub use push::Push;
 pub use r#match::Match;
 pub use round::Round;
 pub use slice::Slice;
@@ -99,6 +122,7 @@ pub use tokenize::Tokenize;
 pub use truncate::Truncate;
 pub use upcase::Upcase;
 pub use uuid_v4::UuidV4;
+pub use values::Values;
 
 use remap::{Result, Value};
 
@@ -0,0 +1,101 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Append;
+
+impl Function for Append {
+    fn identifier(&self) -> &'static str {
+        "append"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "items",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let items = arguments.required_expr("items")?;
+
+        Ok(Box::new(AppendFn { value, items }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::ARRAY
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AppendFn {
+    value: Box<dyn Expression>,
+    items: Box<dyn Expression>,
+}
+
+impl AppendFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, items: Vec<Value>) -> Self {
+        let items = Box::new(Literal::from(items));
+
+        Self { value, items }
+    }
+}
+
+impl Expression for AppendFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let mut list = required!(state, object, self.value, Value::Array(v) => v);
+        let items = required!(state, object, self.items, Value::Array(v) => v);
+
+        list.extend(items);
+
+        Ok(Some(list.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn append() {
+        let cases = vec![
+            (
+                map![],
+                Err("path error: missing path: foo".into()),
+                AppendFn::new(Box::new(Path::from("foo")), vec![]),
+            ),
+            (
+                map!["foo": Vec::<Value>::new()],
+                Ok(Some(Vec::<Value>::new().into())),
+                AppendFn::new(Box::new(Path::from("foo")), vec![]),
+            ),
+            (
+                map!["foo": vec![1, 2]],
+                Ok(Some(
+                    vec![Value::from(1), 2.into(), 3.into(), "x".into()].into(),
+                )),
+                AppendFn::new(Box::new(Path::from("foo")), vec![3.into(), "x".into()]),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -30,6 +30,10 @@ impl Function for Ceil {
 
         Ok(Box::new(CeilFn { value, precision }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::INTEGER | Kind::FLOAT
+    }
 }
 
 #[derive(Debug, Clone)]
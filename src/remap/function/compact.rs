@@ -0,0 +1,210 @@
+use remap::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Compact;
+
+impl Function for Compact {
+    fn identifier(&self) -> &'static str {
+        "compact"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Array(_) | Value::Map(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "recursive",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "null",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "string",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "map",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "array",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let recursive = arguments.optional_expr("recursive")?;
+        let null = arguments.optional_expr("null")?;
+        let string = arguments.optional_expr("string")?;
+        let map = arguments.optional_expr("map")?;
+        let array = arguments.optional_expr("array")?;
+
+        Ok(Box::new(CompactFn {
+            value,
+            recursive,
+            null,
+            string,
+            map,
+            array,
+        }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::ARRAY | Kind::MAP
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompactFn {
+    value: Box<dyn Expression>,
+    recursive: Option<Box<dyn Expression>>,
+    null: Option<Box<dyn Expression>>,
+    string: Option<Box<dyn Expression>>,
+    map: Option<Box<dyn Expression>>,
+    array: Option<Box<dyn Expression>>,
+}
+
+impl CompactFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, recursive: bool, null: bool, string: bool) -> Self {
+        let literal = |v: bool| Some(Box::new(Literal::from(v)) as _);
+
+        Self {
+            value,
+            recursive: literal(recursive),
+            null: literal(null),
+            string: literal(string),
+            map: None,
+            array: None,
+        }
+    }
+}
+
+/// Which kinds of empty values to remove.
+#[derive(Debug, Clone, Copy)]
+struct Options {
+    recursive: bool,
+    null: bool,
+    string: bool,
+    map: bool,
+    array: bool,
+}
+
+impl Options {
+    fn is_empty(&self, value: &Value) -> bool {
+        match value {
+            Value::Null => self.null,
+            Value::String(v) => self.string && v.is_empty(),
+            Value::Map(v) => self.map && v.is_empty(),
+            Value::Array(v) => self.array && v.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl Expression for CompactFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let mut flag = |expr: &Option<Box<dyn Expression>>| -> Result<bool> {
+            Ok(optional!(state, object, expr, Value::Boolean(v) => v).unwrap_or(true))
+        };
+
+        let options = Options {
+            recursive: flag(&self.recursive)?,
+            null: flag(&self.null)?,
+            string: flag(&self.string)?,
+            map: flag(&self.map)?,
+            array: flag(&self.array)?,
+        };
+
+        let value = required!(
+            state, object, self.value,
+            Value::Array(v) => compact_array(v, &options).into(),
+            Value::Map(v) => compact_map(v, &options).into(),
+        );
+
+        Ok(Some(value))
+    }
+}
+
+fn compact_value(value: Value, options: &Options) -> Value {
+    match value {
+        Value::Array(v) if options.recursive => compact_array(v, options).into(),
+        Value::Map(v) if options.recursive => compact_map(v, options).into(),
+        value => value,
+    }
+}
+
+fn compact_array(array: Vec<Value>, options: &Options) -> Vec<Value> {
+    array
+        .into_iter()
+        .map(|v| compact_value(v, options))
+        .filter(|v| !options.is_empty(v))
+        .collect()
+}
+
+fn compact_map(map: BTreeMap<String, Value>, options: &Options) -> BTreeMap<String, Value> {
+    map.into_iter()
+        .map(|(k, v)| (k, compact_value(v, options)))
+        .filter(|(_, v)| !options.is_empty(v))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn compact() {
+        let cases = vec![
+            (
+                map![],
+                Err("path error: missing path: foo".into()),
+                CompactFn::new(Box::new(Path::from("foo")), true, true, true),
+            ),
+            (
+                map!["foo": vec![Value::from(1), Value::Null, "".into(), "x".into()]],
+                Ok(Some(vec![Value::from(1), "x".into()].into())),
+                CompactFn::new(Box::new(Path::from("foo")), true, true, true),
+            ),
+            (
+                map!["foo": vec![Value::from(1), Value::Null, "".into()]],
+                Ok(Some(vec![Value::from(1), "".into()].into())),
+                CompactFn::new(Box::new(Path::from("foo")), true, true, false),
+            ),
+            (
+                map!["foo": map!["a": Value::Null, "b": map!["c": Value::Null], "d": 1]],
+                Ok(Some(map!["d": 1].into())),
+                CompactFn::new(Box::new(Path::from("foo")), true, true, true),
+            ),
+            (
+                map!["foo": map!["a": Value::Null, "b": map!["c": Value::Null], "d": 1]],
+                Ok(Some(map!["b": map!["c": Value::Null], "d": 1].into())),
+                CompactFn::new(Box::new(Path::from("foo")), false, true, true),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
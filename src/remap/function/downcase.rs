@@ -22,6 +22,10 @@ impl Function for Downcase {
 
         Ok(Box::new(DowncaseFn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
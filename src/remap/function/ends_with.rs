@@ -39,6 +39,10 @@ impl Function for EndsWith {
             case_sensitive,
         }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::BOOLEAN
+    }
 }
 
 #[derive(Debug, Clone)]
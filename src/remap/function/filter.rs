@@ -0,0 +1,128 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Filter;
+
+impl Function for Filter {
+    fn identifier(&self) -> &'static str {
+        "filter"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_) | Value::Array(_)),
+            required: true,
+        }]
+    }
+
+    fn accepts_closure(&self) -> bool {
+        true
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let closure = arguments.required_closure(2)?;
+
+        Ok(Box::new(FilterFn { value, closure }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::MAP | Kind::ARRAY
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FilterFn {
+    value: Box<dyn Expression>,
+    closure: Closure,
+}
+
+impl Expression for FilterFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(
+            state, object, self.value,
+            v @ Value::Map(_) => v,
+            v @ Value::Array(_) => v,
+        );
+
+        let mut keep = |key: Value, value: &Value| match self.closure.call(
+            state,
+            object,
+            vec![key, value.clone()],
+        )? {
+            Some(Value::Boolean(keep)) => Ok(keep),
+            Some(v) => Err(format!("closure must return a boolean, got {}", v.kind()).into()),
+            None => Err(Error::from("closure must return a boolean")),
+        };
+
+        let value = match value {
+            Value::Map(map) => {
+                let mut filtered = vec![];
+                for (key, value) in map {
+                    if keep(key.clone().into(), &value)? {
+                        filtered.push((key, value));
+                    }
+                }
+
+                Value::Map(filtered.into_iter().collect())
+            }
+            Value::Array(array) => {
+                let mut filtered = vec![];
+                for (index, value) in array.into_iter().enumerate() {
+                    if keep((index as i64).into(), &value)? {
+                        filtered.push(value);
+                    }
+                }
+
+                Value::Array(filtered)
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use remap::{Program, Runtime};
+
+    #[test]
+    fn filter() {
+        let cases = vec![
+            (
+                map!["foo": map!["a": 1, "b": 2, "c": 3]],
+                r#"filter(.foo) -> |key, value| { $value != 2 }"#,
+                Ok(Some(map!["a": 1, "c": 3].into())),
+            ),
+            (
+                map!["foo": map!["a": 1, "b": 2]],
+                r#"filter(.foo) -> |key, value| { $key == "b" }"#,
+                Ok(Some(map!["b": 2].into())),
+            ),
+            (
+                map!["foo": vec![Value::from("a"), "b".into(), "c".into()]],
+                r#"filter(.foo) -> |index, value| { $index > 0 }"#,
+                Ok(Some(vec![Value::from("b"), "c".into()].into())),
+            ),
+            (
+                map!["foo": vec![Value::from(1)]],
+                r#"filter(.foo) -> |index, value| { $value }"#,
+                Err("function call error: closure must return a boolean, got integer".into()),
+            ),
+        ];
+
+        for (mut object, source, exp) in cases {
+            let program = Program::new(source, &[Box::new(Filter)]).unwrap();
+            let mut runtime = Runtime::new(remap::State::default());
+            let got = runtime
+                .execute(&mut object, &program)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp, "{}", source);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use remap::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Flatten;
+
+impl Function for Flatten {
+    fn identifier(&self) -> &'static str {
+        "flatten"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Array(_) | Value::Map(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(FlattenFn { value }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::ARRAY | Kind::MAP
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FlattenFn {
+    value: Box<dyn Expression>,
+}
+
+impl FlattenFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for FlattenFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(
+            state, object, self.value,
+            Value::Array(v) => {
+                let mut flat = vec![];
+                flatten_array(v, &mut flat);
+                flat.into()
+            },
+            Value::Map(v) => {
+                let mut flat = BTreeMap::new();
+                flatten_map(None, v, &mut flat);
+                flat.into()
+            },
+        );
+
+        Ok(Some(value))
+    }
+}
+
+fn flatten_array(array: Vec<Value>, flat: &mut Vec<Value>) {
+    for value in array {
+        match value {
+            Value::Array(v) => flatten_array(v, flat),
+            value => flat.push(value),
+        }
+    }
+}
+
+/// Nested map keys are joined with a `.`, so that `{"a": {"b": 1}}` becomes
+/// `{"a.b": 1}`.
+fn flatten_map(
+    prefix: Option<&str>,
+    map: BTreeMap<String, Value>,
+    flat: &mut BTreeMap<String, Value>,
+) {
+    for (key, value) in map {
+        let key = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key,
+        };
+
+        match value {
+            Value::Map(v) => flatten_map(Some(&key), v, flat),
+            value => {
+                flat.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn flatten() {
+        let cases = vec![
+            (
+                map![],
+                Err("path error: missing path: foo".into()),
+                FlattenFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map![
+                    "foo":
+                        vec![
+                            Value::from(1),
+                            vec![Value::from(2), vec![3].into()].into(),
+                            Vec::<Value>::new().into()
+                        ]
+                ],
+                Ok(Some(vec![1, 2, 3].into())),
+                FlattenFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": map!["a": 1, "b": map!["c": map!["d": 2], "e": vec![3]]]],
+                Ok(Some(map!["a": 1, "b.c.d": 2, "b.e": vec![3]].into())),
+                FlattenFn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
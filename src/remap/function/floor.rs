@@ -30,6 +30,10 @@ impl Function for Floor {
 
         Ok(Box::new(FloorFn { value, precision }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::INTEGER | Kind::FLOAT
+    }
 }
 
 #[derive(Debug, Clone)]
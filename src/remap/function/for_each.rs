@@ -0,0 +1,102 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ForEach;
+
+impl Function for ForEach {
+    fn identifier(&self) -> &'static str {
+        "for_each"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_) | Value::Array(_)),
+            required: true,
+        }]
+    }
+
+    fn accepts_closure(&self) -> bool {
+        true
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let closure = arguments.required_closure(2)?;
+
+        Ok(Box::new(ForEachFn { value, closure }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::NULL
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ForEachFn {
+    value: Box<dyn Expression>,
+    closure: Closure,
+}
+
+impl Expression for ForEachFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let entries = required!(
+            state, object, self.value,
+            Value::Map(v) => v.into_iter().map(|(key, value)| (key.into(), value)).collect(),
+            Value::Array(v) => v
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| ((index as i64).into(), value))
+                .collect::<Vec<(Value, Value)>>(),
+        );
+
+        for (key, value) in entries {
+            self.closure.call(state, object, vec![key, value])?;
+        }
+
+        Ok(Some(Value::Null))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use remap::{Program, Runtime};
+
+    #[test]
+    fn for_each() {
+        let cases = vec![
+            (
+                map!["foo": map!["a": 1, "b": 2]],
+                "$sum = 0\nfor_each(.foo) -> |key, value| { $sum = $sum + $value }\n$sum",
+                Ok(Some(3.into())),
+            ),
+            (
+                map!["foo": vec![Value::from("a"), "b".into()]],
+                "for_each(.foo) -> |index, value| { .bar = $value }\n.bar",
+                Ok(Some("b".into())),
+            ),
+            (
+                map!["foo": vec![Value::from("a")]],
+                "for_each(.foo) -> |index, value| { $last = $value }",
+                Ok(Some(Value::Null)),
+            ),
+            (
+                map!["foo": vec![Value::from("a")]],
+                "for_each(.foo) -> |index, value| { $last = $value }\n$last",
+                Err("variable error: undefined variable: last".into()),
+            ),
+        ];
+
+        for (mut object, source, exp) in cases {
+            let program = Program::new(source, &[Box::new(ForEach)]).unwrap();
+            let mut runtime = Runtime::new(remap::State::default());
+            let got = runtime
+                .execute(&mut object, &program)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp, "{}", source);
+        }
+    }
+}
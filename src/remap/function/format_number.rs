@@ -48,6 +48,10 @@ impl Function for FormatNumber {
             grouping_separator,
         }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
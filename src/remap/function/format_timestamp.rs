@@ -31,6 +31,10 @@ impl Function for FormatTimestamp {
 
         Ok(Box::new(FormatTimestampFn { value, format }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
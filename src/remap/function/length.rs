@@ -0,0 +1,101 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Length;
+
+impl Function for Length {
+    fn identifier(&self) -> &'static str {
+        "length"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Array(_) | Value::Map(_) | Value::String(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(LengthFn { value }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::INTEGER
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LengthFn {
+    value: Box<dyn Expression>,
+}
+
+impl LengthFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for LengthFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        // Strings are measured in bytes, the same unit `slice` works in.
+        let len = required!(
+            state, object, self.value,
+            Value::Array(v) => v.len(),
+            Value::Map(v) => v.len(),
+            Value::String(v) => v.len(),
+        );
+
+        Ok(Some((len as i64).into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn length() {
+        let cases = vec![
+            (
+                map![],
+                Err("path error: missing path: foo".into()),
+                LengthFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": Vec::<Value>::new()],
+                Ok(Some(0.into())),
+                LengthFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": vec![1, 2, 3]],
+                Ok(Some(3.into())),
+                LengthFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": map!["a": 1, "b": map!["c": 2, "d": 3]]],
+                Ok(Some(2.into())),
+                LengthFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": "café"],
+                Ok(Some(5.into())),
+                LengthFn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
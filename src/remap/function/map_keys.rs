@@ -0,0 +1,101 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MapKeys;
+
+impl Function for MapKeys {
+    fn identifier(&self) -> &'static str {
+        "map_keys"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_)),
+            required: true,
+        }]
+    }
+
+    fn accepts_closure(&self) -> bool {
+        true
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let closure = arguments.required_closure(2)?;
+
+        Ok(Box::new(MapKeysFn { value, closure }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::MAP
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MapKeysFn {
+    value: Box<dyn Expression>,
+    closure: Closure,
+}
+
+impl Expression for MapKeysFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let map = required!(state, object, self.value, Value::Map(v) => v);
+
+        map.into_iter()
+            .map(|(key, value)| {
+                let key = match self
+                    .closure
+                    .call(state, object, vec![key.into(), value.clone()])?
+                {
+                    Some(Value::String(key)) => String::from_utf8_lossy(&key).into_owned(),
+                    Some(v) => {
+                        return Err(format!("closure must return a string, got {}", v.kind()).into())
+                    }
+                    None => return Err("closure must return a string".into()),
+                };
+
+                Ok((key, value))
+            })
+            .collect::<Result<_>>()
+            .map(|map| Some(Value::Map(map)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use remap::{Program, Runtime};
+
+    #[test]
+    fn map_keys() {
+        let cases = vec![
+            (
+                map!["foo": map!["a": 1, "b": 2]],
+                r#"map_keys(.foo) -> |key, value| { "prefix_" + $key }"#,
+                Ok(Some(map!["prefix_a": 1, "prefix_b": 2].into())),
+            ),
+            (
+                map!["foo": map!["a": 1, "b": 2]],
+                r#"map_keys(.foo) -> |key, value| { "same" }"#,
+                Ok(Some(map!["same": 2].into())),
+            ),
+            (
+                map!["foo": map!["a": 1]],
+                r#"map_keys(.foo) -> |key, value| { $value }"#,
+                Err("function call error: closure must return a string, got integer".into()),
+            ),
+        ];
+
+        for (mut object, source, exp) in cases {
+            let program = Program::new(source, &[Box::new(MapKeys)]).unwrap();
+            let mut runtime = Runtime::new(remap::State::default());
+            let got = runtime
+                .execute(&mut object, &program)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp, "{}", source);
+        }
+    }
+}
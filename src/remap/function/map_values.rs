@@ -0,0 +1,115 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MapValues;
+
+impl Function for MapValues {
+    fn identifier(&self) -> &'static str {
+        "map_values"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_) | Value::Array(_)),
+            required: true,
+        }]
+    }
+
+    fn accepts_closure(&self) -> bool {
+        true
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let closure = arguments.required_closure(2)?;
+
+        Ok(Box::new(MapValuesFn { value, closure }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::MAP | Kind::ARRAY
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MapValuesFn {
+    value: Box<dyn Expression>,
+    closure: Closure,
+}
+
+impl Expression for MapValuesFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(
+            state, object, self.value,
+            v @ Value::Map(_) => v,
+            v @ Value::Array(_) => v,
+        );
+
+        let mut call = |key: Value, value: Value| {
+            self.closure
+                .call(state, object, vec![key, value])
+                .map(|value| value.unwrap_or(Value::Null))
+        };
+
+        let value = match value {
+            Value::Map(map) => map
+                .into_iter()
+                .map(|(key, value)| Ok((key.clone(), call(key.into(), value)?)))
+                .collect::<Result<_>>()
+                .map(Value::Map)?,
+            Value::Array(array) => array
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| call((index as i64).into(), value))
+                .collect::<Result<_>>()
+                .map(Value::Array)?,
+            _ => unreachable!(),
+        };
+
+        Ok(Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use remap::{Program, Runtime};
+
+    #[test]
+    fn map_values() {
+        let cases = vec![
+            (
+                map!["foo": map!["a": 1, "b": 2]],
+                r#"map_values(.foo) -> |key, value| { $value + 1 }"#,
+                Ok(Some(map!["a": 2, "b": 3].into())),
+            ),
+            (
+                map!["foo": vec![Value::from("a"), "b".into()]],
+                r#"map_values(.foo) -> |index, value| { $index }"#,
+                Ok(Some(vec![Value::from(0), 1.into()].into())),
+            ),
+            (
+                map!["foo": map!["a": 1]],
+                r#"map_values(.foo) -> |key, value| { if false { 1 } }"#,
+                Ok(Some(map!["a": Value::Null].into())),
+            ),
+            (
+                map!["foo": vec![Value::from(1)]],
+                r#"map_values(.foo) -> |index, value| { $value + $missing }"#,
+                Err("variable error: undefined variable: missing".into()),
+            ),
+        ];
+
+        for (mut object, source, exp) in cases {
+            let program = Program::new(source, &[Box::new(MapValues)]).unwrap();
+            let mut runtime = Runtime::new(remap::State::default());
+            let got = runtime
+                .execute(&mut object, &program)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp, "{}", source);
+        }
+    }
+}
@@ -30,6 +30,10 @@ impl Function for Match {
 
         Ok(Box::new(MatchFn { value, pattern }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::BOOLEAN
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +21,10 @@ impl Function for Md5 {
 
         Ok(Box::new(Md5Fn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,151 @@
+use remap::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Merge;
+
+impl Function for Merge {
+    fn identifier(&self) -> &'static str {
+        "merge"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "to",
+                accepts: |v| matches!(v, Value::Map(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "from",
+                accepts: |v| matches!(v, Value::Map(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "deep",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let to = arguments.required_expr("to")?;
+        let from = arguments.required_expr("from")?;
+        let deep = arguments.optional_expr("deep")?;
+
+        Ok(Box::new(MergeFn { to, from, deep }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::MAP
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MergeFn {
+    to: Box<dyn Expression>,
+    from: Box<dyn Expression>,
+    deep: Option<Box<dyn Expression>>,
+}
+
+impl MergeFn {
+    #[cfg(test)]
+    fn new(to: Box<dyn Expression>, from: Box<dyn Expression>, deep: bool) -> Self {
+        let deep = Some(Box::new(Literal::from(deep)) as _);
+
+        Self { to, from, deep }
+    }
+}
+
+impl Expression for MergeFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let mut to = required!(state, object, self.to, Value::Map(v) => v);
+        let from = required!(state, object, self.from, Value::Map(v) => v);
+        let deep = optional!(state, object, self.deep, Value::Boolean(v) => v).unwrap_or(false);
+
+        merge_maps(&mut to, from, deep);
+
+        Ok(Some(to.into()))
+    }
+}
+
+/// Moves all fields of `from` into `to`, overwriting existing fields. When
+/// `deep` is set, maps found on both sides are merged instead.
+fn merge_maps(to: &mut BTreeMap<String, Value>, from: BTreeMap<String, Value>, deep: bool) {
+    for (key, value) in from {
+        match (to.get_mut(&key), value) {
+            (Some(Value::Map(to)), Value::Map(from)) if deep => merge_maps(to, from, deep),
+            (_, value) => {
+                to.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn merge() {
+        let cases = vec![
+            (
+                map![],
+                Err("path error: missing path: foo".into()),
+                MergeFn::new(
+                    Box::new(Path::from("foo")),
+                    Box::new(Literal::from(map![])),
+                    false,
+                ),
+            ),
+            (
+                map!["foo": map!["a": 1, "b": 2]],
+                Ok(Some(map!["a": 1, "b": "x", "c": 3].into())),
+                MergeFn::new(
+                    Box::new(Path::from("foo")),
+                    Box::new(Literal::from(map!["b": "x", "c": 3])),
+                    false,
+                ),
+            ),
+            (
+                map!["foo": map!["a": map!["b": 1, "c": 2]]],
+                Ok(Some(map!["a": map!["c": 3]].into())),
+                MergeFn::new(
+                    Box::new(Path::from("foo")),
+                    Box::new(Literal::from(map!["a": map!["c": 3]])),
+                    false,
+                ),
+            ),
+            (
+                map!["foo": map!["a": map!["b": 1, "c": 2]]],
+                Ok(Some(map!["a": map!["b": 1, "c": 3]].into())),
+                MergeFn::new(
+                    Box::new(Path::from("foo")),
+                    Box::new(Literal::from(map!["a": map!["c": 3]])),
+                    true,
+                ),
+            ),
+            (
+                map!["foo": map!["a": 1]],
+                Ok(Some(map!["a": map!["b": 2]].into())),
+                MergeFn::new(
+                    Box::new(Path::from("foo")),
+                    Box::new(Literal::from(map!["a": map!["b": 2]])),
+                    true,
+                ),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -12,6 +12,10 @@ impl Function for Now {
     fn compile(&self, _: ArgumentList) -> Result<Box<dyn Expression>> {
         Ok(Box::new(NowFn))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::TIMESTAMP
+    }
 }
 
 #[derive(Debug, Clone)]
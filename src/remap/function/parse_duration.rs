@@ -61,6 +61,10 @@ impl Function for ParseDuration {
 
         Ok(Box::new(ParseDurationFn { value, output }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::FLOAT
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -24,6 +24,10 @@ impl Function for ParseSyslog {
 
         Ok(Box::new(ParseSyslogFn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::MAP
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +40,10 @@ impl Function for ParseTimestamp {
             default,
         }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::TIMESTAMP
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +25,10 @@ impl Function for ParseUrl {
 
         Ok(Box::new(ParseUrlFn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::MAP
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,107 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Push;
+
+impl Function for Push {
+    fn identifier(&self) -> &'static str {
+        "push"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "item",
+                accepts: |_| true,
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let item = arguments.required_expr("item")?;
+
+        Ok(Box::new(PushFn { value, item }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::ARRAY
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PushFn {
+    value: Box<dyn Expression>,
+    item: Box<dyn Expression>,
+}
+
+impl PushFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, item: Value) -> Self {
+        let item = Box::new(Literal::from(item));
+
+        Self { value, item }
+    }
+}
+
+impl Expression for PushFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let mut list = required!(state, object, self.value, Value::Array(v) => v);
+        let item = match self.item.execute(state, object)? {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+
+        list.push(item);
+
+        Ok(Some(list.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn push() {
+        let cases = vec![
+            (
+                map![],
+                Err("path error: missing path: foo".into()),
+                PushFn::new(Box::new(Path::from("foo")), 1.into()),
+            ),
+            (
+                map![],
+                Ok(Some(vec![1].into())),
+                PushFn::new(Box::new(Literal::from(Vec::<Value>::new())), 1.into()),
+            ),
+            (
+                map!["foo": vec![1, 2]],
+                Ok(Some(vec![Value::from(1), 2.into(), "x".into()].into())),
+                PushFn::new(Box::new(Path::from("foo")), "x".into()),
+            ),
+            (
+                map!["foo": vec![1]],
+                Ok(Some(vec![Value::from(1), vec![2, 3].into()].into())),
+                PushFn::new(Box::new(Path::from("foo")), vec![2, 3].into()),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
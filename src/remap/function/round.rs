@@ -30,6 +30,10 @@ impl Function for Round {
 
         Ok(Box::new(RoundFn { value, precision }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::INTEGER | Kind::FLOAT
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +21,10 @@ impl Function for Sha1 {
 
         Ok(Box::new(Sha1Fn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
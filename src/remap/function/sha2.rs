@@ -23,6 +23,10 @@ impl Function for Sha2 {
 
         Ok(Box::new(Sha2Fn { value, variant }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
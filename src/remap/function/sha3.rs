@@ -23,6 +23,10 @@ impl Function for Sha3 {
 
         Ok(Box::new(Sha3Fn { value, variant }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +35,10 @@ impl Function for Slice {
 
         Ok(Box::new(SliceFn { value, start, end }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING | Kind::ARRAY
+    }
 }
 
 #[derive(Debug, Clone)]
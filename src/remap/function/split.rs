@@ -40,6 +40,10 @@ impl Function for Split {
             limit,
         }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::ARRAY
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,6 +22,10 @@ impl Function for StripAnsiEscapeCodes {
 
         Ok(Box::new(StripAnsiEscapeCodesFn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +21,10 @@ impl Function for StripWhitespace {
 
         Ok(Box::new(StripWhitespaceFn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -30,6 +30,10 @@ impl Function for ToBool {
 
         Ok(Box::new(ToBoolFn { value, default }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::BOOLEAN
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -30,6 +30,10 @@ impl Function for ToFloat {
 
         Ok(Box::new(ToFloatFn { value, default }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::FLOAT
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -30,6 +30,10 @@ impl Function for ToInt {
 
         Ok(Box::new(ToIntFn { value, default }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::INTEGER
+    }
 }
 
 #[derive(Debug, Clone)]
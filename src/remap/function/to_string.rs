@@ -29,6 +29,10 @@ impl Function for ToString {
 
         Ok(Box::new(ToStringFn { value, default }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +47,10 @@ impl Function for ToTimestamp {
 
         Ok(Box::new(ToTimestampFn { value, default }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::TIMESTAMP
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,6 +22,10 @@ impl Function for Tokenize {
 
         Ok(Box::new(TokenizeFn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::ARRAY
+    }
 }
 
 #[derive(Debug, Clone)]
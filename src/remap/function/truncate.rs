@@ -39,6 +39,10 @@ impl Function for Truncate {
             ellipsis,
         }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,6 +22,10 @@ impl Function for Upcase {
 
         Ok(Box::new(UpcaseFn { value }))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -12,6 +12,10 @@ impl Function for UuidV4 {
     fn compile(&self, _: ArgumentList) -> Result<Box<dyn Expression>> {
         Ok(Box::new(UuidV4Fn))
     }
+
+    fn return_kind(&self) -> Kind {
+        Kind::STRING
+    }
 }
 
 #[derive(Debug, Clone)]
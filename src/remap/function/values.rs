@@ -0,0 +1,87 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Values;
+
+impl Function for Values {
+    fn identifier(&self) -> &'static str {
+        "values"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::Map(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(ValuesFn { value }))
+    }
+
+    fn return_kind(&self) -> Kind {
+        Kind::ARRAY
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ValuesFn {
+    value: Box<dyn Expression>,
+}
+
+impl ValuesFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for ValuesFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let map = required!(state, object, self.value, Value::Map(v) => v);
+
+        Ok(Some(
+            map.into_iter().map(|(_, v)| v).collect::<Vec<_>>().into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn values() {
+        let cases = vec![
+            (
+                map![],
+                Err("path error: missing path: foo".into()),
+                ValuesFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": map![]],
+                Ok(Some(Vec::<Value>::new().into())),
+                ValuesFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": map!["b": 1, "a": map!["c": 2]]],
+                Ok(Some(vec![Value::from(map!["c": 2]), 1.into()].into())),
+                ValuesFn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -39,6 +39,18 @@ lazy_static! {
         Box::new(StripWhitespace),
         Box::new(StripAnsiEscapeCodes),
         Box::new(Match),
+        Box::new(Push),
+        Box::new(Append),
+        Box::new(Merge),
+        Box::new(Keys),
+        Box::new(Values),
+        Box::new(Length),
+        Box::new(Compact),
+        Box::new(Flatten),
+        Box::new(MapValues),
+        Box::new(MapKeys),
+        Box::new(Filter),
+        Box::new(ForEach),
     ];
 
     // List of both mutable, and immutable functions that can be loaded into a
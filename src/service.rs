@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 
 use crate::cli::handle_config_errors;
-use crate::config;
+use crate::config::{self, ConfigPath, Format};
 
 const DEFAULT_SERVICE_NAME: &str = crate::built_info::PKG_NAME;
 
@@ -30,16 +30,37 @@ struct InstallOpts {
     /// If no configuration file is specified, will target default configuration file.
     #[structopt(name = "config", short, long)]
     config_paths: Vec<PathBuf>,
+
+    /// The configuration files in TOML format that will be used by the service.
+    #[structopt(name = "config-toml", long)]
+    config_paths_toml: Vec<PathBuf>,
+
+    /// The configuration files in YAML format that will be used by the service.
+    #[structopt(name = "config-yaml", long)]
+    config_paths_yaml: Vec<PathBuf>,
+
+    /// The configuration files in JSON format that will be used by the service.
+    #[structopt(name = "config-json", long)]
+    config_paths_json: Vec<PathBuf>,
 }
 
 impl InstallOpts {
+    fn config_paths_with_formats(&self) -> Vec<ConfigPath> {
+        config::merge_path_lists(vec![
+            (&self.config_paths[..], None),
+            (&self.config_paths_toml[..], Some(Format::Toml)),
+            (&self.config_paths_yaml[..], Some(Format::Yaml)),
+            (&self.config_paths_json[..], Some(Format::Json)),
+        ])
+    }
+
     fn service_info(&self) -> ServiceInfo {
         let service_name = self.name.as_deref().unwrap_or(DEFAULT_SERVICE_NAME);
         let display_name = self.display_name.as_deref().unwrap_or("Vector Service");
         let description = crate::built_info::PKG_DESCRIPTION;
 
         let current_exe = ::std::env::current_exe().unwrap();
-        let arguments = create_service_arguments(&self.config_paths).unwrap();
+        let arguments = create_service_arguments(&self.config_paths_with_formats()).unwrap();
 
         ServiceInfo {
             name: OsString::from(service_name),
@@ -189,13 +210,26 @@ fn control_service(_service: &ServiceInfo, _action: ControlAction) -> exitcode::
     exitcode::UNAVAILABLE
 }
 
-fn create_service_arguments(config_paths: &[PathBuf]) -> Option<Vec<OsString>> {
-    let config_paths = config::process_paths(&config_paths)?;
+fn create_service_arguments(config_paths: &[ConfigPath]) -> Option<Vec<OsString>> {
+    let config_paths = config::process_paths(config_paths)?;
     match config::load_from_paths(&config_paths) {
         Ok(_) => Some(
             config_paths
                 .iter()
-                .flat_map(|p| vec![OsString::from("--config"), p.as_os_str().into()])
+                .flat_map(|config_path| match config_path {
+                    ConfigPath::File(p, format) => {
+                        let flag = match format {
+                            None => "--config",
+                            Some(Format::Toml) => "--config-toml",
+                            Some(Format::Yaml) => "--config-yaml",
+                            Some(Format::Json) => "--config-json",
+                        };
+                        vec![OsString::from(flag), p.as_os_str().into()]
+                    }
+                    ConfigPath::Dir(p) => {
+                        vec![OsString::from("--config-dir"), p.as_os_str().into()]
+                    }
+                })
                 .collect::<Vec<OsString>>(),
         ),
         Err(errs) => {
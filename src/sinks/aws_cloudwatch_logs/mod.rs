@@ -7,7 +7,7 @@ use crate::{
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::{FixedRetryPolicy, RetryLogic},
-        BatchConfig, BatchSettings, Compression, EncodedLength, PartitionBatchSink,
+        BatchConfig, BatchSettings, Compression, EncodedEvent, EncodedLength, PartitionBatchSink,
         PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig, TowerRequestSettings, VecBuffer,
     },
     template::Template,
@@ -164,7 +164,8 @@ impl CloudwatchLogsSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(CloudWatchLogsClient::new_with_client(client, region))
     }
 }
@@ -200,7 +201,9 @@ impl SinkConfig for CloudwatchLogsSinkConfig {
             let svc_sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
                 .sink_map_err(|error| error!(message = "Fatal cloudwatchlogs sink error.", %error))
                 .with_flat_map(move |event| {
-                    iter_ok(partition_encode(event, &encoding, &log_group, &log_stream))
+                    iter_ok(EncodedEvent::from_event(event, |event| {
+                        partition_encode(event, &encoding, &log_group, &log_stream)
+                    }))
                 });
             Box::new(svc_sink)
         };
@@ -6,7 +6,7 @@ use crate::{
     },
     rusoto::{self, RegionOrEndpoint},
     sinks::util::{
-        retries::RetryLogic, BatchConfig, BatchSettings, Compression, MetricBuffer,
+        retries::RetryLogic, BatchConfig, BatchSettings, Compression, EncodedEvent, MetricBuffer,
         PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
     },
 };
@@ -114,7 +114,8 @@ impl CloudWatchMetricsSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(CloudWatchClient::new_with_client(client, region))
     }
 }
@@ -146,7 +147,9 @@ impl CloudWatchMetricsSvc {
                     .namespace
                     .take()
                     .unwrap_or_else(|| default_namespace.clone());
-                iter_ok(Some(PartitionInnerBuffer::new(event, namespace)))
+                iter_ok(Some(EncodedEvent::new(PartitionInnerBuffer::new(
+                    event, namespace,
+                ))))
             });
 
         Ok(super::VectorSink::Futures01Sink(Box::new(sink)))
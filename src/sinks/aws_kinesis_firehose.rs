@@ -6,7 +6,8 @@ use crate::{
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Compression, EncodedLength, TowerRequestConfig, VecBuffer,
+        BatchConfig, BatchSettings, Compression, EncodedEvent, EncodedLength, TowerRequestConfig,
+        VecBuffer,
     },
 };
 use bytes::Bytes;
@@ -134,7 +135,8 @@ impl KinesisFirehoseSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(KinesisFirehoseClient::new_with_client(client, region))
     }
 }
@@ -164,7 +166,9 @@ impl KinesisFirehoseService {
                 cx.acker(),
             )
             .sink_map_err(|error| error!(message = "Fatal kinesis firehose sink error.", %error))
-            .with_flat_map(move |e| iter_ok(encode_event(e, &encoding)));
+            .with_flat_map(move |e| {
+                iter_ok(EncodedEvent::from_event(e, |e| encode_event(e, &encoding)))
+            });
 
         Ok(sink)
     }
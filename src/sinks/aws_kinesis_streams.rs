@@ -7,7 +7,8 @@ use crate::{
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Compression, EncodedLength, TowerRequestConfig, VecBuffer,
+        BatchConfig, BatchSettings, Compression, EncodedEvent, EncodedLength, TowerRequestConfig,
+        VecBuffer,
     },
 };
 use bytes::Bytes;
@@ -137,7 +138,8 @@ impl KinesisSinkConfig {
         let client = rusoto::client()?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let client =
+            rusoto_core::Client::new_with_encoding(creds, client, self.compression.try_into()?);
         Ok(KinesisClient::new_with_client(client, region))
     }
 }
@@ -168,7 +170,11 @@ impl KinesisService {
                 cx.acker(),
             )
             .sink_map_err(|error| error!(message = "Fatal kinesis streams sink error.", %error))
-            .with_flat_map(move |e| iter_ok(encode_event(e, &partition_key_field, &encoding)));
+            .with_flat_map(move |e| {
+                iter_ok(EncodedEvent::from_event(e, |e| {
+                    encode_event(e, &partition_key_field, &encoding)
+                }))
+            });
 
         Ok(sink)
     }
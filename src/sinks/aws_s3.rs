@@ -1,14 +1,16 @@
 use crate::{
     config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    event::{Event, LogEvent},
     rusoto::{self, RegionOrEndpoint},
     serde::to_string,
     sinks::util::{
+        buffer::parquet::{self, ParquetBuffer, ParquetConfig},
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Buffer, Compression, InFlightLimit, PartitionBatchSink,
-        PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+        BatchConfig, BatchSettings, Buffer, Compression, EncodedEvent, InFlightLimit,
+        PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt,
+        TowerRequestConfig,
     },
     template::Template,
 };
@@ -55,6 +57,11 @@ pub struct S3SinkConfig {
         default
     )]
     pub encoding: EncodingConfigWithDefault<Encoding>,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub parquet: ParquetConfig,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
     #[serde(default)]
@@ -131,6 +138,7 @@ pub enum Encoding {
     #[derivative(Default)]
     Text,
     Ndjson,
+    Parquet,
 }
 
 inventory::submit! {
@@ -176,13 +184,12 @@ impl S3SinkConfig {
         let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = self.encoding.clone();
 
-        let compression = self.compression;
         let filename_time_format = self
             .filename_time_format
             .clone()
             .unwrap_or_else(|| "%s".into());
         let filename_append_uuid = self.filename_append_uuid.unwrap_or(true);
-        let batch = BatchSettings::default()
+        let batch = BatchSettings::<Buffer>::default()
             .bytes(10_000_000)
             .timeout(300)
             .parse_config(self.batch)?;
@@ -192,9 +199,24 @@ impl S3SinkConfig {
 
         let s3 = S3Sink { client };
 
-        let filename_extension = self.filename_extension.clone();
         let bucket = self.bucket.clone();
-        let options = self.options.clone();
+        let mut options = self.options.clone();
+
+        // Parquet files compress their columns themselves, so the objects
+        // holding them aren't compressed.
+        let is_parquet = *encoding.codec() == Encoding::Parquet;
+        let (compression, filename_extension) = if is_parquet {
+            options
+                .content_type
+                .get_or_insert_with(|| parquet::CONTENT_TYPE.into());
+            let extension = self
+                .filename_extension
+                .clone()
+                .unwrap_or_else(|| parquet::EXTENSION.into());
+            (Compression::None, Some(extension))
+        } else {
+            (self.compression, self.filename_extension.clone())
+        };
 
         let svc = ServiceBuilder::new()
             .map(move |req| {
@@ -211,13 +233,35 @@ impl S3SinkConfig {
             .settings(request, S3RetryLogic)
             .service(s3);
 
-        let buffer = PartitionBuffer::new(Buffer::new(batch.size, self.compression));
-
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .with_flat_map(move |e| iter_ok(encode_event(e, &key_prefix, &encoding)))
-            .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
+        let sink: Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> = if is_parquet {
+            let buffer = PartitionBuffer::new(ParquetBuffer::new(
+                batch.into::<ParquetBuffer>().size,
+                &self.parquet,
+                self.compression,
+            ));
+            Box::new(
+                PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                    .with_flat_map(move |e| {
+                        iter_ok(EncodedEvent::from_event(e, |e| {
+                            prepare_event(e, &key_prefix, &encoding)
+                        }))
+                    })
+                    .sink_map_err(|error| error!(message = "Sink failed to flush.", %error)),
+            )
+        } else {
+            let buffer = PartitionBuffer::new(Buffer::new(batch.size, compression));
+            Box::new(
+                PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                    .with_flat_map(move |e| {
+                        iter_ok(EncodedEvent::from_event(e, |e| {
+                            encode_event(e, &key_prefix, &encoding)
+                        }))
+                    })
+                    .sink_map_err(|error| error!(message = "Sink failed to flush.", %error)),
+            )
+        };
 
-        Ok(super::VectorSink::Futures01Sink(Box::new(sink)))
+        Ok(super::VectorSink::Futures01Sink(sink))
     }
 
     pub async fn healthcheck(self, client: S3Client) -> crate::Result<()> {
@@ -377,11 +421,13 @@ impl RetryLogic for S3RetryLogic {
     }
 }
 
-fn encode_event(
+/// Renders the key of the event's partition and applies the encoding
+/// rules to it.
+fn prepare_event(
     mut event: Event,
     key_prefix: &Template,
     encoding: &EncodingConfigWithDefault<Encoding>,
-) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
+) -> Option<PartitionInnerBuffer<LogEvent, Bytes>> {
     let key = key_prefix
         .render_string(&event)
         .map_err(|missing_keys| {
@@ -395,7 +441,15 @@ fn encode_event(
 
     encoding.apply_rules(&mut event);
 
-    let log = event.into_log();
+    Some(PartitionInnerBuffer::new(event.into_log(), key.into()))
+}
+
+fn encode_event(
+    event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfigWithDefault<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
+    let (log, key) = prepare_event(event, key_prefix, encoding)?.into_parts();
     let bytes = match encoding.codec() {
         Encoding::Ndjson => serde_json::to_vec(&log)
             .map(|mut b| {
@@ -411,15 +465,16 @@ fn encode_event(
             bytes.push(b'\n');
             bytes
         }
+        Encoding::Parquet => unreachable!("Parquet batches are encoded by `ParquetBuffer`."),
     };
 
-    Some(PartitionInnerBuffer::new(bytes, key.into()))
+    Some(PartitionInnerBuffer::new(bytes, key))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::Event;
+    use crate::{event::Event, sinks::util::buffer::parquet::ParquetType};
 
     use std::collections::BTreeMap;
 
@@ -483,6 +538,33 @@ mod tests {
         // assert_eq!(map["key"], "value".to_string());
     }
 
+    #[test]
+    fn s3_parquet_config() {
+        let config: S3SinkConfig = toml::from_str(
+            r#"
+            bucket = "logs"
+            region = "us-east-1"
+            encoding.codec = "parquet"
+
+            [parquet.schema]
+            timestamp = "timestamp"
+            message = "string"
+            "http.status" = "integer"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.encoding.codec(), &Encoding::Parquet);
+        assert_eq!(
+            config.parquet.schema.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("http.status".into(), ParquetType::Integer),
+                ("message".into(), ParquetType::String),
+                ("timestamp".into(), ParquetType::Timestamp),
+            ]
+        );
+    }
+
     #[test]
     fn s3_build_request() {
         let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
@@ -550,7 +632,7 @@ mod integration_tests {
     use pretty_assertions::assert_eq;
     use rusoto_core::region::Region;
     use rusoto_s3::{S3Client, S3};
-    use std::io::{BufRead, BufReader};
+    use std::io::{BufRead, BufReader, Read};
 
     const BUCKET: &str = "router-tests";
 
@@ -657,6 +739,41 @@ mod integration_tests {
         assert_eq!(lines, response_lines.await);
     }
 
+    #[tokio::test]
+    async fn s3_parquet() {
+        let cx = SinkContext::new_test();
+
+        let config = S3SinkConfig {
+            encoding: Encoding::Parquet.into(),
+            compression: Compression::gzip_default(),
+            ..config(1000000).await
+        };
+        let prefix = config.key_prefix.clone();
+        let client = config.create_client().unwrap();
+        let sink = config.new(client, cx).unwrap();
+
+        let (_lines, events) = random_lines_with_stream(100, 10);
+        sink.run(events).await.unwrap();
+
+        let keys = get_keys(prefix.unwrap()).await;
+        assert_eq!(keys.len(), 1);
+
+        let key = keys[0].clone();
+        assert!(key.ends_with(".parquet"));
+
+        let obj = get_object(key).await;
+        assert_eq!(obj.content_encoding, Some("identity".to_string()));
+        assert_eq!(obj.content_type, Some(parquet::CONTENT_TYPE.to_string()));
+
+        let mut body = Vec::new();
+        get_object_output_body(obj)
+            .await
+            .read_to_end(&mut body)
+            .unwrap();
+        assert!(body.starts_with(b"PAR1"));
+        assert!(body.ends_with(b"PAR1"));
+    }
+
     #[tokio::test]
     async fn s3_healthchecks() {
         let config = config(1).await;
@@ -1,7 +1,7 @@
 use crate::{
     buffers::Acker,
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    event::{finalization::EventStatus, Event},
     internal_events::{ConsoleEventProcessed, ConsoleFieldNotFound},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
@@ -133,20 +133,25 @@ struct WriterSink {
 #[async_trait]
 impl StreamSink for WriterSink {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
-        while let Some(event) = input.next().await {
+        while let Some(mut event) = input.next().await {
             self.acker.ack(1);
+            let finalizers = event.take_finalizers();
             if let Some(mut buf) = encode_event(event, &self.encoding) {
                 buf.push('\n');
                 if let Err(error) = self.output.write_all(buf.as_bytes()).await {
                     // Error when writing to stdout/stderr is likely irrecoverable,
                     // so stop the sink.
                     error!(message = "Error writing to output. Stopping sink.", %error);
+                    finalizers.update_status(EventStatus::Errored);
                     return Err(());
                 }
+                finalizers.update_status(EventStatus::Delivered);
 
                 emit!(ConsoleEventProcessed {
                     byte_size: buf.len(),
                 });
+            } else {
+                finalizers.update_status(EventStatus::Failed);
             }
         }
         Ok(())
@@ -153,6 +153,10 @@ impl DatadogLogsConfig {
                     encoder.finish()?,
                 )
             }
+            compression => unreachable!(
+                "The `{}` compression is rejected when the sink is built.",
+                compression.algorithm()
+            ),
         };
 
         request
@@ -166,6 +170,10 @@ impl DatadogLogsConfig {
 #[typetag::serde(name = "datadog_logs")]
 impl SinkConfig for DatadogLogsConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if let Some(compression) = self.compression {
+            compression.check_gzip_only("Datadog")?;
+        }
+
         // Create a different sink depending on which encoding we have chosen.
         // Json and Text have different batching strategies and so each needs to be
         // handled differently.
@@ -392,4 +400,18 @@ mod tests {
             assert_eq!(message, expected[i]);
         }
     }
+
+    #[tokio::test]
+    async fn rejects_unsupported_compression() {
+        let (config, cx) = load_sink::<DatadogLogsConfig>(
+            r#"
+            api_key = "atoken"
+            encoding = "json"
+            compression = "zstd"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.build(cx).await.is_err());
+    }
 }
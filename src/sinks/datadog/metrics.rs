@@ -9,8 +9,8 @@ use crate::{
         util::{
             encode_namespace,
             http::{HttpBatchService, HttpRetryLogic},
-            BatchConfig, BatchSettings, MetricBuffer, PartitionBatchSink, PartitionBuffer,
-            PartitionInnerBuffer, TowerRequestConfig,
+            BatchConfig, BatchSettings, EncodedEvent, MetricBuffer, PartitionBatchSink,
+            PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig,
         },
         Healthcheck, HealthcheckError, UriParseError, VectorSink,
     },
@@ -193,7 +193,9 @@ impl SinkConfig for DatadogConfig {
             .sink_map_err(|error| error!(message = "Fatal datadog metric sink error.", %error))
             .with_flat_map(move |event: Event| {
                 let ep = DatadogEndpoint::from_metric(&event);
-                iter_ok(Some(PartitionInnerBuffer::new(event, ep)))
+                iter_ok(Some(EncodedEvent::new(PartitionInnerBuffer::new(
+                    event, ep,
+                ))))
             });
 
         Ok((VectorSink::Futures01Sink(Box::new(svc_sink)), healthcheck))
@@ -1,4 +1,5 @@
 use crate::{
+    buffers::Rejection,
     config::{DataType, SinkConfig, SinkContext, SinkDescription},
     emit,
     event::Event,
@@ -8,7 +9,7 @@ use crate::{
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
         http::{BatchedHttpSink, HttpSink},
-        retries::{RetryAction, RetryLogic},
+        retries::{ItemOutcome, RetryAction, RetryLogic},
         BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig,
     },
     template::{Template, TemplateError},
@@ -32,6 +33,7 @@ use serde_json::json;
 use snafu::{ResultExt, Snafu};
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::fmt;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -108,6 +110,7 @@ impl SinkConfig for ElasticSearchConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        self.compression.check_gzip_only("Elasticsearch")?;
         let common = ElasticSearchCommon::parse_config(&self)?;
         let client = HttpClient::new(common.tls_settings.clone())?;
 
@@ -281,6 +284,7 @@ struct ESResultItem {
 }
 #[derive(Deserialize, Debug)]
 struct ESIndexResult {
+    status: u16,
     error: Option<ESErrorDetails>,
 }
 #[derive(Deserialize, Debug)]
@@ -290,6 +294,32 @@ struct ESErrorDetails {
     err_type: String,
 }
 
+impl fmt::Display for ESErrorDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error type: {}, reason: {}", self.err_type, self.reason)
+    }
+}
+
+impl ESIndexResult {
+    /// Items rejected for being too many or by a failing node may be
+    /// indexed on a later attempt.
+    fn is_retriable(&self) -> bool {
+        self.error.is_some() && (self.status == 429 || self.status >= 500)
+    }
+}
+
+/// Parses the body of a successful bulk response some items of which failed.
+fn parse_bulk_errors(
+    response: &hyper::Response<Bytes>,
+) -> Option<serde_json::Result<ESResultResponse>> {
+    let body = response.body();
+    if response.status().is_success() && String::from_utf8_lossy(body).contains("\"errors\":true") {
+        Some(serde_json::from_slice(body))
+    } else {
+        None
+    }
+}
+
 impl RetryLogic for ElasticSearchRetryLogic {
     type Error = hyper::Error;
     type Response = hyper::Response<Bytes>;
@@ -315,18 +345,50 @@ impl RetryLogic for ElasticSearchRetryLogic {
                 let body = String::from_utf8_lossy(response.body());
                 RetryAction::DontRetry(format!("client-side error, {}: {}", status, body))
             }
-            _ if status.is_success() => {
-                let body = String::from_utf8_lossy(response.body());
-
-                if body.contains("\"errors\":true") {
-                    RetryAction::DontRetry(get_error_reason(&body))
-                } else {
-                    RetryAction::Successful
-                }
-            }
+            _ if status.is_success() => match parse_bulk_errors(response) {
+                None => RetryAction::Successful,
+                // The whole request is sent again, so items that were indexed
+                // are indexed once more unless `id_key` is set.
+                Some(result) => match result
+                    .ok()
+                    .and_then(|result| {
+                        result
+                            .items
+                            .into_iter()
+                            .find(|item| item.index.is_retriable())
+                    })
+                    .and_then(|item| item.index.error)
+                {
+                    Some(error) => RetryAction::Retry(format!("some items failed, {}", error)),
+                    None => {
+                        let body = String::from_utf8_lossy(response.body());
+                        RetryAction::DontRetry(get_error_reason(&body))
+                    }
+                },
+            },
             _ => RetryAction::DontRetry(format!("response status: {}", status)),
         }
     }
+
+    fn item_outcomes(&self, response: &Self::Response) -> Option<Vec<ItemOutcome>> {
+        let result = parse_bulk_errors(response)?.ok()?;
+        let outcomes = result
+            .items
+            .into_iter()
+            .map(|item| {
+                let retriable = item.index.is_retriable();
+                match item.index.error {
+                    None => ItemOutcome::Delivered,
+                    Some(error) if retriable => ItemOutcome::Errored(error.to_string()),
+                    Some(error) => ItemOutcome::Rejected(Rejection {
+                        reason: error.to_string(),
+                        status: Some(item.index.status),
+                    }),
+                }
+            })
+            .collect();
+        Some(outcomes)
+    }
 }
 
 fn get_error_reason(body: &str) -> String {
@@ -336,7 +398,7 @@ fn get_error_reason(body: &str) -> String {
             json_error
         ),
         Ok(resp) => match resp.items.into_iter().find_map(|item| item.index.error) {
-            Some(error) => format!("error type: {}, reason: {}", error.err_type, error.reason),
+            Some(error) => error.to_string(),
             None => format!("error response: {}", body),
         },
     }
@@ -551,6 +613,49 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn handles_partial_error_response() {
+        let json = r#"{"took":12,"errors":true,"items":[
+            {"index":{"_index":"test","_id":"1","status":201}},
+            {"index":{"_index":"test","_id":"2","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse"}}},
+            {"index":{"_index":"test","_id":"3","status":429,"error":{"type":"es_rejected_execution_exception","reason":"queue full"}}}
+        ]}"#;
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .body(Bytes::from(json))
+            .unwrap();
+        let logic = ElasticSearchRetryLogic;
+        assert!(logic.should_retry_response(&response).is_retryable());
+
+        let outcomes = logic.item_outcomes(&response).unwrap();
+        assert_eq!(
+            outcomes,
+            vec![
+                ItemOutcome::Delivered,
+                ItemOutcome::Rejected(Rejection {
+                    reason: "error type: mapper_parsing_exception, reason: failed to parse".into(),
+                    status: Some(400),
+                }),
+                ItemOutcome::Errored(
+                    "error type: es_rejected_execution_exception, reason: queue full".into()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_compression() {
+        let (config, cx) = crate::sinks::util::test::load_sink::<ElasticSearchConfig>(
+            r#"
+            endpoint = "http://localhost:9200"
+            compression = "zstd"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.build(cx).await.is_err());
+    }
+
     #[test]
     fn allows_using_excepted_fields() {
         let config = ElasticSearchConfig {
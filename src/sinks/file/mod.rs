@@ -2,14 +2,14 @@ use crate::expiring_hash_map::ExpiringHashMap;
 use crate::{
     buffers::Acker,
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    event::{finalization::EventStatus, Event},
     sinks::util::{
+        buffer::compression::Encoder,
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        StreamSink,
+        Compression, StreamSink,
     },
     template::Template,
 };
-use async_compression::tokio_02::write::GzipEncoder;
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::{
@@ -18,7 +18,11 @@ use futures::{
     FutureExt,
 };
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
 
 use tokio::{
     fs::{self, File},
@@ -26,6 +30,8 @@ use tokio::{
 };
 mod bytes_path;
 use bytes_path::BytesPath;
+mod rotation;
+pub use rotation::{RetentionConfig, RotateConfig};
 use std::convert::TryFrom;
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -43,6 +49,16 @@ pub struct FileSinkConfig {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub compression: Compression,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub rotate: RotateConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub retention: RetentionConfig,
 }
 
 inventory::submit! {
@@ -56,6 +72,8 @@ impl GenerateConfig for FileSinkConfig {
             idle_timeout_secs: None,
             encoding: Default::default(),
             compression: Default::default(),
+            rotate: Default::default(),
+            retention: Default::default(),
         })
         .unwrap()
     }
@@ -74,59 +92,133 @@ impl Default for Encoding {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
-#[serde(rename_all = "snake_case")]
-pub enum Compression {
-    Gzip,
-    None,
+/// A file being written to, along with what's needed to tell when it's due
+/// for rotation.
+struct OutFile {
+    writer: FileWriter,
+    size: u64,
+    created: SystemTime,
 }
 
-impl Default for Compression {
-    fn default() -> Self {
-        Compression::None
+impl OutFile {
+    async fn new(file: File, compression: Compression) -> io::Result<Self> {
+        let metadata = file.metadata().await?;
+        Ok(Self {
+            writer: FileWriter::new(file, compression)?,
+            size: metadata.len(),
+            // Not every filesystem records creation times, in which case the
+            // age of the file is counted from when it was opened.
+            created: metadata.created().unwrap_or_else(|_| SystemTime::now()),
+        })
+    }
+
+    async fn write_all(&mut self, src: &[u8]) -> Result<(), std::io::Error> {
+        self.size += self.writer.write_all(src).await? as u64;
+        Ok(())
+    }
+
+    /// Shutdowns by flushing data, writing headers, and syncing all of that
+    /// data and metadata to the filesystem.
+    async fn close(&mut self) -> Result<(), std::io::Error> {
+        self.writer.shutdown().await?;
+        self.writer.sync_all().await
     }
 }
 
-enum OutFile {
+enum FileWriter {
     Regular(File),
-    Gzip(GzipEncoder<File>),
+    Compressed {
+        file: File,
+        encoder: Option<Encoder<Pending>>,
+        pending: Pending,
+    },
 }
 
-impl OutFile {
-    fn new(file: File, compression: Compression) -> Self {
-        match compression {
-            Compression::None => OutFile::Regular(file),
-            Compression::Gzip => OutFile::Gzip(GzipEncoder::new(file)),
-        }
+/// The compressed output of an encoder that is yet to be written to its
+/// file. The encoders are synchronous, so they write here and the output is
+/// then moved to the file asynchronously.
+#[derive(Clone, Default)]
+struct Pending(Arc<Mutex<Vec<u8>>>);
+
+impl Pending {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for Pending {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl FileWriter {
+    fn new(file: File, compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
+            Compression::None => FileWriter::Regular(file),
+            compression => {
+                let pending = Pending::default();
+                FileWriter::Compressed {
+                    file,
+                    encoder: Some(Encoder::new(pending.clone(), compression)?),
+                    pending,
+                }
+            }
+        })
     }
 
     async fn sync_all(&mut self) -> Result<(), std::io::Error> {
         match self {
-            OutFile::Regular(file) => file.sync_all().await,
-            OutFile::Gzip(gzip) => gzip.get_mut().sync_all().await,
+            FileWriter::Regular(file) => file.sync_all().await,
+            FileWriter::Compressed { file, .. } => file.sync_all().await,
         }
     }
 
     async fn shutdown(&mut self) -> Result<(), std::io::Error> {
         match self {
-            OutFile::Regular(file) => file.shutdown().await,
-            OutFile::Gzip(gzip) => gzip.shutdown().await,
+            FileWriter::Regular(file) => file.shutdown().await,
+            FileWriter::Compressed {
+                file,
+                encoder,
+                pending,
+            } => {
+                if let Some(encoder) = encoder.take() {
+                    encoder.finish()?;
+                }
+                file.write_all(&pending.take()).await?;
+                file.shutdown().await
+            }
         }
     }
 
-    async fn write_all(&mut self, src: &[u8]) -> Result<(), std::io::Error> {
+    /// Writes `src` to the file, returning the number of bytes the file
+    /// grew by.
+    async fn write_all(&mut self, src: &[u8]) -> Result<usize, std::io::Error> {
         match self {
-            OutFile::Regular(file) => file.write_all(src).await,
-            OutFile::Gzip(gzip) => gzip.write_all(src).await,
+            FileWriter::Regular(file) => {
+                file.write_all(src).await?;
+                Ok(src.len())
+            }
+            FileWriter::Compressed {
+                file,
+                encoder,
+                pending,
+            } => {
+                encoder
+                    .as_mut()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "File is shut down."))?
+                    .write_all(src)?;
+                let output = pending.take();
+                file.write_all(&output).await?;
+                Ok(output.len())
+            }
         }
     }
-
-    /// Shutdowns by flushing data, writing headers, and syncing all of that
-    /// data and metadata to the filesystem.
-    async fn close(&mut self) -> Result<(), std::io::Error> {
-        self.shutdown().await?;
-        self.sync_all().await
-    }
 }
 
 #[async_trait::async_trait]
@@ -136,6 +228,13 @@ impl SinkConfig for FileSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        if self.compression != Compression::None && self.rotate.compression != Compression::None {
+            return Err(
+                "`rotate.compression` can't be set along with `compression`, as the files are compressed already."
+                    .into(),
+            );
+        }
+
         let sink = FileSink::new(&self, cx.acker());
         Ok((
             super::VectorSink::Stream(Box::new(sink)),
@@ -160,6 +259,8 @@ pub struct FileSink {
     idle_timeout: Duration,
     files: ExpiringHashMap<Bytes, OutFile>,
     compression: Compression,
+    rotate: RotateConfig,
+    retention: RetentionConfig,
 }
 
 impl FileSink {
@@ -171,6 +272,8 @@ impl FileSink {
             idle_timeout: Duration::from_secs(config.idle_timeout_secs.unwrap_or(30)),
             files: ExpiringHashMap::default(),
             compression: config.compression,
+            rotate: config.rotate,
+            retention: config.retention,
         }
     }
 
@@ -202,8 +305,10 @@ impl FileSink {
             tokio::select! {
                 event = input.next() => {
                     match event {
-                        Some(event) => {
-                            self.process_event(event).await;
+                        Some(mut event) => {
+                            let finalizers = event.take_finalizers();
+                            let status = self.process_event(event).await;
+                            finalizers.update_status(status);
                             self.acker.ack(1);
                         },
                         None => {
@@ -248,7 +353,32 @@ impl FileSink {
         Ok(())
     }
 
-    async fn process_event(&mut self, event: Event) {
+    /// Closes the file at `path`, rotates it, and opens a new one in its
+    /// place.
+    async fn rotate_file(&mut self, path: &Bytes, deadline: Instant) {
+        if let Some((mut file, _)) = self.files.remove(path) {
+            if let Err(error) = file.close().await {
+                error!(message = "Failed to close file.", path = ?path, %error);
+            }
+        }
+
+        debug!(message = "Rotating file.", path = ?path);
+        let rotated = rotation::rotate(
+            BytesPath::new(path.clone()).as_ref(),
+            self.rotate.compression,
+            self.retention.max_files,
+        )
+        .await;
+        if let Err(error) = rotated {
+            error!(message = "Failed to rotate file.", path = ?path, %error);
+        }
+
+        if let Some(outfile) = open_out_file(path, self.compression).await {
+            self.files.insert_at(path.clone(), outfile, deadline);
+        }
+    }
+
+    async fn process_event(&mut self, event: Event) -> EventStatus {
         let path = match self.partition_event(&event) {
             Some(path) => path,
             None => {
@@ -256,38 +386,64 @@ impl FileSink {
                 // file.
                 // This is already logged at `partition_event`, so
                 // here we just skip the event.
-                return;
+                return EventStatus::Failed;
             }
         };
 
         let next_deadline = self.deadline_at();
         trace!(message = "Computed next deadline.", next_deadline = ?next_deadline, path = ?path);
 
-        let file = if let Some(file) = self.files.reset_at(&path, next_deadline) {
+        if self.files.reset_at(&path, next_deadline).is_some() {
             trace!(message = "Working with an already opened file.", path = ?path);
-            file
         } else {
             trace!(message = "Opening new file.", ?path);
-            let file = match open_file(BytesPath::new(path.clone())).await {
-                Ok(file) => file,
-                Err(error) => {
-                    // We couldn't open the file for this event.
-                    // Maybe other events will work though! Just log
-                    // the error and skip this event.
-                    error!(message = "Unable to open the file.", path = ?path, %error);
-                    return;
-                }
-            };
+            match open_out_file(&path, self.compression).await {
+                Some(outfile) => self.files.insert_at(path.clone(), outfile, next_deadline),
+                // We couldn't open the file for this event. Maybe other
+                // events will work though! This is already logged at
+                // `open_out_file`, so here we just skip the event.
+                None => return EventStatus::Errored,
+            }
+        }
 
-            let outfile = OutFile::new(file, self.compression);
+        let rotation_due = self
+            .files
+            .get(&path)
+            .map_or(false, |file| self.rotate.is_due(file.size, file.created));
+        if rotation_due {
+            self.rotate_file(&path, next_deadline).await;
+        }
 
-            self.files.insert_at(path.clone(), outfile, next_deadline);
-            self.files.get_mut(&path).unwrap()
+        let file = match self.files.get_mut(&path) {
+            Some(file) => file,
+            None => return EventStatus::Errored,
         };
 
         trace!(message = "Writing an event to file.", path = ?path);
-        if let Err(error) = write_event_to_file(file, event, &self.encoding).await {
-            error!(message = "Failed to write file.", path = ?path, %error);
+        match write_event_to_file(file, event, &self.encoding).await {
+            Ok(()) => EventStatus::Delivered,
+            Err(error) => {
+                error!(message = "Failed to write file.", path = ?path, %error);
+                EventStatus::Errored
+            }
+        }
+    }
+}
+
+async fn open_out_file(path: &Bytes, compression: Compression) -> Option<OutFile> {
+    let file = match open_file(BytesPath::new(path.clone())).await {
+        Ok(file) => file,
+        Err(error) => {
+            error!(message = "Unable to open the file.", path = ?path, %error);
+            return None;
+        }
+    };
+
+    match OutFile::new(file, compression).await {
+        Ok(outfile) => Some(outfile),
+        Err(error) => {
+            error!(message = "Unable to open the file.", path = ?path, %error);
+            None
         }
     }
 }
@@ -342,8 +498,8 @@ impl StreamSink for FileSink {
 mod tests {
     use super::*;
     use crate::test_util::{
-        lines_from_file, lines_from_gzip_file, random_events_with_stream, random_lines_with_stream,
-        temp_dir, temp_file, trace_init,
+        lines_from_file, lines_from_gzip_file, lines_from_zstd_file, random_events_with_stream,
+        random_lines_with_stream, temp_dir, temp_file, trace_init,
     };
     use futures::stream;
     use std::convert::TryInto;
@@ -364,6 +520,8 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotate: Default::default(),
+            retention: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -388,7 +546,9 @@ mod tests {
             path: template.clone().try_into().unwrap(),
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
-            compression: Compression::Gzip,
+            compression: Compression::gzip_default(),
+            rotate: Default::default(),
+            retention: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -403,6 +563,70 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn single_partition_zstd() {
+        trace_init();
+
+        let template = temp_file();
+
+        let config = FileSinkConfig {
+            path: template.clone().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: Encoding::Text.into(),
+            compression: Compression::zstd_default(),
+            rotate: Default::default(),
+            retention: Default::default(),
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        let (input, _) = random_lines_with_stream(100, 64);
+
+        let events = Box::pin(stream::iter(input.clone().into_iter().map(Event::from)));
+        sink.run(events).await.unwrap();
+
+        let output = lines_from_zstd_file(template);
+        assert_eq!(input, output);
+    }
+
+    #[tokio::test]
+    async fn rotation() {
+        trace_init();
+
+        let directory = temp_dir();
+        let path = directory.join("app.log");
+
+        let config = FileSinkConfig {
+            path: path.clone().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: Encoding::Text.into(),
+            compression: Compression::None,
+            rotate: RotateConfig {
+                max_bytes: Some(100),
+                compression: Compression::gzip_default(),
+                ..Default::default()
+            },
+            retention: RetentionConfig { max_files: Some(2) },
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        // Each line is 50 bytes with its newline, so files rotate every two.
+        let input = (0..7).map(|i| format!("{:049}", i)).collect::<Vec<_>>();
+
+        let events = Box::pin(stream::iter(input.clone().into_iter().map(Event::from)));
+        sink.run(events).await.unwrap();
+
+        assert_eq!(lines_from_file(&path), &input[6..]);
+        assert_eq!(
+            lines_from_gzip_file(directory.join("app.log.1.gz")),
+            &input[4..6]
+        );
+        assert_eq!(
+            lines_from_gzip_file(directory.join("app.log.2.gz")),
+            &input[2..4]
+        );
+        assert!(!directory.join("app.log.3.gz").exists());
+    }
+
     #[tokio::test]
     async fn many_partitions() {
         trace_init();
@@ -419,6 +643,8 @@ mod tests {
             idle_timeout_secs: None,
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotate: Default::default(),
+            retention: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -500,6 +726,8 @@ mod tests {
             idle_timeout_secs: Some(1),
             encoding: Encoding::Text.into(),
             compression: Compression::None,
+            rotate: Default::default(),
+            retention: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -0,0 +1,215 @@
+//! Rotation of the files written by the sink.
+//!
+//! Rotating `app.log` renames it to `app.log.1`, after the files rotated
+//! before it have been shifted up by one (`app.log.1` to `app.log.2` and so
+//! on), and drops the oldest of them to keep to the retention limit.
+
+use crate::sinks::util::{buffer::compression::Encoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tokio::fs;
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RotateConfig {
+    pub max_bytes: Option<u64>,
+    pub max_age_secs: Option<u64>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub compression: Compression,
+}
+
+impl RotateConfig {
+    /// Whether a file of `size` bytes, created at `created`, is due for
+    /// rotation.
+    pub fn is_due(&self, size: u64, created: SystemTime) -> bool {
+        let too_large = self.max_bytes.map_or(false, |max_bytes| size >= max_bytes);
+        let too_old = self.max_age_secs.map_or(false, |max_age_secs| {
+            created
+                .elapsed()
+                .map_or(false, |age| age >= Duration::from_secs(max_age_secs))
+        });
+        too_large || too_old
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionConfig {
+    pub max_files: Option<usize>,
+}
+
+/// Rotates the file at `path`, compressing it with `compression` once it's
+/// renamed, and keeping at most `max_files` rotated files.
+pub async fn rotate(
+    path: &Path,
+    compression: Compression,
+    max_files: Option<usize>,
+) -> io::Result<()> {
+    if max_files == Some(0) {
+        return fs::remove_file(path).await;
+    }
+
+    let suffix = suffix(compression);
+    let mut rotated = Vec::new();
+    while let Some(file) = find_rotated(path, rotated.len() + 1, suffix).await {
+        rotated.push(file);
+    }
+
+    // Make room for the file being rotated, which becomes the first one.
+    let keep = max_files.map_or(rotated.len(), |max_files| rotated.len().min(max_files - 1));
+    for (file, _) in rotated.drain(keep..) {
+        fs::remove_file(file).await?;
+    }
+    for (index, (file, compressed)) in rotated.iter().enumerate().rev() {
+        let suffix = if *compressed { suffix } else { "" };
+        fs::rename(file, numbered(path, index + 2, suffix)).await?;
+    }
+
+    let first = numbered(path, 1, "");
+    fs::rename(path, &first).await?;
+    if compression != Compression::None {
+        compress(first, numbered(path, 1, suffix), compression).await?;
+    }
+    Ok(())
+}
+
+/// Finds the `number`th rotated file of `path`, and whether it's compressed.
+async fn find_rotated(path: &Path, number: usize, suffix: &str) -> Option<(PathBuf, bool)> {
+    if !suffix.is_empty() {
+        let compressed = numbered(path, number, suffix);
+        if fs::metadata(&compressed).await.is_ok() {
+            return Some((compressed, true));
+        }
+    }
+    let plain = numbered(path, number, "");
+    if fs::metadata(&plain).await.is_ok() {
+        return Some((plain, false));
+    }
+    None
+}
+
+fn numbered(path: &Path, number: usize, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".{}{}", number, suffix));
+    name.into()
+}
+
+fn suffix(compression: Compression) -> &'static str {
+    match compression {
+        Compression::None => "",
+        Compression::Gzip(_) => ".gz",
+        Compression::Zstd(_) => ".zst",
+        Compression::Snappy => ".sz",
+        Compression::Lz4 => ".lz4",
+    }
+}
+
+/// Compresses the file at `from` into `to`, and removes it once done.
+async fn compress(from: PathBuf, to: PathBuf, compression: Compression) -> io::Result<()> {
+    tokio::task::spawn_blocking(move || {
+        let mut input = std::fs::File::open(&from)?;
+        let mut encoder = Encoder::new(std::fs::File::create(&to)?, compression)?;
+        io::copy(&mut input, &mut encoder)?;
+        encoder.finish()?.sync_all()?;
+        std::fs::remove_file(&from)
+    })
+    .await
+    .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{lines_from_file, lines_from_gzip_file, temp_dir};
+
+    fn write(path: &Path, line: &str) {
+        std::fs::write(path, format!("{}\n", line)).unwrap();
+    }
+
+    #[test]
+    fn rotates_by_size_and_age() {
+        let now = SystemTime::now();
+        let config = RotateConfig {
+            max_bytes: Some(100),
+            max_age_secs: Some(60),
+            ..Default::default()
+        };
+
+        assert!(!config.is_due(99, now));
+        assert!(config.is_due(100, now));
+        assert!(config.is_due(0, now - Duration::from_secs(61)));
+        assert!(!RotateConfig::default().is_due(u64::MAX, SystemTime::UNIX_EPOCH));
+    }
+
+    #[tokio::test]
+    async fn shifts_rotated_files() {
+        let directory = temp_dir();
+        let path = directory.join("app.log");
+
+        for line in &["first", "second", "third"] {
+            write(&path, line);
+            rotate(&path, Compression::None, None).await.unwrap();
+        }
+
+        assert!(!path.exists());
+        assert_eq!(lines_from_file(directory.join("app.log.1")), vec!["third"]);
+        assert_eq!(lines_from_file(directory.join("app.log.2")), vec!["second"]);
+        assert_eq!(lines_from_file(directory.join("app.log.3")), vec!["first"]);
+    }
+
+    #[tokio::test]
+    async fn keeps_max_files() {
+        let directory = temp_dir();
+        let path = directory.join("app.log");
+
+        for line in &["first", "second", "third"] {
+            write(&path, line);
+            rotate(&path, Compression::None, Some(2)).await.unwrap();
+        }
+
+        assert_eq!(lines_from_file(directory.join("app.log.1")), vec!["third"]);
+        assert_eq!(lines_from_file(directory.join("app.log.2")), vec!["second"]);
+        assert!(!directory.join("app.log.3").exists());
+
+        write(&path, "fourth");
+        rotate(&path, Compression::None, Some(0)).await.unwrap();
+        assert!(!path.exists());
+        assert!(!directory.join("app.log.3").exists());
+    }
+
+    #[tokio::test]
+    async fn compresses_rotated_files() {
+        let directory = temp_dir();
+        let path = directory.join("app.log");
+
+        // A file rotated before compression was turned on keeps its name.
+        write(&path, "plain");
+        rotate(&path, Compression::None, None).await.unwrap();
+
+        for line in &["first", "second"] {
+            write(&path, line);
+            rotate(&path, Compression::gzip_default(), Some(3))
+                .await
+                .unwrap();
+        }
+
+        assert!(!directory.join("app.log.1").exists());
+        assert_eq!(
+            lines_from_gzip_file(directory.join("app.log.1.gz")),
+            vec!["second"]
+        );
+        assert_eq!(
+            lines_from_gzip_file(directory.join("app.log.2.gz")),
+            vec!["first"]
+        );
+        assert_eq!(lines_from_file(directory.join("app.log.3")), vec!["plain"]);
+    }
+}
@@ -1,15 +1,17 @@
 use super::{healthcheck_response, GcpAuthConfig, GcpCredentials, Scope};
 use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    event::{Event, LogEvent},
     http::{HttpClient, HttpClientFuture},
     serde::to_string,
     sinks::{
         util::{
+            buffer::parquet::{self, ParquetBuffer, ParquetConfig},
             encoding::{EncodingConfig, EncodingConfiguration},
             retries::{RetryAction, RetryLogic},
-            BatchConfig, BatchSettings, Buffer, Compression, InFlightLimit, PartitionBatchSink,
-            PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+            BatchConfig, BatchSettings, Buffer, Compression, EncodedEvent, InFlightLimit,
+            PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt,
+            TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
@@ -66,6 +68,8 @@ pub struct GcsSinkConfig {
     filename_extension: Option<String>,
     encoding: EncodingConfig<Encoding>,
     #[serde(default)]
+    parquet: ParquetConfig,
+    #[serde(default)]
     compression: Compression,
     #[serde(default)]
     batch: BatchConfig,
@@ -88,6 +92,7 @@ fn default_config(e: Encoding) -> GcsSinkConfig {
         filename_append_uuid: Default::default(),
         filename_extension: Default::default(),
         encoding: e.into(),
+        parquet: Default::default(),
         compression: Compression::gzip_default(),
         batch: Default::default(),
         request: Default::default(),
@@ -133,6 +138,7 @@ lazy_static! {
 enum Encoding {
     Text,
     Ndjson,
+    Parquet,
 }
 
 impl Encoding {
@@ -140,6 +146,7 @@ impl Encoding {
         match self {
             Self::Text => "text/plain",
             Self::Ndjson => "application/x-ndjson",
+            Self::Parquet => parquet::CONTENT_TYPE,
         }
     }
 }
@@ -213,7 +220,7 @@ impl GcsSink {
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
         let encoding = config.encoding.clone();
 
-        let batch = BatchSettings::default()
+        let batch = BatchSettings::<Buffer>::default()
             .bytes(bytesize::mib(10u64))
             .timeout(300)
             .parse_config(config.batch)?;
@@ -228,13 +235,40 @@ impl GcsSink {
             .settings(request, GcsRetryLogic)
             .service(self);
 
-        let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
-
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .sink_map_err(|error| error!(message = "Fatal gcp_cloud_storage error.", %error))
-            .with_flat_map(move |e| iter_ok(encode_event(e, &key_prefix, &encoding)));
-
-        Ok(VectorSink::Futures01Sink(Box::new(sink)))
+        let sink: Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> =
+            if *encoding.codec() == Encoding::Parquet {
+                let buffer = PartitionBuffer::new(ParquetBuffer::new(
+                    batch.into::<ParquetBuffer>().size,
+                    &config.parquet,
+                    config.compression,
+                ));
+                Box::new(
+                    PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                        .sink_map_err(
+                            |error| error!(message = "Fatal gcp_cloud_storage error.", %error),
+                        )
+                        .with_flat_map(move |e| {
+                            iter_ok(EncodedEvent::from_event(e, |e| {
+                                prepare_event(e, &key_prefix, &encoding)
+                            }))
+                        }),
+                )
+            } else {
+                let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
+                Box::new(
+                    PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
+                        .sink_map_err(
+                            |error| error!(message = "Fatal gcp_cloud_storage error.", %error),
+                        )
+                        .with_flat_map(move |e| {
+                            iter_ok(EncodedEvent::from_event(e, |e| {
+                                encode_event(e, &key_prefix, &encoding)
+                            }))
+                        }),
+                )
+            };
+
+        Ok(VectorSink::Futures01Sink(sink))
     }
 
     async fn healthcheck(mut self) -> crate::Result<()> {
@@ -354,8 +388,15 @@ impl RequestSettings {
             .acl
             .map(|acl| HeaderValue::from_str(&to_string(acl)).unwrap());
         let content_type = HeaderValue::from_str(config.encoding.codec().content_type()).unwrap();
-        let content_encoding = config
-            .compression
+        // Parquet files compress their columns themselves, so the objects
+        // holding them aren't compressed.
+        let is_parquet = *config.encoding.codec() == Encoding::Parquet;
+        let compression = if is_parquet {
+            Compression::None
+        } else {
+            config.compression
+        };
+        let content_encoding = compression
             .content_encoding()
             .map(|ce| HeaderValue::from_str(&to_string(ce)).unwrap());
         let storage_class = config.storage_class.unwrap_or_default();
@@ -370,10 +411,13 @@ impl RequestSettings {
                     .collect::<Result<Vec<_>, _>>()
             })
             .unwrap_or_else(|| Ok(vec![]))?;
-        let extension = config
-            .filename_extension
-            .clone()
-            .unwrap_or_else(|| config.compression.extension().into());
+        let extension = config.filename_extension.clone().unwrap_or_else(|| {
+            if is_parquet {
+                parquet::EXTENSION.into()
+            } else {
+                compression.extension().into()
+            }
+        });
         let time_format = config
             .filename_time_format
             .clone()
@@ -400,11 +444,13 @@ fn make_header((name, value): (&String, &String)) -> crate::Result<(HeaderName,
     ))
 }
 
-fn encode_event(
+/// Renders the key of the event's partition and applies the encoding
+/// rules to it.
+fn prepare_event(
     mut event: Event,
     key_prefix: &Template,
     encoding: &EncodingConfig<Encoding>,
-) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
+) -> Option<PartitionInnerBuffer<LogEvent, Bytes>> {
     let key = key_prefix
         .render_string(&event)
         .map_err(|missing_keys| {
@@ -416,7 +462,15 @@ fn encode_event(
         })
         .ok()?;
     encoding.apply_rules(&mut event);
-    let log = event.into_log();
+    Some(PartitionInnerBuffer::new(event.into_log(), key.into()))
+}
+
+fn encode_event(
+    event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
+    let (log, key) = prepare_event(event, key_prefix, encoding)?.into_parts();
     let bytes = match encoding.codec() {
         Encoding::Ndjson => serde_json::to_vec(&log)
             .map(|mut b| {
@@ -432,9 +486,10 @@ fn encode_event(
             bytes.push(b'\n');
             bytes
         }
+        Encoding::Parquet => unreachable!("Parquet batches are encoded by `ParquetBuffer`."),
     };
 
-    Some(PartitionInnerBuffer::new(bytes, key.into()))
+    Some(PartitionInnerBuffer::new(bytes, key))
 }
 
 #[derive(Clone)]
@@ -542,6 +597,22 @@ mod tests {
         .expect("Could not create request settings")
     }
 
+    #[test]
+    fn gcs_build_parquet_request() {
+        let settings = RequestSettings::new(&GcsSinkConfig {
+            filename_time_format: Some("date".into()),
+            filename_append_uuid: Some(false),
+            ..default_config(Encoding::Parquet)
+        })
+        .unwrap();
+        assert_eq!(settings.content_type, parquet::CONTENT_TYPE);
+        assert_eq!(settings.content_encoding, None);
+
+        let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
+        let req = RequestWrapper::new(buf, settings);
+        assert_eq!(req.key, "key/date.parquet".to_string());
+    }
+
     #[test]
     fn gcs_build_request() {
         let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
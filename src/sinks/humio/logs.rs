@@ -71,6 +71,7 @@ impl From<Encoding> for splunk_hec::Encoding {
 #[typetag::serde(name = "humio_logs")]
 impl SinkConfig for HumioLogsConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        self.compression.check_gzip_only("Humio")?;
         self.build_hec_config().build(cx).await
     }
 
@@ -11,7 +11,7 @@ use crate::{
             encode_namespace,
             http::{HttpBatchService, HttpRetryLogic},
             statistic::{validate_quantiles, DistributionStatistic},
-            BatchConfig, BatchSettings, MetricBuffer, TowerRequestConfig,
+            BatchConfig, BatchSettings, EncodedEvent, MetricBuffer, TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
@@ -19,7 +19,7 @@ use crate::{
 };
 use bytes::Bytes;
 use futures::future::{ready, BoxFuture};
-use futures01::Sink;
+use futures01::{stream::iter_ok, Sink};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
@@ -140,7 +140,8 @@ impl InfluxDBSvc {
                 batch.timeout,
                 cx.acker(),
             )
-            .sink_map_err(|error| error!(message = "Fatal influxdb sink error.", %error));
+            .sink_map_err(|error| error!(message = "Fatal influxdb sink error.", %error))
+            .with_flat_map(|event| iter_ok(Some(EncodedEvent::new(event))));
 
         Ok(VectorSink::Futures01Sink(Box::new(sink)))
     }
@@ -1,7 +1,10 @@
 use crate::{
     buffers::Acker,
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::{Event, Value},
+    event::{
+        finalization::{EventFinalizers, EventStatus},
+        Event, Value,
+    },
     kafka::{KafkaAuthConfig, KafkaCompression},
     serde::to_string,
     sinks::util::encoding::{EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration},
@@ -14,6 +17,8 @@ use futures01::{
 };
 use rdkafka::{
     consumer::{BaseConsumer, Consumer},
+    error::{KafkaError, RDKafkaError},
+    message::OwnedHeaders,
     producer::{DeliveryFuture, FutureProducer, FutureRecord},
 };
 use serde::{Deserialize, Serialize};
@@ -30,6 +35,12 @@ enum BuildError {
     KafkaCreateFailed { source: rdkafka::error::KafkaError },
     #[snafu(display("invalid topic template: {}", source))]
     TopicTemplate { source: TemplateError },
+    #[snafu(display("invalid key template: {}", source))]
+    KeyTemplate { source: TemplateError },
+    #[snafu(display("invalid partition template: {}", source))]
+    PartitionTemplate { source: TemplateError },
+    #[snafu(display("`key` and `key_field` can't both be set"))]
+    KeyConflict,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -37,6 +48,9 @@ pub struct KafkaSinkConfig {
     bootstrap_servers: String,
     topic: String,
     key_field: Option<String>,
+    key: Option<String>,
+    headers_field: Option<String>,
+    partition: Option<String>,
     encoding: EncodingConfigWithDefault<Encoding>,
     #[serde(default)]
     compression: KafkaCompression,
@@ -69,9 +83,11 @@ pub enum Encoding {
 pub struct KafkaSink {
     producer: FutureProducer,
     topic: Template,
-    key_field: Option<String>,
+    key: Option<Key>,
+    headers_field: Option<String>,
+    partition: Option<Template>,
     encoding: EncodingConfig<Encoding>,
-    in_flight: FuturesUnordered<MetadataFuture<Compat<DeliveryFuture>, usize>>,
+    in_flight: FuturesUnordered<MetadataFuture<Compat<DeliveryFuture>, (usize, EventFinalizers)>>,
 
     acker: Acker,
     seq_head: usize,
@@ -79,6 +95,13 @@ pub struct KafkaSink {
     pending_acks: HashSet<usize>,
 }
 
+/// Where the message key comes from: the raw bytes of a log field, or a
+/// rendered template.
+enum Key {
+    Field(String),
+    Template(Template),
+}
+
 inventory::submit! {
     SinkDescription::new::<KafkaSinkConfig>("kafka")
 }
@@ -108,7 +131,7 @@ impl SinkConfig for KafkaSinkConfig {
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Log
+        DataType::Any
     }
 
     fn sink_type(&self) -> &'static str {
@@ -139,11 +162,24 @@ impl KafkaSinkConfig {
 
 impl KafkaSink {
     fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
+        let key = match (config.key, config.key_field) {
+            (Some(_), Some(_)) => return Err(BuildError::KeyConflict.into()),
+            (Some(key), None) => Some(Key::Template(Template::try_from(key).context(KeyTemplate)?)),
+            (None, Some(field)) => Some(Key::Field(field)),
+            (None, None) => None,
+        };
+        let partition = match config.partition {
+            Some(partition) => Some(Template::try_from(partition).context(PartitionTemplate)?),
+            None => None,
+        };
+
         let producer = config.to_rdkafka()?.create().context(KafkaCreateFailed)?;
         Ok(KafkaSink {
             producer,
             topic: Template::try_from(config.topic).context(TopicTemplate)?,
-            key_field: config.key_field,
+            key,
+            headers_field: config.headers_field,
+            partition,
             encoding: config.encoding.into(),
             in_flight: FuturesUnordered::new(),
             acker,
@@ -152,49 +188,102 @@ impl KafkaSink {
             pending_acks: HashSet::new(),
         })
     }
+
+    /// Drops an event that rdkafka refused to enqueue for a reason other than
+    /// a full queue, such as a partition the topic doesn't have.
+    fn reject(&mut self, mut item: Event, error: KafkaError) {
+        error!(message = "Kafka rejected the event; dropping it.", %error);
+        item.take_finalizers().update_status(EventStatus::Failed);
+
+        let seqno = self.seq_head;
+        self.seq_head += 1;
+        self.ack(seqno);
+    }
+
+    fn ack(&mut self, seqno: usize) {
+        self.pending_acks.insert(seqno);
+
+        let mut num_to_ack = 0;
+        while self.pending_acks.remove(&self.seq_tail) {
+            num_to_ack += 1;
+            self.seq_tail += 1
+        }
+        self.acker.ack(num_to_ack);
+    }
 }
 
 impl Sink for KafkaSink {
     type SinkItem = Event;
     type SinkError = ();
 
-    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+    fn start_send(
+        &mut self,
+        mut item: Self::SinkItem,
+    ) -> StartSend<Self::SinkItem, Self::SinkError> {
         let topic = self.topic.render_string(&item).map_err(|missing_keys| {
             error!(message = "Missing keys for topic.", missing_keys = ?missing_keys);
         })?;
 
-        let (key, body) = encode_event(item.clone(), &self.key_field, &self.encoding);
+        let headers = self
+            .headers_field
+            .as_ref()
+            .and_then(|field| get_headers(&item, field));
+        let partition = self
+            .partition
+            .as_ref()
+            .and_then(|partition| render_partition(partition, &item));
+        let (key, body) = encode_event(item.clone(), &self.key, &self.encoding);
 
         let mut record = FutureRecord::to(&topic).key(&key).payload(&body[..]);
 
-        if let Some(Value::Timestamp(timestamp)) = item.as_log().get(log_schema().timestamp_key()) {
+        let timestamp = match &item {
+            Event::Log(log) => log
+                .get(log_schema().timestamp_key())
+                .and_then(Value::as_timestamp),
+            Event::Metric(metric) => metric.timestamp.as_ref(),
+        };
+        if let Some(timestamp) = timestamp {
             record = record.timestamp(timestamp.timestamp_millis());
         }
+        if let Some(headers) = headers {
+            record = record.headers(headers);
+        }
+        if let Some(partition) = partition {
+            record = record.partition(partition);
+        }
 
         debug!(message = "Sending event.", count = 1);
         let future = match self.producer.send_result(record) {
             Ok(f) => f,
-            Err((error, record)) => {
-                // Docs suggest this will only happen when the producer queue is full, so let's
-                // treat it as we do full buffers in other sinks
-                debug!(message = "The rdkafka queue full.", %error);
+            Err((KafkaError::MessageProduction(RDKafkaError::QueueFull), record)) => {
+                // Treat a full producer queue as we do full buffers in other sinks
+                debug!(message = "The rdkafka queue full.");
                 self.poll_complete()?;
 
                 match self.producer.send_result(record) {
                     Ok(f) => f,
-                    Err((error, _record)) => {
-                        debug!(message = "The rdkafka queue still full.", %error);
+                    Err((KafkaError::MessageProduction(RDKafkaError::QueueFull), _record)) => {
+                        debug!(message = "The rdkafka queue still full.");
                         return Ok(AsyncSink::NotReady(item));
                     }
+                    Err((error, _record)) => {
+                        self.reject(item, error);
+                        return Ok(AsyncSink::Ready);
+                    }
                 }
             }
+            Err((error, _record)) => {
+                self.reject(item, error);
+                return Ok(AsyncSink::Ready);
+            }
         };
 
         let seqno = self.seq_head;
         self.seq_head += 1;
 
+        let finalizers = item.take_finalizers();
         self.in_flight
-            .push(Compat::new(future).join(future01::ok(seqno)));
+            .push(Compat::new(future).join(future01::ok((seqno, finalizers))));
         Ok(AsyncSink::Ready)
     }
 
@@ -208,22 +297,21 @@ impl Sink for KafkaSink {
                 Ok(Async::Ready(None)) => return Ok(Async::Ready(())),
 
                 // request finished, check for success
-                Ok(Async::Ready(Some((result, seqno)))) => {
+                Ok(Async::Ready(Some((result, (seqno, finalizers))))) => {
                     match result {
-                        Ok((partition, offset)) => trace!(
-                            message = "Produced message.", parition = ?partition, offset = ?offset
-                        ),
-                        Err((error, _msg)) => error!(message = "Kafka error.", %error),
+                        Ok((partition, offset)) => {
+                            trace!(
+                                message = "Produced message.", parition = ?partition, offset = ?offset
+                            );
+                            finalizers.update_status(EventStatus::Delivered);
+                        }
+                        Err((error, _msg)) => {
+                            error!(message = "Kafka error.", %error);
+                            finalizers.update_status(EventStatus::Errored);
+                        }
                     };
 
-                    self.pending_acks.insert(seqno);
-
-                    let mut num_to_ack = 0;
-                    while self.pending_acks.remove(&self.seq_tail) {
-                        num_to_ack += 1;
-                        self.seq_tail += 1
-                    }
-                    self.acker.ack(num_to_ack);
+                    self.ack(seqno);
                 }
 
                 // request got canceled (according to docs)
@@ -262,26 +350,87 @@ async fn healthcheck(config: KafkaSinkConfig) -> crate::Result<()> {
     Ok(())
 }
 
+/// Turns the map held by `field` into the record's headers.
+fn get_headers(event: &Event, field: &str) -> Option<OwnedHeaders> {
+    let map = match event {
+        Event::Log(log) => match log.get(field)? {
+            Value::Map(map) => map,
+            _ => {
+                warn!(
+                    message = "Headers field is not a map, skipping headers.",
+                    %field,
+                    rate_limit_secs = 30
+                );
+                return None;
+            }
+        },
+        Event::Metric(_) => return None,
+    };
+
+    Some(
+        map.iter()
+            .fold(OwnedHeaders::new(), |headers, (name, value)| {
+                headers.add(name.as_str(), &value.as_bytes()[..])
+            }),
+    )
+}
+
+/// The partition to send the event to, leaving it to the producer's
+/// partitioner when the template can't be rendered into a partition number.
+fn render_partition(partition: &Template, event: &Event) -> Option<i32> {
+    let rendered = match partition.render_string(event) {
+        Ok(rendered) => rendered,
+        Err(missing_keys) => {
+            warn!(
+                message = "Missing keys for partition, using the default partitioner.",
+                ?missing_keys,
+                rate_limit_secs = 30
+            );
+            return None;
+        }
+    };
+    match rendered.trim().parse() {
+        Ok(partition) => Some(partition),
+        Err(_) => {
+            warn!(
+                message = "Partition is not a number, using the default partitioner.",
+                partition = %rendered,
+                rate_limit_secs = 30
+            );
+            None
+        }
+    }
+}
+
 fn encode_event(
     mut event: Event,
-    key_field: &Option<String>,
+    key: &Option<Key>,
     encoding: &EncodingConfig<Encoding>,
 ) -> (Vec<u8>, Vec<u8>) {
-    let key = key_field
-        .as_ref()
-        .and_then(|f| event.as_log().get(f))
-        .map(|v| v.as_bytes().to_vec())
-        .unwrap_or_default();
+    // Events missing the key field, or the fields of the key template, get a
+    // blank key.
+    let key = match key {
+        Some(Key::Field(field)) => match &event {
+            Event::Log(log) => log.get(field).map(|v| v.as_bytes().to_vec()),
+            Event::Metric(_) => None,
+        },
+        Some(Key::Template(template)) => template.render(&event).ok().map(|key| key.to_vec()),
+        None => None,
+    }
+    .unwrap_or_default();
 
     encoding.apply_rules(&mut event);
 
-    let body = match encoding.codec() {
-        Encoding::Json => serde_json::to_vec(&event.as_log()).unwrap(),
-        Encoding::Text => event
-            .as_log()
-            .get(log_schema().message_key())
-            .map(|v| v.as_bytes().to_vec())
-            .unwrap_or_default(),
+    let body = match event {
+        Event::Log(log) => match encoding.codec() {
+            Encoding::Json => serde_json::to_vec(&log).unwrap(),
+            Encoding::Text => log
+                .get(log_schema().message_key())
+                .map(|v| v.as_bytes().to_vec())
+                .unwrap_or_default(),
+        },
+        // Metrics have no message, so they are always encoded as JSON.
+        Event::Metric(metric) => serde_json::to_vec(&metric).unwrap(),
     };
 
     (key, body)
@@ -290,7 +439,8 @@ fn encode_event(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::Event;
+    use crate::event::{Event, Metric, MetricKind, MetricValue};
+    use rdkafka::message::Headers;
     use std::collections::BTreeMap;
 
     #[test]
@@ -321,7 +471,7 @@ mod tests {
 
         let (key, bytes) = encode_event(
             event,
-            &Some("key".into()),
+            &Some(Key::Field("key".into())),
             &EncodingConfig::from(Encoding::Json),
         );
 
@@ -340,7 +490,7 @@ mod tests {
 
         let (key, bytes) = encode_event(
             event,
-            &Some("key".into()),
+            &Some(Key::Field("key".into())),
             &EncodingConfigWithDefault {
                 codec: Encoding::Json,
                 except_fields: Some(vec!["key".into()]),
@@ -354,6 +504,112 @@ mod tests {
         assert_eq!(&key[..], b"value");
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn kafka_encode_event_key_template() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("tenant", "acme");
+        event.as_mut_log().insert("user", "alice");
+
+        let key = Some(Key::Template(
+            Template::try_from("{{ tenant }}/{{ user }}").unwrap(),
+        ));
+        let encoding = EncodingConfig::from(Encoding::Text);
+
+        let (key_bytes, _) = encode_event(event, &key, &encoding);
+        assert_eq!(&key_bytes[..], b"acme/alice");
+
+        let (key_bytes, _) = encode_event(Event::from("hello"), &key, &encoding);
+        assert!(key_bytes.is_empty());
+    }
+
+    #[test]
+    fn kafka_encode_event_key_field_is_raw() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("key", "100%{{ not a template }}");
+
+        let (key, _) = encode_event(
+            event,
+            &Some(Key::Field("key".into())),
+            &EncodingConfig::from(Encoding::Text),
+        );
+
+        assert_eq!(&key[..], b"100%{{ not a template }}");
+    }
+
+    #[test]
+    fn kafka_encode_event_metric() {
+        let metric = Metric {
+            name: "requests".into(),
+            namespace: None,
+            timestamp: None,
+            tags: Some(
+                vec![("host".to_owned(), "web-1".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        };
+
+        let (key, bytes) = encode_event(
+            Event::Metric(metric.clone()),
+            &Some(Key::Template(
+                Template::try_from("{{ name }}-{{ tags.host }}").unwrap(),
+            )),
+            &EncodingConfig::from(Encoding::Text),
+        );
+
+        assert_eq!(&key[..], b"requests-web-1");
+        assert_eq!(serde_json::from_slice::<Metric>(&bytes).unwrap(), metric);
+    }
+
+    #[test]
+    fn kafka_get_headers() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("headers.trace_id", "abc123");
+        event.as_mut_log().insert("headers.attempt", 2);
+        event.as_mut_log().insert("message_id", "1");
+
+        let headers = get_headers(&event, "headers").unwrap();
+        let headers = (0..headers.count())
+            .map(|index| headers.get(index).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            headers,
+            vec![("attempt", &b"2"[..]), ("trace_id", &b"abc123"[..])]
+        );
+
+        assert!(get_headers(&event, "message_id").is_none());
+        assert!(get_headers(&event, "missing").is_none());
+    }
+
+    #[test]
+    fn kafka_render_partition() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("shard", 3);
+        event.as_mut_log().insert("region", "eu");
+
+        let render =
+            |template: &str| render_partition(&Template::try_from(template).unwrap(), &event);
+        assert_eq!(render("{{ shard }}"), Some(3));
+        assert_eq!(render("7"), Some(7));
+        assert_eq!(render("{{ region }}"), None);
+        assert_eq!(render("{{ missing }}"), None);
+    }
+
+    #[test]
+    fn kafka_key_conflict() {
+        let config = KafkaSinkConfig {
+            bootstrap_servers: "localhost:9091".into(),
+            topic: "topic".into(),
+            key_field: Some("user_id".into()),
+            key: Some("{{ user_id }}".into()),
+            ..Default::default()
+        };
+
+        assert!(KafkaSink::new(config, Acker::Null).is_err());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -473,6 +729,50 @@ mod integration_test {
         .await;
     }
 
+    #[tokio::test]
+    async fn kafka_drops_events_for_unknown_partition() {
+        let topic = format!("test-{}", random_string(10));
+
+        let config = KafkaSinkConfig {
+            bootstrap_servers: "localhost:9091".into(),
+            topic: topic.clone(),
+            partition: Some("100".into()),
+            encoding: EncodingConfigWithDefault::from(Encoding::Text),
+            socket_timeout_ms: 60000,
+            message_timeout_ms: 10000,
+            ..Default::default()
+        };
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let sink = KafkaSink::new(config, acker).unwrap();
+
+        let num_events = 10;
+        let (_input, events) = random_lines_with_stream(100, num_events);
+        let mut events = events.map(Ok);
+
+        // The topic only has one partition, so sending must finish without
+        // delivering anything instead of waiting on the partition forever.
+        tokio::time::timeout(
+            Duration::from_secs(60),
+            sink.sink_compat().send_all(&mut events),
+        )
+        .await
+        .expect("Sending to an unknown partition hung.")
+        .unwrap();
+
+        assert_eq!(
+            ack_counter.load(std::sync::atomic::Ordering::Relaxed),
+            num_events
+        );
+
+        let mut client_config = rdkafka::ClientConfig::new();
+        client_config.set("bootstrap.servers", "localhost:9091");
+        let consumer: BaseConsumer = client_config.create().unwrap();
+        let (low, high) = consumer
+            .fetch_watermarks(&topic, 0, Duration::from_secs(3))
+            .unwrap();
+        assert_eq!((0, 0), (low, high));
+    }
+
     async fn kafka_happy_path(
         server: &str,
         sasl: Option<KafkaSaslConfig>,
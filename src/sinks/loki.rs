@@ -17,10 +17,13 @@ use crate::{
     event::{self, Event, Value},
     http::{Auth, HttpClient},
     sinks::util::{
-        buffer::loki::{LokiBuffer, LokiEvent, LokiRecord},
+        buffer::{
+            compression::Encoder,
+            loki::{LokiBuffer, LokiEvent, LokiRecord},
+        },
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
         http::{BatchedHttpSink, HttpSink},
-        BatchConfig, BatchSettings, TowerRequestConfig, UriSerde,
+        BatchConfig, BatchSettings, Compression, TowerRequestConfig, UriSerde,
     },
     template::Template,
     tls::{TlsOptions, TlsSettings},
@@ -29,7 +32,7 @@ use derivative::Derivative;
 use futures::FutureExt;
 use futures01::Sink;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, io::Write};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -48,6 +51,9 @@ pub struct LokiConfig {
 
     auth: Option<Auth>,
 
+    #[serde(default)]
+    compression: Compression,
+
     #[serde(default)]
     request: TowerRequestConfig,
 
@@ -183,11 +189,18 @@ impl HttpSink for LokiConfig {
 
     async fn build_request(&self, json: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
         let body = serde_json::to_vec(&json).unwrap();
+        let mut encoder = Encoder::new(Vec::new(), self.compression)?;
+        encoder.write_all(&body)?;
+        let body = encoder.finish()?;
 
         let uri = format!("{}loki/api/v1/push", self.endpoint);
 
         let mut req = http::Request::post(uri).header("Content-Type", "application/json");
 
+        if let Some(ce) = self.compression.content_encoding() {
+            req = req.header("Content-Encoding", ce);
+        }
+
         if let Some(tenant_id) = &self.tenant_id {
             req = req.header("X-Scope-OrgID", tenant_id);
         }
@@ -298,6 +311,28 @@ mod tests {
         assert_eq!(record.labels[0], ("bar".to_string(), "bar".to_string()));
     }
 
+    #[tokio::test]
+    async fn compresses_requests() {
+        let (config, _cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {test_name = "placeholder"}
+            compression = "zstd"
+        "#,
+        )
+        .unwrap();
+
+        let json = serde_json::json!({ "streams": [] });
+        let req = config.build_request(json.clone()).await.unwrap();
+
+        assert_eq!(req.headers()["Content-Encoding"], "zstd");
+        let body = zstd::decode_all(req.body().as_slice()).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            json
+        );
+    }
+
     #[tokio::test]
     async fn healthcheck_includes_auth() {
         let (mut config, _cx) = load_sink::<LokiConfig>(
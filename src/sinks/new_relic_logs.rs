@@ -106,6 +106,8 @@ impl SinkConfig for NewRelicLogsConfig {
 
 impl NewRelicLogsConfig {
     fn create_config(&self) -> crate::Result<HttpSinkConfig> {
+        self.compression.check_gzip_only("New Relic")?;
+
         let mut headers: IndexMap<String, String> = IndexMap::new();
 
         if let Some(license_key) = &self.license_key {
@@ -189,6 +191,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn new_relic_logs_check_config_unsupported_compression() {
+        let mut nr_config = NewRelicLogsConfig::default();
+        nr_config.license_key = Some("foo".to_owned());
+        nr_config.compression = Compression::Lz4;
+
+        assert_eq!(
+            nr_config.create_config().unwrap_err().to_string(),
+            "The `lz4` compression is not supported by New Relic, use `gzip` or `none`."
+        );
+    }
+
     #[test]
     fn new_relic_logs_check_config_defaults() {
         let mut nr_config = NewRelicLogsConfig::default();
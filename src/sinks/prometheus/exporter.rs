@@ -2,10 +2,13 @@ use crate::{
     buffers::Acker,
     config::{DataType, GenerateConfig, Resource, SinkConfig, SinkContext, SinkDescription},
     event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
-    sinks::util::{
-        encode_namespace,
-        statistic::{validate_quantiles, DistributionStatistic},
-        MetricEntry, StreamSink,
+    sinks::{
+        util::{
+            encode_namespace,
+            statistic::{validate_quantiles, DistributionStatistic},
+            MetricEntry, StreamSink,
+        },
+        Healthcheck, VectorSink,
     },
     Event,
 };
@@ -95,10 +98,7 @@ impl GenerateConfig for PrometheusSinkConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "prometheus")]
 impl SinkConfig for PrometheusSinkConfig {
-    async fn build(
-        &self,
-        cx: SinkContext,
-    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
         if self.flush_period_secs < MIN_FLUSH_PERIOD_SECS {
             return Err(Box::new(BuildError::FlushPeriodTooShort {
                 min: MIN_FLUSH_PERIOD_SECS,
@@ -110,7 +110,7 @@ impl SinkConfig for PrometheusSinkConfig {
         let sink = PrometheusSink::new(self.clone(), cx.acker());
         let healthcheck = future::ok(()).boxed();
 
-        Ok((super::VectorSink::Stream(Box::new(sink)), healthcheck))
+        Ok((VectorSink::Stream(Box::new(sink)), healthcheck))
     }
 
     fn input_type(&self) -> DataType {
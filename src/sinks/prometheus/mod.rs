@@ -0,0 +1,5 @@
+mod exporter;
+mod remote_write;
+
+pub use exporter::PrometheusSinkConfig;
+pub use remote_write::RemoteWriteConfig;
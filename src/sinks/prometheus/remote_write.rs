@@ -0,0 +1,586 @@
+use super::exporter::{default_flush_period_secs, default_histogram_buckets};
+use crate::{
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
+    http::{Auth, HttpClient},
+    sinks::{
+        util::{
+            encode_namespace,
+            http::{BatchedHttpSink, HttpSink},
+            BatchConfig, BatchSettings, EncodedLength, MetricEntry, TowerRequestConfig, UriSerde,
+            VecBuffer,
+        },
+        Healthcheck, HealthcheckError, VectorSink,
+    },
+    tls::{TlsOptions, TlsSettings},
+    Event,
+};
+use chrono::Utc;
+use futures::FutureExt;
+use futures01::Sink;
+use http::Uri;
+use prometheus_parser::proto::{
+    metric_metadata::MetricType, Label, MetricMetadata, Sample, TimeSeries, WriteRequest,
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteWriteConfig {
+    pub endpoint: UriSerde,
+    pub default_namespace: Option<String>,
+    #[serde(default = "default_histogram_buckets")]
+    pub buckets: Vec<f64>,
+    #[serde(default = "default_flush_period_secs")]
+    pub flush_period_secs: u64,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub auth: Option<Auth>,
+    pub tls: Option<TlsOptions>,
+}
+
+inventory::submit! {
+    SinkDescription::new::<RemoteWriteConfig>("prometheus_remote_write")
+}
+
+impl GenerateConfig for RemoteWriteConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(r#"endpoint = "http://localhost:8087/""#).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "prometheus_remote_write")]
+impl SinkConfig for RemoteWriteConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+        let batch = BatchSettings::default()
+            .events(1_000)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls)?;
+
+        let healthcheck = healthcheck(self.clone(), client.clone()).boxed();
+
+        let sink = RemoteWriteSink::new(self.clone());
+        let sink = BatchedHttpSink::new(
+            sink,
+            VecBuffer::new(batch.size),
+            request,
+            batch.timeout,
+            client,
+            cx.acker(),
+        )
+        .sink_map_err(
+            |error| error!(message = "Fatal prometheus_remote_write sink error.", %error),
+        );
+
+        Ok((VectorSink::Futures01Sink(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "prometheus_remote_write"
+    }
+}
+
+struct RemoteWriteSink {
+    config: RemoteWriteConfig,
+    totals: Mutex<Totals>,
+}
+
+/// The running totals of the incremental metrics seen so far, as Prometheus
+/// only understands absolute values. Series that were not updated for a flush
+/// period are forgotten, so their totals start over from zero.
+struct Totals {
+    series: HashMap<MetricEntry, Instant>,
+    flush_period: Duration,
+    last_flush: Instant,
+}
+
+impl Totals {
+    fn new(flush_period: Duration) -> Self {
+        Self {
+            series: HashMap::new(),
+            flush_period,
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Adds the incremental `metric` to the total of its series.
+    fn add(&mut self, metric: &Metric) -> Metric {
+        let now = Instant::now();
+        if now.duration_since(self.last_flush) >= self.flush_period {
+            let flush_period = self.flush_period;
+            self.series
+                .retain(|_, updated| now.duration_since(*updated) < flush_period);
+            self.last_flush = now;
+        }
+
+        let mut total = match self.series.remove_entry(&MetricEntry(metric.to_absolute())) {
+            Some((MetricEntry(total), _)) => total,
+            None => {
+                let mut total = metric.to_absolute();
+                total.reset();
+                total
+            }
+        };
+        total.add(metric);
+        total.timestamp = metric.timestamp;
+        self.series.insert(MetricEntry(total.clone()), now);
+        total
+    }
+}
+
+/// The series and metadata of a single metric. Events are batched in this
+/// form so that each of them is acknowledged exactly once.
+#[derive(Clone, Debug)]
+struct EncodedMetric {
+    series: Vec<TimeSeries>,
+    metadata: MetricMetadata,
+}
+
+impl EncodedLength for EncodedMetric {
+    fn encoded_length(&self) -> usize {
+        self.series.iter().map(Message::encoded_len).sum()
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for RemoteWriteSink {
+    type Input = EncodedMetric;
+    type Output = Vec<EncodedMetric>;
+
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let metric = self.normalize(event.into_metric());
+        Some(encode_metric(
+            self.config.default_namespace.as_deref(),
+            metric,
+        ))
+    }
+
+    async fn build_request(&self, metrics: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+        build_request(&self.config, encode_request(metrics)?)
+    }
+}
+
+impl RemoteWriteSink {
+    fn new(config: RemoteWriteConfig) -> Self {
+        let flush_period = Duration::from_secs(config.flush_period_secs);
+        Self {
+            config,
+            totals: Mutex::new(Totals::new(flush_period)),
+        }
+    }
+
+    /// Turns the metric into an absolute counter, gauge, histogram or
+    /// summary. Distributions become histograms with the configured
+    /// buckets, and sets become gauges of their number of values.
+    fn normalize(&self, mut metric: Metric) -> Metric {
+        match &metric.value {
+            MetricValue::Set { values } => {
+                metric.value = MetricValue::Gauge {
+                    value: values.len() as f64,
+                };
+                metric.kind = MetricKind::Absolute;
+            }
+            MetricValue::Distribution {
+                values,
+                sample_rates,
+                ..
+            } => {
+                metric.value = histogram(values, sample_rates, &self.config.buckets);
+            }
+            _ => (),
+        }
+
+        match metric.kind {
+            MetricKind::Absolute => metric,
+            MetricKind::Incremental => self.totals.lock().unwrap().add(&metric),
+        }
+    }
+}
+
+fn histogram(values: &[f64], sample_rates: &[u32], buckets: &[f64]) -> MetricValue {
+    let mut counts = vec![0; buckets.len()];
+    let mut sum = 0.0;
+    let mut count = 0;
+    for (value, rate) in values.iter().zip(sample_rates) {
+        for (bucket, bucket_count) in buckets.iter().zip(counts.iter_mut()) {
+            if value <= bucket {
+                *bucket_count += rate;
+            }
+        }
+        sum += value * f64::from(*rate);
+        count += rate;
+    }
+    MetricValue::AggregatedHistogram {
+        buckets: buckets.to_vec(),
+        counts,
+        count,
+        sum,
+    }
+}
+
+/// Encodes a normalized metric into its series, named like the ones of the
+/// `prometheus` sink's exposition.
+fn encode_metric(default_namespace: Option<&str>, metric: Metric) -> EncodedMetric {
+    let name = encode_namespace(
+        metric.namespace.as_deref().or(default_namespace),
+        '_',
+        &metric.name,
+    );
+    let timestamp = metric.timestamp.unwrap_or_else(Utc::now).timestamp_millis();
+    let tags = metric.tags.unwrap_or_default();
+
+    let mut series = Vec::new();
+    let mut push = |suffix: &str, extra: Option<(&str, String)>, value: f64| {
+        series.push(TimeSeries {
+            labels: labels(format!("{}{}", name, suffix), &tags, extra),
+            samples: vec![Sample { value, timestamp }],
+        })
+    };
+    let metric_type = match metric.value {
+        MetricValue::Counter { value } => {
+            push("", None, value);
+            MetricType::Counter
+        }
+        MetricValue::Gauge { value } => {
+            push("", None, value);
+            MetricType::Gauge
+        }
+        MetricValue::AggregatedHistogram {
+            buckets,
+            counts,
+            count,
+            sum,
+        } => {
+            for (bucket, bucket_count) in buckets.iter().zip(counts) {
+                push(
+                    "_bucket",
+                    Some(("le", bucket.to_string())),
+                    f64::from(bucket_count),
+                );
+            }
+            push("_bucket", Some(("le", "+Inf".into())), f64::from(count));
+            push("_sum", None, sum);
+            push("_count", None, f64::from(count));
+            MetricType::Histogram
+        }
+        MetricValue::AggregatedSummary {
+            quantiles,
+            values,
+            count,
+            sum,
+        } => {
+            for (quantile, value) in quantiles.iter().zip(values) {
+                push("", Some(("quantile", quantile.to_string())), value);
+            }
+            push("_sum", None, sum);
+            push("_count", None, f64::from(count));
+            MetricType::Summary
+        }
+        MetricValue::Set { .. } | MetricValue::Distribution { .. } => {
+            unreachable!("sets and distributions are normalized away")
+        }
+    };
+
+    EncodedMetric {
+        series,
+        metadata: MetricMetadata {
+            r#type: metric_type as i32,
+            metric_family_name: name,
+            ..Default::default()
+        },
+    }
+}
+
+/// Prometheus expects the labels of a series to be sorted by name.
+fn labels(
+    name: String,
+    tags: &BTreeMap<String, String>,
+    extra: Option<(&str, String)>,
+) -> Vec<Label> {
+    let mut labels: Vec<Label> = tags
+        .iter()
+        .map(|(name, value)| Label {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    if let Some((name, value)) = extra {
+        labels.push(Label {
+            name: name.into(),
+            value,
+        });
+    }
+    labels.push(Label {
+        name: "__name__".into(),
+        value: name,
+    });
+    labels.sort_by(|a, b| a.name.cmp(&b.name));
+    labels
+}
+
+/// Encodes the metrics into a snappy compressed write request, which
+/// describes each metric family once.
+fn encode_request(metrics: Vec<EncodedMetric>) -> crate::Result<Vec<u8>> {
+    let mut families = HashSet::new();
+    let mut request = WriteRequest::default();
+    for metric in metrics {
+        if families.insert(metric.metadata.metric_family_name.clone()) {
+            request.metadata.push(metric.metadata);
+        }
+        request.timeseries.extend(metric.series);
+    }
+
+    let mut body = Vec::with_capacity(request.encoded_len());
+    request.encode(&mut body)?;
+    Ok(snap::raw::Encoder::new().compress_vec(&body)?)
+}
+
+fn build_request<B>(config: &RemoteWriteConfig, body: B) -> crate::Result<http::Request<B>> {
+    let mut request = http::Request::post(Uri::from(config.endpoint.clone()))
+        .header("Content-Type", "application/x-protobuf")
+        .header("Content-Encoding", "snappy")
+        .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+        .body(body)?;
+    if let Some(auth) = &config.auth {
+        auth.apply(&mut request);
+    }
+    Ok(request)
+}
+
+/// Remote write has no health endpoint, so this sends an empty write
+/// request, which the receivers accept without storing anything.
+async fn healthcheck(config: RemoteWriteConfig, mut client: HttpClient) -> crate::Result<()> {
+    let body = encode_request(Vec::new())?;
+    let request = build_request(&config, hyper::Body::from(body))?;
+    let response = client.send(request).await?;
+
+    match response.status() {
+        status if status.is_success() => Ok(()),
+        status => Err(HealthcheckError::UnexpectedStatus { status }.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server, load_sink},
+        test_util::next_addr,
+    };
+    use chrono::TimeZone;
+    use futures::{stream, StreamExt};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RemoteWriteConfig>();
+    }
+
+    fn sink() -> RemoteWriteSink {
+        RemoteWriteSink::new(toml::from_str(r#"endpoint = "http://localhost:8087/""#).unwrap())
+    }
+
+    fn metric(kind: MetricKind, value: MetricValue) -> Metric {
+        Metric {
+            name: "requests".into(),
+            namespace: Some("vector".into()),
+            timestamp: Some(Utc.timestamp(1_600_000_000, 0)),
+            tags: Some(
+                vec![("code".to_owned(), "200".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind,
+            value,
+        }
+    }
+
+    fn series(metric: &EncodedMetric) -> Vec<(Vec<(&str, &str)>, f64)> {
+        metric
+            .series
+            .iter()
+            .map(|series| {
+                let labels = series
+                    .labels
+                    .iter()
+                    .map(|label| (label.name.as_str(), label.value.as_str()))
+                    .collect();
+                (labels, series.samples[0].value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn accumulates_incremental_metrics() {
+        let sink = sink();
+        let counter = |value| metric(MetricKind::Incremental, MetricValue::Counter { value });
+
+        assert_eq!(
+            sink.normalize(counter(1.0)).value,
+            MetricValue::Counter { value: 1.0 }
+        );
+        assert_eq!(
+            sink.normalize(counter(2.0)).value,
+            MetricValue::Counter { value: 3.0 }
+        );
+
+        let absolute = sink.normalize(metric(
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 10.0 },
+        ));
+        assert_eq!(absolute.value, MetricValue::Counter { value: 10.0 });
+
+        // Absolute metrics are passed through without touching the totals.
+        let total = sink.normalize(counter(1.0));
+        assert_eq!(total.kind, MetricKind::Absolute);
+        assert_eq!(total.value, MetricValue::Counter { value: 4.0 });
+    }
+
+    #[test]
+    fn expires_incremental_totals() {
+        let sink = RemoteWriteSink::new(
+            toml::from_str(
+                r#"
+                endpoint = "http://localhost:8087/"
+                flush_period_secs = 0
+                "#,
+            )
+            .unwrap(),
+        );
+        let counter = |value| metric(MetricKind::Incremental, MetricValue::Counter { value });
+
+        assert_eq!(
+            sink.normalize(counter(1.0)).value,
+            MetricValue::Counter { value: 1.0 }
+        );
+        assert_eq!(
+            sink.normalize(counter(2.0)).value,
+            MetricValue::Counter { value: 2.0 }
+        );
+    }
+
+    #[test]
+    fn encodes_counters() {
+        let sink = sink();
+        let encoded = sink
+            .encode_event(metric(MetricKind::Absolute, MetricValue::Counter { value: 2.0 }).into())
+            .unwrap();
+
+        assert_eq!(
+            series(&encoded),
+            vec![(vec![("__name__", "vector_requests"), ("code", "200")], 2.0)]
+        );
+        assert_eq!(encoded.series[0].samples[0].timestamp, 1_600_000_000_000);
+        assert_eq!(encoded.metadata.metric_family_name, "vector_requests");
+        assert_eq!(encoded.metadata.r#type, MetricType::Counter as i32);
+    }
+
+    #[test]
+    fn encodes_distributions_as_histograms() {
+        let sink = sink();
+        let encoded = sink
+            .encode_event(
+                metric(
+                    MetricKind::Incremental,
+                    MetricValue::Distribution {
+                        values: vec![0.2, 3.0],
+                        sample_rates: vec![2, 1],
+                        statistic: crate::event::metric::StatisticKind::Histogram,
+                    },
+                )
+                .into(),
+            )
+            .unwrap();
+
+        let series = series(&encoded);
+        assert_eq!(
+            series[5],
+            (
+                vec![
+                    ("__name__", "vector_requests_bucket"),
+                    ("code", "200"),
+                    ("le", "0.25")
+                ],
+                2.0
+            )
+        );
+        assert_eq!(
+            series[11],
+            (
+                vec![
+                    ("__name__", "vector_requests_bucket"),
+                    ("code", "200"),
+                    ("le", "+Inf")
+                ],
+                3.0
+            )
+        );
+        assert_eq!(
+            series[12],
+            (
+                vec![("__name__", "vector_requests_sum"), ("code", "200")],
+                3.4
+            )
+        );
+        assert_eq!(
+            series[13],
+            (
+                vec![("__name__", "vector_requests_count"), ("code", "200")],
+                3.0
+            )
+        );
+        assert_eq!(encoded.metadata.r#type, MetricType::Histogram as i32);
+    }
+
+    #[tokio::test]
+    async fn sends_snappy_compressed_write_requests() {
+        let address = next_addr();
+        let (config, cx) = load_sink::<RemoteWriteConfig>(&format!(
+            r#"endpoint = "http://{}/write"
+            auth.strategy = "bearer"
+            auth.token = "secret""#,
+            address
+        ))
+        .unwrap();
+
+        let (rx, trigger, server) = build_test_server(address);
+        tokio::spawn(server);
+
+        let (sink, _) = config.build(cx).await.unwrap();
+        let events = vec![
+            metric(MetricKind::Absolute, MetricValue::Gauge { value: 1.0 }).into(),
+            metric(MetricKind::Absolute, MetricValue::Gauge { value: 2.0 }).into(),
+        ];
+        sink.run(stream::iter(events)).await.unwrap();
+        drop(trigger);
+
+        let requests: Vec<_> = rx.collect().await;
+        assert_eq!(requests.len(), 1);
+        let (parts, body) = &requests[0];
+        assert_eq!(parts.uri.path(), "/write");
+        assert_eq!(parts.headers["content-encoding"], "snappy");
+        assert_eq!(parts.headers["authorization"], "Bearer secret");
+
+        let body = snap::raw::Decoder::new().decompress_vec(body).unwrap();
+        let request = WriteRequest::decode(body.as_slice()).unwrap();
+        assert_eq!(request.timeseries.len(), 2);
+        assert_eq!(request.timeseries[1].samples[0].value, 2.0);
+        assert_eq!(request.metadata.len(), 1);
+        assert_eq!(request.metadata[0].r#type, MetricType::Gauge as i32);
+    }
+}
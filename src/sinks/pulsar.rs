@@ -1,7 +1,7 @@
 use crate::{
     buffers::Acker,
     config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
-    event::Event,
+    event::{finalization::EventStatus, Event},
     sinks::util::encoding::{EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration},
 };
 use futures::{lock::Mutex, FutureExt, TryFutureExt};
@@ -149,16 +149,27 @@ impl Sink for PulsarSink {
     type SinkItem = Event;
     type SinkError = ();
 
-    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
-        let message = encode_event(item, &self.encoding).map_err(|_| ())?;
+    fn start_send(
+        &mut self,
+        mut item: Self::SinkItem,
+    ) -> StartSend<Self::SinkItem, Self::SinkError> {
+        let finalizers = item.take_finalizers();
+        let message = encode_event(item, &self.encoding).map_err(|_| {
+            finalizers.update_status(EventStatus::Failed);
+        })?;
 
         let producer = Arc::clone(&self.producer);
         let fut = async move {
             let mut locked = producer.lock().await;
-            match locked.send(message.clone()).await {
+            let result = match locked.send(message.clone()).await {
                 Ok(fut) => fut.await,
                 Err(e) => Err(e),
-            }
+            };
+            finalizers.update_status(match result {
+                Ok(_) => EventStatus::Delivered,
+                Err(_) => EventStatus::Errored,
+            });
+            result
         };
 
         let seqno = self.seq_head;
@@ -0,0 +1,581 @@
+use crate::{
+    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    event::Event,
+    internal_events::{RedisEventSent, RedisMissingKeys},
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        retries::RetryLogic,
+        BatchConfig, BatchSettings, EncodedEvent, EncodedLength, InFlightLimit, TowerRequestConfig,
+        VecBuffer,
+    },
+    template::{Template, TemplateError},
+};
+use bytes::Bytes;
+use futures::{future::BoxFuture, FutureExt};
+use futures01::{stream::iter_ok, Sink};
+use lazy_static::lazy_static;
+use redis::{aio::ConnectionManager, ErrorKind, RedisError};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    convert::TryFrom,
+    fmt,
+    task::{Context, Poll},
+};
+use tower::Service;
+use tracing_futures::Instrument;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid key template: {}", source))]
+    KeyTemplate { source: TemplateError },
+    #[snafu(display("Invalid Redis URL: {}", source))]
+    InvalidUrl { source: RedisError },
+    #[snafu(display("Could not connect to Redis: {}", source))]
+    Connect { source: RedisError },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RedisSinkConfig {
+    url: String,
+    key: String,
+    #[serde(default)]
+    data_type: RedisDataType,
+    #[serde(default)]
+    list: ListOption,
+    encoding: EncodingConfig<Encoding>,
+    #[serde(default)]
+    batch: BatchConfig,
+    #[serde(default)]
+    request: TowerRequestConfig,
+}
+
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        // A single request at a time keeps the order of the events.
+        in_flight_limit: InFlightLimit::Fixed(1),
+        timeout_secs: Some(30),
+        rate_limit_num: Some(u64::max_value()),
+        ..Default::default()
+    };
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RedisDataType {
+    /// Pushes the events onto a list.
+    #[derivative(Default)]
+    List,
+    /// Publishes the events to a channel.
+    Channel,
+    /// Appends the events to a stream.
+    Stream,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ListOption {
+    #[serde(default)]
+    method: ListMethod,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ListMethod {
+    Lpush,
+    #[derivative(Default)]
+    Rpush,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    #[derivative(Default)]
+    Text,
+    Json,
+}
+
+inventory::submit! {
+    SinkDescription::new::<RedisSinkConfig>("redis")
+}
+
+impl GenerateConfig for RedisSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl SinkConfig for RedisSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let key = Template::try_from(self.key.as_str()).context(KeyTemplate)?;
+        let client = redis::Client::open(self.url.as_str()).context(InvalidUrl)?;
+        let connection = client
+            .get_tokio_connection_manager()
+            .await
+            .context(Connect)?;
+
+        let batch = BatchSettings::default()
+            .events(100)
+            .timeout(1)
+            .parse_config(self.batch)?;
+        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let encoding = self.encoding.clone();
+
+        let service = RedisService {
+            connection: connection.clone(),
+            data_type: self.data_type,
+            method: self.list.method,
+        };
+        let sink = request
+            .batch_sink(
+                RedisRetryLogic,
+                service,
+                VecBuffer::new(batch.size),
+                batch.timeout,
+                cx.acker(),
+            )
+            .sink_map_err(|error| error!(message = "Fatal redis sink error.", %error))
+            .with_flat_map(move |event| {
+                iter_ok(EncodedEvent::from_event(event, |event| {
+                    encode_event(event, &key, &encoding)
+                }))
+            });
+
+        Ok((
+            super::VectorSink::Futures01Sink(Box::new(sink)),
+            healthcheck(connection).boxed(),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "redis"
+    }
+}
+
+async fn healthcheck(mut connection: ConnectionManager) -> crate::Result<()> {
+    redis::cmd("PING")
+        .query_async::<_, ()>(&mut connection)
+        .await
+        .map_err(Into::into)
+}
+
+/// An encoded event, along with the key it's sent to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedisEntry {
+    key: String,
+    value: Bytes,
+}
+
+impl EncodedLength for RedisEntry {
+    fn encoded_length(&self) -> usize {
+        self.key.len() + self.value.len()
+    }
+}
+
+#[derive(Clone)]
+struct RedisService {
+    connection: ConnectionManager,
+    data_type: RedisDataType,
+    method: ListMethod,
+}
+
+impl Service<Vec<RedisEntry>> for RedisService {
+    type Response = ();
+    type Error = RedisError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, entries: Vec<RedisEntry>) -> Self::Future {
+        debug!(
+            message = "Sending events.",
+            events = %entries.len(),
+        );
+
+        let mut connection = self.connection.clone();
+        let pipeline = build_pipeline(&entries, self.data_type, self.method);
+
+        Box::pin(
+            async move {
+                pipeline.query_async::<_, ()>(&mut connection).await?;
+                for entry in entries {
+                    emit!(RedisEventSent {
+                        byte_size: entry.value.len()
+                    });
+                }
+                Ok(())
+            }
+            .instrument(info_span!("request")),
+        )
+    }
+}
+
+impl fmt::Debug for RedisService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisService")
+            .field("data_type", &self.data_type)
+            .field("method", &self.method)
+            .finish()
+    }
+}
+
+/// Builds the commands sending a batch. They run in a transaction, so a
+/// connection failing midway doesn't leave the batch partly applied.
+fn build_pipeline(
+    entries: &[RedisEntry],
+    data_type: RedisDataType,
+    method: ListMethod,
+) -> redis::Pipeline {
+    let mut pipeline = redis::pipe();
+    pipeline.atomic();
+    match data_type {
+        RedisDataType::List => {
+            let command = match method {
+                ListMethod::Lpush => "LPUSH",
+                ListMethod::Rpush => "RPUSH",
+            };
+            // Consecutive entries for the same list are pushed by a single
+            // command, which keeps their order.
+            let mut start = 0;
+            while start < entries.len() {
+                let key = &entries[start].key;
+                let end = entries[start..]
+                    .iter()
+                    .position(|entry| &entry.key != key)
+                    .map_or(entries.len(), |count| start + count);
+                pipeline.cmd(command).arg(key);
+                for entry in &entries[start..end] {
+                    pipeline.arg(&entry.value[..]);
+                }
+                pipeline.ignore();
+                start = end;
+            }
+        }
+        RedisDataType::Channel => {
+            for entry in entries {
+                pipeline
+                    .cmd("PUBLISH")
+                    .arg(&entry.key)
+                    .arg(&entry.value[..])
+                    .ignore();
+            }
+        }
+        RedisDataType::Stream => {
+            for entry in entries {
+                pipeline
+                    .cmd("XADD")
+                    .arg(&entry.key)
+                    .arg("*")
+                    .arg(log_schema().message_key())
+                    .arg(&entry.value[..])
+                    .ignore();
+            }
+        }
+    }
+    pipeline
+}
+
+#[derive(Debug, Clone)]
+struct RedisRetryLogic;
+
+impl RetryLogic for RedisRetryLogic {
+    type Error = RedisError;
+    type Response = ();
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_io_error()
+            || matches!(
+                error.kind(),
+                ErrorKind::BusyLoadingError | ErrorKind::TryAgain
+            )
+    }
+}
+
+fn encode_event(
+    mut event: Event,
+    key: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<RedisEntry> {
+    let key = key
+        .render_string(&event)
+        .map_err(|missing_keys| {
+            emit!(RedisMissingKeys {
+                keys: &missing_keys
+            });
+        })
+        .ok()?;
+
+    encoding.apply_rules(&mut event);
+
+    let log = event.into_log();
+    let value = match encoding.codec() {
+        Encoding::Json => serde_json::to_vec(&log).expect("Error encoding event as json."),
+        Encoding::Text => log
+            .get(log_schema().message_key())
+            .map(|v| v.as_bytes().to_vec())
+            .unwrap_or_default(),
+    };
+
+    Some(RedisEntry {
+        key,
+        value: Bytes::from(value),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisSinkConfig>();
+    }
+
+    fn entry(key: &str, value: &str) -> RedisEntry {
+        RedisEntry {
+            key: key.into(),
+            value: Bytes::from(value.to_owned()),
+        }
+    }
+
+    #[test]
+    fn redis_encode_event_text() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("app", "api");
+
+        let entry = encode_event(
+            event,
+            &Template::try_from("logs:{{ app }}").unwrap(),
+            &Encoding::Text.into(),
+        )
+        .unwrap();
+
+        assert_eq!(entry.key, "logs:api");
+        assert_eq!(&entry.value[..], b"hello world");
+    }
+
+    #[test]
+    fn redis_encode_event_json() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("key", "value");
+
+        let mut encoding: EncodingConfig<_> = Encoding::Json.into();
+        encoding.except_fields = Some(vec!["key".into()]);
+
+        let entry = encode_event(event, &Template::try_from("logs").unwrap(), &encoding).unwrap();
+        let map: BTreeMap<String, String> = serde_json::from_slice(&entry.value[..]).unwrap();
+
+        assert_eq!(map[&log_schema().message_key().to_string()], "hello world");
+        assert!(!map.contains_key("key"));
+    }
+
+    #[test]
+    fn redis_encode_event_missing_key() {
+        let entry = encode_event(
+            Event::from("hello world"),
+            &Template::try_from("logs:{{ app }}").unwrap(),
+            &Encoding::Text.into(),
+        );
+
+        assert_eq!(entry, None);
+    }
+
+    #[test]
+    fn redis_pipeline_groups_list_pushes() {
+        let entries = vec![
+            entry("a", "1"),
+            entry("a", "2"),
+            entry("b", "3"),
+            entry("a", "4"),
+        ];
+        let pipeline = build_pipeline(&entries, RedisDataType::List, ListMethod::Rpush);
+
+        let expected = redis::pipe()
+            .atomic()
+            .cmd("RPUSH")
+            .arg("a")
+            .arg("1")
+            .arg("2")
+            .ignore()
+            .cmd("RPUSH")
+            .arg("b")
+            .arg("3")
+            .ignore()
+            .cmd("RPUSH")
+            .arg("a")
+            .arg("4")
+            .ignore()
+            .get_packed_pipeline();
+        assert_eq!(pipeline.get_packed_pipeline(), expected);
+    }
+
+    #[test]
+    fn redis_pipeline_publishes_and_appends() {
+        let entries = vec![entry("a", "1"), entry("a", "2")];
+
+        let pipeline = build_pipeline(&entries, RedisDataType::Channel, ListMethod::Rpush);
+        let expected = redis::pipe()
+            .atomic()
+            .cmd("PUBLISH")
+            .arg("a")
+            .arg("1")
+            .ignore()
+            .cmd("PUBLISH")
+            .arg("a")
+            .arg("2")
+            .ignore()
+            .get_packed_pipeline();
+        assert_eq!(pipeline.get_packed_pipeline(), expected);
+
+        let pipeline = build_pipeline(&entries[..1], RedisDataType::Stream, ListMethod::Rpush);
+        let expected = redis::pipe()
+            .atomic()
+            .cmd("XADD")
+            .arg("a")
+            .arg("*")
+            .arg(log_schema().message_key())
+            .arg("1")
+            .ignore()
+            .get_packed_pipeline();
+        assert_eq!(pipeline.get_packed_pipeline(), expected);
+    }
+
+    #[test]
+    fn redis_parses_data_type_options() {
+        let config: RedisSinkConfig = toml::from_str(
+            r#"url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            data_type = "list"
+            list.method = "lpush"
+            encoding.codec = "text""#,
+        )
+        .unwrap();
+        assert_eq!(config.data_type, RedisDataType::List);
+        assert_eq!(config.list.method, ListMethod::Lpush);
+
+        let config: RedisSinkConfig = toml::from_str(
+            r#"url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            data_type = "stream"
+            encoding.codec = "text""#,
+        )
+        .unwrap();
+        assert_eq!(config.data_type, RedisDataType::Stream);
+        assert_eq!(config.list.method, ListMethod::Rpush);
+    }
+}
+
+#[cfg(feature = "redis-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::test_util::{random_lines_with_stream, random_string, trace_init};
+    use futures::{compat::Sink01CompatExt, SinkExt, StreamExt};
+    use redis::AsyncCommands;
+
+    const REDIS_URL: &str = "redis://127.0.0.1:6379/0";
+
+    fn config(key: &str, data_type: RedisDataType) -> RedisSinkConfig {
+        RedisSinkConfig {
+            url: REDIS_URL.to_owned(),
+            key: key.to_owned(),
+            data_type,
+            list: ListOption::default(),
+            encoding: Encoding::Text.into(),
+            batch: BatchConfig::default(),
+            request: TowerRequestConfig::default(),
+        }
+    }
+
+    async fn connection() -> redis::aio::Connection {
+        redis::Client::open(REDIS_URL)
+            .unwrap()
+            .get_async_connection()
+            .await
+            .unwrap()
+    }
+
+    async fn send_lines(config: RedisSinkConfig, num_lines: usize) -> Vec<String> {
+        let (sink, healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+        healthcheck.await.expect("Health check failed");
+
+        let (lines, events) = random_lines_with_stream(100, num_lines);
+        let mut events = events.map(Ok);
+        let _ = sink
+            .into_futures01sink()
+            .sink_compat()
+            .send_all(&mut events)
+            .await
+            .unwrap();
+        lines
+    }
+
+    #[tokio::test]
+    async fn redis_sink_list() {
+        trace_init();
+
+        let key = format!("test-list-{}", random_string(10));
+        let lines = send_lines(config(&key, RedisDataType::List), 1_000).await;
+
+        let mut connection = connection().await;
+        let pushed: Vec<String> = connection.lrange(&key, 0, -1).await.unwrap();
+        assert_eq!(pushed, lines);
+    }
+
+    #[tokio::test]
+    async fn redis_sink_channel() {
+        trace_init();
+
+        let key = format!("test-channel-{}", random_string(10));
+        let mut pubsub = connection().await.into_pubsub();
+        pubsub.subscribe(&key).await.unwrap();
+
+        let lines = send_lines(config(&key, RedisDataType::Channel), 100).await;
+
+        let published = pubsub
+            .on_message()
+            .take(lines.len())
+            .map(|message| message.get_payload::<String>().unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(published, lines);
+    }
+
+    #[tokio::test]
+    async fn redis_sink_stream() {
+        trace_init();
+
+        let key = format!("test-stream-{}", random_string(10));
+        let lines = send_lines(config(&key, RedisDataType::Stream), 1_000).await;
+
+        let mut connection = connection().await;
+        let reply: redis::streams::StreamRangeReply = connection.xrange_all(&key).await.unwrap();
+        let appended = reply
+            .ids
+            .iter()
+            .map(|entry| entry.get::<String>(log_schema().message_key()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(appended, lines);
+    }
+}
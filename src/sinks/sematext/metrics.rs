@@ -7,14 +7,14 @@ use crate::{
     sinks::influxdb::{encode_timestamp, encode_uri, influx_line_protocol, Field, ProtocolVersion},
     sinks::util::{
         http::{HttpBatchService, HttpRetryLogic},
-        BatchConfig, BatchSettings, MetricBuffer, TowerRequestConfig,
+        BatchConfig, BatchSettings, EncodedEvent, MetricBuffer, TowerRequestConfig,
     },
     sinks::{Healthcheck, HealthcheckError, VectorSink},
     vector_version, Result,
 };
 use futures::future::{ready, BoxFuture};
 use futures::FutureExt;
-use futures01::Sink;
+use futures01::{stream::iter_ok, Sink};
 use http::{StatusCode, Uri};
 use hyper::{Body, Request};
 use lazy_static::lazy_static;
@@ -153,7 +153,8 @@ impl SematextMetricsService {
                 batch.timeout,
                 cx.acker(),
             )
-            .sink_map_err(|error| error!(message = "Fatal sematext metrics sink error.", %error));
+            .sink_map_err(|error| error!(message = "Fatal sematext metrics sink error.", %error))
+            .with_flat_map(|event| iter_ok(Some(EncodedEvent::new(event))));
 
         Ok(VectorSink::Futures01Sink(Box::new(sink)))
     }
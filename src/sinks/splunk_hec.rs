@@ -93,6 +93,7 @@ impl SinkConfig for HecSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         validate_host(&self.endpoint)?;
+        self.compression.check_gzip_only("Splunk HEC")?;
 
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(1u64))
@@ -5,7 +5,9 @@ use crate::{
     event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
     event::Event,
     internal_events::StatsdInvalidMetricReceived,
-    sinks::util::{encode_namespace, BatchConfig, BatchSettings, BatchSink, Buffer, Compression},
+    sinks::util::{
+        encode_namespace, BatchConfig, BatchSettings, BatchSink, Buffer, Compression, EncodedEvent,
+    },
     sinks::util::{
         tcp::TcpSinkConfig,
         udp::{UdpService, UdpSinkConfig},
@@ -110,7 +112,9 @@ impl SinkConfig for StatsdSinkConfig {
                 )
                 .sink_map_err(|error| error!(message = "Fatal statsd sink error.", %error))
                 .with_flat_map(move |event| {
-                    stream::iter_ok(encode_event(event, default_namespace.as_deref()))
+                    stream::iter_ok(
+                        encode_event(event, default_namespace.as_deref()).map(EncodedEvent::new),
+                    )
                 });
 
                 Ok((
@@ -7,8 +7,8 @@ use crate::{
     metrics::{self, capture_metrics, get_controller},
     sinks::{
         util::{
-            retries::RetryLogic, BatchSettings, EncodedLength, InFlightLimit, TowerRequestConfig,
-            VecBuffer,
+            retries::RetryLogic, BatchSettings, EncodedEvent, EncodedLength, InFlightLimit,
+            TowerRequestConfig, VecBuffer,
         },
         Healthcheck, VectorSink,
     },
@@ -25,7 +25,7 @@ use futures::{
     future::{self, pending, BoxFuture},
     FutureExt,
 };
-use futures01::Sink;
+use futures01::{stream::iter_ok, Sink};
 use rand::{thread_rng, Rng};
 use rand_distr::Exp1;
 use serde::{Deserialize, Serialize};
@@ -172,6 +172,7 @@ impl SinkConfig for TestConfig {
         );
         *self.controller_stats.lock().unwrap() = stats;
 
+        let sink = sink.with_flat_map(|event| iter_ok(Some(EncodedEvent::new(event))));
         Ok((VectorSink::Futures01Sink(Box::new(sink)), healthcheck))
     }
 
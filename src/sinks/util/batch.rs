@@ -1,3 +1,8 @@
+use super::{retries::ItemOutcome, EncodedEvent};
+use crate::{
+    buffers::Rejection,
+    event::finalization::{EventFinalizers, EventStatus},
+};
 use derivative::Derivative;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
@@ -189,6 +194,13 @@ pub trait Batch: Sized {
     fn finish(self) -> Self::Output;
     fn num_items(&self) -> usize;
 
+    /// Finish the batch, failing when its items can't be encoded. Batch
+    /// sinks finish their batches through this, so batches that only encode
+    /// their items once finished override it.
+    fn try_finish(self) -> crate::Result<Self::Output> {
+        Ok(self.finish())
+    }
+
     /// Replace the current batch with a fresh one, returning the old one.
     fn fresh_replace(&mut self) -> Self
     where
@@ -199,10 +211,13 @@ pub trait Batch: Sized {
     }
 }
 
-#[derive(Clone, Debug)]
+/// Wraps a batch, keeping track of whether it filled up and of the
+/// finalizers of the events pushed into it.
+#[derive(Debug)]
 pub struct StatefulBatch<B> {
     inner: B,
     was_full: bool,
+    finalizers: BatchFinalizers,
 }
 
 impl<B> From<B> for StatefulBatch<B> {
@@ -210,61 +225,112 @@ impl<B> From<B> for StatefulBatch<B> {
         Self {
             inner,
             was_full: false,
+            finalizers: Default::default(),
         }
     }
 }
 
-impl<B> StatefulBatch<B> {
+impl<B> StatefulBatch<B>
+where
+    B: Batch,
+{
     pub fn was_full(&self) -> bool {
         self.was_full
     }
 
-    pub fn into_inner(self) -> B {
-        self.inner
+    pub fn push(&mut self, item: EncodedEvent<B::Input>) -> PushResult<EncodedEvent<B::Input>> {
+        if self.was_full {
+            return PushResult::Overflow(item);
+        }
+
+        let EncodedEvent { item, finalizers } = item;
+        match self.inner.push(item) {
+            PushResult::Ok(full) => {
+                self.was_full = full;
+                self.finalizers.0.push(finalizers);
+                PushResult::Ok(full)
+            }
+            PushResult::Overflow(item) => {
+                self.was_full = true;
+                PushResult::Overflow(EncodedEvent { item, finalizers })
+            }
+        }
     }
-}
 
-impl<B> Batch for StatefulBatch<B>
-where
-    B: Batch,
-{
-    type Input = B::Input;
-    type Output = B::Output;
+    pub fn is_empty(&self) -> bool {
+        !self.was_full && self.inner.is_empty()
+    }
 
-    fn get_settings_defaults(
-        config: BatchConfig,
-        defaults: BatchSettings<Self>,
-    ) -> Result<BatchSettings<Self>, BatchError> {
-        Ok(B::get_settings_defaults(config, defaults.into())?.into())
+    pub fn fresh(&self) -> Self {
+        self.inner.fresh().into()
     }
 
-    fn push(&mut self, item: Self::Input) -> PushResult<Self::Input> {
-        if self.was_full {
-            PushResult::Overflow(item)
-        } else {
-            let result = self.inner.push(item);
-            self.was_full =
-                matches!(result, PushResult::Overflow(_)) || matches!(result, PushResult::Ok(true));
-            result
-        }
+    /// Replace the current batch with a fresh one, returning the old one.
+    pub fn fresh_replace(&mut self) -> Self {
+        let fresh = self.fresh();
+        std::mem::replace(self, fresh)
     }
 
-    fn is_empty(&self) -> bool {
-        !self.was_full && self.inner.is_empty()
+    /// Finishes the batch, handing back the finalizers of its events
+    /// along with it. If the batch can't be finished, its finalizers are
+    /// dropped, marking its events as errored.
+    pub fn finish(self) -> crate::Result<(B::Output, BatchFinalizers)> {
+        let output = self.inner.try_finish()?;
+        Ok((output, self.finalizers))
     }
 
-    fn fresh(&self) -> Self {
-        Self {
-            inner: self.inner.fresh(),
-            was_full: false,
+    pub fn num_items(&self) -> usize {
+        self.inner.num_items()
+    }
+}
+
+/// The finalizers of the events in a batch, one entry per item. Unless they
+/// are resolved with the status of the request the batch was sent in, for
+/// example because the sink shut down first, they are marked as errored once
+/// dropped.
+#[derive(Debug, Default)]
+pub struct BatchFinalizers(Vec<EventFinalizers>);
+
+impl BatchFinalizers {
+    pub fn resolve(mut self, status: EventStatus) {
+        for finalizers in std::mem::take(&mut self.0) {
+            finalizers.update_status(status);
         }
     }
 
-    fn finish(self) -> Self::Output {
-        self.inner.finish()
+    /// Resolves the finalizers as failed, sending their events to the sink's
+    /// dead-letter output if it has one.
+    pub fn reject(mut self, rejection: &Rejection) {
+        for finalizers in std::mem::take(&mut self.0) {
+            finalizers.reject(rejection);
+        }
     }
 
-    fn num_items(&self) -> usize {
-        self.inner.num_items()
+    /// Resolves the finalizers of each item with its own outcome. If the
+    /// outcomes don't line up with the items, all of them are errored.
+    pub fn resolve_items(mut self, outcomes: &[ItemOutcome]) {
+        if outcomes.len() != self.0.len() {
+            error!(
+                message = "Response items don't match the request.",
+                items = self.0.len(),
+                outcomes = outcomes.len(),
+            );
+            return;
+        }
+        for (finalizers, outcome) in std::mem::take(&mut self.0).into_iter().zip(outcomes) {
+            match outcome {
+                ItemOutcome::Delivered => finalizers.update_status(EventStatus::Delivered),
+                ItemOutcome::Errored(_) => finalizers.update_status(EventStatus::Errored),
+                ItemOutcome::Rejected(rejection) => finalizers.reject(rejection),
+            }
+        }
+    }
+}
+
+impl Drop for BatchFinalizers {
+    fn drop(&mut self) {
+        for finalizers in &self.0 {
+            finalizers.update_status(EventStatus::Errored);
+        }
     }
 }
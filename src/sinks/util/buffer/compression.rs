@@ -1,17 +1,30 @@
+use flate2::write::GzEncoder;
 use serde::{de, ser};
-use std::fmt;
+use std::{
+    fmt,
+    io::{self, Write},
+};
 
 pub const GZIP_NONE: usize = 0;
 pub const GZIP_FAST: usize = 1;
 pub const GZIP_DEFAULT: usize = 6;
 pub const GZIP_BEST: usize = 9;
 
+pub const ZSTD_FAST: usize = 1;
+pub const ZSTD_DEFAULT: usize = 3;
+pub const ZSTD_BEST: usize = 19;
+pub const ZSTD_MAX: usize = 21;
+
 #[derive(Debug, Derivative, Copy, Clone, Eq, PartialEq)]
 #[derivative(Default)]
 pub enum Compression {
     #[derivative(Default)]
     None,
     Gzip(Option<usize>),
+    Zstd(Option<usize>),
+    /// Snappy in its framing format, so the output can be streamed.
+    Snappy,
+    Lz4,
 }
 
 impl Compression {
@@ -19,10 +32,38 @@ impl Compression {
         Compression::Gzip(None)
     }
 
+    pub const fn zstd_default() -> Compression {
+        Compression::Zstd(None)
+    }
+
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip(_) => "gzip",
+            Self::Zstd(_) => "zstd",
+            Self::Snappy => "snappy",
+            Self::Lz4 => "lz4",
+        }
+    }
+
+    /// Fails for the codecs other than `gzip`, which `service` does not
+    /// accept as a `Content-Encoding`.
+    pub fn check_gzip_only(self, service: &str) -> crate::Result<()> {
+        match self {
+            Self::None | Self::Gzip(_) => Ok(()),
+            compression => Err(format!(
+                "The `{}` compression is not supported by {}, use `gzip` or `none`.",
+                compression.algorithm(),
+                service
+            )
+            .into()),
+        }
+    }
+
     pub fn content_encoding(&self) -> Option<&'static str> {
         match self {
             Self::None => None,
-            Self::Gzip(_) => Some("gzip"),
+            compression => Some(compression.algorithm()),
         }
     }
 
@@ -30,23 +71,129 @@ impl Compression {
         match self {
             Self::None => "log",
             Self::Gzip(_) => "log.gz",
+            Self::Zstd(_) => "log.zst",
+            Self::Snappy => "log.sz",
+            Self::Lz4 => "log.lz4",
         }
     }
 }
 
 #[cfg(feature = "rusoto_core")]
-impl From<Compression> for rusoto_core::encoding::ContentEncoding {
-    fn from(compression: Compression) -> Self {
+impl std::convert::TryFrom<Compression> for rusoto_core::encoding::ContentEncoding {
+    type Error = crate::Error;
+
+    fn try_from(compression: Compression) -> Result<Self, Self::Error> {
         match compression {
-            Compression::None => rusoto_core::encoding::ContentEncoding::Identity,
+            Compression::None => Ok(rusoto_core::encoding::ContentEncoding::Identity),
             Compression::Gzip(level) => {
                 let level = level.unwrap_or(GZIP_DEFAULT);
-                rusoto_core::encoding::ContentEncoding::Gzip(None, level as u32)
+                Ok(rusoto_core::encoding::ContentEncoding::Gzip(
+                    None,
+                    level as u32,
+                ))
             }
+            compression => Err(format!(
+                "The `{}` compression is not supported by AWS, use `gzip` or `none`.",
+                compression.algorithm()
+            )
+            .into()),
         }
     }
 }
 
+/// A writer compressing everything written to it with a `Compression`
+/// before passing it on to the inner writer.
+pub enum Encoder<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+    Snappy(Box<snap::write::FrameEncoder<W>>),
+    Lz4(lz4::Encoder<W>),
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn new(writer: W, compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
+            Compression::None => Encoder::Plain(writer),
+            Compression::Gzip(level) => {
+                let level = level.unwrap_or(GZIP_FAST);
+                Encoder::Gzip(GzEncoder::new(
+                    writer,
+                    flate2::Compression::new(level as u32),
+                ))
+            }
+            Compression::Zstd(level) => {
+                let level = level.unwrap_or(ZSTD_DEFAULT);
+                Encoder::Zstd(zstd::stream::write::Encoder::new(writer, level as i32)?)
+            }
+            Compression::Snappy => {
+                Encoder::Snappy(Box::new(snap::write::FrameEncoder::new(writer)))
+            }
+            Compression::Lz4 => Encoder::Lz4(lz4::EncoderBuilder::new().build(writer)?),
+        })
+    }
+
+    /// Writes out the end of the compressed stream and returns the inner
+    /// writer.
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Encoder::Plain(writer) => Ok(writer),
+            Encoder::Gzip(encoder) => encoder.finish(),
+            Encoder::Zstd(encoder) => encoder.finish(),
+            Encoder::Snappy(encoder) => encoder
+                .into_inner()
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string())),
+            Encoder::Lz4(encoder) => {
+                let (writer, result) = encoder.finish();
+                result.map(|_| writer)
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Encoder::Plain(writer) => writer.write(buf),
+            Encoder::Gzip(encoder) => encoder.write(buf),
+            Encoder::Zstd(encoder) => encoder.write(buf),
+            Encoder::Snappy(encoder) => encoder.write(buf),
+            Encoder::Lz4(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::Plain(writer) => writer.flush(),
+            Encoder::Gzip(encoder) => encoder.flush(),
+            Encoder::Zstd(encoder) => encoder.flush(),
+            Encoder::Snappy(encoder) => encoder.flush(),
+            Encoder::Lz4(encoder) => encoder.flush(),
+        }
+    }
+}
+
+impl<W: Write> fmt::Debug for Encoder<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let algorithm = match self {
+            Encoder::Plain(_) => "none",
+            Encoder::Gzip(_) => "gzip",
+            Encoder::Zstd(_) => "zstd",
+            Encoder::Snappy(_) => "snappy",
+            Encoder::Lz4(_) => "lz4",
+        };
+        f.debug_tuple("Encoder").field(&algorithm).finish()
+    }
+}
+
+/// A compression level is either a number or a name, such as `best`.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Level {
+    Number(usize),
+    Name(String),
+}
+
 impl<'de> de::Deserialize<'de> for Compression {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -68,6 +215,9 @@ impl<'de> de::Deserialize<'de> for Compression {
                 match s {
                     "none" => Ok(Compression::None),
                     "gzip" => Ok(Compression::gzip_default()),
+                    "zstd" => Ok(Compression::zstd_default()),
+                    "snappy" => Ok(Compression::Snappy),
+                    "lz4" => Ok(Compression::Lz4),
                     _ => Err(de::Error::invalid_value(de::Unexpected::Str(s), &self)),
                 }
             }
@@ -91,9 +241,9 @@ impl<'de> de::Deserialize<'de> for Compression {
                             if level.is_some() {
                                 return Err(de::Error::duplicate_field("level"));
                             }
-                            level = Some(match map.next_value::<usize>() {
-                                Ok(value) => value.to_string(),
-                                Err(_) => map.next_value::<&str>()?.to_string(),
+                            level = Some(match map.next_value::<Level>()? {
+                                Level::Number(value) => value.to_string(),
+                                Level::Name(name) => name,
                             });
                         }
                         _ => return Err(de::Error::unknown_field(key, &["algorithm", "level"])),
@@ -101,39 +251,76 @@ impl<'de> de::Deserialize<'de> for Compression {
                 }
 
                 match algorithm.ok_or_else(|| de::Error::missing_field("algorithm"))? {
-                    "none" => match level {
-                        Some(_) => Err(de::Error::unknown_field("level", &["algorithm"])),
-                        None => Ok(Compression::None),
-                    },
+                    "none" => without_level(level, Compression::None),
                     "gzip" => Ok(Compression::Gzip(match level {
-                        Some(level) => Some(match level.as_str() {
-                            "none" => GZIP_NONE,
-                            "fast" => GZIP_FAST,
-                            "default" => GZIP_DEFAULT,
-                            "best" => GZIP_BEST,
-                            value => match value.parse::<usize>() {
-                                Ok(level) if level <= 9 => level,
-                                Ok(level) => {
-                                    return Err(de::Error::invalid_value(
-                                        de::Unexpected::Unsigned(level as u64),
-                                        &self,
-                                    ))
-                                }
-                                Err(_) => {
-                                    return Err(de::Error::invalid_value(
-                                        de::Unexpected::Str(value),
-                                        &self,
-                                    ))
-                                }
-                            },
-                        }),
+                        Some(level) => Some(parse_level(
+                            &level,
+                            &[
+                                ("none", GZIP_NONE),
+                                ("fast", GZIP_FAST),
+                                ("default", GZIP_DEFAULT),
+                                ("best", GZIP_BEST),
+                            ],
+                            GZIP_BEST,
+                            &self,
+                        )?),
                         None => None,
                     })),
-                    algorithm => Err(de::Error::unknown_variant(algorithm, &["none", "gzip"])),
+                    "zstd" => Ok(Compression::Zstd(match level {
+                        Some(level) => Some(parse_level(
+                            &level,
+                            &[
+                                ("fast", ZSTD_FAST),
+                                ("default", ZSTD_DEFAULT),
+                                ("best", ZSTD_BEST),
+                            ],
+                            ZSTD_MAX,
+                            &self,
+                        )?),
+                        None => None,
+                    })),
+                    "snappy" => without_level(level, Compression::Snappy),
+                    "lz4" => without_level(level, Compression::Lz4),
+                    algorithm => Err(de::Error::unknown_variant(
+                        algorithm,
+                        &["none", "gzip", "zstd", "snappy", "lz4"],
+                    )),
                 }
             }
         }
 
+        fn without_level<E: de::Error>(
+            level: Option<String>,
+            compression: Compression,
+        ) -> Result<Compression, E> {
+            match level {
+                Some(_) => Err(de::Error::unknown_field("level", &["algorithm"])),
+                None => Ok(compression),
+            }
+        }
+
+        fn parse_level<E: de::Error>(
+            level: &str,
+            names: &[(&str, usize)],
+            max: usize,
+            expected: &dyn de::Expected,
+        ) -> Result<usize, E> {
+            if let Some((_, value)) = names.iter().find(|(name, _)| *name == level) {
+                return Ok(*value);
+            }
+            match level.parse::<usize>() {
+                Ok(value) if value <= max => Ok(value),
+                Ok(value) => Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(value as u64),
+                    expected,
+                )),
+                Err(_) => Err(de::Error::invalid_value(
+                    de::Unexpected::Str(level),
+                    expected,
+                )),
+            }
+        }
+
         deserializer.deserialize_any(StringOrMap)
     }
 }
@@ -158,7 +345,131 @@ impl ser::Serialize for Compression {
                     level => map.serialize_entry("level", &level)?,
                 };
             }
+            Compression::Zstd(level) => {
+                map.serialize_entry("algorithm", "zstd")?;
+                match level.unwrap_or(ZSTD_DEFAULT) {
+                    ZSTD_FAST => map.serialize_entry("level", "fast")?,
+                    ZSTD_DEFAULT => map.serialize_entry("level", "default")?,
+                    ZSTD_BEST => map.serialize_entry("level", "best")?,
+                    level => map.serialize_entry("level", &level)?,
+                };
+            }
+            compression => map.serialize_entry("algorithm", compression.algorithm())?,
         };
         map.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[derive(serde::Deserialize, serde::Serialize)]
+    struct Config {
+        compression: Compression,
+    }
+
+    fn parse(text: &str) -> Result<Compression, toml::de::Error> {
+        toml::from_str::<Config>(text).map(|config| config.compression)
+    }
+
+    #[test]
+    fn deserializes_algorithms_and_levels() {
+        assert_eq!(
+            parse(r#"compression = "zstd""#).unwrap(),
+            Compression::zstd_default()
+        );
+        assert_eq!(
+            parse(r#"compression = "snappy""#).unwrap(),
+            Compression::Snappy
+        );
+        assert_eq!(parse(r#"compression = "lz4""#).unwrap(), Compression::Lz4);
+        assert_eq!(
+            parse(r#"compression = { algorithm = "zstd", level = 9 }"#).unwrap(),
+            Compression::Zstd(Some(9))
+        );
+        assert_eq!(
+            parse(r#"compression = { algorithm = "zstd", level = "best" }"#).unwrap(),
+            Compression::Zstd(Some(ZSTD_BEST))
+        );
+        assert_eq!(
+            parse(r#"compression = { algorithm = "gzip", level = "fast" }"#).unwrap(),
+            Compression::Gzip(Some(GZIP_FAST))
+        );
+
+        assert!(parse(r#"compression = { algorithm = "zstd", level = 22 }"#).is_err());
+        assert!(parse(r#"compression = { algorithm = "gzip", level = 10 }"#).is_err());
+        assert!(parse(r#"compression = { algorithm = "lz4", level = 1 }"#).is_err());
+        assert!(parse(r#"compression = "brotli""#).is_err());
+    }
+
+    #[test]
+    fn serializes_round_trip() {
+        for compression in &[
+            Compression::None,
+            Compression::Gzip(Some(GZIP_BEST)),
+            Compression::Zstd(Some(ZSTD_BEST)),
+            Compression::Zstd(Some(12)),
+            Compression::Snappy,
+            Compression::Lz4,
+        ] {
+            let text = toml::to_string(&Config {
+                compression: *compression,
+            })
+            .unwrap();
+            assert_eq!(parse(&text).unwrap(), *compression);
+        }
+    }
+
+    fn decode(compression: Compression, data: &[u8]) -> Vec<u8> {
+        let mut decoded = Vec::new();
+        match compression {
+            Compression::None => decoded.extend_from_slice(data),
+            Compression::Gzip(_) => {
+                flate2::read::GzDecoder::new(data)
+                    .read_to_end(&mut decoded)
+                    .unwrap();
+            }
+            Compression::Zstd(_) => {
+                zstd::stream::read::Decoder::new(data)
+                    .unwrap()
+                    .read_to_end(&mut decoded)
+                    .unwrap();
+            }
+            Compression::Snappy => {
+                snap::read::FrameDecoder::new(data)
+                    .read_to_end(&mut decoded)
+                    .unwrap();
+            }
+            Compression::Lz4 => {
+                lz4::Decoder::new(data)
+                    .unwrap()
+                    .read_to_end(&mut decoded)
+                    .unwrap();
+            }
+        }
+        decoded
+    }
+
+    #[test]
+    fn encoder_round_trip() {
+        let input = b"It's going down, I'm yelling timber\n".repeat(100);
+        for &compression in &[
+            Compression::None,
+            Compression::gzip_default(),
+            Compression::zstd_default(),
+            Compression::Snappy,
+            Compression::Lz4,
+        ] {
+            let mut encoder = Encoder::new(Vec::new(), compression).unwrap();
+            encoder.write_all(&input).unwrap();
+            let output = encoder.finish().unwrap();
+
+            if compression != Compression::None {
+                assert!(output.len() < input.len());
+            }
+            assert_eq!(decode(compression, &output), input);
+        }
+    }
+}
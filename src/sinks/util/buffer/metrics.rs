@@ -286,7 +286,7 @@ fn compress_distribution(values: Vec<f64>, sample_rates: Vec<u32>) -> (Vec<f64>,
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::sinks::util::BatchSink;
+    use crate::sinks::util::{BatchSink, EncodedEvent};
     use crate::{
         buffers::Acker,
         event::metric::{Metric, MetricValue, StatisticKind},
@@ -332,7 +332,8 @@ mod test {
             MetricBuffer::new(batch_size),
             Duration::from_secs(0),
             acker,
-        );
+        )
+        .with(|event| Ok::<_, crate::Error>(EncodedEvent::new(event)));
 
         (buffered, sent_requests)
     }
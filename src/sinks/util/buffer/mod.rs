@@ -1,13 +1,15 @@
 use super::batch::{
     err_event_too_large, Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult,
 };
-use flate2::write::GzEncoder;
+use compression::Encoder;
 use std::io::Write;
 
 pub mod compression;
 pub mod json;
 pub mod loki;
 pub mod metrics;
+#[cfg(feature = "parquet")]
+pub mod parquet;
 pub mod partition;
 pub mod vec;
 
@@ -16,32 +18,18 @@ pub use partition::{Partition, PartitionBuffer, PartitionInnerBuffer};
 
 #[derive(Debug)]
 pub struct Buffer {
-    inner: InnerBuffer,
+    inner: Encoder<Vec<u8>>,
     num_items: usize,
     num_bytes: usize,
     settings: BatchSize<Self>,
     compression: Compression,
 }
 
-#[derive(Debug)]
-pub enum InnerBuffer {
-    Plain(Vec<u8>),
-    Gzip(GzEncoder<Vec<u8>>),
-}
-
 impl Buffer {
     pub fn new(settings: BatchSize<Self>, compression: Compression) -> Self {
         let buffer = Vec::with_capacity(settings.bytes);
-        let inner = match compression {
-            Compression::None => InnerBuffer::Plain(buffer),
-            Compression::Gzip(level) => {
-                let level = level.unwrap_or(GZIP_FAST);
-                InnerBuffer::Gzip(GzEncoder::new(
-                    buffer,
-                    flate2::Compression::new(level as u32),
-                ))
-            }
-        };
+        let inner =
+            Encoder::new(buffer, compression).expect("Creating an encoder over a Vec can't fail");
         Self {
             inner,
             num_items: 0,
@@ -53,21 +41,13 @@ impl Buffer {
 
     pub fn push(&mut self, input: &[u8]) {
         self.num_items += 1;
-        match &mut self.inner {
-            InnerBuffer::Plain(inner) => {
-                inner.extend_from_slice(input);
-            }
-            InnerBuffer::Gzip(inner) => {
-                inner.write_all(input).unwrap();
-            }
-        }
+        self.inner.write_all(input).unwrap();
     }
 
     pub fn is_empty(&self) -> bool {
-        match &self.inner {
-            InnerBuffer::Plain(inner) => inner.is_empty(),
-            InnerBuffer::Gzip(inner) => inner.get_ref().is_empty(),
-        }
+        // The compressed encoders may hold on to their input before writing
+        // anything out, so the output can't tell whether we've been pushed to.
+        self.num_items == 0
     }
 }
 
@@ -111,12 +91,9 @@ impl Batch for Buffer {
     }
 
     fn finish(self) -> Self::Output {
-        match self.inner {
-            InnerBuffer::Plain(inner) => inner,
-            InnerBuffer::Gzip(inner) => inner
-                .finish()
-                .expect("This can't fail because the inner writer is a Vec"),
-        }
+        self.inner
+            .finish()
+            .expect("This can't fail because the inner writer is a Vec")
     }
 
     fn num_items(&self) -> usize {
@@ -126,9 +103,9 @@ impl Batch for Buffer {
 
 #[cfg(test)]
 mod test {
-    use super::{Buffer, Compression};
+    use super::{Batch, Buffer, Compression, PushResult};
     use crate::buffers::Acker;
-    use crate::sinks::util::{BatchSettings, BatchSink};
+    use crate::sinks::util::{BatchSettings, BatchSink, EncodedEvent};
     use futures::{compat::Future01CompatExt, future};
     use futures01::Sink;
     use std::{
@@ -166,7 +143,7 @@ mod test {
 
         let _ = buffered
             .sink_map_err(drop)
-            .send_all(futures01::stream::iter_ok(input))
+            .send_all(futures01::stream::iter_ok(input.map(EncodedEvent::new)))
             .compat()
             .await
             .unwrap();
@@ -195,4 +172,30 @@ mod test {
         .take(100_000)
         .flatten()));
     }
+
+    #[test]
+    fn zstd() {
+        let batch_size = BatchSettings::default().bytes(100_000).events(1_000).size;
+        let mut buffer = Buffer::new(batch_size, Compression::zstd_default());
+        assert!(Batch::is_empty(&buffer));
+
+        let line = b"It's going down, I'm yelling timber\n".to_vec();
+        for _ in 0..100 {
+            assert!(matches!(
+                Batch::push(&mut buffer, line.clone()),
+                PushResult::Ok(false)
+            ));
+        }
+        assert!(!Batch::is_empty(&buffer));
+
+        let output = buffer.finish();
+        assert!(output.len() < line.len() * 100);
+
+        let mut decompressed = Vec::new();
+        zstd::stream::read::Decoder::new(output.as_slice())
+            .unwrap()
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, line.repeat(100));
+    }
 }
@@ -0,0 +1,422 @@
+use super::{
+    super::batch::{
+        err_event_too_large, Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult,
+    },
+    Compression,
+};
+use crate::event::{LogEvent, Value};
+use chrono::DateTime;
+use parquet::{
+    basic::{Compression as ParquetCompression, LogicalType, Repetition, Type as PhysicalType},
+    column::writer::ColumnWriter,
+    data_type::ByteArray,
+    errors::ParquetError,
+    file::{
+        properties::WriterProperties,
+        writer::{FileWriter, InMemoryWriteableCursor, SerializedFileWriter},
+    },
+    schema::types::Type,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+
+pub const CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+pub const EXTENSION: &str = "parquet";
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetConfig {
+    /// The columns of the written files, by field path. When empty, the
+    /// columns are inferred from the top-level fields of each batch.
+    #[serde(default)]
+    pub schema: BTreeMap<String, ParquetType>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+}
+
+impl ParquetType {
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bytes(_) | Value::Map(_) | Value::Array(_) => Some(Self::String),
+            Value::Integer(_) => Some(Self::Integer),
+            Value::Float(_) => Some(Self::Float),
+            Value::Boolean(_) => Some(Self::Boolean),
+            Value::Timestamp(_) => Some(Self::Timestamp),
+            Value::Null => None,
+        }
+    }
+
+    /// The type of a column holding values of both types.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Integer, Self::Float) | (Self::Float, Self::Integer) => Self::Float,
+            _ => Self::String,
+        }
+    }
+
+    fn column(self, name: &str) -> Result<Type, ParquetError> {
+        let (physical_type, logical_type) = match self {
+            Self::String => (PhysicalType::BYTE_ARRAY, LogicalType::UTF8),
+            Self::Integer => (PhysicalType::INT64, LogicalType::NONE),
+            Self::Float => (PhysicalType::DOUBLE, LogicalType::NONE),
+            Self::Boolean => (PhysicalType::BOOLEAN, LogicalType::NONE),
+            Self::Timestamp => (PhysicalType::INT64, LogicalType::TIMESTAMP_MILLIS),
+        };
+        Type::primitive_type_builder(name, physical_type)
+            .with_repetition(Repetition::OPTIONAL)
+            .with_logical_type(logical_type)
+            .build()
+    }
+}
+
+/// A `batch` implementation writing log events as a Parquet file, with a
+/// single row group holding the whole batch.
+#[derive(Debug)]
+pub struct ParquetBuffer {
+    rows: Vec<LogEvent>,
+    num_bytes: usize,
+    settings: BatchSize<Self>,
+    schema: Arc<BTreeMap<String, ParquetType>>,
+    compression: Compression,
+}
+
+impl ParquetBuffer {
+    pub fn new(
+        settings: BatchSize<Self>,
+        config: &ParquetConfig,
+        compression: Compression,
+    ) -> Self {
+        Self {
+            rows: Vec::new(),
+            num_bytes: 0,
+            settings,
+            schema: Arc::new(config.schema.clone()),
+            compression,
+        }
+    }
+
+    fn write(&self) -> Result<Vec<u8>, ParquetError> {
+        // Declared columns are field paths, while inferred ones are
+        // top-level field names which may contain dots.
+        let (schema, flat) = if self.schema.is_empty() {
+            (infer_schema(&self.rows), true)
+        } else {
+            (self.schema.as_ref().clone(), false)
+        };
+
+        let mut fields = schema
+            .iter()
+            .map(|(name, column_type)| column_type.column(name).map(Arc::new))
+            .collect::<Result<Vec<_>, _>>()?;
+        let message = Type::group_type_builder("vector")
+            .with_fields(&mut fields)
+            .build()?;
+        let properties = WriterProperties::builder()
+            .set_compression(parquet_compression(self.compression))
+            .build();
+
+        let cursor = InMemoryWriteableCursor::default();
+        let mut writer =
+            SerializedFileWriter::new(cursor.clone(), Arc::new(message), Arc::new(properties))?;
+        let mut row_group = writer.next_row_group()?;
+        // Columns are written in the order of the schema's fields.
+        for (name, column_type) in &schema {
+            let mut column = match row_group.next_column()? {
+                Some(column) => column,
+                None => break,
+            };
+            let rows = &self.rows;
+            match &mut column {
+                ColumnWriter::ByteArrayColumnWriter(typed) => {
+                    let (values, def_levels) = column_values(rows, name, flat, to_string);
+                    typed.write_batch(&values, Some(&def_levels[..]), None)?;
+                }
+                ColumnWriter::Int64ColumnWriter(typed) => {
+                    let convert = match column_type {
+                        ParquetType::Timestamp => to_timestamp_millis,
+                        _ => to_integer,
+                    };
+                    let (values, def_levels) = column_values(rows, name, flat, convert);
+                    typed.write_batch(&values, Some(&def_levels[..]), None)?;
+                }
+                ColumnWriter::DoubleColumnWriter(typed) => {
+                    let (values, def_levels) = column_values(rows, name, flat, to_float);
+                    typed.write_batch(&values, Some(&def_levels[..]), None)?;
+                }
+                ColumnWriter::BoolColumnWriter(typed) => {
+                    let (values, def_levels) = column_values(rows, name, flat, to_boolean);
+                    typed.write_batch(&values, Some(&def_levels[..]), None)?;
+                }
+                _ => unreachable!("Parquet columns are only created with the types above."),
+            }
+            row_group.close_column(column)?;
+        }
+        writer.close_row_group(row_group)?;
+        writer.close()?;
+
+        Ok(cursor.data())
+    }
+}
+
+impl Batch for ParquetBuffer {
+    type Input = LogEvent;
+    type Output = Vec<u8>;
+
+    fn get_settings_defaults(
+        config: BatchConfig,
+        defaults: BatchSettings<Self>,
+    ) -> Result<BatchSettings<Self>, BatchError> {
+        Ok(config
+            .use_size_as_bytes()?
+            .get_settings_or_default(defaults))
+    }
+
+    fn push(&mut self, item: Self::Input) -> PushResult<Self::Input> {
+        let item_bytes = item
+            .as_map()
+            .iter()
+            .map(|(name, value)| name.len() + value_size(value))
+            .sum::<usize>();
+        let new_bytes = self.num_bytes + item_bytes;
+        if self.is_empty() && item_bytes > self.settings.bytes {
+            err_event_too_large(item_bytes)
+        } else if self.rows.len() >= self.settings.events || new_bytes > self.settings.bytes {
+            PushResult::Overflow(item)
+        } else {
+            self.rows.push(item);
+            self.num_bytes = new_bytes;
+            PushResult::Ok(
+                self.rows.len() >= self.settings.events || new_bytes >= self.settings.bytes,
+            )
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn fresh(&self) -> Self {
+        Self {
+            rows: Vec::new(),
+            num_bytes: 0,
+            settings: self.settings,
+            schema: Arc::clone(&self.schema),
+            compression: self.compression,
+        }
+    }
+
+    fn finish(self) -> Self::Output {
+        self.write()
+            .expect("Parquet batches are finished with `try_finish` by batch sinks.")
+    }
+
+    fn try_finish(self) -> crate::Result<Self::Output> {
+        Ok(self.write()?)
+    }
+
+    fn num_items(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+fn infer_schema(rows: &[LogEvent]) -> BTreeMap<String, ParquetType> {
+    let mut schema = BTreeMap::new();
+    for row in rows {
+        for (name, value) in row.as_map() {
+            if let Some(value_type) = ParquetType::of(value) {
+                schema
+                    .entry(name.clone())
+                    .and_modify(|column_type: &mut ParquetType| {
+                        *column_type = column_type.merge(value_type)
+                    })
+                    .or_insert(value_type);
+            }
+        }
+    }
+    schema
+}
+
+/// Collects the values of a column and their definition levels, missing
+/// values and values that can't be converted to the column's type being
+/// written as nulls.
+fn column_values<T>(
+    rows: &[LogEvent],
+    name: &str,
+    flat: bool,
+    convert: fn(&Value) -> Option<T>,
+) -> (Vec<T>, Vec<i16>) {
+    let mut values = Vec::with_capacity(rows.len());
+    let mut def_levels = Vec::with_capacity(rows.len());
+    for row in rows {
+        let value = if flat {
+            row.get_flat(name)
+        } else {
+            row.get(name)
+        };
+        match value.and_then(convert) {
+            Some(value) => {
+                values.push(value);
+                def_levels.push(1);
+            }
+            None => def_levels.push(0),
+        }
+    }
+    (values, def_levels)
+}
+
+fn to_string(value: &Value) -> Option<ByteArray> {
+    match value {
+        Value::Null => None,
+        Value::Bytes(bytes) => Some(bytes.to_vec().into()),
+        value => Some(value.to_string_lossy().into_bytes().into()),
+    }
+}
+
+fn to_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(integer) => Some(*integer),
+        _ => None,
+    }
+}
+
+fn to_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(float) => Some(*float),
+        Value::Integer(integer) => Some(*integer as f64),
+        _ => None,
+    }
+}
+
+fn to_boolean(value: &Value) -> Option<bool> {
+    match value {
+        Value::Boolean(boolean) => Some(*boolean),
+        _ => None,
+    }
+}
+
+fn to_timestamp_millis(value: &Value) -> Option<i64> {
+    match value {
+        Value::Timestamp(timestamp) => Some(timestamp.timestamp_millis()),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map(|timestamp| timestamp.timestamp_millis()),
+        _ => None,
+    }
+}
+
+fn parquet_compression(compression: Compression) -> ParquetCompression {
+    match compression {
+        Compression::None => ParquetCompression::UNCOMPRESSED,
+        Compression::Gzip(_) => ParquetCompression::GZIP,
+        Compression::Zstd(_) => ParquetCompression::ZSTD,
+        Compression::Snappy => ParquetCompression::SNAPPY,
+        Compression::Lz4 => ParquetCompression::LZ4,
+    }
+}
+
+/// An estimate of the size of a value once written to a column.
+fn value_size(value: &Value) -> usize {
+    match value {
+        Value::Bytes(bytes) => bytes.len(),
+        Value::Integer(_) | Value::Float(_) | Value::Timestamp(_) => 8,
+        Value::Boolean(_) => 1,
+        Value::Map(map) => map
+            .iter()
+            .map(|(name, value)| name.len() + value_size(value))
+            .sum(),
+        Value::Array(array) => array.iter().map(value_size).sum(),
+        Value::Null => 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn log(fields: Vec<(&str, Value)>) -> LogEvent {
+        fields.into_iter().collect()
+    }
+
+    #[test]
+    fn infers_schema_from_batch() {
+        let rows = vec![
+            log(vec![
+                ("message", "first".into()),
+                ("status", 200.into()),
+                ("duration", 1.into()),
+                ("ok", true.into()),
+                ("missing", Value::Null),
+            ]),
+            log(vec![
+                ("message", "second".into()),
+                ("status", "unknown".into()),
+                ("duration", 1.5.into()),
+                ("time", Utc.timestamp(1_600_000_000, 0).into()),
+                ("http.method", "GET".into()),
+            ]),
+        ];
+
+        let schema = infer_schema(&rows);
+        assert_eq!(
+            schema.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("duration".into(), ParquetType::Float),
+                ("http.method".into(), ParquetType::String),
+                ("message".into(), ParquetType::String),
+                ("ok".into(), ParquetType::Boolean),
+                ("status".into(), ParquetType::String),
+                ("time".into(), ParquetType::Timestamp),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_values_to_column_types() {
+        let rows = vec![
+            log(vec![("time", "2020-09-13T12:26:40Z".into())]),
+            log(vec![("time", Utc.timestamp(1_600_000_000, 0).into())]),
+            log(vec![("time", "not a time".into())]),
+            log(vec![]),
+        ];
+
+        let (values, def_levels) = column_values(&rows, "time", false, to_timestamp_millis);
+        assert_eq!(values, vec![1_600_000_000_000, 1_600_000_000_000]);
+        assert_eq!(def_levels, vec![1, 1, 0, 0]);
+
+        let nested = log(vec![("tags", vec!["a", "b"].into())]);
+        assert_eq!(
+            to_string(nested.get("tags").unwrap()),
+            Some(ByteArray::from(r#"["a","b"]"#))
+        );
+    }
+
+    #[test]
+    fn writes_parquet_files() {
+        let settings = BatchSettings::default().bytes(1_000_000).events(2);
+        let mut buffer = ParquetBuffer::new(
+            settings.size,
+            &ParquetConfig::default(),
+            Compression::gzip_default(),
+        );
+
+        let row = log(vec![("message", "hello".into()), ("count", 1.into())]);
+        assert_eq!(buffer.push(row.clone()), PushResult::Ok(false));
+        assert_eq!(buffer.push(row.clone()), PushResult::Ok(true));
+        assert_eq!(buffer.push(row.clone()), PushResult::Overflow(row));
+
+        let file = buffer.try_finish().unwrap();
+        assert!(file.starts_with(b"PAR1"));
+        assert!(file.ends_with(b"PAR1"));
+    }
+}
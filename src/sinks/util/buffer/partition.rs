@@ -61,6 +61,12 @@ where
         PartitionInnerBuffer { inner, key }
     }
 
+    fn try_finish(mut self) -> crate::Result<Self::Output> {
+        let key = self.key.take().unwrap();
+        let inner = self.inner.try_finish()?;
+        Ok(PartitionInnerBuffer { inner, key })
+    }
+
     fn num_items(&self) -> usize {
         self.inner.num_items()
     }
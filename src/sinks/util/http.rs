@@ -1,6 +1,6 @@
 use super::{
     retries::{RetryAction, RetryLogic},
-    sink, Batch, TowerBatchedSink, TowerRequestSettings,
+    sink, Batch, EncodedEvent, TowerBatchedSink, TowerRequestSettings,
 };
 use crate::{buffers::Acker, event::Event, http::HttpClient};
 use bytes::{Buf, Bytes};
@@ -55,7 +55,7 @@ where
     // An empty slot is needed to buffer an item where we encoded it but
     // the inner sink is applying back pressure. This trick is used in the `WithFlatMap`
     // sink combinator. https://docs.rs/futures/0.1.29/src/futures/sink/with_flat_map.rs.html#20
-    slot: Option<B::Input>,
+    slot: Option<EncodedEvent<B::Input>>,
 }
 
 impl<T, B> BatchedHttpSink<T, B, HttpRetryLogic>
@@ -136,7 +136,8 @@ where
         }
         assert!(self.slot.is_none(), "poll_complete did not clear slot");
 
-        if let Some(item) = self.sink.encode_event(item) {
+        let sink = &self.sink;
+        if let Some(item) = EncodedEvent::from_event(item, |event| sink.encode_event(event)) {
             self.slot = Some(item);
             self.poll_complete()?;
         }
@@ -213,6 +214,10 @@ impl<T: fmt::Debug> sink::Response for http::Response<T> {
     fn is_successful(&self) -> bool {
         self.status().is_success()
     }
+
+    fn status_code(&self) -> Option<u16> {
+        Some(self.status().as_u16())
+    }
 }
 
 #[derive(Debug, Default, Clone)]
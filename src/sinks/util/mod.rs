@@ -16,7 +16,10 @@ pub mod udp;
 pub mod unix;
 pub mod uri;
 
-use crate::event::Event;
+use crate::event::{
+    finalization::{EventFinalizers, EventStatus},
+    Event,
+};
 use bytes::Bytes;
 use encoding::{EncodingConfig, EncodingConfiguration};
 use serde::{Deserialize, Serialize};
@@ -44,6 +47,47 @@ enum SinkBuildError {
     MissingPort,
 }
 
+/// An item a sink encoded from an event, along with the finalizers of that
+/// event. The finalizers travel with the item until the sink knows whether
+/// it was delivered; an item that is dropped on the way resolves them as
+/// dropped.
+#[derive(Debug)]
+pub struct EncodedEvent<I> {
+    pub item: I,
+    pub finalizers: EventFinalizers,
+}
+
+impl<I> EncodedEvent<I> {
+    /// An item without finalizers, such as one that was not encoded from an event.
+    pub fn new(item: I) -> Self {
+        Self {
+            item,
+            finalizers: Default::default(),
+        }
+    }
+
+    /// Encodes `event` with `encode`, carrying its finalizers over to the result.
+    /// An event that fails to encode is marked as failed, so sources waiting on
+    /// it do not take it as delivered.
+    pub fn from_event(mut event: Event, encode: impl FnOnce(Event) -> Option<I>) -> Option<Self> {
+        let finalizers = event.take_finalizers();
+        match encode(event) {
+            Some(item) => Some(Self { item, finalizers }),
+            None => {
+                finalizers.update_status(EventStatus::Failed);
+                None
+            }
+        }
+    }
+
+    pub fn map<T>(self, f: impl FnOnce(I) -> T) -> EncodedEvent<T> {
+        EncodedEvent {
+            item: f(self.item),
+            finalizers: self.finalizers,
+        }
+    }
+}
+
 /**
  * Enum representing different ways to encode events as they are sent into a Sink.
  */
@@ -93,3 +137,23 @@ pub fn encode_namespace<'a>(
         .map(|namespace| format!("{}{}{}", namespace, delimiter, name))
         .unwrap_or_else(|| name.into_owned())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::EncodedEvent;
+    use crate::event::{
+        finalization::{BatchNotifier, BatchStatus},
+        Event,
+    };
+
+    #[tokio::test]
+    async fn encoded_event_fails_events_that_do_not_encode() {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let mut event = Event::from("hello");
+        event.add_batch_notifier(&batch);
+        drop(batch);
+
+        assert!(EncodedEvent::<()>::from_event(event, |_| None).is_none());
+        assert_eq!(receiver.await, BatchStatus::Failed);
+    }
+}
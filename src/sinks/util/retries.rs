@@ -1,14 +1,15 @@
-use crate::Error;
-use futures::FutureExt;
+use super::sink::Response;
+use crate::{buffers::Rejection, Error};
+use futures::{future::BoxFuture, FutureExt};
 use std::{
-    cmp,
+    cmp, fmt,
     future::Future,
     pin::Pin,
     task::{Context, Poll},
     time::Duration,
 };
 use tokio::time::{delay_for, Delay};
-use tower::{retry::Policy, timeout::error::Elapsed};
+use tower::{layer::Layer, retry::Policy, timeout::error::Elapsed, Service};
 
 pub enum RetryAction {
     /// Indicate that this request should be retried with a reason
@@ -29,8 +30,42 @@ pub trait RetryLogic: Clone + Send + Sync + 'static {
         // Treat the default as the request is successful
         RetryAction::Successful
     }
+
+    /// The outcome of each item of the request, in order, for responses that
+    /// report on the items individually, such as bulk APIs. By default the
+    /// outcome of the response applies to all items.
+    fn item_outcomes(&self, _response: &Self::Response) -> Option<Vec<ItemOutcome>> {
+        None
+    }
+}
+
+/// The outcome of a single item of a request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemOutcome {
+    Delivered,
+    /// The item hit a transient error, and retries were exhausted.
+    Errored(String),
+    Rejected(Rejection),
 }
 
+/// A response that reported on the items of its request individually, some
+/// of which were not delivered.
+#[derive(Debug)]
+pub struct PartialRejection(pub Vec<ItemOutcome>);
+
+impl fmt::Display for PartialRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failed = self
+            .0
+            .iter()
+            .filter(|outcome| **outcome != ItemOutcome::Delivered)
+            .count();
+        write!(f, "{} of {} items not delivered", failed, self.0.len())
+    }
+}
+
+impl std::error::Error for PartialRejection {}
+
 #[derive(Debug, Clone)]
 pub struct FixedRetryPolicy<L> {
     remaining_attempts: usize,
@@ -164,6 +199,82 @@ impl<L: RetryLogic> Future for RetryPolicyFuture<L> {
     }
 }
 
+/// Fails requests whose final response `L` decided not to retry with a
+/// `Rejection`, so that the events in them can be routed to the sink's
+/// dead-letter output. Responses that report on their items individually
+/// fail with a `PartialRejection` if any item was not delivered.
+#[derive(Debug, Clone)]
+pub struct RejectNonRetriableLayer<L> {
+    logic: L,
+}
+
+impl<L> RejectNonRetriableLayer<L> {
+    pub fn new(logic: L) -> Self {
+        Self { logic }
+    }
+}
+
+impl<S, L: Clone> Layer<S> for RejectNonRetriableLayer<L> {
+    type Service = RejectNonRetriable<S, L>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RejectNonRetriable {
+            inner,
+            logic: self.logic.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RejectNonRetriable<S, L> {
+    inner: S,
+    logic: L,
+}
+
+impl<S, L, Request> Service<Request> for RejectNonRetriable<S, L>
+where
+    S: Service<Request>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    S::Response: Response + Send + 'static,
+    L: RetryLogic<Response = S::Response>,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let logic = self.logic.clone();
+        let response = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = response.await.map_err(Into::into)?;
+            if let Some(outcomes) = logic.item_outcomes(&response) {
+                return if outcomes
+                    .iter()
+                    .all(|outcome| *outcome == ItemOutcome::Delivered)
+                {
+                    Ok(response)
+                } else {
+                    Err(PartialRejection(outcomes).into())
+                };
+            }
+            match logic.should_retry_response(&response) {
+                RetryAction::DontRetry(reason) => Err(Rejection {
+                    reason,
+                    status: response.status_code(),
+                }
+                .into()),
+                _ => Ok(response),
+            }
+        })
+    }
+}
+
 impl RetryAction {
     pub fn is_retryable(&self) -> bool {
         matches!(self, RetryAction::Retry(_))
@@ -299,6 +410,47 @@ mod tests {
         assert_eq!(Duration::from_secs(10), policy.backoff());
     }
 
+    #[tokio::test]
+    async fn non_retriable_response_is_rejected() {
+        trace_init();
+
+        let layer = RejectNonRetriableLayer::new(SvcRetryLogic);
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        assert_ready_ok!(svc.poll_ready());
+        let fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_response("world");
+        assert_eq!(fut.await.unwrap(), "world");
+
+        assert_ready_ok!(svc.poll_ready());
+        let mut fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_response("bad");
+        let error = assert_ready_err!(fut.poll());
+        assert_eq!(
+            error.downcast_ref::<Rejection>(),
+            Some(&Rejection {
+                reason: "bad response".into(),
+                status: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn partially_delivered_response_is_rejected_per_item() {
+        trace_init();
+
+        let layer = RejectNonRetriableLayer::new(SvcRetryLogic);
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        assert_ready_ok!(svc.poll_ready());
+        let mut fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_response("partial");
+        let error = assert_ready_err!(fut.poll());
+        let outcomes = &error.downcast_ref::<PartialRejection>().unwrap().0;
+        assert_eq!(outcomes[0], ItemOutcome::Delivered);
+        assert!(matches!(outcomes[1], ItemOutcome::Rejected(_)));
+    }
+
     #[derive(Debug, Clone)]
     struct SvcRetryLogic;
 
@@ -309,6 +461,26 @@ mod tests {
         fn is_retriable_error(&self, error: &Self::Error) -> bool {
             error.0
         }
+
+        fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+            match *response {
+                "bad" => RetryAction::DontRetry("bad response".into()),
+                _ => RetryAction::Successful,
+            }
+        }
+
+        fn item_outcomes(&self, response: &Self::Response) -> Option<Vec<ItemOutcome>> {
+            match *response {
+                "partial" => Some(vec![
+                    ItemOutcome::Delivered,
+                    ItemOutcome::Rejected(Rejection {
+                        reason: "bad item".into(),
+                        status: None,
+                    }),
+                ]),
+                _ => None,
+            }
+        }
     }
 
     #[derive(Debug)]
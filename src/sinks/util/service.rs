@@ -1,7 +1,7 @@
 use super::auto_concurrency::{
     AutoConcurrencyLimit, AutoConcurrencyLimitLayer, AutoConcurrencySettings,
 };
-use super::retries::{FixedRetryPolicy, RetryLogic};
+use super::retries::{FixedRetryPolicy, RejectNonRetriable, RejectNonRetriableLayer, RetryLogic};
 use super::sink::Response;
 use super::{Batch, BatchSink};
 use crate::buffers::Acker;
@@ -23,7 +23,10 @@ use tower::{
     Service, ServiceBuilder,
 };
 
-pub type Svc<S, L> = RateLimit<Retry<FixedRetryPolicy<L>, AutoConcurrencyLimit<Timeout<S>, L>>>;
+pub type Svc<S, L> = RejectNonRetriable<
+    RateLimit<Retry<FixedRetryPolicy<L>, AutoConcurrencyLimit<Timeout<S>, L>>>,
+    L,
+>;
 pub type TowerBatchedSink<S, B, L, Request> = BatchSink<Svc<S, L>, B, Request>;
 
 pub trait ServiceBuilderExt<L> {
@@ -270,6 +273,7 @@ impl TowerRequestSettings {
     {
         let policy = self.retry_policy(retry_logic.clone());
         ServiceBuilder::new()
+            .layer(RejectNonRetriableLayer::new(retry_logic.clone()))
             .rate_limit(self.rate_limit_num, self.rate_limit_duration)
             .retry(policy)
             .layer(AutoConcurrencyLimitLayer::new(
@@ -33,10 +33,15 @@
 //! the sink to allow it to notify the consumer that the request has succeeded.
 
 use super::{
-    batch::{Batch, PushResult, StatefulBatch},
+    batch::{Batch, BatchFinalizers, PushResult, StatefulBatch},
     buffer::partition::Partition,
+    retries::{ItemOutcome, PartialRejection},
+    EncodedEvent,
+};
+use crate::{
+    buffers::{Acker, Rejection},
+    event::{finalization::EventStatus, Event},
 };
-use crate::{buffers::Acker, Event};
 use async_trait::async_trait;
 use futures::{
     compat::{Compat, Future01CompatExt},
@@ -143,7 +148,7 @@ where
     S::Response: Response,
     B: Batch<Output = Request>,
 {
-    type SinkItem = B::Input;
+    type SinkItem = EncodedEvent<B::Input>;
     type SinkError = crate::Error;
 
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
@@ -200,10 +205,13 @@ where
                     let batch = self.batch.fresh_replace();
 
                     let batch_size = batch.num_items();
-                    let request = batch.finish();
-
-                    let fut = self.service.call(request, batch_size).compat();
-                    tokio::spawn(fut);
+                    match batch.finish() {
+                        Ok((request, finalizers)) => {
+                            let fut = self.service.call(request, batch_size, finalizers).compat();
+                            tokio::spawn(fut);
+                        }
+                        Err(error) => self.service.discard(batch_size, error),
+                    }
 
                     // Remove the now-sent batch's linger timeout
                     self.linger = None;
@@ -302,7 +310,7 @@ pub struct PartitionBatchSink<B, S, K, Request> {
     partitions: HashMap<K, StatefulBatch<B>>,
     timeout: Duration,
     closing: bool,
-    sending: VecDeque<B>,
+    sending: VecDeque<StatefulBatch<B>>,
     lingers: FuturesUnordered<LingerDelay<K>>,
     linger_handles: HashMap<K, oneshot::Sender<K>>,
 }
@@ -362,21 +370,28 @@ where
         self.lingers.push(Box::new(fut));
     }
 
-    fn poll_send(&mut self, batch: B) -> Poll<(), crate::Error> {
+    fn poll_send(&mut self, batch: StatefulBatch<B>) -> Poll<(), crate::Error> {
         if let Async::NotReady = self.service.poll_ready()? {
             self.sending.push_front(batch);
         } else {
             let batch_size = batch.num_items();
-            let batch = batch.finish();
-
-            let fut = self.service.call(batch, batch_size).compat();
-            tokio::spawn(fut);
+            match batch.finish() {
+                Ok((batch, finalizers)) => {
+                    let fut = self.service.call(batch, batch_size, finalizers).compat();
+                    tokio::spawn(fut);
+                }
+                Err(error) => self.service.discard(batch_size, error),
+            }
         }
 
         self.service.poll_complete()
     }
 
-    fn handle_full_batch(&mut self, item: B::Input, partition: &K) -> FullBatchResult<B::Input> {
+    fn handle_full_batch(
+        &mut self,
+        item: EncodedEvent<B::Input>,
+        partition: &K,
+    ) -> FullBatchResult<EncodedEvent<B::Input>> {
         trace!("Batch full; driving service to completion.");
         if let Err(error) = self.poll_complete() {
             return FullBatchResult::Result(Err(error));
@@ -424,7 +439,7 @@ where
     S::Error: Into<crate::Error> + Send + 'static,
     S::Response: Response,
 {
-    type SinkItem = B::Input;
+    type SinkItem = EncodedEvent<B::Input>;
     type SinkError = crate::Error;
 
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
@@ -448,7 +463,7 @@ where
             }
         }
 
-        let partition = item.partition();
+        let partition = item.item.partition();
 
         let item = match self.partitions.get_mut(&partition) {
             Some(batch) => {
@@ -551,7 +566,7 @@ where
         }
 
         for batch in ready_batches.into_iter().chain(partitions) {
-            self.poll_send(batch.into_inner())?;
+            self.poll_send(batch)?;
         }
 
         // If we still have an inflight partition then
@@ -628,6 +643,7 @@ where
         &mut self,
         req: Request,
         batch_size: usize,
+        finalizers: BatchFinalizers,
     ) -> Box<dyn Future<Item = (), Error = ()> + Send + 'static> {
         let seqno = self.seq_head;
         self.seq_head += 1;
@@ -644,18 +660,43 @@ where
             in_flight_requests = self.in_flight.len()
         );
         let response = Compat::new(Box::pin(self.service.call(req)))
-            .map_err(Into::into)
+            .map_err(Into::<crate::Error>::into)
             .then(move |result| {
-                match result {
+                let outcome = match result {
                     Ok(response) if response.is_successful() => {
                         trace!(message = "Response successful.", response = ?response);
+                        Outcome::Status(EventStatus::Delivered)
                     }
                     Ok(response) => {
                         error!(message = "Response wasn't successful.", response = ?response);
+                        let status = response.status_code();
+                        let reason = match status {
+                            Some(status) => format!("response status: {}", status),
+                            None => "unsuccessful response".into(),
+                        };
+                        Outcome::Rejected(Rejection { reason, status })
                     }
-                    Err(error) => {
-                        error!(message = "Request failed.", %error,);
-                    }
+                    Err(error) => match error.downcast::<Rejection>() {
+                        Ok(rejection) => {
+                            error!(message = "Request rejected.", reason = %rejection.reason);
+                            Outcome::Rejected(*rejection)
+                        }
+                        Err(error) => match error.downcast::<PartialRejection>() {
+                            Ok(rejection) => {
+                                error!(message = "Request partially rejected.", %rejection);
+                                Outcome::Items(rejection.0)
+                            }
+                            Err(error) => {
+                                error!(message = "Request failed.", %error,);
+                                Outcome::Status(EventStatus::Errored)
+                            }
+                        },
+                    },
+                };
+                match outcome {
+                    Outcome::Status(status) => finalizers.resolve(status),
+                    Outcome::Rejected(rejection) => finalizers.reject(&rejection),
+                    Outcome::Items(outcomes) => finalizers.resolve_items(&outcomes),
                 }
 
                 // If the rx end is dropped we still completed
@@ -670,6 +711,19 @@ where
         Box::new(response)
     }
 
+    /// Drops a batch that could not be finished. It is still acked in
+    /// sequence with the requests around it, as its events won't be sent.
+    fn discard(&mut self, batch_size: usize, error: crate::Error) {
+        error!(message = "Failed to finish batch; dropping it.", %error, rate_limit_secs = 30);
+
+        let seqno = self.seq_head;
+        self.seq_head += 1;
+
+        let (tx, rx) = oneshot::channel();
+        let _ = tx.send((seqno, batch_size));
+        self.in_flight.push(rx);
+    }
+
     fn poll_complete(&mut self) -> Poll<(), crate::Error> {
         loop {
             match self.in_flight.poll() {
@@ -707,12 +761,25 @@ where
     }
 }
 
+/// How a request ended, as far as the events in it are concerned.
+#[derive(Debug)]
+enum Outcome {
+    Status(EventStatus),
+    Rejected(Rejection),
+    Items(Vec<ItemOutcome>),
+}
+
 // === Response ===
 
 pub trait Response: fmt::Debug {
     fn is_successful(&self) -> bool {
         true
     }
+
+    /// The status of the response, such as an HTTP status code.
+    fn status_code(&self) -> Option<u16> {
+        None
+    }
 }
 
 impl Response for () {}
@@ -723,7 +790,12 @@ mod tests {
     use super::*;
     use crate::{
         buffers::Acker,
-        sinks::util::{buffer::partition::Partition, BatchSettings, EncodedLength, VecBuffer},
+        event::finalization::{BatchNotifier, BatchStatus},
+        sinks::util::{
+            batch::{BatchConfig, BatchError},
+            buffer::partition::Partition,
+            BatchSettings, EncodedLength, VecBuffer,
+        },
     };
     use bytes::Bytes;
     use futures::{compat::Future01CompatExt, future};
@@ -765,7 +837,7 @@ mod tests {
 
         let _ = buffered
             .sink_map_err(drop)
-            .send_all(futures01::stream::iter_ok(0..22))
+            .send_all(futures01::stream::iter_ok((0..22).map(EncodedEvent::new)))
             .compat()
             .await
             .unwrap();
@@ -802,9 +874,9 @@ mod tests {
 
             let mut sink = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
 
-            assert!(sink.start_send(0).unwrap().is_ready());
-            assert!(sink.start_send(1).unwrap().is_ready());
-            assert!(sink.start_send(2).unwrap().is_ready());
+            assert!(sink.start_send(EncodedEvent::new(0)).unwrap().is_ready());
+            assert!(sink.start_send(EncodedEvent::new(1)).unwrap().is_ready());
+            assert!(sink.start_send(EncodedEvent::new(2)).unwrap().is_ready());
             yield_now().await;
 
             assert_eq!(ack_counter.load(Relaxed), 0);
@@ -822,9 +894,9 @@ mod tests {
 
             assert_eq!(ack_counter.load(Relaxed), 3);
 
-            assert!(sink.start_send(3).unwrap().is_ready());
-            assert!(sink.start_send(4).unwrap().is_ready());
-            assert!(sink.start_send(5).unwrap().is_ready());
+            assert!(sink.start_send(EncodedEvent::new(3)).unwrap().is_ready());
+            assert!(sink.start_send(EncodedEvent::new(4)).unwrap().is_ready());
+            assert!(sink.start_send(EncodedEvent::new(5)).unwrap().is_ready());
 
             advance_time(Duration::from_secs(2)).await;
 
@@ -847,6 +919,185 @@ mod tests {
         .await;
     }
 
+    fn with_notifier(item: usize, batch: &Arc<BatchNotifier>) -> EncodedEvent<usize> {
+        let mut event = Event::from("").with_batch_notifier(batch);
+        EncodedEvent {
+            item,
+            finalizers: event.take_finalizers(),
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_sink_resolves_finalizers_with_their_request() {
+        let (acker, _) = Acker::new_for_testing();
+
+        let svc = tower::service_fn(|req: Vec<usize>| {
+            if req.contains(&2) {
+                future::err("bad")
+            } else {
+                future::ok("good")
+            }
+        });
+        let batch = BatchSettings::default().bytes(9999).events(1);
+        let buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+
+        let (dropped, dropped_receiver) = BatchNotifier::new_with_receiver();
+        let (delivered, delivered_receiver) = BatchNotifier::new_with_receiver();
+        let (errored, errored_receiver) = BatchNotifier::new_with_receiver();
+        // The first event never makes it into a batch, as if the sink had
+        // failed to encode it, which must not shift the others' statuses.
+        drop(with_notifier(0, &dropped));
+        let items = vec![with_notifier(1, &delivered), with_notifier(2, &errored)];
+        drop((dropped, delivered, errored));
+
+        let _ = buffered
+            .sink_map_err(drop)
+            .send_all(futures01::stream::iter_ok(items))
+            .compat()
+            .await
+            .unwrap();
+
+        assert_eq!(dropped_receiver.await, BatchStatus::Delivered);
+        assert_eq!(delivered_receiver.await, BatchStatus::Delivered);
+        assert_eq!(errored_receiver.await, BatchStatus::Errored);
+    }
+
+    #[tokio::test]
+    async fn batch_sink_resolves_finalizers_with_their_item() {
+        let (acker, _) = Acker::new_for_testing();
+
+        let svc = tower::service_fn(|_: Vec<usize>| {
+            future::err::<(), _>(PartialRejection(vec![
+                ItemOutcome::Delivered,
+                ItemOutcome::Rejected(Rejection {
+                    reason: "bad item".into(),
+                    status: Some(400),
+                }),
+            ]))
+        });
+        let batch = BatchSettings::default().bytes(9999).events(2);
+        let buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+
+        let (delivered, delivered_receiver) = BatchNotifier::new_with_receiver();
+        let (failed, failed_receiver) = BatchNotifier::new_with_receiver();
+        let items = vec![with_notifier(1, &delivered), with_notifier(2, &failed)];
+        drop((delivered, failed));
+
+        let _ = buffered
+            .sink_map_err(drop)
+            .send_all(futures01::stream::iter_ok(items))
+            .compat()
+            .await
+            .unwrap();
+
+        assert_eq!(delivered_receiver.await, BatchStatus::Delivered);
+        assert_eq!(failed_receiver.await, BatchStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn batch_sink_errors_unsent_finalizers_on_drop() {
+        let (acker, _) = Acker::new_for_testing();
+
+        let svc = tower::service_fn(|_: Vec<usize>| future::ok::<_, std::io::Error>(()));
+        let batch = BatchSettings::default().bytes(9999).events(10);
+        let mut buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        assert!(buffered
+            .start_send(with_notifier(0, &batch))
+            .unwrap()
+            .is_ready());
+        drop((batch, buffered));
+
+        assert_eq!(receiver.await, BatchStatus::Errored);
+    }
+
+    /// A batch of a single item, which fails to finish when that item is odd.
+    #[derive(Debug, Default)]
+    struct OddFailingBuffer(Option<usize>);
+
+    impl Batch for OddFailingBuffer {
+        type Input = usize;
+        type Output = usize;
+
+        fn get_settings_defaults(
+            _config: BatchConfig,
+            defaults: BatchSettings<Self>,
+        ) -> Result<BatchSettings<Self>, BatchError> {
+            Ok(defaults)
+        }
+
+        fn push(&mut self, item: Self::Input) -> PushResult<Self::Input> {
+            match self.0 {
+                Some(_) => PushResult::Overflow(item),
+                None => {
+                    self.0 = Some(item);
+                    PushResult::Ok(true)
+                }
+            }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.0.is_none()
+        }
+
+        fn fresh(&self) -> Self {
+            Self(None)
+        }
+
+        fn finish(self) -> Self::Output {
+            self.0.unwrap()
+        }
+
+        fn try_finish(self) -> crate::Result<Self::Output> {
+            match self.finish() {
+                item if item % 2 == 1 => Err("odd item".into()),
+                item => Ok(item),
+            }
+        }
+
+        fn num_items(&self) -> usize {
+            self.0.iter().count()
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_sink_errors_batches_that_fail_to_finish() {
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let sent_requests = Arc::new(Mutex::new(Vec::new()));
+
+        let svc = tower::service_fn(|req| {
+            let sent_requests = Arc::clone(&sent_requests);
+
+            sent_requests.lock().unwrap().push(req);
+
+            future::ok::<_, std::io::Error>(())
+        });
+        let buffered = BatchSink::new(svc, OddFailingBuffer::default(), TIMEOUT, acker);
+
+        let (delivered, delivered_receiver) = BatchNotifier::new_with_receiver();
+        let (errored, errored_receiver) = BatchNotifier::new_with_receiver();
+        let items = vec![
+            with_notifier(0, &delivered),
+            with_notifier(1, &errored),
+            with_notifier(2, &delivered),
+        ];
+        drop((delivered, errored));
+
+        let _ = buffered
+            .sink_map_err(drop)
+            .send_all(futures01::stream::iter_ok(items))
+            .compat()
+            .await
+            .unwrap();
+
+        assert_eq!(&*sent_requests.lock().unwrap(), &vec![0, 2]);
+        // The dropped batch is still taken out of the buffer.
+        assert_eq!(ack_counter.load(Relaxed), 3);
+        assert_eq!(delivered_receiver.await, BatchStatus::Delivered);
+        assert_eq!(errored_receiver.await, BatchStatus::Errored);
+    }
+
     #[tokio::test]
     async fn batch_sink_buffers_messages_until_limit() {
         let (acker, _) = Acker::new_for_testing();
@@ -864,7 +1115,7 @@ mod tests {
 
         let _ = buffered
             .sink_map_err(drop)
-            .send_all(futures01::stream::iter_ok(0..22))
+            .send_all(futures01::stream::iter_ok((0..22).map(EncodedEvent::new)))
             .compat()
             .await
             .unwrap();
@@ -894,8 +1145,14 @@ mod tests {
         let batch = BatchSettings::default().bytes(9999).events(10);
         let mut buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
 
-        assert!(buffered.start_send(0).unwrap().is_ready());
-        assert!(buffered.start_send(1).unwrap().is_ready());
+        assert!(buffered
+            .start_send(EncodedEvent::new(0))
+            .unwrap()
+            .is_ready());
+        assert!(buffered
+            .start_send(EncodedEvent::new(1))
+            .unwrap()
+            .is_ready());
 
         futures01::future::poll_fn(|| buffered.close())
             .compat()
@@ -921,8 +1178,14 @@ mod tests {
             let batch = BatchSettings::default().bytes(9999).events(10);
             let mut buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
 
-            assert!(buffered.start_send(0).unwrap().is_ready());
-            assert!(buffered.start_send(1).unwrap().is_ready());
+            assert!(buffered
+                .start_send(EncodedEvent::new(0))
+                .unwrap()
+                .is_ready());
+            assert!(buffered
+                .start_send(EncodedEvent::new(1))
+                .unwrap()
+                .is_ready());
 
             // Move clock forward by linger timeout + 1 sec
             advance_time(TIMEOUT + Duration::from_secs(1)).await;
@@ -953,7 +1216,7 @@ mod tests {
 
         let (_buffered, _) = buffered
             .sink_map_err(drop)
-            .send_all(futures01::stream::iter_ok(0..22))
+            .send_all(futures01::stream::iter_ok((0..22).map(EncodedEvent::new)))
             .compat()
             .await
             .unwrap();
@@ -986,7 +1249,9 @@ mod tests {
         let input = vec![Partitions::A, Partitions::B];
         let (_buffered, _) = buffered
             .sink_map_err(drop)
-            .send_all(futures01::stream::iter_ok(input))
+            .send_all(futures01::stream::iter_ok(
+                input.into_iter().map(EncodedEvent::new),
+            ))
             .compat()
             .await
             .unwrap();
@@ -1013,7 +1278,9 @@ mod tests {
         let input = vec![Partitions::A, Partitions::B, Partitions::A, Partitions::B];
         let (_buffered, _) = buffered
             .sink_map_err(drop)
-            .send_all(futures01::stream::iter_ok(input))
+            .send_all(futures01::stream::iter_ok(
+                input.into_iter().map(EncodedEvent::new),
+            ))
             .compat()
             .await
             .unwrap();
@@ -1045,7 +1312,7 @@ mod tests {
             let mut buffered =
                 PartitionBatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
 
-            buffered.start_send(1 as usize).unwrap();
+            buffered.start_send(EncodedEvent::new(1 as usize)).unwrap();
             buffered.poll_complete().unwrap();
 
             advance_time(TIMEOUT + Duration::from_secs(1)).await;
@@ -1079,8 +1346,8 @@ mod tests {
             let mut sink = ServiceSink::new(svc, acker);
 
             // send some initial requests
-            let mut fut1 = sink.call(1, 1);
-            let mut fut2 = sink.call(2, 2);
+            let mut fut1 = sink.call(1, 1, Default::default());
+            let mut fut2 = sink.call(2, 2, Default::default());
 
             assert_eq!(ack_counter.load(Relaxed), 0);
 
@@ -1091,8 +1358,8 @@ mod tests {
             assert_eq!(ack_counter.load(Relaxed), 3);
 
             // send one request that will error and one normal
-            let mut fut3 = sink.call(3, 3); // i will error
-            let mut fut4 = sink.call(4, 4);
+            let mut fut3 = sink.call(3, 3, Default::default()); // i will error
+            let mut fut4 = sink.call(4, 4, Default::default());
 
             // make sure they all "worked"
             assert!(fut3.poll().unwrap().is_ready());
@@ -1,5 +1,7 @@
+use super::EncodedEvent;
 use crate::{
     buffers::Acker,
+    event::finalization::{EventFinalizers, EventStatus},
     internal_events::{SocketEventsSent, SocketMode},
 };
 use bytes::Bytes;
@@ -28,7 +30,8 @@ pub enum ShutdownCheck {
 /// - Call `shutdown_check` on each `poll_flush`, so we can stop sending data if other side disconnected.
 /// - Flush all data on each `poll_ready` if total number of events in queue more than some limit.
 /// - Count event size on each `start_send`.
-/// - Ack all sent events on successful `poll_flush` and `poll_close` or on `Drop`.
+/// - Ack all sent events on successful `poll_flush` and `poll_close` or on `Drop`, marking their
+///   finalizers as delivered only if the flush succeeded.
 #[pin_project(PinnedDrop)]
 pub struct BytesSink<T>
 where
@@ -41,6 +44,7 @@ where
     socket_mode: SocketMode,
     events_total: usize,
     bytes_total: usize,
+    finalizers: EventFinalizers,
 }
 
 impl<T> BytesSink<T>
@@ -58,12 +62,14 @@ where
             shutdown_check: Box::new(shutdown_check),
             events_total: 0,
             bytes_total: 0,
+            finalizers: Default::default(),
             acker,
             socket_mode,
         }
     }
 
-    fn ack(&mut self) {
+    fn ack(&mut self, status: EventStatus) {
+        std::mem::take(&mut self.finalizers).update_status(status);
         if self.events_total > 0 {
             self.acker.ack(self.events_total);
 
@@ -85,11 +91,11 @@ where
     T: AsyncWrite + Unpin,
 {
     fn drop(self: Pin<&mut Self>) {
-        self.get_mut().ack()
+        self.get_mut().ack(EventStatus::Errored)
     }
 }
 
-impl<T> Sink<Bytes> for BytesSink<T>
+impl<T> Sink<EncodedEvent<Bytes>> for BytesSink<T>
 where
     T: AsyncWrite + Unpin,
 {
@@ -105,11 +111,12 @@ where
         self.project().inner.poll_ready(cx)
     }
 
-    fn start_send(self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+    fn start_send(self: Pin<&mut Self>, item: EncodedEvent<Bytes>) -> Result<(), Self::Error> {
         let pinned = self.project();
         *pinned.events_total += 1;
-        *pinned.bytes_total += item.len();
-        pinned.inner.start_send(item)
+        *pinned.bytes_total += item.item.len();
+        pinned.finalizers.merge(item.finalizers);
+        pinned.inner.start_send(item.item)
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -127,13 +134,20 @@ where
         }
 
         let result = ready!(self.as_mut().project().inner.poll_flush(cx));
-        self.as_mut().get_mut().ack();
+        self.as_mut().get_mut().ack(status_of(&result));
         Poll::Ready(result)
     }
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         let result = ready!(self.as_mut().project().inner.poll_close(cx));
-        self.as_mut().get_mut().ack();
+        self.as_mut().get_mut().ack(status_of(&result));
         Poll::Ready(result)
     }
 }
+
+fn status_of<T, E>(result: &Result<T, E>) -> EventStatus {
+    match result {
+        Ok(_) => EventStatus::Delivered,
+        Err(_) => EventStatus::Errored,
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     sinks::{
         util::{
             socket_bytes_sink::{BytesSink, ShutdownCheck},
-            SinkBuildError, StreamSink,
+            EncodedEvent, SinkBuildError, StreamSink,
         },
         Healthcheck, VectorSink,
     },
@@ -193,7 +193,10 @@ impl StreamSink for TcpSink {
         // connection only when we have something to send.
         let encode_event = Arc::clone(&self.encode_event);
         let mut input = input
-            .map(|event| encode_event(event).unwrap_or_else(Bytes::new))
+            .map(|event| {
+                EncodedEvent::from_event(event, |event| encode_event(event))
+                    .unwrap_or_else(|| EncodedEvent::new(Bytes::new()))
+            })
             .peekable();
 
         while Pin::new(&mut input).peek().await.is_some() {
@@ -3,6 +3,7 @@ use crate::{
     buffers::Acker,
     config::SinkContext,
     dns,
+    event::finalization::EventStatus,
     internal_events::{
         SocketEventsSent, SocketMode, UdpSendIncomplete, UdpSocketConnectionEstablished,
         UdpSocketConnectionFailed, UdpSocketError,
@@ -233,21 +234,29 @@ impl StreamSink for UdpSink {
 
         while Pin::new(&mut input).peek().await.is_some() {
             let mut socket = self.connector.connect_backoff().await;
-            while let Some(event) = input.next().await {
+            while let Some(mut event) = input.next().await {
                 self.acker.ack(1);
 
+                let finalizers = event.take_finalizers();
                 let bytes = match (self.encode_event)(event) {
                     Some(bytes) => bytes,
-                    None => continue,
+                    None => {
+                        finalizers.update_status(EventStatus::Failed);
+                        continue;
+                    }
                 };
 
                 match udp_send(&mut socket, &bytes).await {
-                    Ok(()) => emit!(SocketEventsSent {
-                        mode: SocketMode::Udp,
-                        count: 1,
-                        byte_size: bytes.len(),
-                    }),
+                    Ok(()) => {
+                        finalizers.update_status(EventStatus::Delivered);
+                        emit!(SocketEventsSent {
+                            mode: SocketMode::Udp,
+                            count: 1,
+                            byte_size: bytes.len(),
+                        });
+                    }
                     Err(error) => {
+                        finalizers.update_status(EventStatus::Errored);
                         emit!(UdpSocketError { error });
                         break;
                     }
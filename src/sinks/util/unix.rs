@@ -9,7 +9,7 @@ use crate::{
     sinks::{
         util::{
             socket_bytes_sink::{BytesSink, ShutdownCheck},
-            StreamSink,
+            EncodedEvent, StreamSink,
         },
         Healthcheck, VectorSink,
     },
@@ -136,7 +136,10 @@ impl StreamSink for UnixSink {
     async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
         let encode_event = Arc::clone(&self.encode_event);
         let mut input = input
-            .map(|event| encode_event(event).unwrap_or_else(Bytes::new))
+            .map(|event| {
+                EncodedEvent::from_event(event, |event| encode_event(event))
+                    .unwrap_or_else(|| EncodedEvent::new(Bytes::new()))
+            })
             .peekable();
 
         while Pin::new(&mut input).peek().await.is_some() {
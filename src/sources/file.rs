@@ -1,8 +1,13 @@
 use super::util::MultilineConfig;
 use crate::{
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
-    event::Event,
-    internal_events::{FileEventReceived, FileSourceInternalEventsEmitter},
+    event::{
+        finalization::{BatchNotifier, BatchStatus, BatchStatusReceiver},
+        Event,
+    },
+    internal_events::{
+        FileEventDeliveryFailed, FileEventReceived, FileSourceInternalEventsEmitter,
+    },
     line_agg::{self, LineAgg},
     shutdown::ShutdownSignal,
     trace::{current_span, Instrument},
@@ -11,9 +16,10 @@ use crate::{
 use bytes::Bytes;
 use file_source::{
     paths_provider::glob::{Glob, MatchOptions},
-    FileServer, Fingerprinter,
+    Checkpointer, CheckpointsView, FileFingerprint, FilePosition, FileServer, Fingerprinter, Line,
 };
 use futures::{
+    channel::mpsc,
     compat::{Compat, Future01CompatExt},
     future::{FutureExt, TryFutureExt},
     stream::{Stream, StreamExt},
@@ -24,6 +30,7 @@ use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::convert::TryInto;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::task::spawn_blocking;
 
@@ -78,6 +85,9 @@ pub struct FileConfig {
     pub max_read_bytes: usize,
     pub oldest_first: bool,
     pub remove_after: Option<u64>,
+    /// Only checkpoint a line once its event has been delivered by every sink
+    /// it was sent to.
+    pub acknowledgements: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
@@ -134,6 +144,7 @@ impl Default for FileConfig {
             max_read_bytes: 2048,
             oldest_first: false,
             remove_after: None,
+            acknowledgements: false,
         }
     }
 }
@@ -205,14 +216,18 @@ pub fn file_source(
         start_at_beginning: config.start_at_beginning,
         ignore_before,
         max_line_bytes: config.max_line_bytes,
-        data_dir,
         glob_minimum_cooldown,
         fingerprinter: config.fingerprint.clone().into(),
         oldest_first: config.oldest_first,
         remove_after: config.remove_after.map(Duration::from_secs),
         emitter: FileSourceInternalEventsEmitter,
+        acknowledgements: config.acknowledgements,
     };
 
+    let checkpointer = Checkpointer::new(&data_dir);
+    let checkpoints = checkpointer.view();
+    let acknowledgements = config.acknowledgements;
+
     let file_key = config.file_key.clone();
     let host_key = config
         .host_key
@@ -230,10 +245,10 @@ pub fn file_source(
         info!(message = "Starting file server.", include = ?include, exclude = ?exclude);
 
         // sizing here is just a guess
-        let (tx, rx) = futures::channel::mpsc::channel::<Vec<(Bytes, String)>>(2);
+        let (tx, rx) = mpsc::channel::<Vec<Line>>(2);
         let rx = rx.map(futures::stream::iter).flatten();
 
-        let messages: Box<dyn Stream<Item = (Bytes, String)> + Send + std::marker::Unpin> =
+        let messages: Box<dyn Stream<Item = Line> + Send + std::marker::Unpin> =
             if let Some(ref multiline_config) = multiline_config {
                 wrap_with_line_agg(
                     rx,
@@ -251,17 +266,39 @@ pub fn file_source(
                 Box::new(rx)
             };
 
+        let acks = if acknowledgements {
+            let (tx, rx) = mpsc::unbounded();
+            tokio::spawn(update_acked_checkpoints(checkpoints, rx));
+            Some(tx)
+        } else {
+            None
+        };
+
         // Once file server ends this will run until it has finished processing remaining
         // logs in the queue.
         let span = current_span();
         let span2 = span.clone();
-        let messages01 = Compat::new(StreamExt::map(
-            messages,
-            move |(msg, file): (Bytes, String)| {
-                let _enter = span2.enter();
-                Ok::<_, ()>(create_event(msg, file, &host_key, &hostname, &file_key))
-            },
-        ));
+        let messages01 = Compat::new(StreamExt::map(messages, move |line: Line| {
+            let _enter = span2.enter();
+            let mut event = create_event(
+                line.text,
+                line.filename.clone(),
+                &host_key,
+                &hostname,
+                &file_key,
+            );
+            if let Some(acks) = &acks {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                event.add_batch_notifier(&batch);
+                let _ = acks.unbounded_send(PendingCheckpoint {
+                    receiver,
+                    filename: line.filename,
+                    file_id: line.file_id,
+                    offset: line.offset,
+                });
+            }
+            Ok::<_, ()>(event)
+        }));
         tokio::spawn(
             futures01::Stream::forward(messages01, out.sink_map_err(|e| error!(%e)))
                 .map(|_| ())
@@ -272,7 +309,7 @@ pub fn file_source(
         let span = info_span!("file_server");
         spawn_blocking(move || {
             let _enter = span.enter();
-            let result = file_server.run(tx, shutdown);
+            let result = file_server.run(tx, shutdown, checkpointer);
             // Panic if we encounter any error originating from the file server.
             // We're at the `spawn_blocking` call, the panic will be caught and
             // passed to the `JoinHandle` error, similar to the usual threads.
@@ -284,14 +321,55 @@ pub fn file_source(
     }))
 }
 
+struct PendingCheckpoint {
+    receiver: BatchStatusReceiver,
+    filename: String,
+    file_id: FileFingerprint,
+    offset: FilePosition,
+}
+
+/// Advances the checkpoints of lines as their events are acknowledged. Lines are
+/// processed in the order they were read, so a checkpoint never skips past a
+/// line that is still in flight.
+async fn update_acked_checkpoints(
+    checkpoints: Arc<CheckpointsView>,
+    mut pending: mpsc::UnboundedReceiver<PendingCheckpoint>,
+) {
+    while let Some(PendingCheckpoint {
+        receiver,
+        filename,
+        file_id,
+        offset,
+    }) = pending.next().await
+    {
+        match receiver.await {
+            BatchStatus::Delivered => checkpoints.update(file_id, offset),
+            status => emit!(FileEventDeliveryFailed {
+                file: &filename,
+                status
+            }),
+        }
+    }
+}
+
 fn wrap_with_line_agg(
-    rx: impl Stream<Item = (Bytes, String)> + Send + std::marker::Unpin + 'static,
+    rx: impl Stream<Item = Line> + Send + std::marker::Unpin + 'static,
     config: line_agg::Config,
-) -> Box<dyn Stream<Item = (Bytes, String)> + Send + std::marker::Unpin + 'static> {
+) -> Box<dyn Stream<Item = Line> + Send + std::marker::Unpin + 'static> {
     let logic = line_agg::Logic::new(config);
+    // Aggregated lines keep the checkpoint of their first line, so a partially
+    // delivered aggregate is read again rather than lost.
     Box::new(
-        LineAgg::new(rx.map(|(line, src)| (src, line, ())), logic)
-            .map(|(src, line, _context)| (line, src)),
+        LineAgg::new(
+            rx.map(|line| (line.filename, line.text, (line.file_id, line.offset))),
+            logic,
+        )
+        .map(|(filename, text, (file_id, offset))| Line {
+            text,
+            filename,
+            file_id,
+            offset,
+        }),
     )
 }
 
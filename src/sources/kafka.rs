@@ -1,7 +1,13 @@
 use crate::{
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
-    event::{Event, Value},
-    internal_events::{KafkaEventFailed, KafkaEventReceived, KafkaOffsetUpdateFailed},
+    event::{
+        finalization::{BatchNotifier, BatchStatus, BatchStatusReceiver},
+        Event, Value,
+    },
+    internal_events::{
+        KafkaEventDeliveryFailed, KafkaEventFailed, KafkaEventReceived, KafkaOffsetHeldBack,
+        KafkaOffsetUpdateFailed, KafkaPartitionStalled, KafkaPartitionStatistics, KafkaRebalance,
+    },
     kafka::KafkaAuthConfig,
     shutdown::ShutdownSignal,
     Pipeline,
@@ -9,18 +15,26 @@ use crate::{
 use bytes::Bytes;
 use chrono::{TimeZone, Utc};
 use futures::{
+    channel::mpsc,
     compat::{Compat, Future01CompatExt},
+    stream::FuturesUnordered,
     FutureExt, StreamExt,
 };
 use futures01::Sink;
 use rdkafka::{
     config::ClientConfig,
-    consumer::{Consumer, StreamConsumer},
-    message::Message,
+    consumer::{Consumer, ConsumerContext, Rebalance, StreamConsumer},
+    message::{Headers, Message},
+    statistics::Statistics,
+    ClientContext, Offset, TopicPartitionList,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 #[derive(Debug, Snafu)]
 enum BuildError {
@@ -28,13 +42,23 @@ enum BuildError {
     KafkaCreateError { source: rdkafka::error::KafkaError },
     #[snafu(display("Could not subscribe to Kafka topics: {}", source))]
     KafkaSubscribeError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Could not look up Kafka offsets for timestamps: {}", source))]
+    KafkaOffsetsForTimesError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Could not assign Kafka partitions: {}", source))]
+    KafkaAssignError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Either `topics` or `partitions` must be set, but not both"))]
+    TopicsOrPartitions,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct KafkaSourceConfig {
     bootstrap_servers: String,
+    #[serde(default)]
     topics: Vec<String>,
+    /// Partitions to consume from instead of subscribing to `topics`.
+    #[serde(default)]
+    partitions: Vec<PartitionAssignment>,
     group_id: String,
     #[serde(default = "default_auto_offset_reset")]
     auto_offset_reset: String,
@@ -47,9 +71,16 @@ pub struct KafkaSourceConfig {
     #[serde(default = "default_commit_interval_ms")]
     commit_interval_ms: u64,
     key_field: Option<String>,
+    headers_field: Option<String>,
+    #[serde(default = "default_statistics_interval_ms")]
+    statistics_interval_ms: u64,
     librdkafka_options: Option<HashMap<String, String>>,
     #[serde(flatten)]
     auth: KafkaAuthConfig,
+    /// Only store a message's offset once its event has been delivered by
+    /// every sink it was sent to.
+    #[serde(default)]
+    acknowledgements: bool,
 }
 
 fn default_session_timeout_ms() -> u64 {
@@ -72,6 +103,35 @@ fn default_auto_offset_reset() -> String {
     "largest".into() // default in librdkafka
 }
 
+fn default_statistics_interval_ms() -> u64 {
+    0 // default in librdkafka
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct PartitionAssignment {
+    topic: String,
+    partition: i32,
+    #[serde(default)]
+    start_offset: StartOffset,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+enum StartOffset {
+    /// The offset stored for the consumer group, falling back to
+    /// `auto_offset_reset` if there is none.
+    #[derivative(Default)]
+    Stored,
+    Earliest,
+    Latest,
+    Offset(i64),
+    /// The earliest offset whose timestamp, in milliseconds, is at least
+    /// this one.
+    Timestamp(i64),
+}
+
 inventory::submit! {
     SourceDescription::new::<KafkaSourceConfig>("kafka")
 }
@@ -88,7 +148,7 @@ impl SourceConfig for KafkaSourceConfig {
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<super::Source> {
-        kafka_source(self, shutdown, out)
+        kafka_source(self, shutdown, out).await
     }
 
     fn output_type(&self) -> DataType {
@@ -100,13 +160,33 @@ impl SourceConfig for KafkaSourceConfig {
     }
 }
 
-fn kafka_source(
+async fn kafka_source(
     config: &KafkaSourceConfig,
     shutdown: ShutdownSignal,
     out: Pipeline,
 ) -> crate::Result<super::Source> {
     let key_field = config.key_field.clone();
-    let consumer = Arc::new(create_consumer(config)?);
+    let headers_field = config.headers_field.clone();
+    let acked_offsets = AckedOffsets::default();
+    // Resolving start timestamps to offsets blocks on the brokers.
+    let consumer = {
+        let config = config.clone();
+        let acked_offsets = acked_offsets.clone();
+        tokio::task::spawn_blocking(move || create_consumer(&config, acked_offsets)).await??
+    };
+    let consumer = Arc::new(consumer);
+
+    let acks = if config.acknowledgements {
+        let (tx, rx) = mpsc::unbounded();
+        tokio::spawn(store_acked_offsets(
+            Arc::clone(&consumer),
+            acked_offsets,
+            rx,
+        ));
+        Some(tx)
+    } else {
+        None
+    };
 
     let fut = async move {
         Arc::clone(&consumer)
@@ -114,7 +194,9 @@ fn kafka_source(
             .take_until(shutdown.clone())
             .then(move |message| {
                 let key_field = key_field.clone();
+                let headers_field = headers_field.clone();
                 let consumer = Arc::clone(&consumer);
+                let acks = acks.clone();
 
                 async move {
                     match message {
@@ -162,9 +244,40 @@ fn kafka_source(
                                 }
                             }
 
-                            consumer.store_offset(&msg).map_err(|error| {
-                                emit!(KafkaOffsetUpdateFailed { error });
-                            })?;
+                            if let Some(headers_field) = &headers_field {
+                                let mut headers = BTreeMap::new();
+                                if let Some(msg_headers) = msg.headers() {
+                                    for index in 0..msg_headers.count() {
+                                        if let Some((name, value)) = msg_headers.get(index) {
+                                            headers.insert(
+                                                name.to_owned(),
+                                                Value::from(Bytes::from(value.to_owned())),
+                                            );
+                                        }
+                                    }
+                                }
+                                log.insert(headers_field, Value::from(headers));
+                            }
+
+                            match acks {
+                                Some(acks) => {
+                                    let (batch, receiver) = BatchNotifier::new_with_receiver();
+                                    event.add_batch_notifier(&batch);
+                                    let pending = PendingOffset {
+                                        receiver,
+                                        topic: msg.topic().to_owned(),
+                                        partition: msg.partition(),
+                                        offset: msg.offset(),
+                                    };
+                                    // The task only goes away along with the consumer.
+                                    let _ = acks.unbounded_send(pending);
+                                }
+                                None => {
+                                    consumer.store_offset(&msg).map_err(|error| {
+                                        emit!(KafkaOffsetUpdateFailed { error });
+                                    })?;
+                                }
+                            }
 
                             Ok(event)
                         }
@@ -194,7 +307,170 @@ fn kafka_source(
     Ok(Box::new(Compat::new(fut.boxed())))
 }
 
-fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer> {
+struct PendingOffset {
+    receiver: BatchStatusReceiver,
+    topic: String,
+    partition: i32,
+    offset: i64,
+}
+
+impl PendingOffset {
+    async fn wait(self) -> (String, i32, i64, BatchStatus) {
+        let status = self.receiver.await;
+        (self.topic, self.partition, self.offset, status)
+    }
+}
+
+/// Stores the offsets of messages as their events are acknowledged. Messages of
+/// a partition are processed in the order they were consumed, so a stored
+/// offset never skips past a message that is still in flight, while each
+/// partition waits on its own messages only.
+async fn store_acked_offsets(
+    consumer: Arc<StreamConsumer<KafkaSourceContext>>,
+    offsets: AckedOffsets,
+    mut pending: mpsc::UnboundedReceiver<PendingOffset>,
+) {
+    // Messages queued behind the one being waited on, for each partition.
+    let mut queued: HashMap<(String, i32), VecDeque<PendingOffset>> = HashMap::new();
+    // The first message still in flight of each partition.
+    let mut waiting = FuturesUnordered::new();
+
+    loop {
+        tokio::select! {
+            Some(message) = pending.next() => {
+                let key = (message.topic.clone(), message.partition);
+                match queued.get_mut(&key) {
+                    Some(queue) => queue.push_back(message),
+                    None => {
+                        queued.insert(key, VecDeque::new());
+                        waiting.push(message.wait());
+                    }
+                }
+            }
+            Some((topic, partition, offset, status)) = waiting.next() => {
+                if status != BatchStatus::Delivered {
+                    emit!(KafkaEventDeliveryFailed {
+                        topic: &topic,
+                        partition,
+                        offset,
+                        status,
+                    });
+                }
+
+                if let Some(next) = offsets.acked(&topic, partition, offset, status) {
+                    let mut tpl = TopicPartitionList::new();
+                    tpl.add_partition_offset(&topic, partition, Offset::Offset(next));
+                    if let Err(error) = consumer.store_offsets(&tpl) {
+                        emit!(KafkaOffsetUpdateFailed { error });
+                    }
+                }
+
+                let key = (topic, partition);
+                match queued.get_mut(&key).and_then(VecDeque::pop_front) {
+                    Some(message) => waiting.push(message.wait()),
+                    None => {
+                        queued.remove(&key);
+                    }
+                }
+            }
+            else => break,
+        }
+    }
+}
+
+/// Tracks which partitions had a message that was not delivered. The stored
+/// offset of such a partition stays at that message, so that it is consumed
+/// again after a restart, even if later messages are delivered. Partitions are
+/// cleared on rebalance, as the consumer then resumes from the stored offsets.
+#[derive(Clone, Debug, Default)]
+struct AckedOffsets {
+    stalled: Arc<Mutex<HashSet<(String, i32)>>>,
+}
+
+impl AckedOffsets {
+    /// Returns the offset to store for the partition of an acknowledged message,
+    /// if any.
+    fn acked(&self, topic: &str, partition: i32, offset: i64, status: BatchStatus) -> Option<i64> {
+        let mut stalled = self.stalled.lock().expect("mutex poisoned");
+        let key = (topic.to_owned(), partition);
+        if stalled.contains(&key) {
+            emit!(KafkaOffsetHeldBack {
+                topic,
+                partition,
+                offset,
+            });
+            None
+        } else if status == BatchStatus::Delivered {
+            // Stored offsets point at the next message to consume.
+            Some(offset + 1)
+        } else {
+            emit!(KafkaPartitionStalled {
+                topic,
+                partition,
+                stalled: true,
+            });
+            stalled.insert(key);
+            None
+        }
+    }
+
+    fn clear(&self) {
+        let mut stalled = self.stalled.lock().expect("mutex poisoned");
+        for (topic, partition) in stalled.drain() {
+            emit!(KafkaPartitionStalled {
+                topic: &topic,
+                partition,
+                stalled: false,
+            });
+        }
+    }
+}
+
+/// Reports the statistics of the consumer and logs rebalances.
+struct KafkaSourceContext {
+    acked_offsets: AckedOffsets,
+}
+
+impl ClientContext for KafkaSourceContext {
+    fn stats(&self, statistics: Statistics) {
+        for (topic, topic_statistics) in &statistics.topics {
+            for (partition, partition_statistics) in &topic_statistics.partitions {
+                // librdkafka reports messages not yet assigned to a partition
+                // under the internal partition -1.
+                if *partition < 0 {
+                    continue;
+                }
+                emit!(KafkaPartitionStatistics {
+                    topic,
+                    partition: *partition,
+                    consumer_lag: partition_statistics.consumer_lag,
+                    consumed_offset: partition_statistics.app_offset,
+                    committed_offset: partition_statistics.committed_offset,
+                    high_watermark: partition_statistics.hi_offset,
+                });
+            }
+        }
+    }
+}
+
+impl ConsumerContext for KafkaSourceContext {
+    fn post_rebalance(&self, rebalance: &Rebalance) {
+        emit!(KafkaRebalance { rebalance });
+        match rebalance {
+            Rebalance::Assign(_) | Rebalance::Revoke => self.acked_offsets.clear(),
+            Rebalance::Error(_) => (),
+        }
+    }
+}
+
+fn create_consumer(
+    config: &KafkaSourceConfig,
+    acked_offsets: AckedOffsets,
+) -> crate::Result<StreamConsumer<KafkaSourceContext>> {
+    if config.topics.is_empty() == config.partitions.is_empty() {
+        return Err(BuildError::TopicsOrPartitions.into());
+    }
+
     let mut client_config = ClientConfig::new();
     client_config
         .set("group.id", &config.group_id)
@@ -210,6 +486,10 @@ fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer>
             &config.commit_interval_ms.to_string(),
         )
         .set("enable.auto.offset.store", "false")
+        .set(
+            "statistics.interval.ms",
+            &config.statistics_interval_ms.to_string(),
+        )
         .set("client.id", "vector");
 
     config.auth.apply(&mut client_config)?;
@@ -220,17 +500,67 @@ fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer>
         }
     }
 
-    let consumer: StreamConsumer = client_config.create().context(KafkaCreateError)?;
-    let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
-    consumer.subscribe(&topics).context(KafkaSubscribeError)?;
+    let consumer: StreamConsumer<KafkaSourceContext> = client_config
+        .create_with_context(KafkaSourceContext { acked_offsets })
+        .context(KafkaCreateError)?;
+    if config.partitions.is_empty() {
+        let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
+        consumer.subscribe(&topics).context(KafkaSubscribeError)?;
+    } else {
+        let assignment = partition_assignment(
+            &consumer,
+            &config.partitions,
+            Duration::from_millis(config.socket_timeout_ms),
+        )?;
+        consumer.assign(&assignment).context(KafkaAssignError)?;
+    }
 
     Ok(consumer)
 }
 
+fn partition_assignment(
+    consumer: &StreamConsumer<KafkaSourceContext>,
+    partitions: &[PartitionAssignment],
+    timeout: Duration,
+) -> crate::Result<TopicPartitionList> {
+    let mut assignment = TopicPartitionList::new();
+    let mut timestamps = TopicPartitionList::new();
+    for PartitionAssignment {
+        topic,
+        partition,
+        start_offset,
+    } in partitions
+    {
+        let offset = match *start_offset {
+            StartOffset::Stored => Offset::Stored,
+            StartOffset::Earliest => Offset::Beginning,
+            StartOffset::Latest => Offset::End,
+            StartOffset::Offset(offset) => Offset::Offset(offset),
+            StartOffset::Timestamp(timestamp) => {
+                timestamps.add_partition_offset(topic, *partition, Offset::Offset(timestamp));
+                continue;
+            }
+        };
+        assignment.add_partition_offset(topic, *partition, offset);
+    }
+
+    // Timestamps are resolved to offsets by the brokers.
+    if timestamps.count() > 0 {
+        let offsets = consumer
+            .offsets_for_times(timestamps, timeout)
+            .context(KafkaOffsetsForTimesError)?;
+        for element in offsets.elements() {
+            assignment.add_partition_offset(element.topic(), element.partition(), element.offset());
+        }
+    }
+
+    Ok(assignment)
+}
+
 #[cfg(test)]
 mod test {
-    use super::{kafka_source, KafkaSourceConfig};
-    use crate::{shutdown::ShutdownSignal, Pipeline};
+    use super::{kafka_source, AckedOffsets, KafkaSourceConfig, StartOffset};
+    use crate::{event::finalization::BatchStatus, shutdown::ShutdownSignal, Pipeline};
 
     #[test]
     fn generate_config() {
@@ -252,19 +582,106 @@ mod test {
         }
     }
 
-    #[test]
-    fn kafka_source_create_ok() {
+    #[tokio::test]
+    async fn kafka_source_create_ok() {
         let config = make_config();
-        assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_ok());
+        assert!(
+            kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0)
+                .await
+                .is_ok()
+        );
     }
 
-    #[test]
-    fn kafka_source_create_incorrect_auto_offset_reset() {
+    #[tokio::test]
+    async fn kafka_source_create_incorrect_auto_offset_reset() {
         let config = KafkaSourceConfig {
             auto_offset_reset: "incorrect-auto-offset-reset".to_string(),
             ..make_config()
         };
-        assert!(kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0).is_err());
+        assert!(
+            kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn kafka_source_create_with_partitions() {
+        let config: KafkaSourceConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            group_id = "group-id"
+
+            [[partitions]]
+            topic = "my-topic"
+            partition = 0
+
+            [[partitions]]
+            topic = "my-topic"
+            partition = 1
+            start_offset = "earliest"
+
+            [[partitions]]
+            topic = "other-topic"
+            partition = 0
+            start_offset = { offset = 42 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.partitions[0].start_offset, StartOffset::Stored);
+        assert_eq!(config.partitions[1].start_offset, StartOffset::Earliest);
+        assert_eq!(config.partitions[2].start_offset, StartOffset::Offset(42));
+        assert!(
+            kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn acked_offsets_stop_at_first_undelivered_message() {
+        let offsets = AckedOffsets::default();
+
+        assert_eq!(
+            offsets.acked("topic", 0, 10, BatchStatus::Delivered),
+            Some(11)
+        );
+        assert_eq!(offsets.acked("topic", 0, 11, BatchStatus::Errored), None);
+        assert_eq!(offsets.acked("topic", 0, 12, BatchStatus::Delivered), None);
+        // Other partitions are unaffected.
+        assert_eq!(
+            offsets.acked("topic", 1, 5, BatchStatus::Delivered),
+            Some(6)
+        );
+        assert_eq!(
+            offsets.acked("other", 0, 7, BatchStatus::Delivered),
+            Some(8)
+        );
+
+        // A rebalance resumes the partition from its stored offset.
+        offsets.clear();
+        assert_eq!(
+            offsets.acked("topic", 0, 11, BatchStatus::Delivered),
+            Some(12)
+        );
+    }
+
+    #[tokio::test]
+    async fn kafka_source_create_with_topics_and_partitions() {
+        let config = KafkaSourceConfig {
+            partitions: vec![super::PartitionAssignment {
+                topic: "my-topic".to_string(),
+                partition: 0,
+                start_offset: StartOffset::Latest,
+            }],
+            ..make_config()
+        };
+        assert!(
+            kafka_source(&config, ShutdownSignal::noop(), Pipeline::new_test().0)
+                .await
+                .is_err()
+        );
     }
 }
 
@@ -281,6 +698,7 @@ mod integration_test {
     use futures::compat::Future01CompatExt;
     use rdkafka::{
         config::ClientConfig,
+        message::OwnedHeaders,
         producer::{FutureProducer, FutureRecord},
         util::Timeout,
     };
@@ -298,7 +716,8 @@ mod integration_test {
         let record = FutureRecord::to(&topic)
             .payload(text)
             .key(key)
-            .timestamp(timestamp);
+            .timestamp(timestamp)
+            .headers(OwnedHeaders::new().add("trace_id", "abc123"));
 
         if let Err(error) = producer.send(record, Timeout::Never).await {
             panic!("Cannot send event to Kafka: {:?}", error);
@@ -321,6 +740,7 @@ mod integration_test {
             session_timeout_ms: 6000,
             commit_interval_ms: 5000,
             key_field: Some("message_key".to_string()),
+            headers_field: Some("headers".to_string()),
             socket_timeout_ms: 60000,
             fetch_wait_max_ms: 100,
             ..Default::default()
@@ -339,6 +759,7 @@ mod integration_test {
         let (tx, rx) = Pipeline::new_test();
         tokio::spawn(
             kafka_source(&config, ShutdownSignal::noop(), tx)
+                .await
                 .unwrap()
                 .compat(),
         );
@@ -349,6 +770,7 @@ mod integration_test {
             "my message".into()
         );
         assert_eq!(events[0].as_log()["message_key"], "my key".into());
+        assert_eq!(events[0].as_log()["headers.trace_id"], "abc123".into());
         assert_eq!(
             events[0].as_log()[log_schema().source_type_key()],
             "kafka".into()
@@ -19,7 +19,7 @@ use crate::{
     Pipeline,
 };
 use bytes::Bytes;
-use file_source::{FileServer, FileServerShutdown, Fingerprinter};
+use file_source::{Checkpointer, FileServer, FileServerShutdown, Fingerprinter, Line};
 use k8s_openapi::api::core::v1::Pod;
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
@@ -266,8 +266,6 @@ impl Source {
             // Max line length to expect during regular log reads, see the
             // explanation above.
             max_line_bytes,
-            // The directory where to keep the checkpoints.
-            data_dir,
             // This value specifies not exactly the globbing, but interval
             // between the polling the files to watch from the `paths_provider`.
             glob_minimum_cooldown,
@@ -291,23 +289,27 @@ impl Source {
             remove_after: None,
             // The standard emitter.
             emitter: FileSourceInternalEventsEmitter,
+            // Lines are checkpointed as soon as they are read.
+            acknowledgements: false,
         };
 
-        let (file_source_tx, file_source_rx) =
-            futures::channel::mpsc::channel::<Vec<(Bytes, String)>>(2);
+        // The directory where to keep the checkpoints.
+        let checkpointer = Checkpointer::new(&data_dir);
+
+        let (file_source_tx, file_source_rx) = futures::channel::mpsc::channel::<Vec<Line>>(2);
 
         let mut parser = parser::build();
         let partial_events_merger = Box::new(partial_events_merger::build(auto_partial_merge));
 
         let events = file_source_rx.map(futures::stream::iter);
         let events = events.flatten();
-        let events = events.map(move |(bytes, file)| {
+        let events = events.map(move |line| {
             emit!(KubernetesLogsEventReceived {
-                file: &file,
-                byte_size: bytes.len(),
+                file: &line.filename,
+                byte_size: line.text.len(),
             });
-            let mut event = create_event(bytes, &file);
-            if annotator.annotate(&mut event, &file).is_none() {
+            let mut event = create_event(line.text, &line.filename);
+            if annotator.annotate(&mut event, &line.filename).is_none() {
                 emit!(KubernetesLogsEventAnnotationFailed { event: &event });
             }
             event
@@ -336,12 +338,11 @@ impl Source {
         }
         {
             let (slot, shutdown) = lifecycle.add();
-            let fut = util::run_file_server(file_server, file_source_tx, shutdown).map(|result| {
-                match result {
+            let fut = util::run_file_server(file_server, file_source_tx, shutdown, checkpointer)
+                .map(|result| match result {
                     Ok(FileServerShutdown) => info!(message = "File server completed gracefully."),
                     Err(error) => error!(message = "File server exited with an error.", %error),
-                }
-            });
+                });
             slot.bind(Box::pin(fut));
         }
         {
@@ -0,0 +1,645 @@
+//! Conversion of OTLP export requests into events.
+//!
+//! Log records become log events carrying their resource and scope, while
+//! every data point of a metric becomes a metric event tagged with its
+//! attributes and those of its resource and scope.
+
+use super::proto::{
+    collector::{logs::v1::ExportLogsServiceRequest, metrics::v1::ExportMetricsServiceRequest},
+    common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
+    logs::v1::LogRecord,
+    metrics::v1::{
+        metric::Data, number_data_point, AggregationTemporality, DataPointFlags,
+        HistogramDataPoint, NumberDataPoint, SummaryDataPoint,
+    },
+};
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Metric, MetricKind, MetricValue, Value},
+};
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::BTreeMap;
+
+pub(super) fn logs(request: ExportLogsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_logs in request.resource_logs {
+        let resource = resource_logs
+            .resource
+            .map(|resource| attributes(resource.attributes))
+            .filter(|attributes| !attributes.is_empty());
+        for scope_logs in resource_logs.scope_logs {
+            let scope = scope_logs.scope.and_then(scope_value);
+            for record in scope_logs.log_records {
+                let mut log = log(record);
+                if let Some(resource) = &resource {
+                    log.insert("resources", Value::Map(resource.clone()));
+                }
+                if let Some(scope) = &scope {
+                    log.insert("scope", scope.clone());
+                }
+                events.push(Event::Log(log));
+            }
+        }
+    }
+    events
+}
+
+fn log(record: LogRecord) -> LogEvent {
+    let mut log = LogEvent::default();
+
+    log.insert(
+        log_schema().message_key(),
+        record.body.map_or(Value::Null, any_value),
+    );
+    let observed = timestamp(record.observed_time_unix_nano);
+    let time = timestamp(record.time_unix_nano)
+        .or(observed)
+        .unwrap_or_else(Utc::now);
+    log.insert(log_schema().timestamp_key(), time);
+    if let Some(observed) = observed {
+        log.insert("observed_timestamp", observed);
+    }
+    log.insert(log_schema().source_type_key(), Bytes::from("opentelemetry"));
+
+    if !record.attributes.is_empty() {
+        log.insert("attributes", Value::Map(attributes(record.attributes)));
+    }
+    if !record.severity_text.is_empty() {
+        log.insert("severity_text", record.severity_text);
+    }
+    if record.severity_number != 0 {
+        log.insert("severity_number", record.severity_number as i64);
+    }
+    if !record.trace_id.is_empty() {
+        log.insert("trace_id", hex::encode(record.trace_id));
+    }
+    if !record.span_id.is_empty() {
+        log.insert("span_id", hex::encode(record.span_id));
+    }
+    if record.flags != 0 {
+        log.insert("flags", record.flags as i64);
+    }
+    if record.dropped_attributes_count != 0 {
+        log.insert(
+            "dropped_attributes_count",
+            record.dropped_attributes_count as i64,
+        );
+    }
+
+    log
+}
+
+pub(super) fn metrics(request: ExportMetricsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_metrics in request.resource_metrics {
+        let mut tags = BTreeMap::new();
+        if let Some(resource) = resource_metrics.resource {
+            for (key, value) in attributes(resource.attributes) {
+                tags.insert(format!("resource.{}", key), tag_value(value));
+            }
+        }
+
+        for scope_metrics in resource_metrics.scope_metrics {
+            let mut tags = tags.clone();
+            if let Some(scope) = scope_metrics.scope {
+                if !scope.name.is_empty() {
+                    tags.insert("scope.name".into(), scope.name);
+                }
+                if !scope.version.is_empty() {
+                    tags.insert("scope.version".into(), scope.version);
+                }
+                for (key, value) in attributes(scope.attributes) {
+                    tags.insert(format!("scope.{}", key), tag_value(value));
+                }
+            }
+
+            for metric in scope_metrics.metrics {
+                let name = metric.name;
+                let points: Vec<DataPoint> = match metric.data {
+                    Some(Data::Gauge(gauge)) => gauge
+                        .data_points
+                        .into_iter()
+                        .filter_map(|point| number(point, MetricKind::Absolute, false))
+                        .collect(),
+                    Some(Data::Sum(sum)) => {
+                        let kind = kind(sum.aggregation_temporality);
+                        let monotonic = sum.is_monotonic;
+                        sum.data_points
+                            .into_iter()
+                            .filter_map(|point| number(point, kind.clone(), monotonic))
+                            .collect()
+                    }
+                    Some(Data::Histogram(histogram)) => {
+                        let kind = kind(histogram.aggregation_temporality);
+                        histogram
+                            .data_points
+                            .into_iter()
+                            .filter_map(|point| self::histogram(point, kind.clone()))
+                            .collect()
+                    }
+                    Some(Data::Summary(summary)) => summary
+                        .data_points
+                        .into_iter()
+                        .filter_map(self::summary)
+                        .collect(),
+                    Some(Data::ExponentialHistogram(_)) => {
+                        warn!(
+                            message = "Exponential histograms are not supported; dropping metric.",
+                            metric = %name,
+                            rate_limit_secs = 30
+                        );
+                        Vec::new()
+                    }
+                    None => Vec::new(),
+                };
+
+                events.extend(points.into_iter().map(|point| {
+                    let mut point_tags = tags.clone();
+                    point_tags.extend(point.tags);
+                    Event::Metric(Metric {
+                        name: name.clone(),
+                        namespace: None,
+                        timestamp: point.timestamp,
+                        tags: if point_tags.is_empty() {
+                            None
+                        } else {
+                            Some(point_tags)
+                        },
+                        kind: point.kind,
+                        value: point.value,
+                    })
+                }));
+            }
+        }
+    }
+    events
+}
+
+/// The parts of a metric event that differ between data points.
+struct DataPoint {
+    tags: BTreeMap<String, String>,
+    timestamp: Option<DateTime<Utc>>,
+    kind: MetricKind,
+    value: MetricValue,
+}
+
+impl DataPoint {
+    /// Returns `None` for points that explicitly have no value, which OTLP
+    /// uses to mark series as stale.
+    fn new(
+        flags: u32,
+        attributes: Vec<KeyValue>,
+        time_unix_nano: u64,
+        kind: MetricKind,
+        value: MetricValue,
+    ) -> Option<Self> {
+        if flags & DataPointFlags::NoRecordedValueMask as u32 != 0 {
+            return None;
+        }
+        Some(Self {
+            tags: self::attributes(attributes)
+                .into_iter()
+                .map(|(key, value)| (key, tag_value(value)))
+                .collect(),
+            timestamp: timestamp(time_unix_nano),
+            kind,
+            value,
+        })
+    }
+}
+
+/// Monotonic sums are counters, anything else is a gauge.
+fn number(point: NumberDataPoint, kind: MetricKind, monotonic: bool) -> Option<DataPoint> {
+    let value = match point.value? {
+        number_data_point::Value::AsDouble(value) => value,
+        number_data_point::Value::AsInt(value) => value as f64,
+    };
+    let value = if monotonic {
+        MetricValue::Counter { value }
+    } else {
+        MetricValue::Gauge { value }
+    };
+    DataPoint::new(
+        point.flags,
+        point.attributes,
+        point.time_unix_nano,
+        kind,
+        value,
+    )
+}
+
+fn histogram(point: HistogramDataPoint, kind: MetricKind) -> Option<DataPoint> {
+    // OTLP counts the values of each bucket separately, with a last bucket for
+    // everything above the highest explicit bound. Our buckets are cumulative
+    // instead, leaving that last one implied by the total count.
+    let counts: Vec<u32> = point
+        .bucket_counts
+        .iter()
+        .scan(0u64, |total, count| {
+            *total = total.saturating_add(*count);
+            Some(saturating_u32(*total))
+        })
+        .take(point.explicit_bounds.len())
+        .collect();
+    let mut buckets = point.explicit_bounds;
+    buckets.truncate(counts.len());
+    let value = MetricValue::AggregatedHistogram {
+        buckets,
+        counts,
+        count: saturating_u32(point.count),
+        sum: point.sum,
+    };
+    DataPoint::new(
+        point.flags,
+        point.attributes,
+        point.time_unix_nano,
+        kind,
+        value,
+    )
+}
+
+fn summary(point: SummaryDataPoint) -> Option<DataPoint> {
+    let (quantiles, values) = point
+        .quantile_values
+        .into_iter()
+        .map(|quantile| (quantile.quantile, quantile.value))
+        .unzip();
+    let value = MetricValue::AggregatedSummary {
+        quantiles,
+        values,
+        count: saturating_u32(point.count),
+        sum: point.sum,
+    };
+    DataPoint::new(
+        point.flags,
+        point.attributes,
+        point.time_unix_nano,
+        MetricKind::Absolute,
+        value,
+    )
+}
+
+/// Delta temporality maps to incremental metrics, cumulative to absolute ones.
+fn kind(temporality: i32) -> MetricKind {
+    if temporality == AggregationTemporality::Delta as i32 {
+        MetricKind::Incremental
+    } else {
+        MetricKind::Absolute
+    }
+}
+
+fn saturating_u32(value: u64) -> u32 {
+    value.min(u32::MAX as u64) as u32
+}
+
+fn timestamp(unix_nano: u64) -> Option<DateTime<Utc>> {
+    if unix_nano == 0 {
+        None
+    } else {
+        Some(Utc.timestamp_nanos(unix_nano as i64))
+    }
+}
+
+fn scope_value(scope: InstrumentationScope) -> Option<Value> {
+    let mut map = BTreeMap::new();
+    if !scope.name.is_empty() {
+        map.insert("name".into(), Value::from(scope.name));
+    }
+    if !scope.version.is_empty() {
+        map.insert("version".into(), Value::from(scope.version));
+    }
+    if !scope.attributes.is_empty() {
+        map.insert(
+            "attributes".into(),
+            Value::Map(attributes(scope.attributes)),
+        );
+    }
+    if map.is_empty() {
+        None
+    } else {
+        Some(Value::Map(map))
+    }
+}
+
+fn attributes(attributes: Vec<KeyValue>) -> BTreeMap<String, Value> {
+    attributes
+        .into_iter()
+        .map(|attribute| {
+            let value = attribute.value.map_or(Value::Null, any_value);
+            (attribute.key, value)
+        })
+        .collect()
+}
+
+fn any_value(value: AnyValue) -> Value {
+    match value.value {
+        Some(any_value::Value::StringValue(value)) => Value::from(value),
+        Some(any_value::Value::BoolValue(value)) => Value::Boolean(value),
+        Some(any_value::Value::IntValue(value)) => Value::Integer(value),
+        Some(any_value::Value::DoubleValue(value)) => Value::Float(value),
+        Some(any_value::Value::ArrayValue(array)) => {
+            Value::Array(array.values.into_iter().map(any_value).collect())
+        }
+        Some(any_value::Value::KvlistValue(list)) => Value::Map(attributes(list.values)),
+        Some(any_value::Value::BytesValue(value)) => Value::Bytes(value.into()),
+        None => Value::Null,
+    }
+}
+
+fn tag_value(value: Value) -> String {
+    match value {
+        Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Value::Null => String::new(),
+        value => value.to_string_lossy(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::opentelemetry::proto::{
+        common::v1::ArrayValue,
+        logs::v1::{ResourceLogs, ScopeLogs},
+        metrics::v1::{
+            summary_data_point::ValueAtQuantile, Gauge, Histogram, Metric as OtlpMetric,
+            ResourceMetrics, ScopeMetrics, Sum, Summary,
+        },
+        resource::v1::Resource,
+    };
+
+    fn string(value: &str) -> AnyValue {
+        AnyValue {
+            value: Some(any_value::Value::StringValue(value.into())),
+        }
+    }
+
+    fn key_value(key: &str, value: AnyValue) -> KeyValue {
+        KeyValue {
+            key: key.into(),
+            value: Some(value),
+        }
+    }
+
+    fn resource() -> Option<Resource> {
+        Some(Resource {
+            attributes: vec![key_value("service.name", string("checkout"))],
+            dropped_attributes_count: 0,
+        })
+    }
+
+    fn scope() -> Option<InstrumentationScope> {
+        Some(InstrumentationScope {
+            name: "io.opentelemetry.http".into(),
+            version: "1.2.3".into(),
+            attributes: vec![],
+            dropped_attributes_count: 0,
+        })
+    }
+
+    #[test]
+    fn converts_logs() {
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                resource: resource(),
+                scope_logs: vec![ScopeLogs {
+                    scope: scope(),
+                    log_records: vec![LogRecord {
+                        time_unix_nano: 1_600_000_000_000_000_123,
+                        severity_number: 9,
+                        severity_text: "INFO".into(),
+                        body: Some(string("request served")),
+                        attributes: vec![
+                            key_value(
+                                "http.status_code",
+                                AnyValue {
+                                    value: Some(any_value::Value::IntValue(200)),
+                                },
+                            ),
+                            key_value(
+                                "tags",
+                                AnyValue {
+                                    value: Some(any_value::Value::ArrayValue(ArrayValue {
+                                        values: vec![string("a"), string("b")],
+                                    })),
+                                },
+                            ),
+                        ],
+                        trace_id: vec![0xab; 16],
+                        span_id: vec![0x01, 0x02],
+                        ..Default::default()
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        };
+
+        let events = logs(request);
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+
+        assert_eq!(log[log_schema().message_key()], "request served".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_600_000_000, 123).into()
+        );
+        assert_eq!(log[log_schema().source_type_key()], "opentelemetry".into());
+        assert!(log.get("observed_timestamp").is_none());
+        assert_eq!(log["severity_text"], "INFO".into());
+        assert_eq!(log["severity_number"], 9.into());
+        assert_eq!(log["trace_id"], "ab".repeat(16).into());
+        assert_eq!(log["span_id"], "0102".into());
+        assert_eq!(log["scope.name"], "io.opentelemetry.http".into());
+        assert_eq!(log["scope.version"], "1.2.3".into());
+
+        let resources = match &log["resources"] {
+            Value::Map(map) => map,
+            other => panic!("unexpected resources {:?}", other),
+        };
+        assert_eq!(resources["service.name"], "checkout".into());
+
+        let attributes = match &log["attributes"] {
+            Value::Map(map) => map,
+            other => panic!("unexpected attributes {:?}", other),
+        };
+        assert_eq!(attributes["http.status_code"], 200.into());
+        assert_eq!(
+            attributes["tags"],
+            Value::Array(vec!["a".into(), "b".into()])
+        );
+    }
+
+    fn metrics_request(metrics: Vec<OtlpMetric>) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: resource(),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: scope(),
+                    metrics,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+    }
+
+    fn otlp_metric(name: &str, data: Data) -> OtlpMetric {
+        OtlpMetric {
+            name: name.into(),
+            data: Some(data),
+            ..Default::default()
+        }
+    }
+
+    fn number_point(value: number_data_point::Value) -> NumberDataPoint {
+        NumberDataPoint {
+            attributes: vec![key_value("host", string("a"))],
+            time_unix_nano: 1_600_000_000_000_000_000,
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    fn values(events: Vec<Event>) -> Vec<(String, MetricKind, MetricValue)> {
+        events
+            .into_iter()
+            .map(|event| {
+                let metric = event.into_metric();
+                (metric.name, metric.kind, metric.value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn converts_metric_tags() {
+        let request = metrics_request(vec![otlp_metric(
+            "load",
+            Data::Gauge(Gauge {
+                data_points: vec![number_point(number_data_point::Value::AsDouble(0.5))],
+            }),
+        )]);
+
+        let events = metrics(request);
+        assert_eq!(events.len(), 1);
+        let metric = events[0].as_metric();
+
+        assert_eq!(metric.timestamp, Some(Utc.timestamp(1_600_000_000, 0)));
+        let tags = metric.tags.as_ref().unwrap();
+        assert_eq!(tags["host"], "a");
+        assert_eq!(tags["resource.service.name"], "checkout");
+        assert_eq!(tags["scope.name"], "io.opentelemetry.http");
+        assert_eq!(tags["scope.version"], "1.2.3");
+    }
+
+    #[test]
+    fn converts_metric_values() {
+        let request = metrics_request(vec![
+            otlp_metric(
+                "load",
+                Data::Gauge(Gauge {
+                    data_points: vec![number_point(number_data_point::Value::AsDouble(0.5))],
+                }),
+            ),
+            otlp_metric(
+                "requests",
+                Data::Sum(Sum {
+                    data_points: vec![number_point(number_data_point::Value::AsInt(3))],
+                    aggregation_temporality: AggregationTemporality::Delta as i32,
+                    is_monotonic: true,
+                }),
+            ),
+            otlp_metric(
+                "connections",
+                Data::Sum(Sum {
+                    data_points: vec![number_point(number_data_point::Value::AsInt(7))],
+                    aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                    is_monotonic: false,
+                }),
+            ),
+            otlp_metric(
+                "latency",
+                Data::Histogram(Histogram {
+                    data_points: vec![HistogramDataPoint {
+                        count: 6,
+                        sum: 12.5,
+                        bucket_counts: vec![1, 2, 3],
+                        explicit_bounds: vec![1.0, 5.0],
+                        ..Default::default()
+                    }],
+                    aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                }),
+            ),
+            otlp_metric(
+                "duration",
+                Data::Summary(Summary {
+                    data_points: vec![SummaryDataPoint {
+                        count: 4,
+                        sum: 10.0,
+                        quantile_values: vec![
+                            ValueAtQuantile {
+                                quantile: 0.5,
+                                value: 2.0,
+                            },
+                            ValueAtQuantile {
+                                quantile: 0.99,
+                                value: 4.0,
+                            },
+                        ],
+                        ..Default::default()
+                    }],
+                }),
+            ),
+            otlp_metric(
+                "stale",
+                Data::Gauge(Gauge {
+                    data_points: vec![NumberDataPoint {
+                        flags: DataPointFlags::NoRecordedValueMask as u32,
+                        ..number_point(number_data_point::Value::AsDouble(1.0))
+                    }],
+                }),
+            ),
+        ]);
+
+        assert_eq!(
+            values(metrics(request)),
+            vec![
+                (
+                    "load".into(),
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 0.5 }
+                ),
+                (
+                    "requests".into(),
+                    MetricKind::Incremental,
+                    MetricValue::Counter { value: 3.0 }
+                ),
+                (
+                    "connections".into(),
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 7.0 }
+                ),
+                (
+                    "latency".into(),
+                    MetricKind::Absolute,
+                    MetricValue::AggregatedHistogram {
+                        buckets: vec![1.0, 5.0],
+                        counts: vec![1, 3],
+                        count: 6,
+                        sum: 12.5,
+                    }
+                ),
+                (
+                    "duration".into(),
+                    MetricKind::Absolute,
+                    MetricValue::AggregatedSummary {
+                        quantiles: vec![0.5, 0.99],
+                        values: vec![2.0, 4.0],
+                        count: 4,
+                        sum: 10.0,
+                    }
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use super::{
+    convert,
+    proto::collector::{
+        logs::v1::{
+            logs_service_server::{LogsService, LogsServiceServer},
+            ExportLogsServiceRequest, ExportLogsServiceResponse,
+        },
+        metrics::v1::{
+            metrics_service_server::{MetricsService, MetricsServiceServer},
+            ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+        },
+    },
+    send_events,
+};
+use crate::{
+    event::Event,
+    shutdown::ShutdownSignal,
+    tls::{MaybeTlsIncomingStream, MaybeTlsListener},
+    Pipeline,
+};
+use futures::FutureExt;
+use prost::Message;
+use std::net::SocketAddr;
+use tokio::net::TcpStream;
+use tonic::{
+    transport::{server::Connected, Server},
+    Request, Response, Status,
+};
+
+impl Connected for MaybeTlsIncomingStream<TcpStream> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        Some(self.peer_addr())
+    }
+}
+
+#[derive(Clone)]
+struct Service {
+    out: Pipeline,
+}
+
+impl Service {
+    async fn send(&self, events: Vec<Event>, byte_size: usize) -> Result<(), Status> {
+        send_events(self.out.clone(), events, byte_size)
+            .await
+            .map_err(|_| Status::unavailable("Server is shutting down."))
+    }
+}
+
+#[tonic::async_trait]
+impl LogsService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportLogsServiceRequest>,
+    ) -> Result<Response<ExportLogsServiceResponse>, Status> {
+        let request = request.into_inner();
+        let byte_size = request.encoded_len();
+        self.send(convert::logs(request), byte_size).await?;
+        Ok(Response::new(ExportLogsServiceResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let request = request.into_inner();
+        let byte_size = request.encoded_len();
+        self.send(convert::metrics(request), byte_size).await?;
+        Ok(Response::new(ExportMetricsServiceResponse::default()))
+    }
+}
+
+pub(super) async fn run(
+    listener: MaybeTlsListener,
+    out: Pipeline,
+    shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let service = Service { out };
+    let result = Server::builder()
+        .add_service(LogsServiceServer::new(service.clone()))
+        .add_service(MetricsServiceServer::new(service))
+        .serve_with_incoming_shutdown(listener.accept_stream(), shutdown.clone().map(|_| ()))
+        .await;
+    // We need to drop the last copy of ShutdownSignalToken only after server has shut down.
+    drop(shutdown);
+    result.map_err(|error| error!(message = "OTLP/gRPC server failed.", %error))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::log_schema,
+        event::{Event, MetricValue},
+        sources::opentelemetry::proto::{
+            collector::{
+                logs::v1::{logs_service_client::LogsServiceClient, ExportLogsServiceRequest},
+                metrics::v1::{
+                    metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest,
+                },
+            },
+            common::v1::{any_value, AnyValue},
+            logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+            metrics::v1::{
+                metric::Data, number_data_point, Gauge, Metric, NumberDataPoint, ResourceMetrics,
+                ScopeMetrics,
+            },
+        },
+        sources::opentelemetry::tests::source,
+        test_util::{collect_n, trace_init},
+    };
+
+    #[tokio::test]
+    async fn receives_logs() {
+        trace_init();
+
+        let (rx, grpc_address, _) = source().await;
+        let mut client = LogsServiceClient::connect(format!("http://{}", grpc_address))
+            .await
+            .unwrap();
+
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                scope_logs: vec![ScopeLogs {
+                    log_records: vec![LogRecord {
+                        body: Some(AnyValue {
+                            value: Some(any_value::Value::StringValue("hello".into())),
+                        }),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        client.export(request).await.unwrap();
+
+        let events = collect_n(rx, 1).await.unwrap();
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "hello".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_metrics() {
+        trace_init();
+
+        let (rx, grpc_address, _) = source().await;
+        let mut client = MetricsServiceClient::connect(format!("http://{}", grpc_address))
+            .await
+            .unwrap();
+
+        let request = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                scope_metrics: vec![ScopeMetrics {
+                    metrics: vec![Metric {
+                        name: "load".into(),
+                        data: Some(Data::Gauge(Gauge {
+                            data_points: vec![NumberDataPoint {
+                                value: Some(number_data_point::Value::AsDouble(0.5)),
+                                ..Default::default()
+                            }],
+                        })),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        client.export(request).await.unwrap();
+
+        let events = collect_n(rx, 1).await.unwrap();
+        let metric = events.into_iter().next().map(Event::into_metric).unwrap();
+        assert_eq!(metric.name, "load");
+        assert_eq!(metric.value, MetricValue::Gauge { value: 0.5 });
+    }
+}
@@ -0,0 +1,253 @@
+use super::{
+    convert,
+    proto::collector::{
+        logs::v1::ExportLogsServiceResponse, metrics::v1::ExportMetricsServiceResponse,
+    },
+    send_events,
+};
+use crate::{
+    event::Event, internal_events::OpenTelemetryProtoDecodeError, shutdown::ShutdownSignal,
+    tls::MaybeTlsListener, Pipeline,
+};
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+use futures::FutureExt;
+use http::{header::CONTENT_TYPE, StatusCode};
+use prost::Message;
+use std::io::Read;
+use warp::{filters::BoxedFilter, path, reply::Response, Filter, Reply};
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// The largest request body accepted once decompressed, so that a small
+/// compressed request can't make us allocate without bounds.
+const MAX_DECOMPRESSED_SIZE: usize = 32 * 1024 * 1024;
+
+pub(super) async fn run(
+    listener: MaybeTlsListener,
+    out: Pipeline,
+    shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let logs = export_service(
+        path!("v1" / "logs").boxed(),
+        out.clone(),
+        convert::logs,
+        ExportLogsServiceResponse::default,
+    );
+    let metrics = export_service(
+        path!("v1" / "metrics").boxed(),
+        out,
+        convert::metrics,
+        ExportMetricsServiceResponse::default,
+    );
+
+    let _ = warp::serve(logs.or(metrics).unify())
+        .serve_incoming_with_graceful_shutdown(
+            listener.accept_stream(),
+            shutdown.clone().map(|_| ()),
+        )
+        .await;
+    // We need to drop the last copy of ShutdownSignalToken only after server has shut down.
+    drop(shutdown);
+    Ok(())
+}
+
+/// Accepts protobuf encoded export requests on `path`, optionally compressed
+/// with gzip, and answers them with a protobuf encoded response.
+fn export_service<Req, Resp>(
+    path: BoxedFilter<()>,
+    out: Pipeline,
+    convert: fn(Req) -> Vec<Event>,
+    response: fn() -> Resp,
+) -> BoxedFilter<(Response,)>
+where
+    Req: Message + Default + 'static,
+    Resp: Message + 'static,
+{
+    warp::post()
+        .and(path)
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::header::optional::<String>("content-encoding"))
+        .and(warp::body::bytes())
+        .and_then(
+            move |content_type: Option<String>, encoding: Option<String>, body: Bytes| {
+                let out = out.clone();
+                async move {
+                    if content_type.as_deref() != Some(PROTOBUF_CONTENT_TYPE) {
+                        return Ok::<_, warp::Rejection>(status(
+                            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                        ));
+                    }
+
+                    let body = match decompress(encoding.as_deref(), body) {
+                        Ok(body) => body,
+                        Err(code) => return Ok(status(code)),
+                    };
+                    let request = match Req::decode(body.as_ref()) {
+                        Ok(request) => request,
+                        Err(error) => {
+                            emit!(OpenTelemetryProtoDecodeError { error });
+                            return Ok(status(StatusCode::BAD_REQUEST));
+                        }
+                    };
+
+                    Ok(match send_events(out, convert(request), body.len()).await {
+                        Ok(()) => protobuf(response()),
+                        Err(()) => status(StatusCode::SERVICE_UNAVAILABLE),
+                    })
+                }
+            },
+        )
+        .boxed()
+}
+
+fn decompress(encoding: Option<&str>, body: Bytes) -> Result<Bytes, StatusCode> {
+    match encoding {
+        None | Some("identity") => Ok(body),
+        Some("gzip") => {
+            let mut decoded = Vec::new();
+            GzDecoder::new(body.as_ref())
+                .take(MAX_DECOMPRESSED_SIZE as u64 + 1)
+                .read_to_end(&mut decoded)
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            if decoded.len() > MAX_DECOMPRESSED_SIZE {
+                return Err(StatusCode::PAYLOAD_TOO_LARGE);
+            }
+            Ok(decoded.into())
+        }
+        Some(_) => Err(StatusCode::UNSUPPORTED_MEDIA_TYPE),
+    }
+}
+
+fn protobuf(message: impl Message) -> Response {
+    let mut body = Vec::with_capacity(message.encoded_len());
+    message
+        .encode(&mut body)
+        .expect("Vec has enough capacity for the message");
+    warp::reply::with_header(body, CONTENT_TYPE, PROTOBUF_CONTENT_TYPE).into_response()
+}
+
+fn status(code: StatusCode) -> Response {
+    warp::reply::with_status(warp::reply(), code).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::log_schema,
+        sources::opentelemetry::{
+            proto::{
+                collector::logs::v1::ExportLogsServiceRequest,
+                common::v1::{any_value, AnyValue},
+                logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+            },
+            tests::source,
+        },
+        test_util::{collect_n, trace_init},
+    };
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    fn logs_request() -> Vec<u8> {
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                scope_logs: vec![ScopeLogs {
+                    log_records: vec![LogRecord {
+                        body: Some(AnyValue {
+                            value: Some(any_value::Value::StringValue("hello".into())),
+                        }),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let mut body = Vec::new();
+        request.encode(&mut body).unwrap();
+        body
+    }
+
+    async fn post(
+        address: std::net::SocketAddr,
+        content_type: &str,
+        encoding: Option<&str>,
+        body: Vec<u8>,
+    ) -> reqwest::Response {
+        let mut request = reqwest::Client::new()
+            .post(&format!("http://{}/v1/logs", address))
+            .header("content-type", content_type)
+            .body(body);
+        if let Some(encoding) = encoding {
+            request = request.header("content-encoding", encoding);
+        }
+        request.send().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn receives_logs() {
+        trace_init();
+
+        let (rx, _, address) = source().await;
+
+        let response = post(address, PROTOBUF_CONTENT_TYPE, None, logs_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], PROTOBUF_CONTENT_TYPE);
+        let body = response.bytes().await.unwrap();
+        assert!(ExportLogsServiceResponse::decode(body).is_ok());
+
+        let events = collect_n(rx, 1).await.unwrap();
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "hello".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn receives_gzipped_logs() {
+        trace_init();
+
+        let (rx, _, address) = source().await;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&logs_request()).unwrap();
+        let body = encoder.finish().unwrap();
+
+        let response = post(address, PROTOBUF_CONTENT_TYPE, Some("gzip"), body).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let events = collect_n(rx, 1).await.unwrap();
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "hello".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_requests() {
+        trace_init();
+
+        let (_rx, _, address) = source().await;
+
+        let response = post(address, "application/json", None, b"{}".to_vec()).await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let response = post(address, PROTOBUF_CONTENT_TYPE, None, vec![0xff; 4]).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn limits_decompressed_size() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&vec![0; MAX_DECOMPRESSED_SIZE + 1])
+            .unwrap();
+        let body = encoder.finish().unwrap();
+
+        assert_eq!(
+            decompress(Some("gzip"), body.into()),
+            Err(StatusCode::PAYLOAD_TOO_LARGE)
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Receives logs and metrics over the OpenTelemetry protocol (OTLP), both as
+//! gRPC and as protobuf over HTTP.
+
+use crate::{
+    config::{DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    event::Event,
+    internal_events::OpenTelemetryEventsReceived,
+    shutdown::ShutdownSignal,
+    tls::{MaybeTlsSettings, TlsConfig},
+    Pipeline,
+};
+use futures::{compat::Future01CompatExt, FutureExt, TryFutureExt};
+use futures01::Sink;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+mod convert;
+mod grpc;
+mod http;
+
+/// Code generated from the OTLP protobuf definitions in `proto/opentelemetry`.
+#[allow(clippy::all)]
+pub mod proto {
+    pub mod collector {
+        pub mod logs {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.collector.logs.v1.rs"
+                ));
+            }
+        }
+        pub mod metrics {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.collector.metrics.v1.rs"
+                ));
+            }
+        }
+    }
+    pub mod common {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.common.v1.rs"
+            ));
+        }
+    }
+    pub mod logs {
+        pub mod v1 {
+            include!(concat!(env!("OUT_DIR"), "/opentelemetry.proto.logs.v1.rs"));
+        }
+    }
+    pub mod metrics {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.metrics.v1.rs"
+            ));
+        }
+    }
+    pub mod resource {
+        pub mod v1 {
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/opentelemetry.proto.resource.v1.rs"
+            ));
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpentelemetryConfig {
+    /// Where to accept OTLP/gRPC requests
+    grpc: ListenerConfig,
+    /// Where to accept OTLP/HTTP requests
+    http: ListenerConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct ListenerConfig {
+    address: SocketAddr,
+    tls: Option<TlsConfig>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<OpentelemetryConfig>("opentelemetry")
+}
+
+impl GenerateConfig for OpentelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            grpc: ListenerConfig {
+                address: "0.0.0.0:4317".parse().unwrap(),
+                tls: None,
+            },
+            http: ListenerConfig {
+                address: "0.0.0.0:4318".parse().unwrap(),
+                tls: None,
+            },
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SourceConfig for OpentelemetryConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let grpc_tls = MaybeTlsSettings::from_config(&self.grpc.tls, true)?.with_http2_alpn();
+        let grpc_listener = grpc_tls.bind(&self.grpc.address).await?;
+        let http_tls = MaybeTlsSettings::from_config(&self.http.tls, true)?;
+        let http_listener = http_tls.bind(&self.http.address).await?;
+
+        let grpc = grpc::run(grpc_listener, out.clone(), shutdown.clone());
+        let http = http::run(http_listener, out, shutdown);
+        let fut = async move {
+            futures::try_join!(grpc, http)?;
+            Ok(())
+        };
+        Ok(Box::new(fut.boxed().compat()))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn source_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+}
+
+/// Forwards the events of one export request, failing only once the
+/// downstream components are gone.
+async fn send_events(out: Pipeline, events: Vec<Event>, byte_size: usize) -> Result<(), ()> {
+    emit!(OpenTelemetryEventsReceived {
+        count: events.len(),
+        byte_size,
+    });
+    out.send_all(futures01::stream::iter_ok(events))
+        .compat()
+        .await
+        .map(|_| ())
+        .map_err(|error| {
+            error!(message = "Failed to forward events, downstream is closed.", %error);
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{next_addr, wait_for_tcp};
+    use futures01::sync::mpsc;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<OpentelemetryConfig>();
+    }
+
+    /// Starts a source, returning its output and its gRPC and HTTP addresses
+    pub(super) async fn source() -> (mpsc::Receiver<Event>, SocketAddr, SocketAddr) {
+        let (sender, recv) = Pipeline::new_test();
+        let grpc_address = next_addr();
+        let http_address = next_addr();
+        let config = OpentelemetryConfig {
+            grpc: ListenerConfig {
+                address: grpc_address,
+                tls: None,
+            },
+            http: ListenerConfig {
+                address: http_address,
+                tls: None,
+            },
+        };
+        let source = config
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                sender,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(source.compat());
+        wait_for_tcp(grpc_address).await;
+        wait_for_tcp(http_address).await;
+        (recv, grpc_address, http_address)
+    }
+}
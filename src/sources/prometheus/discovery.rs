@@ -0,0 +1,312 @@
+//! Discovers the targets to scrape, from the configured endpoints and from
+//! target files like Prometheus' `file_sd_configs`, and resolves their labels.
+
+use super::relabel::Relabel;
+use crate::internal_events::{PrometheusInvalidTarget, PrometheusTargetsFileError};
+use http::Uri;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+const ADDRESS_LABEL: &str = "__address__";
+const SCHEME_LABEL: &str = "__scheme__";
+const METRICS_PATH_LABEL: &str = "__metrics_path__";
+const PARAM_LABEL_PREFIX: &str = "__param_";
+const FILEPATH_LABEL: &str = "__meta_filepath";
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FileSdConfig {
+    pub files: Vec<String>,
+}
+
+/// A group of targets sharing the same labels, as found in target files.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+struct TargetGroup {
+    targets: Vec<String>,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Snafu)]
+pub enum TargetsFileError {
+    #[snafu(display("Could not read targets file: {}", source))]
+    ReadFile { source: std::io::Error },
+    #[snafu(display("Could not parse targets file as JSON: {}", source))]
+    ParseJson { source: serde_json::Error },
+    #[snafu(display("Could not parse targets file as YAML: {}", source))]
+    ParseYaml { source: serde_yaml::Error },
+    #[snafu(display("Targets files must have a `.json`, `.yml` or `.yaml` extension"))]
+    UnknownExtension,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub url: Uri,
+    pub labels: BTreeMap<String, String>,
+}
+
+pub struct Discovery {
+    static_targets: Vec<BTreeMap<String, String>>,
+    patterns: Vec<String>,
+    files: BTreeMap<PathBuf, Vec<TargetGroup>>,
+    defaults: BTreeMap<String, String>,
+    relabels: Vec<Relabel>,
+}
+
+impl Discovery {
+    pub fn new(
+        static_targets: Vec<BTreeMap<String, String>>,
+        patterns: Vec<String>,
+        defaults: BTreeMap<String, String>,
+        relabels: Vec<Relabel>,
+    ) -> Self {
+        Self {
+            static_targets,
+            patterns,
+            files: BTreeMap::new(),
+            defaults,
+            relabels,
+        }
+    }
+
+    /// Returns the targets to scrape, re-reading the target files first.
+    pub fn targets(&mut self) -> Vec<Target> {
+        self.refresh_files();
+
+        let file_targets = self.files.iter().flat_map(|(path, groups)| {
+            groups.iter().flat_map(move |group| {
+                group.targets.iter().map(move |address| {
+                    let mut labels = group.labels.clone();
+                    labels.insert(ADDRESS_LABEL.into(), address.clone());
+                    labels.insert(FILEPATH_LABEL.into(), path.display().to_string());
+                    labels
+                })
+            })
+        });
+
+        self.static_targets
+            .iter()
+            .cloned()
+            .chain(file_targets)
+            .filter_map(|labels| self.resolve(labels))
+            .collect()
+    }
+
+    fn refresh_files(&mut self) {
+        let mut found = HashSet::new();
+        for pattern in &self.patterns {
+            // Patterns are validated when the source is built.
+            let paths = match glob::glob(pattern) {
+                Ok(paths) => paths,
+                Err(_) => continue,
+            };
+            for path in paths.filter_map(Result::ok) {
+                // Keep the last targets read from a file if it is being
+                // rewritten, rather than dropping them for a scrape.
+                match read_targets_file(&path) {
+                    Ok(groups) => {
+                        self.files.insert(path.clone(), groups);
+                    }
+                    Err(error) => emit!(PrometheusTargetsFileError { path: &path, error }),
+                }
+                found.insert(path);
+            }
+        }
+        self.files.retain(|path, _| found.contains(path));
+    }
+
+    /// Applies the defaults and the relabeling to the labels of a target,
+    /// and builds the URL to scrape from them.
+    fn resolve(&self, mut labels: BTreeMap<String, String>) -> Option<Target> {
+        for (name, value) in &self.defaults {
+            labels.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+        if !self
+            .relabels
+            .iter()
+            .all(|relabel| relabel.apply(&mut labels))
+        {
+            return None;
+        }
+
+        let address = labels.get(ADDRESS_LABEL).cloned().unwrap_or_default();
+        let params = labels
+            .iter()
+            .filter_map(|(name, value)| {
+                name.strip_prefix(PARAM_LABEL_PREFIX)
+                    .map(|param| (param, value.as_str()))
+            })
+            .collect::<Vec<_>>();
+        let query = if params.is_empty() {
+            String::new()
+        } else {
+            let query = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(params)
+                .finish();
+            format!("?{}", query)
+        };
+        let url = format!(
+            "{}://{}{}{}",
+            labels
+                .get(SCHEME_LABEL)
+                .map(String::as_str)
+                .unwrap_or("http"),
+            address,
+            labels
+                .get(METRICS_PATH_LABEL)
+                .map(String::as_str)
+                .unwrap_or(""),
+            query
+        );
+        let url = match url.parse::<Uri>() {
+            Ok(url) => url,
+            Err(error) => {
+                emit!(PrometheusInvalidTarget { url, error });
+                return None;
+            }
+        };
+
+        labels.entry("instance".into()).or_insert(address);
+        labels.retain(|name, _| !name.starts_with("__"));
+        Some(Target { url, labels })
+    }
+}
+
+/// Splits a configured endpoint into the labels describing its target.
+pub fn endpoint_labels(endpoint: &Uri) -> BTreeMap<String, String> {
+    let mut labels = BTreeMap::new();
+    if let Some(authority) = endpoint.authority() {
+        labels.insert(ADDRESS_LABEL.into(), authority.to_string());
+    }
+    if let Some(scheme) = endpoint.scheme_str() {
+        labels.insert(SCHEME_LABEL.into(), scheme.into());
+    }
+    labels.insert(METRICS_PATH_LABEL.into(), endpoint.path().into());
+    if let Some(query) = endpoint.query() {
+        for (name, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            labels.insert(format!("{}{}", PARAM_LABEL_PREFIX, name), value.into());
+        }
+    }
+    labels
+}
+
+pub fn default_labels(job: &str, scheme: &str, metrics_path: &str) -> BTreeMap<String, String> {
+    vec![
+        ("job".to_owned(), job.to_owned()),
+        (SCHEME_LABEL.to_owned(), scheme.to_owned()),
+        (METRICS_PATH_LABEL.to_owned(), metrics_path.to_owned()),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn read_targets_file(path: &Path) -> Result<Vec<TargetGroup>, TargetsFileError> {
+    let contents = fs::read(path).context(ReadFile)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_slice(&contents).context(ParseJson),
+        Some("yml") | Some("yaml") => serde_yaml::from_slice(&contents).context(ParseYaml),
+        _ => Err(TargetsFileError::UnknownExtension),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn labels(labels: &[(&str, &str)]) -> BTreeMap<String, String> {
+        labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn relabel(config: &str) -> Relabel {
+        Relabel::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn resolves_endpoints() {
+        let endpoint = "https://node:9100/probe?module=http_2xx"
+            .parse::<Uri>()
+            .unwrap();
+        let mut target_labels = endpoint_labels(&endpoint);
+        target_labels.insert("env".into(), "prod".into());
+
+        let mut discovery = Discovery::new(
+            vec![target_labels],
+            vec![],
+            default_labels("node", "http", "/metrics"),
+            vec![],
+        );
+
+        assert_eq!(
+            discovery.targets(),
+            vec![Target {
+                url: endpoint,
+                labels: labels(&[("env", "prod"), ("instance", "node:9100"), ("job", "node")]),
+            }]
+        );
+    }
+
+    #[test]
+    fn discovers_targets_from_files() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("web.json"),
+            r#"[{"targets": ["web-1:9100", "web-2:9100"], "labels": {"team": "web"}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("db.yml"),
+            "- targets: ['db-1:9187']\n  labels:\n    __metrics_path__: /stats\n",
+        )
+        .unwrap();
+
+        let mut discovery = Discovery::new(
+            vec![],
+            vec![
+                format!("{}/*.json", dir.display()),
+                format!("{}/*.yml", dir.display()),
+            ],
+            default_labels("nodes", "http", "/metrics"),
+            vec![relabel(
+                r#"
+                source_labels = ["__address__"]
+                regex = "web-2:.*"
+                action = "drop"
+                "#,
+            )],
+        );
+
+        let targets = discovery.targets();
+        assert_eq!(
+            targets,
+            vec![
+                Target {
+                    url: "http://db-1:9187/stats".parse().unwrap(),
+                    labels: labels(&[("instance", "db-1:9187"), ("job", "nodes")]),
+                },
+                Target {
+                    url: "http://web-1:9100/metrics".parse().unwrap(),
+                    labels: labels(&[
+                        ("instance", "web-1:9100"),
+                        ("job", "nodes"),
+                        ("team", "web")
+                    ]),
+                },
+            ]
+        );
+
+        // Unreadable files keep their last targets, removed files lose them.
+        fs::write(dir.join("web.json"), "[{").unwrap();
+        fs::remove_file(dir.join("db.yml")).unwrap();
+        assert_eq!(discovery.targets(), targets[1..].to_vec());
+    }
+}
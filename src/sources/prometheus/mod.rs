@@ -1,35 +1,84 @@
 use crate::{
     config::{self, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
     internal_events::{
         PrometheusErrorResponse, PrometheusEventReceived, PrometheusHttpError,
-        PrometheusParseError, PrometheusRequestCompleted,
+        PrometheusParseError, PrometheusRequestCompleted, PrometheusScrapeTimeout,
     },
     shutdown::ShutdownSignal,
     Event, Pipeline,
 };
-use futures::{compat::Sink01CompatExt, future, stream, FutureExt, StreamExt, TryFutureExt};
+use chrono::Utc;
+use futures::{
+    compat::Sink01CompatExt,
+    stream::{self, FuturesUnordered},
+    FutureExt, SinkExt, StreamExt, TryFutureExt,
+};
 use futures01::Sink;
-use hyper::{Body, Client, Request};
+use hyper::{client::HttpConnector, Body, Client, Request};
 use hyper_openssl::HttpsConnector;
 use serde::{Deserialize, Serialize};
-use snafu::ResultExt;
-use std::time::{Duration, Instant};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
 
+mod discovery;
 pub mod parser;
+mod relabel;
+mod remote_write;
+
+pub use discovery::TargetsFileError;
+use discovery::{default_labels, endpoint_labels, Discovery, FileSdConfig, Target};
+use relabel::{Relabel, RelabelConfig};
+
+#[derive(Debug, Snafu)]
+enum ConfigError {
+    #[snafu(display("`scrape_timeout_secs` must not be greater than `scrape_interval_secs`"))]
+    ScrapeTimeoutTooLong,
+    #[snafu(display("Invalid targets file pattern {:?}: {}", pattern, source))]
+    InvalidPattern {
+        pattern: String,
+        source: glob::PatternError,
+    },
+}
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct PrometheusConfig {
     // Deprecated name
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
+    scrape_timeout_secs: Option<u64>,
+    job_name: Option<String>,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+    #[serde(default)]
+    honor_labels: bool,
+    #[serde(default)]
+    file_sd_configs: Vec<FileSdConfig>,
+    #[serde(default = "default_scheme")]
+    scheme: String,
+    #[serde(default = "default_metrics_path")]
+    metrics_path: String,
+    #[serde(default)]
+    relabel_configs: Vec<RelabelConfig>,
 }
 
 pub fn default_scrape_interval_secs() -> u64 {
     15
 }
 
+fn default_scheme() -> String {
+    "http".into()
+}
+
+fn default_metrics_path() -> String {
+    "/metrics".into()
+}
+
 inventory::submit! {
     SourceDescription::new::<PrometheusConfig>("prometheus")
 }
@@ -39,6 +88,14 @@ impl GenerateConfig for PrometheusConfig {
         toml::Value::try_from(Self {
             endpoints: vec!["http://localhost:9090/metrics".to_string()],
             scrape_interval_secs: default_scrape_interval_secs(),
+            scrape_timeout_secs: None,
+            job_name: None,
+            labels: BTreeMap::new(),
+            honor_labels: false,
+            file_sd_configs: vec![],
+            scheme: default_scheme(),
+            metrics_path: default_metrics_path(),
+            relabel_configs: vec![],
         })
         .unwrap()
     }
@@ -49,17 +106,60 @@ impl GenerateConfig for PrometheusConfig {
 impl SourceConfig for PrometheusConfig {
     async fn build(
         &self,
-        _name: &str,
+        name: &str,
         _globals: &GlobalOptions,
         shutdown: ShutdownSignal,
         out: Pipeline,
     ) -> crate::Result<super::Source> {
-        let urls = self
+        let scrape_timeout_secs = self
+            .scrape_timeout_secs
+            .unwrap_or(self.scrape_interval_secs);
+        if scrape_timeout_secs > self.scrape_interval_secs {
+            return Err(ConfigError::ScrapeTimeoutTooLong.into());
+        }
+
+        let static_targets = self
             .endpoints
             .iter()
-            .map(|s| s.parse::<http::Uri>().context(super::UriParseError))
-            .collect::<Result<Vec<http::Uri>, super::BuildError>>()?;
-        Ok(prometheus(urls, self.scrape_interval_secs, shutdown, out))
+            .map(|s| {
+                let url = s.parse::<http::Uri>().context(super::UriParseError)?;
+                let mut labels = endpoint_labels(&url);
+                labels.extend(self.labels.clone());
+                Ok::<_, super::BuildError>(labels)
+            })
+            .collect::<Result<Vec<_>, super::BuildError>>()?;
+
+        let patterns = self
+            .file_sd_configs
+            .iter()
+            .flat_map(|config| config.files.iter().cloned())
+            .collect::<Vec<_>>();
+        for pattern in &patterns {
+            glob::Pattern::new(pattern).context(InvalidPattern { pattern })?;
+        }
+
+        let relabels = self
+            .relabel_configs
+            .iter()
+            .map(Relabel::new)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let job = self.job_name.as_deref().unwrap_or(name);
+        let discovery = Discovery::new(
+            static_targets,
+            patterns,
+            default_labels(job, &self.scheme, &self.metrics_path),
+            relabels,
+        );
+
+        Ok(prometheus(
+            discovery,
+            Duration::from_secs(self.scrape_interval_secs),
+            Duration::from_secs(scrape_timeout_secs),
+            self.honor_labels,
+            shutdown,
+            out,
+        ))
     }
 
     fn output_type(&self) -> crate::config::DataType {
@@ -72,103 +172,262 @@ impl SourceConfig for PrometheusConfig {
 }
 
 fn prometheus(
-    urls: Vec<http::Uri>,
-    interval: u64,
+    mut discovery: Discovery,
+    interval: Duration,
+    timeout: Duration,
+    honor_labels: bool,
     shutdown: ShutdownSignal,
     out: Pipeline,
 ) -> super::Source {
-    let out = out
+    let mut out = out
         .sink_map_err(|error| error!(message = "Error sending metric.", %error))
         .sink_compat();
-    let task = tokio::time::interval(Duration::from_secs(interval))
-        .take_until(shutdown)
-        .map(move |_| stream::iter(urls.clone()))
-        .flatten()
-        .map(move |url| {
-            let https = HttpsConnector::new().expect("TLS initialization failed");
-            let client = Client::builder().build(https);
-
-            let request = Request::get(&url)
-                .body(Body::empty())
-                .expect("error creating request");
-
-            let start = Instant::now();
-            client
-                .request(request)
-                .and_then(|response| async move {
-                    let (header, body) = response.into_parts();
-                    let body = hyper::body::to_bytes(body).await?;
-                    Ok((header, body))
-                })
-                .into_stream()
-                .filter_map(move |response| {
-                    future::ready(match response {
-                        Ok((header, body)) if header.status == hyper::StatusCode::OK => {
-                            emit!(PrometheusRequestCompleted {
-                                start,
-                                end: Instant::now()
-                            });
-
-                            let byte_size = body.len();
-                            let body = String::from_utf8_lossy(&body);
-
-                            match parser::parse(&body) {
-                                Ok(metrics) => {
-                                    emit!(PrometheusEventReceived {
-                                        byte_size,
-                                        count: metrics.len(),
-                                    });
-                                    Some(stream::iter(metrics).map(Event::Metric).map(Ok))
-                                }
-                                Err(error) => {
-                                    if url.path() == "/" {
-                                        // https://github.com/timberio/vector/pull/3801#issuecomment-700723178
-                                        warn!(
-                                            message = "No path is set on the endpoint and we got a parse error, did you mean to use /metrics? This behavior changed in version 0.11.",
-                                            endpoint = %url
-                                        );
-                            }
-                                    emit!(PrometheusParseError {
-                                        error,
-                                        url: url.clone(),
-                                        body,
-                                    });
-                                    None
-                                }
-                            }
-                        }
-                        Ok((header, _)) => {
-                            if header.status == hyper::StatusCode::NOT_FOUND && url.path() == "/" {
-                                // https://github.com/timberio/vector/pull/3801#issuecomment-700723178
-                                warn!(
-                                    message = "No path is set on the endpoint and we got a 404, did you mean to use /metrics? This behavior changed in version 0.11.",
-                                    endpoint = %url
-                                );
+    let https = HttpsConnector::new().expect("TLS initialization failed");
+    let client = Client::builder().build(https);
+
+    let task = async move {
+        let mut ticks = tokio::time::interval(interval).take_until(shutdown);
+        while ticks.next().await.is_some() {
+            // Targets are scraped concurrently, each within its own timeout,
+            // and their metrics are sent as soon as they come in.
+            let client = &client;
+            let mut scrapes = discovery
+                .targets()
+                .into_iter()
+                .map(|target| async move {
+                    let metrics =
+                        match tokio::time::timeout(timeout, scrape(client, &target.url)).await {
+                            Ok(metrics) => metrics,
+                            Err(_) => {
+                                emit!(PrometheusScrapeTimeout {
+                                    url: target.url.clone(),
+                                    timeout,
+                                });
+                                None
                             }
-                            emit!(PrometheusErrorResponse {
-                                code: header.status,
-                                url: url.clone(),
-                            });
-                            None
-                        }
-                        Err(error) => {
-                            emit!(PrometheusHttpError {
-                                error,
-                                url: url.clone(),
-                            });
-                            None
-                        }
-                    })
+                        };
+                    (target, metrics)
                 })
-                .flatten()
-        })
-        .flatten()
-        .forward(out)
-        .inspect(|_| info!("Finished sending."));
+                .collect::<FuturesUnordered<_>>();
+
+            while let Some((target, metrics)) = scrapes.next().await {
+                let up = metrics.is_some();
+
+                let mut events = metrics
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|metric| Event::Metric(with_target_labels(metric, &target, honor_labels)))
+                    .collect::<Vec<_>>();
+                events.push(Event::Metric(up_metric(&target, up)));
+
+                out.send_all(&mut stream::iter(events).map(Ok)).await?;
+            }
+        }
+        Ok::<(), ()>(())
+    }
+    .inspect(|_| info!("Finished sending."));
 
     Box::new(task.boxed().compat())
 }
 
+/// Scrapes a target, returning `None` if it could not be scraped.
+async fn scrape(
+    client: &Client<HttpsConnector<HttpConnector>>,
+    url: &http::Uri,
+) -> Option<Vec<Metric>> {
+    let request = Request::get(url)
+        .body(Body::empty())
+        .expect("error creating request");
+
+    let start = Instant::now();
+    let response = client
+        .request(request)
+        .and_then(|response| async move {
+            let (header, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            Ok((header, body))
+        })
+        .await;
+
+    match response {
+        Ok((header, body)) if header.status == hyper::StatusCode::OK => {
+            emit!(PrometheusRequestCompleted {
+                start,
+                end: Instant::now()
+            });
+
+            let byte_size = body.len();
+            let body = String::from_utf8_lossy(&body);
+
+            match parser::parse(&body) {
+                Ok(metrics) => {
+                    emit!(PrometheusEventReceived {
+                        byte_size,
+                        count: metrics.len(),
+                    });
+                    Some(metrics)
+                }
+                Err(error) => {
+                    if url.path() == "/" {
+                        // https://github.com/timberio/vector/pull/3801#issuecomment-700723178
+                        warn!(
+                            message = "No path is set on the endpoint and we got a parse error, did you mean to use /metrics? This behavior changed in version 0.11.",
+                            endpoint = %url
+                        );
+                    }
+                    emit!(PrometheusParseError {
+                        error,
+                        url: url.clone(),
+                        body,
+                    });
+                    None
+                }
+            }
+        }
+        Ok((header, _)) => {
+            if header.status == hyper::StatusCode::NOT_FOUND && url.path() == "/" {
+                // https://github.com/timberio/vector/pull/3801#issuecomment-700723178
+                warn!(
+                    message = "No path is set on the endpoint and we got a 404, did you mean to use /metrics? This behavior changed in version 0.11.",
+                    endpoint = %url
+                );
+            }
+            emit!(PrometheusErrorResponse {
+                code: header.status,
+                url: url.clone(),
+            });
+            None
+        }
+        Err(error) => {
+            emit!(PrometheusHttpError {
+                error,
+                url: url.clone(),
+            });
+            None
+        }
+    }
+}
+
+/// Adds the labels of the target to the tags of a scraped metric. Scraped
+/// tags conflicting with them are kept if `honor_labels` is set, and are
+/// renamed to `exported_<name>` otherwise.
+fn with_target_labels(mut metric: Metric, target: &Target, honor_labels: bool) -> Metric {
+    let tags = metric.tags.get_or_insert_with(BTreeMap::new);
+    for (name, value) in &target.labels {
+        if honor_labels {
+            tags.entry(name.clone()).or_insert_with(|| value.clone());
+        } else {
+            if let Some(exported) = tags.remove(name) {
+                tags.insert(format!("exported_{}", name), exported);
+            }
+            tags.insert(name.clone(), value.clone());
+        }
+    }
+    if tags.is_empty() {
+        metric.tags = None;
+    }
+    metric
+}
+
+/// The `up` metric reports whether the target could be scraped.
+fn up_metric(target: &Target, up: bool) -> Metric {
+    Metric {
+        name: "up".into(),
+        namespace: None,
+        timestamp: Some(Utc::now()),
+        tags: if target.labels.is_empty() {
+            None
+        } else {
+            Some(target.labels.clone())
+        },
+        kind: MetricKind::Absolute,
+        value: MetricValue::Gauge {
+            value: if up { 1.0 } else { 0.0 },
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(labels: &[(&str, &str)]) -> Target {
+        Target {
+            url: "http://localhost:9100/metrics".parse().unwrap(),
+            labels: labels
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn metric(tags: &[(&str, &str)]) -> Metric {
+        Metric {
+            name: "requests_total".into(),
+            namespace: None,
+            timestamp: None,
+            tags: Some(
+                tags.iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            ),
+            kind: MetricKind::Absolute,
+            value: MetricValue::Counter { value: 1.0 },
+        }
+    }
+
+    #[test]
+    fn renames_conflicting_tags() {
+        let target = target(&[("instance", "localhost:9100"), ("job", "node")]);
+
+        assert_eq!(
+            with_target_labels(metric(&[("code", "200"), ("job", "app")]), &target, false),
+            metric(&[
+                ("code", "200"),
+                ("exported_job", "app"),
+                ("instance", "localhost:9100"),
+                ("job", "node"),
+            ])
+        );
+    }
+
+    #[test]
+    fn honors_scraped_tags() {
+        let target = target(&[("instance", "localhost:9100"), ("job", "node")]);
+
+        assert_eq!(
+            with_target_labels(metric(&[("code", "200"), ("job", "app")]), &target, true),
+            metric(&[
+                ("code", "200"),
+                ("instance", "localhost:9100"),
+                ("job", "app"),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_scrape_timeout_above_interval() {
+        let config: PrometheusConfig = toml::from_str(
+            r#"
+            endpoints = ["http://localhost:9100/metrics"]
+            scrape_interval_secs = 5
+            scrape_timeout_secs = 10
+            "#,
+        )
+        .unwrap();
+
+        assert!(config
+            .build(
+                "in",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                Pipeline::new_test().0,
+            )
+            .await
+            .is_err());
+    }
+}
+
 #[cfg(feature = "sinks-prometheus")]
 #[cfg(test)]
 mod test {
@@ -244,6 +503,14 @@ mod test {
             PrometheusConfig {
                 endpoints: vec![format!("http://{}", in_addr)],
                 scrape_interval_secs: 1,
+                scrape_timeout_secs: None,
+                job_name: None,
+                labels: BTreeMap::new(),
+                honor_labels: false,
+                file_sd_configs: vec![],
+                scheme: default_scheme(),
+                metrics_path: default_metrics_path(),
+                relabel_configs: vec![],
             },
         );
         config.add_sink(
@@ -273,33 +540,38 @@ mod test {
             .lines()
             .collect::<Vec<_>>();
 
+        // Every metric is tagged with its target, which also reports an `up` metric.
+        let target = format!("instance=\"{}\",job=\"in\"", in_addr);
         assert_eq!(lines, vec![
             "# HELP vector_promhttp_metric_handler_requests_total promhttp_metric_handler_requests_total",
             "# TYPE vector_promhttp_metric_handler_requests_total counter",
-            "vector_promhttp_metric_handler_requests_total{code=\"200\"} 100",
-            "vector_promhttp_metric_handler_requests_total{code=\"404\"} 7",
+            format!("vector_promhttp_metric_handler_requests_total{{code=\"200\",{}}} 100", target).as_str(),
+            format!("vector_promhttp_metric_handler_requests_total{{code=\"404\",{}}} 7", target).as_str(),
             "# HELP vector_prometheus_remote_storage_samples_in_total prometheus_remote_storage_samples_in_total",
             "# TYPE vector_prometheus_remote_storage_samples_in_total gauge",
-            "vector_prometheus_remote_storage_samples_in_total 57011636",
+            format!("vector_prometheus_remote_storage_samples_in_total{{{}}} 57011636", target).as_str(),
             "# HELP vector_http_request_duration_seconds http_request_duration_seconds",
             "# TYPE vector_http_request_duration_seconds histogram",
-            "vector_http_request_duration_seconds_bucket{le=\"0.05\"} 24054",
-            "vector_http_request_duration_seconds_bucket{le=\"0.1\"} 33444",
-            "vector_http_request_duration_seconds_bucket{le=\"0.2\"} 100392",
-            "vector_http_request_duration_seconds_bucket{le=\"0.5\"} 129389",
-            "vector_http_request_duration_seconds_bucket{le=\"1\"} 133988",
-            "vector_http_request_duration_seconds_bucket{le=\"+Inf\"} 144320",
-            "vector_http_request_duration_seconds_sum 53423",
-            "vector_http_request_duration_seconds_count 144320",
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"0.05\"}} 24054", target).as_str(),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"0.1\"}} 33444", target).as_str(),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"0.2\"}} 100392", target).as_str(),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"0.5\"}} 129389", target).as_str(),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"1\"}} 133988", target).as_str(),
+            format!("vector_http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} 144320", target).as_str(),
+            format!("vector_http_request_duration_seconds_sum{{{}}} 53423", target).as_str(),
+            format!("vector_http_request_duration_seconds_count{{{}}} 144320", target).as_str(),
             "# HELP vector_rpc_duration_seconds rpc_duration_seconds",
             "# TYPE vector_rpc_duration_seconds summary",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.01\"} 3102",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.05\"} 3272",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.5\"} 4773",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.9\"} 9001",
-            "vector_rpc_duration_seconds{code=\"200\",quantile=\"0.99\"} 76656",
-            "vector_rpc_duration_seconds_sum{code=\"200\"} 17560473",
-            "vector_rpc_duration_seconds_count{code=\"200\"} 2693",
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.01\"}} 3102", target).as_str(),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.05\"}} 3272", target).as_str(),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.5\"}} 4773", target).as_str(),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.9\"}} 9001", target).as_str(),
+            format!("vector_rpc_duration_seconds{{code=\"200\",{},quantile=\"0.99\"}} 76656", target).as_str(),
+            format!("vector_rpc_duration_seconds_sum{{code=\"200\",{}}} 17560473", target).as_str(),
+            format!("vector_rpc_duration_seconds_count{{code=\"200\",{}}} 2693", target).as_str(),
+            "# HELP vector_up up",
+            "# TYPE vector_up gauge",
+            format!("vector_up{{{}}} 1", target).as_str(),
             ],
         );
 
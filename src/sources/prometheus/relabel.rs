@@ -0,0 +1,241 @@
+//! Prometheus' `relabel_configs`, which rewrite the labels of a target
+//! before it is scraped, or drop the target altogether.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Snafu)]
+pub enum RelabelError {
+    #[snafu(display("Invalid relabeling regex {:?}: {}", regex, source))]
+    InvalidRegex { regex: String, source: regex::Error },
+    #[snafu(display("Relabeling with the `replace` action requires a `target_label`"))]
+    MissingTargetLabel,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelConfig {
+    #[serde(default)]
+    source_labels: Vec<String>,
+    #[serde(default = "default_separator")]
+    separator: String,
+    #[serde(default = "default_regex")]
+    regex: String,
+    target_label: Option<String>,
+    #[serde(default = "default_replacement")]
+    replacement: String,
+    #[serde(default)]
+    action: RelabelAction,
+}
+
+fn default_separator() -> String {
+    ";".into()
+}
+
+fn default_regex() -> String {
+    "(.*)".into()
+}
+
+fn default_replacement() -> String {
+    "$1".into()
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Derivative)]
+#[serde(rename_all = "lowercase")]
+#[derivative(Default)]
+pub enum RelabelAction {
+    /// Sets `target_label` to `replacement` if `regex` matches the
+    /// concatenated `source_labels`.
+    #[derivative(Default)]
+    Replace,
+    /// Drops targets for which `regex` doesn't match the concatenated
+    /// `source_labels`.
+    Keep,
+    /// Drops targets for which `regex` matches the concatenated
+    /// `source_labels`.
+    Drop,
+    /// Copies the labels whose name matches `regex` to the label named by
+    /// `replacement`.
+    Labelmap,
+    /// Removes the labels whose name matches `regex`.
+    Labeldrop,
+    /// Removes the labels whose name doesn't match `regex`.
+    Labelkeep,
+}
+
+/// A compiled `RelabelConfig`.
+#[derive(Clone, Debug)]
+pub struct Relabel {
+    config: RelabelConfig,
+    regex: Regex,
+}
+
+impl Relabel {
+    pub fn new(config: &RelabelConfig) -> Result<Self, RelabelError> {
+        if config.action == RelabelAction::Replace && config.target_label.is_none() {
+            return Err(RelabelError::MissingTargetLabel);
+        }
+        // Like Prometheus, the regex must match the whole value.
+        let regex = Regex::new(&format!("^(?:{})$", config.regex)).context(InvalidRegex {
+            regex: config.regex.clone(),
+        })?;
+        Ok(Self {
+            config: config.clone(),
+            regex,
+        })
+    }
+
+    /// Applies the relabeling to `labels`, returning `false` if the target
+    /// they describe must be dropped.
+    pub fn apply(&self, labels: &mut BTreeMap<String, String>) -> bool {
+        let value = self
+            .config
+            .source_labels
+            .iter()
+            .map(|name| labels.get(name).map(String::as_str).unwrap_or(""))
+            .collect::<Vec<_>>()
+            .join(&self.config.separator);
+
+        match self.config.action {
+            RelabelAction::Replace => {
+                if let Some(captures) = self.regex.captures(&value) {
+                    let target_label = self.config.target_label.as_deref().unwrap_or("");
+                    let mut target = String::new();
+                    captures.expand(target_label, &mut target);
+                    let mut replacement = String::new();
+                    captures.expand(&self.config.replacement, &mut replacement);
+                    if replacement.is_empty() {
+                        labels.remove(&target);
+                    } else if !target.is_empty() {
+                        labels.insert(target, replacement);
+                    }
+                }
+                true
+            }
+            RelabelAction::Keep => self.regex.is_match(&value),
+            RelabelAction::Drop => !self.regex.is_match(&value),
+            RelabelAction::Labelmap => {
+                let mapped = labels
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        self.regex.captures(name).map(|captures| {
+                            let mut target = String::new();
+                            captures.expand(&self.config.replacement, &mut target);
+                            (target, value.clone())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                labels.extend(mapped);
+                true
+            }
+            RelabelAction::Labeldrop => {
+                let regex = &self.regex;
+                labels.retain(|name, _| !regex.is_match(name));
+                true
+            }
+            RelabelAction::Labelkeep => {
+                let regex = &self.regex;
+                labels.retain(|name, _| regex.is_match(name));
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relabel(config: &str) -> Relabel {
+        Relabel::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn labels(labels: &[(&str, &str)]) -> BTreeMap<String, String> {
+        labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn replaces_labels() {
+        let relabel = relabel(
+            r#"
+            source_labels = ["__address__"]
+            regex = "(.*):\\d+"
+            target_label = "host"
+            "#,
+        );
+
+        let mut target = labels(&[("__address__", "web-1:9100")]);
+        assert!(relabel.apply(&mut target));
+        assert_eq!(
+            target,
+            labels(&[("__address__", "web-1:9100"), ("host", "web-1")])
+        );
+
+        // The regex must match the whole value.
+        let mut target = labels(&[("__address__", "web-1")]);
+        assert!(relabel.apply(&mut target));
+        assert_eq!(target, labels(&[("__address__", "web-1")]));
+    }
+
+    #[test]
+    fn keeps_and_drops_targets() {
+        let keep = relabel(
+            r#"
+            source_labels = ["env", "team"]
+            regex = "prod;.*"
+            action = "keep"
+            "#,
+        );
+        assert!(keep.apply(&mut labels(&[("env", "prod"), ("team", "edge")])));
+        assert!(!keep.apply(&mut labels(&[("env", "staging")])));
+
+        let drop = relabel(
+            r#"
+            source_labels = ["env"]
+            regex = "staging"
+            action = "drop"
+            "#,
+        );
+        assert!(drop.apply(&mut labels(&[("env", "prod")])));
+        assert!(!drop.apply(&mut labels(&[("env", "staging")])));
+    }
+
+    #[test]
+    fn maps_and_drops_label_names() {
+        let mut target = labels(&[("__meta_rack", "a1"), ("env", "prod"), ("tmp", "1")]);
+
+        assert!(relabel(
+            r#"
+            regex = "__meta_(.+)"
+            action = "labelmap"
+            "#
+        )
+        .apply(&mut target));
+        assert!(relabel(
+            r#"
+            regex = "tmp"
+            action = "labeldrop"
+            "#
+        )
+        .apply(&mut target));
+
+        assert_eq!(
+            target,
+            labels(&[("__meta_rack", "a1"), ("env", "prod"), ("rack", "a1")])
+        );
+    }
+
+    #[test]
+    fn requires_target_label_for_replace() {
+        let config = toml::from_str(r#"source_labels = ["env"]"#).unwrap();
+        assert!(matches!(
+            Relabel::new(&config),
+            Err(RelabelError::MissingTargetLabel)
+        ));
+    }
+}
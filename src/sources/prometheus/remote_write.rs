@@ -0,0 +1,389 @@
+use crate::{
+    config::{self, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    event::metric::{Metric, MetricKind, MetricValue},
+    shutdown::ShutdownSignal,
+    sources::util::{ErrorMessage, HttpSource, HttpSourceAuthConfig},
+    tls::TlsConfig,
+    Event, Pipeline,
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use prometheus_parser::proto::{metric_metadata::MetricType, WriteRequest};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use warp::http::{HeaderMap, StatusCode};
+
+const SOURCE_NAME: &str = "prometheus_remote_write";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct PrometheusRemoteWriteConfig {
+    address: SocketAddr,
+    tls: Option<TlsConfig>,
+    auth: Option<HttpSourceAuthConfig>,
+}
+
+inventory::submit! {
+    SourceDescription::new::<PrometheusRemoteWriteConfig>(SOURCE_NAME)
+}
+
+impl GenerateConfig for PrometheusRemoteWriteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: "127.0.0.1:9090".parse().unwrap(),
+            tls: None,
+            auth: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "prometheus_remote_write")]
+impl SourceConfig for PrometheusRemoteWriteConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<crate::sources::Source> {
+        let source = RemoteWriteSource::default();
+        source.run(self.address, "", &self.tls, &self.auth, out, shutdown)
+    }
+
+    fn output_type(&self) -> config::DataType {
+        config::DataType::Metric
+    }
+
+    fn source_type(&self) -> &'static str {
+        SOURCE_NAME
+    }
+}
+
+/// Prometheus sends the metadata of metric families in their own requests
+/// from time to time, so it is kept around for the samples that follow.
+#[derive(Clone, Default)]
+struct RemoteWriteSource {
+    types: Arc<Mutex<HashMap<String, MetricType>>>,
+}
+
+impl HttpSource for RemoteWriteSource {
+    fn build_event(
+        &self,
+        body: Bytes,
+        _header_map: HeaderMap,
+        _query_parameters: HashMap<String, String>,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        // The remote write protocol always compresses bodies with snappy's
+        // block format, whether or not the `Content-Encoding` is set.
+        let body = snap::raw::Decoder::new()
+            .decompress_vec(&body)
+            .map_err(|error| {
+                ErrorMessage::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("Could not decompress write request: {}", error),
+                )
+            })?;
+        let request = WriteRequest::decode(body.as_slice()).map_err(|error| {
+            ErrorMessage::new(
+                StatusCode::BAD_REQUEST,
+                format!("Could not decode write request: {}", error),
+            )
+        })?;
+        let mut types = self.types.lock().unwrap();
+        Ok(decode_request(request, &mut types))
+    }
+}
+
+/// Converts every sample of a write request into an absolute metric. Samples
+/// are counters if the metadata seen so far marks their series as cumulative,
+/// or, lacking metadata, if they are named like cumulative series.
+fn decode_request(request: WriteRequest, types: &mut HashMap<String, MetricType>) -> Vec<Event> {
+    for metadata in request.metadata {
+        match MetricType::from_i32(metadata.r#type) {
+            None | Some(MetricType::Unknown) => (),
+            Some(metric_type) => {
+                types.insert(metadata.metric_family_name, metric_type);
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+    for series in request.timeseries {
+        let mut name = None;
+        let mut tags = BTreeMap::new();
+        for label in series.labels {
+            if label.name == "__name__" {
+                name = Some(label.value);
+            } else {
+                tags.insert(label.name, label.value);
+            }
+        }
+        let name = match name {
+            Some(name) => name,
+            None => continue,
+        };
+        let counter = is_counter(&name, types);
+        let tags = if tags.is_empty() { None } else { Some(tags) };
+
+        for sample in series.samples {
+            let value = if counter {
+                MetricValue::Counter {
+                    value: sample.value,
+                }
+            } else {
+                MetricValue::Gauge {
+                    value: sample.value,
+                }
+            };
+            events.push(Event::Metric(Metric {
+                name: name.clone(),
+                namespace: None,
+                timestamp: Some(Utc.timestamp_millis(sample.timestamp)),
+                tags: tags.clone(),
+                kind: MetricKind::Absolute,
+                value,
+            }));
+        }
+    }
+    events
+}
+
+fn is_counter(name: &str, types: &HashMap<String, MetricType>) -> bool {
+    if let Some(metric_type) = types.get(name) {
+        return *metric_type == MetricType::Counter;
+    }
+    // The buckets, sums and counts of histograms and summaries are
+    // cumulative too, but their metadata is named after the family.
+    let family_type = ["_bucket", "_sum", "_count"].iter().find_map(|suffix| {
+        name.strip_suffix(suffix)
+            .and_then(|family| types.get(family))
+    });
+    match family_type {
+        Some(metric_type) => {
+            *metric_type == MetricType::Histogram || *metric_type == MetricType::Summary
+        }
+        None => ["_total", "_bucket", "_sum", "_count"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus_parser::proto::{Label, MetricMetadata, Sample, TimeSeries};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PrometheusRemoteWriteConfig>();
+    }
+
+    fn series(name: &str, labels: &[(&str, &str)], value: f64) -> TimeSeries {
+        let mut labels: Vec<Label> = labels
+            .iter()
+            .map(|(name, value)| Label {
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        labels.push(Label {
+            name: "__name__".into(),
+            value: name.into(),
+        });
+        TimeSeries {
+            labels,
+            samples: vec![Sample {
+                value,
+                timestamp: 1_600_000_000_000,
+            }],
+        }
+    }
+
+    fn metadata(name: &str, metric_type: MetricType) -> MetricMetadata {
+        MetricMetadata {
+            r#type: metric_type as i32,
+            metric_family_name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decodes_samples() {
+        let request = WriteRequest {
+            timeseries: vec![
+                series("requests_total", &[("code", "200")], 12.0),
+                series("temperature", &[], 21.5),
+                series("latency_bucket", &[("le", "0.5")], 3.0),
+                series("latency_sum", &[], 1.2),
+                series("unknown", &[], 1.0),
+                series("temperature_count", &[], 4.0),
+            ],
+            metadata: vec![
+                metadata("requests_total", MetricType::Counter),
+                metadata("temperature", MetricType::Gauge),
+                metadata("latency", MetricType::Histogram),
+            ],
+        };
+
+        let metrics: Vec<Metric> = decode_request(request, &mut HashMap::new())
+            .into_iter()
+            .map(Event::into_metric)
+            .collect();
+
+        assert_eq!(
+            metrics[0],
+            Metric {
+                name: "requests_total".into(),
+                namespace: None,
+                timestamp: Some(Utc.timestamp(1_600_000_000, 0)),
+                tags: Some(
+                    vec![("code".to_owned(), "200".to_owned())]
+                        .into_iter()
+                        .collect()
+                ),
+                kind: MetricKind::Absolute,
+                value: MetricValue::Counter { value: 12.0 },
+            }
+        );
+        assert_eq!(metrics[1].tags, None);
+        assert_eq!(metrics[1].value, MetricValue::Gauge { value: 21.5 });
+        assert_eq!(metrics[2].value, MetricValue::Counter { value: 3.0 });
+        assert_eq!(metrics[3].value, MetricValue::Counter { value: 1.2 });
+        assert_eq!(metrics[4].value, MetricValue::Gauge { value: 1.0 });
+        // The family's metadata wins over the naming convention.
+        assert_eq!(metrics[5].value, MetricValue::Gauge { value: 4.0 });
+    }
+
+    #[test]
+    fn decodes_samples_without_metadata() {
+        let mut types = HashMap::new();
+        let request = WriteRequest {
+            timeseries: vec![
+                series("requests_total", &[], 12.0),
+                series("latency_count", &[], 3.0),
+                series("temperature", &[], 21.5),
+                series("errors", &[], 2.0),
+            ],
+            metadata: vec![],
+        };
+        let values: Vec<MetricValue> = decode_request(request, &mut types)
+            .into_iter()
+            .map(|event| event.into_metric().value)
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                MetricValue::Counter { value: 12.0 },
+                MetricValue::Counter { value: 3.0 },
+                MetricValue::Gauge { value: 21.5 },
+                MetricValue::Gauge { value: 2.0 },
+            ]
+        );
+
+        // Metadata sent in an earlier request applies to later samples.
+        let metadata_only = WriteRequest {
+            timeseries: vec![],
+            metadata: vec![metadata("errors", MetricType::Counter)],
+        };
+        assert!(decode_request(metadata_only, &mut types).is_empty());
+        let request = WriteRequest {
+            timeseries: vec![series("errors", &[], 3.0)],
+            metadata: vec![],
+        };
+        let metric = decode_request(request, &mut types).remove(0).into_metric();
+        assert_eq!(metric.value, MetricValue::Counter { value: 3.0 });
+    }
+
+    #[test]
+    fn skips_series_without_name() {
+        let mut unnamed = series("unnamed", &[], 1.0);
+        unnamed.labels.clear();
+        let request = WriteRequest {
+            timeseries: vec![unnamed],
+            metadata: vec![],
+        };
+
+        assert!(decode_request(request, &mut HashMap::new()).is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "sinks-prometheus"))]
+mod integration_tests {
+    use super::*;
+    use crate::{
+        config::{SinkConfig, SinkContext},
+        sinks::prometheus::RemoteWriteConfig,
+        test_util::{collect_n, next_addr, wait_for_tcp},
+    };
+    use futures::{compat::Future01CompatExt, stream};
+
+    #[tokio::test]
+    async fn receives_metrics_over_http() {
+        let address = next_addr();
+        let (tx, rx) = Pipeline::new_test();
+
+        let source = PrometheusRemoteWriteConfig {
+            address,
+            auth: None,
+            tls: None,
+        };
+        let source = source
+            .build(
+                "source",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(source.compat());
+        wait_for_tcp(address).await;
+
+        let sink: RemoteWriteConfig =
+            toml::from_str(&format!("endpoint = \"http://{}/\"", address)).unwrap();
+        let (sink, _) = sink.build(SinkContext::new_test()).await.unwrap();
+
+        let events = vec![
+            Event::Metric(Metric {
+                name: "requests_total".into(),
+                namespace: None,
+                timestamp: Some(Utc.timestamp(1_600_000_000, 0)),
+                tags: None,
+                kind: MetricKind::Incremental,
+                value: MetricValue::Counter { value: 2.0 },
+            }),
+            Event::Metric(Metric {
+                name: "temperature".into(),
+                namespace: None,
+                timestamp: Some(Utc.timestamp(1_600_000_000, 0)),
+                tags: Some(
+                    vec![("room".to_owned(), "kitchen".to_owned())]
+                        .into_iter()
+                        .collect(),
+                ),
+                kind: MetricKind::Absolute,
+                value: MetricValue::Gauge { value: 21.5 },
+            }),
+        ];
+        sink.run(stream::iter(events.clone())).await.unwrap();
+
+        let output = collect_n(rx, 2).await.unwrap();
+        assert_eq!(
+            output,
+            vec![
+                Event::Metric(Metric {
+                    kind: MetricKind::Absolute,
+                    ..events[0].as_metric().clone()
+                }),
+                events[1].clone(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,630 @@
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription,
+    },
+    event::{
+        finalization::{BatchNotifier, BatchStatus, BatchStatusReceiver},
+        Event, Value,
+    },
+    internal_events::{
+        RedisAckFailed, RedisEventDeliveryFailed, RedisEventReceived, RedisReceiveFailed,
+    },
+    shutdown::ShutdownSignal,
+    Pipeline,
+};
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::{
+    compat::Future01CompatExt,
+    future::{FutureExt, TryFutureExt},
+    stream::StreamExt,
+};
+use futures01::{stream::iter_ok, Sink};
+use redis::{
+    aio::{Connection, ConnectionManager},
+    streams::{StreamId, StreamReadOptions, StreamReadReply},
+    AsyncCommands, RedisError,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::task::Poll;
+use tokio::time::{delay_for, Duration};
+
+/// How long blocking reads wait for data before checking for shutdown.
+const BLOCK_TIMEOUT_SECS: usize = 1;
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid Redis URL: {}", source))]
+    InvalidUrl { source: RedisError },
+    #[snafu(display("Could not connect to Redis: {}", source))]
+    Connect { source: RedisError },
+    #[snafu(display("Could not create consumer group: {}", source))]
+    CreateGroup { source: RedisError },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedisSourceConfig {
+    url: String,
+    key: String,
+    #[serde(default)]
+    data_type: RedisDataType,
+    #[serde(default)]
+    list: ListOption,
+    #[serde(default)]
+    stream: StreamOption,
+    key_field: Option<String>,
+    /// Only acknowledge a stream entry once its event has been delivered by
+    /// every sink it was sent to.
+    #[serde(default)]
+    acknowledgements: bool,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RedisDataType {
+    /// Pops the events off a list.
+    #[derivative(Default)]
+    List,
+    /// Subscribes to a channel.
+    Channel,
+    /// Reads a stream as part of a consumer group.
+    Stream,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ListOption {
+    #[serde(default)]
+    method: ListMethod,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ListMethod {
+    #[derivative(Default)]
+    Blpop,
+    Brpop,
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOption {
+    #[serde(default = "default_group")]
+    #[derivative(Default(value = "default_group()"))]
+    group: String,
+    /// Defaults to the hostname.
+    consumer: Option<String>,
+    #[serde(default = "default_count")]
+    #[derivative(Default(value = "default_count()"))]
+    count: usize,
+}
+
+fn default_group() -> String {
+    "vector".into()
+}
+
+fn default_count() -> usize {
+    100
+}
+
+inventory::submit! {
+    SourceDescription::new::<RedisSourceConfig>("redis")
+}
+
+impl GenerateConfig for RedisSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"url = "redis://127.0.0.1:6379/0"
+            key = "vector""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl SourceConfig for RedisSourceConfig {
+    async fn build(
+        &self,
+        _name: &str,
+        _globals: &GlobalOptions,
+        shutdown: ShutdownSignal,
+        out: Pipeline,
+    ) -> crate::Result<super::Source> {
+        let client = redis::Client::open(self.url.as_str()).context(InvalidUrl)?;
+        let fut = match self.data_type {
+            RedisDataType::List => {
+                let connection = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(Connect)?;
+                list_source(self.clone(), connection, shutdown, out).boxed()
+            }
+            RedisDataType::Channel => {
+                let connection = client.get_async_connection().await.context(Connect)?;
+                channel_source(self.clone(), client, connection, shutdown, out).boxed()
+            }
+            RedisDataType::Stream => {
+                let mut connection = client
+                    .get_tokio_connection_manager()
+                    .await
+                    .context(Connect)?;
+                create_group(&mut connection, &self.key, &self.stream.group).await?;
+                stream_source(self.clone(), connection, shutdown, out).boxed()
+            }
+        };
+        Ok(Box::new(fut.compat()))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn source_type(&self) -> &'static str {
+        "redis"
+    }
+}
+
+async fn list_source(
+    config: RedisSourceConfig,
+    mut connection: ConnectionManager,
+    mut shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
+    let command = match config.list.method {
+        ListMethod::Blpop => "BLPOP",
+        ListMethod::Brpop => "BRPOP",
+    };
+
+    while !matches!(futures::poll!(&mut shutdown), Poll::Ready(_)) {
+        let popped: Option<(String, Vec<u8>)> = match redis::cmd(command)
+            .arg(&config.key)
+            .arg(BLOCK_TIMEOUT_SECS)
+            .query_async(&mut connection)
+            .await
+        {
+            Ok(popped) => popped,
+            Err(error) => {
+                emit!(RedisReceiveFailed { error });
+                delay_for(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        if let Some((key, payload)) = popped {
+            emit!(RedisEventReceived {
+                byte_size: payload.len()
+            });
+            let event = create_event(payload, &key, &config.key_field);
+            out = send(out, vec![event]).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn channel_source(
+    config: RedisSourceConfig,
+    client: redis::Client,
+    connection: Connection,
+    mut shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
+    let mut connection = Some(connection);
+
+    while !matches!(futures::poll!(&mut shutdown), Poll::Ready(_)) {
+        // Messages published while the connection is down are lost, as
+        // channels don't keep them for subscribers.
+        let connection = match connection.take() {
+            Some(connection) => Ok(connection),
+            None => client.get_async_connection().await,
+        };
+        let mut pubsub = match connection {
+            Ok(connection) => connection.into_pubsub(),
+            Err(error) => {
+                emit!(RedisReceiveFailed { error });
+                delay_for(RETRY_DELAY).await;
+                continue;
+            }
+        };
+        if let Err(error) = pubsub.subscribe(&config.key).await {
+            emit!(RedisReceiveFailed { error });
+            delay_for(RETRY_DELAY).await;
+            continue;
+        }
+
+        let mut messages = pubsub.on_message().take_until(shutdown.clone());
+        while let Some(message) = messages.next().await {
+            let payload = match message.get_payload::<Vec<u8>>() {
+                Ok(payload) => payload,
+                Err(error) => {
+                    emit!(RedisReceiveFailed { error });
+                    continue;
+                }
+            };
+            emit!(RedisEventReceived {
+                byte_size: payload.len()
+            });
+            let event = create_event(payload, message.get_channel_name(), &config.key_field);
+            out = send(out, vec![event]).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Creates the consumer group reading the stream, along with the stream if
+/// it doesn't exist yet. A new group starts from the first entry.
+async fn create_group(
+    connection: &mut ConnectionManager,
+    key: &str,
+    group: &str,
+) -> crate::Result<()> {
+    match connection
+        .xgroup_create_mkstream::<_, _, _, ()>(key, group, "0")
+        .await
+    {
+        Err(error) if error.code() != Some("BUSYGROUP") => {
+            Err(BuildError::CreateGroup { source: error }.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+async fn stream_source(
+    config: RedisSourceConfig,
+    mut connection: ConnectionManager,
+    mut shutdown: ShutdownSignal,
+    mut out: Pipeline,
+) -> Result<(), ()> {
+    let group = config.stream.group;
+    let consumer = config
+        .stream
+        .consumer
+        .or_else(|| crate::get_hostname().ok())
+        .unwrap_or_else(|| "vector".into());
+    let count = config.stream.count;
+    // `xread_options` takes its options by value, so each read builds its own.
+    let options = || {
+        StreamReadOptions::default()
+            .group(&group, &consumer)
+            .count(count)
+            .block(BLOCK_TIMEOUT_SECS * 1000)
+    };
+
+    // Entries read by this consumer that were never acknowledged, such as
+    // when it stopped before delivering them, are read again before any new
+    // ones. Reading from `0` returns them, and `>` the new ones.
+    let mut pending = Some("0".to_owned());
+
+    while !matches!(futures::poll!(&mut shutdown), Poll::Ready(_)) {
+        let id = pending.as_deref().unwrap_or(">");
+        let reply: StreamReadReply = match connection
+            .xread_options(&[&config.key], &[id], options())
+            .await
+        {
+            Ok(reply) => reply,
+            Err(error) => {
+                emit!(RedisReceiveFailed { error });
+                delay_for(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        let entries = reply
+            .keys
+            .into_iter()
+            .flat_map(|key| key.ids)
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            // All pending entries have been read again.
+            pending = None;
+            continue;
+        }
+        if pending.is_some() {
+            pending = entries.last().map(|entry| entry.id.clone());
+        }
+
+        let ids = entries
+            .iter()
+            .map(|entry| entry.id.clone())
+            .collect::<Vec<_>>();
+        let mut events = entries
+            .into_iter()
+            .map(|entry| create_stream_event(entry, &config.key, &config.key_field))
+            .collect::<Vec<_>>();
+
+        if config.acknowledgements {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            for event in &mut events {
+                event.add_batch_notifier(&batch);
+            }
+            drop(batch);
+            tokio::spawn(ack_when_delivered(
+                connection.clone(),
+                config.key.clone(),
+                group.clone(),
+                ids,
+                receiver,
+            ));
+            out = send(out, events).await?;
+        } else {
+            out = send(out, events).await?;
+            ack(&mut connection, &config.key, &group, &ids).await;
+        }
+    }
+    Ok(())
+}
+
+/// Acknowledges the stream entries of a batch of events once they're
+/// delivered. Entries of undelivered events are left pending, to be read
+/// again when the source restarts.
+async fn ack_when_delivered(
+    mut connection: ConnectionManager,
+    key: String,
+    group: String,
+    ids: Vec<String>,
+    receiver: BatchStatusReceiver,
+) {
+    match receiver.await {
+        BatchStatus::Delivered => ack(&mut connection, &key, &group, &ids).await,
+        status => emit!(RedisEventDeliveryFailed {
+            count: ids.len(),
+            status,
+        }),
+    }
+}
+
+async fn ack(connection: &mut ConnectionManager, key: &str, group: &str, ids: &[String]) {
+    if let Err(error) = connection.xack::<_, _, _, ()>(key, group, ids).await {
+        emit!(RedisAckFailed {
+            count: ids.len(),
+            error,
+        });
+    }
+}
+
+async fn send(out: Pipeline, events: Vec<Event>) -> Result<Pipeline, ()> {
+    out.send_all(iter_ok(events))
+        .compat()
+        .map_ok(|(out, _)| out)
+        .map_err(|error| error!(message = "Error sending event.", %error))
+        .await
+}
+
+fn create_event(payload: Vec<u8>, key: &str, key_field: &Option<String>) -> Event {
+    let mut event = Event::from(Bytes::from(payload));
+    let log = event.as_mut_log();
+    log.insert(log_schema().source_type_key(), Bytes::from("redis"));
+    if let Some(key_field) = key_field {
+        log.insert(key_field, key);
+    }
+    event
+}
+
+/// Turns the fields of a stream entry into those of an event, timestamped
+/// with the time the entry was added.
+fn create_stream_event(entry: StreamId, key: &str, key_field: &Option<String>) -> Event {
+    let mut event = Event::new_empty_log();
+    let log = event.as_mut_log();
+
+    let mut byte_size = 0;
+    for (field, value) in entry.map {
+        match redis::from_redis_value::<Vec<u8>>(&value) {
+            Ok(value) => {
+                byte_size += value.len();
+                log.insert_flat(field, Value::from(Bytes::from(value)));
+            }
+            Err(error) => emit!(RedisReceiveFailed { error }),
+        }
+    }
+    emit!(RedisEventReceived { byte_size });
+
+    // Entry IDs start with the milliseconds timestamp they were added at.
+    let timestamp = entry
+        .id
+        .split('-')
+        .next()
+        .and_then(|millis| millis.parse().ok())
+        .and_then(|millis| Utc.timestamp_millis_opt(millis).latest())
+        .unwrap_or_else(Utc::now);
+    log.insert(log_schema().timestamp_key(), timestamp);
+    log.insert(log_schema().source_type_key(), Bytes::from("redis"));
+    if let Some(key_field) = key_field {
+        log.insert(key_field, key);
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisSourceConfig>();
+    }
+
+    #[test]
+    fn parses_data_type_options() {
+        let config: RedisSourceConfig = toml::from_str(
+            r#"url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            list.method = "brpop""#,
+        )
+        .unwrap();
+        assert_eq!(config.data_type, RedisDataType::List);
+        assert_eq!(config.list.method, ListMethod::Brpop);
+        assert_eq!(config.stream, StreamOption::default());
+
+        let config: RedisSourceConfig = toml::from_str(
+            r#"url = "redis://127.0.0.1:6379/0"
+            key = "vector"
+            data_type = "stream"
+            stream.group = "aggregators"
+            stream.consumer = "aggregator-1""#,
+        )
+        .unwrap();
+        assert_eq!(config.data_type, RedisDataType::Stream);
+        assert_eq!(config.stream.group, "aggregators");
+        assert_eq!(config.stream.consumer.as_deref(), Some("aggregator-1"));
+        assert_eq!(config.stream.count, 100);
+    }
+
+    #[test]
+    fn creates_events() {
+        let event = create_event(b"hello".to_vec(), "logs", &Some("redis_key".into()));
+        let log = event.as_log();
+
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log[log_schema().source_type_key()], "redis".into());
+        assert_eq!(log["redis_key"], "logs".into());
+        assert!(log.contains(log_schema().timestamp_key()));
+    }
+
+    #[test]
+    fn creates_stream_events() {
+        let mut map = HashMap::new();
+        map.insert("message".into(), redis::Value::Data(b"hello".to_vec()));
+        map.insert("http.status".into(), redis::Value::Data(b"200".to_vec()));
+        let entry = StreamId {
+            id: "1518951480106-0".into(),
+            map,
+        };
+
+        let event = create_stream_event(entry, "logs", &None);
+        let log = event.as_log();
+
+        assert_eq!(log["message"], "hello".into());
+        assert_eq!(log.get_flat("http.status"), Some(&"200".into()));
+        assert_eq!(log[log_schema().source_type_key()], "redis".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp_millis(1518951480106).into()
+        );
+    }
+}
+
+#[cfg(feature = "redis-integration-tests")]
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::test_util::{collect_n, random_string, trace_init};
+
+    const REDIS_URL: &str = "redis://127.0.0.1:6379/0";
+
+    fn config(key: &str, data_type: RedisDataType) -> RedisSourceConfig {
+        RedisSourceConfig {
+            url: REDIS_URL.to_owned(),
+            key: key.to_owned(),
+            data_type,
+            list: ListOption::default(),
+            stream: StreamOption::default(),
+            key_field: Some("redis_key".into()),
+            acknowledgements: false,
+        }
+    }
+
+    async fn connection() -> Connection {
+        redis::Client::open(REDIS_URL)
+            .unwrap()
+            .get_async_connection()
+            .await
+            .unwrap()
+    }
+
+    async fn start(config: RedisSourceConfig) -> futures01::sync::mpsc::Receiver<Event> {
+        let (tx, rx) = Pipeline::new_test();
+        let source = config
+            .build(
+                "default",
+                &GlobalOptions::default(),
+                ShutdownSignal::noop(),
+                tx,
+            )
+            .await
+            .unwrap();
+        tokio::spawn(source.compat());
+        rx
+    }
+
+    fn messages(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| event.as_log()[log_schema().message_key()].to_string_lossy())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn redis_source_list() {
+        trace_init();
+
+        let key = format!("test-list-{}", random_string(10));
+        let mut connection = connection().await;
+        connection
+            .rpush::<_, _, ()>(&key, vec!["first", "second"])
+            .await
+            .unwrap();
+
+        let rx = start(config(&key, RedisDataType::List)).await;
+        let events = collect_n(rx, 2).await.unwrap();
+
+        assert_eq!(messages(&events), vec!["first", "second"]);
+        assert_eq!(events[0].as_log()["redis_key"], key.into());
+        let remaining: usize = connection.llen(&key).await.unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn redis_source_channel() {
+        trace_init();
+
+        let key = format!("test-channel-{}", random_string(10));
+        let rx = start(config(&key, RedisDataType::Channel)).await;
+        // Give the source time to subscribe.
+        delay_for(Duration::from_secs(1)).await;
+
+        let mut connection = connection().await;
+        for message in &["first", "second"] {
+            connection
+                .publish::<_, _, ()>(&key, *message)
+                .await
+                .unwrap();
+        }
+
+        let events = collect_n(rx, 2).await.unwrap();
+        assert_eq!(messages(&events), vec!["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn redis_source_stream() {
+        trace_init();
+
+        let key = format!("test-stream-{}", random_string(10));
+        let mut connection = connection().await;
+        for message in &["first", "second"] {
+            connection
+                .xadd::<_, _, _, _, ()>(&key, "*", &[("message", *message)])
+                .await
+                .unwrap();
+        }
+
+        let mut config = config(&key, RedisDataType::Stream);
+        config.acknowledgements = true;
+        let rx = start(config).await;
+        let events = collect_n(rx, 2).await.unwrap();
+        assert_eq!(messages(&events), vec!["first", "second"]);
+
+        // The delivered events' entries get acknowledged.
+        drop(events);
+        delay_for(Duration::from_secs(1)).await;
+        let pending: redis::streams::StreamPendingReply =
+            connection.xpending(&key, "vector").await.unwrap();
+        assert_eq!(pending.count(), 0);
+    }
+}
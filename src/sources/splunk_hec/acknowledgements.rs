@@ -0,0 +1,265 @@
+//! Splunk HEC indexer acknowledgements.
+//!
+//! Clients that use them send each request on a channel, identified by the
+//! `X-Splunk-Request-Channel` header or the `channel` query parameter. Every
+//! request on a channel is answered with an ack ID, which the client later
+//! passes to `/services/collector/ack` to find out whether the events of that
+//! request have been delivered. IDs are never reused, even by a channel that
+//! was forgotten and then used again, so a stale ID can't be reported as
+//! delivered for a newer request.
+
+use super::ApiError;
+use crate::event::finalization::{BatchNotifier, BatchStatus};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct IndexerAcknowledgementsConfig {
+    /// Issue ack IDs for requests sent on a channel
+    pub enabled: bool,
+    /// Maximum number of channels to track acks for. Once reached, the
+    /// channel that has been idle for the longest time is forgotten.
+    pub max_channels: usize,
+    /// Maximum number of acks per channel that are pending or haven't been
+    /// queried yet. Requests beyond that are rejected until the client queries
+    /// its acks.
+    pub max_pending_acks_per_channel: usize,
+}
+
+impl Default for IndexerAcknowledgementsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_channels: 1_000_000,
+            max_pending_acks_per_channel: 1_000_000,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Channel {
+    /// Requests whose events haven't been finalized yet.
+    pending: HashSet<u64>,
+    /// Requests whose events have been delivered, until the client queries
+    /// them.
+    delivered: HashSet<u64>,
+    last_used: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub(super) struct IndexerAcknowledgements {
+    config: IndexerAcknowledgementsConfig,
+    channels: Mutex<HashMap<String, Channel>>,
+    next_id: AtomicU64,
+}
+
+impl IndexerAcknowledgements {
+    pub(super) fn new(config: IndexerAcknowledgementsConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            channels: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+        })
+    }
+
+    /// Issues an ack ID for a request on `channel`, returning it along with the
+    /// batch notifier to attach to the events of the request.
+    pub(super) fn create_ack(
+        self: &Arc<Self>,
+        channel: &str,
+    ) -> Result<(u64, Arc<BatchNotifier>), ApiError> {
+        let id = {
+            let mut channels = self.channels.lock().expect("poisoned lock");
+
+            if !channels.contains_key(channel) && channels.len() >= self.config.max_channels {
+                let idle = channels
+                    .iter()
+                    .min_by_key(|(_, channel)| channel.last_used)
+                    .map(|(name, _)| name.clone());
+                if let Some(idle) = idle {
+                    debug!(message = "Too many channels; forgetting the longest idle channel.", channel = %idle);
+                    channels.remove(&idle);
+                }
+            }
+
+            let state = channels.entry(channel.to_owned()).or_default();
+            if state.pending.len() + state.delivered.len()
+                >= self.config.max_pending_acks_per_channel
+            {
+                return Err(ApiError::ServerBusy);
+            }
+
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            state.pending.insert(id);
+            state.last_used = Some(Instant::now());
+            id
+        };
+
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let acks = Arc::clone(self);
+        let channel = channel.to_owned();
+        tokio::spawn(async move {
+            let delivered = receiver.await == BatchStatus::Delivered;
+            acks.finish(&channel, id, delivered);
+        });
+
+        Ok((id, batch))
+    }
+
+    /// Forgets the ack ID of a request that has failed, so that it will never
+    /// be reported as delivered.
+    pub(super) fn forget(&self, channel: &str, id: u64) {
+        let mut channels = self.channels.lock().expect("poisoned lock");
+        if let Some(state) = channels.get_mut(channel) {
+            state.pending.remove(&id);
+            state.delivered.remove(&id);
+        }
+    }
+
+    fn finish(&self, channel: &str, id: u64, delivered: bool) {
+        let mut channels = self.channels.lock().expect("poisoned lock");
+        if let Some(state) = channels.get_mut(channel) {
+            // Failed requests are forgotten, so that the client resends them
+            // once it gives up waiting for their acks.
+            if state.pending.remove(&id) && delivered {
+                state.delivered.insert(id);
+            }
+        }
+    }
+
+    /// Reports which of the given ack IDs have been delivered. Delivered IDs are
+    /// only reported once, like Splunk does.
+    pub(super) fn query(&self, channel: &str, ids: &[u64]) -> BTreeMap<u64, bool> {
+        let mut channels = self.channels.lock().expect("poisoned lock");
+        let mut state = channels.get_mut(channel);
+
+        if let Some(state) = state.as_mut() {
+            state.last_used = Some(Instant::now());
+        }
+
+        ids.iter()
+            .map(|id| {
+                let delivered = state
+                    .as_mut()
+                    .map_or(false, |state| state.delivered.remove(id));
+                (*id, delivered)
+            })
+            .collect()
+    }
+}
+
+/// Body of a request to `/services/collector/ack`.
+#[derive(Deserialize, Debug)]
+pub(super) struct AckRequest {
+    pub acks: Vec<u64>,
+}
+
+/// Body of the response to an ack query.
+#[derive(Serialize, Debug)]
+pub(super) struct AckResponse {
+    pub acks: BTreeMap<u64, bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{finalization::EventStatus, Event};
+    use std::time::Duration;
+
+    fn acks(config: IndexerAcknowledgementsConfig) -> Arc<IndexerAcknowledgements> {
+        IndexerAcknowledgements::new(IndexerAcknowledgementsConfig {
+            enabled: true,
+            ..config
+        })
+    }
+
+    async fn finalize(batch: Arc<BatchNotifier>, status: EventStatus) {
+        let mut event = Event::from("message").with_batch_notifier(&batch);
+        drop(batch);
+        event.take_finalizers().update_status(status);
+        drop(event);
+        // Let the spawned task record the status.
+        tokio::task::yield_now().await;
+        tokio::task::yield_now().await;
+    }
+
+    #[tokio::test]
+    async fn reports_delivered_acks_once() {
+        let acks = acks(Default::default());
+
+        let (first, first_batch) = acks.create_ack("channel").unwrap();
+        let (second, second_batch) = acks.create_ack("channel").unwrap();
+        let (other, _other_batch) = acks.create_ack("other").unwrap();
+        assert_eq!((first, second, other), (0, 1, 2));
+
+        finalize(first_batch, EventStatus::Delivered).await;
+        finalize(second_batch, EventStatus::Failed).await;
+
+        let expected = vec![(0, true), (1, false), (2, false)]
+            .into_iter()
+            .collect();
+        assert_eq!(acks.query("channel", &[0, 1, 2]), expected);
+
+        let expected = vec![(0, false)].into_iter().collect();
+        assert_eq!(acks.query("channel", &[0]), expected);
+        assert_eq!(acks.query("unknown", &[0]), expected);
+    }
+
+    #[tokio::test]
+    async fn limits_pending_acks() {
+        let acks = acks(IndexerAcknowledgementsConfig {
+            max_pending_acks_per_channel: 1,
+            ..Default::default()
+        });
+
+        let (id, batch) = acks.create_ack("channel").unwrap();
+        assert!(matches!(
+            acks.create_ack("channel"),
+            Err(ApiError::ServerBusy)
+        ));
+
+        finalize(batch, EventStatus::Delivered).await;
+        assert!(acks.create_ack("channel").is_err());
+
+        acks.query("channel", &[id]);
+        assert!(acks.create_ack("channel").is_ok());
+    }
+
+    #[tokio::test]
+    async fn forgets_idle_channels() {
+        let acks = acks(IndexerAcknowledgementsConfig {
+            max_channels: 2,
+            ..Default::default()
+        });
+
+        let (_, first) = acks.create_ack("first").unwrap();
+        tokio::time::delay_for(Duration::from_millis(1)).await;
+        let (_, second) = acks.create_ack("second").unwrap();
+        tokio::time::delay_for(Duration::from_millis(1)).await;
+        acks.create_ack("third").unwrap();
+
+        finalize(first, EventStatus::Delivered).await;
+        finalize(second, EventStatus::Delivered).await;
+
+        let expected = vec![(0, false)].into_iter().collect();
+        assert_eq!(acks.query("first", &[0]), expected);
+        let expected = vec![(1, true)].into_iter().collect();
+        assert_eq!(acks.query("second", &[1]), expected);
+
+        // A forgotten channel that comes back doesn't reuse its old IDs.
+        tokio::time::delay_for(Duration::from_millis(1)).await;
+        let (id, batch) = acks.create_ack("first").unwrap();
+        assert_eq!(id, 3);
+        finalize(batch, EventStatus::Delivered).await;
+        let expected = vec![(0, false), (3, true)].into_iter().collect();
+        assert_eq!(acks.query("first", &[0, 3]), expected);
+    }
+}
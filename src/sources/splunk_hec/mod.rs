@@ -1,6 +1,9 @@
 use crate::{
     config::{log_schema, DataType, GlobalOptions, SourceConfig, SourceDescription},
-    event::{Event, LogEvent, Value},
+    event::{
+        finalization::{BatchNotifier, BatchStatus},
+        Event, LogEvent, Value,
+    },
     internal_events::{
         SplunkHECEventReceived, SplunkHECRequestBodyInvalid, SplunkHECRequestError,
         SplunkHECRequestReceived,
@@ -19,12 +22,19 @@ use serde::{de, Deserialize, Serialize};
 use serde_json::{de::IoRead, json, Deserializer, Value as JsonValue};
 use snafu::Snafu;
 use std::{
+    collections::HashMap,
     io::Read,
     net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
 };
 
 use warp::{filters::BoxedFilter, path, reject::Rejection, reply::Response, Filter, Reply};
 
+mod acknowledgements;
+
+pub use acknowledgements::IndexerAcknowledgementsConfig;
+use acknowledgements::{AckRequest, AckResponse, IndexerAcknowledgements};
+
 // Event fields unique to splunk_hec source
 pub const CHANNEL: &str = "splunk_channel";
 pub const INDEX: &str = "splunk_index";
@@ -41,6 +51,10 @@ pub struct SplunkConfig {
     /// Splunk HEC token
     token: Option<String>,
     tls: Option<TlsConfig>,
+    /// Only respond once the received events have been delivered
+    acknowledgements: bool,
+    /// Issue ack IDs that clients can query for delivery of their events
+    indexer_acknowledgements: IndexerAcknowledgementsConfig,
 }
 
 inventory::submit! {
@@ -65,6 +79,8 @@ impl Default for SplunkConfig {
             address: default_socket_address(),
             token: None,
             tls: None,
+            acknowledgements: false,
+            indexer_acknowledgements: IndexerAcknowledgementsConfig::default(),
         }
     }
 }
@@ -88,6 +104,7 @@ impl SourceConfig for SplunkConfig {
         let event_service = source.event_service(out.clone());
         let raw_service = source.raw_service(out.clone());
         let health_service = source.health_service(out);
+        let ack_service = source.ack_service();
         let options = SplunkSource::options();
 
         let services = path!("services" / "collector" / ..)
@@ -106,6 +123,8 @@ impl SourceConfig for SplunkConfig {
                     .unify()
                     .or(health_service)
                     .unify()
+                    .or(ack_service)
+                    .unify()
                     .or(options)
                     .unify(),
             )
@@ -140,6 +159,8 @@ impl SourceConfig for SplunkConfig {
 /// Shared data for responding to requests.
 struct SplunkSource {
     credentials: Option<Bytes>,
+    acknowledgements: bool,
+    indexer_acknowledgements: Option<Arc<IndexerAcknowledgements>>,
 }
 
 impl SplunkSource {
@@ -149,14 +170,24 @@ impl SplunkSource {
                 .token
                 .as_ref()
                 .map(|token| format!("Splunk {}", token).into()),
+            acknowledgements: config.acknowledgements,
+            indexer_acknowledgements: if config.indexer_acknowledgements.enabled {
+                Some(IndexerAcknowledgements::new(
+                    config.indexer_acknowledgements.clone(),
+                ))
+            } else {
+                None
+            },
         }
     }
 
     fn event_service(&self, out: Pipeline) -> BoxedFilter<(Response,)> {
+        let acknowledgements = self.acknowledgements;
+        let indexer_acknowledgements = self.indexer_acknowledgements.clone();
         warp::post()
             .and(path!("event").or(path!("event" / "1.0")))
             .and(self.authorization())
-            .and(warp::header::optional::<String>("x-splunk-request-channel"))
+            .and(Self::channel())
             .and(warp::header::optional::<String>("host"))
             .and(self.gzip())
             .and(warp::body::bytes())
@@ -168,20 +199,33 @@ impl SplunkSource {
                       gzip: bool,
                       body: Bytes| {
                     let out = out.clone();
+                    let indexer_acknowledgements = indexer_acknowledgements.clone();
                     async move {
                         // Construct event parser
                         if gzip {
-                            EventStream::new(GzDecoder::new(body.reader()), channel, host)
-                                .forward(out.clone().sink_map_err(|_| ApiError::ServerShutdown))
-                                .map(|_| ())
-                                .compat()
-                                .await
+                            let events = EventStream::new(
+                                GzDecoder::new(body.reader()),
+                                channel.clone(),
+                                host,
+                            );
+                            send_events(
+                                events,
+                                out,
+                                acknowledgements,
+                                indexer_acknowledgements,
+                                channel,
+                            )
+                            .await
                         } else {
-                            EventStream::new(body.reader(), channel, host)
-                                .forward(out.clone().sink_map_err(|_| ApiError::ServerShutdown))
-                                .map(|_| ())
-                                .compat()
-                                .await
+                            let events = EventStream::new(body.reader(), channel.clone(), host);
+                            send_events(
+                                events,
+                                out,
+                                acknowledgements,
+                                indexer_acknowledgements,
+                                channel,
+                            )
+                            .await
                         }
                     }
                 },
@@ -191,33 +235,37 @@ impl SplunkSource {
     }
 
     fn raw_service(&self, out: Pipeline) -> BoxedFilter<(Response,)> {
+        let acknowledgements = self.acknowledgements;
+        let indexer_acknowledgements = self.indexer_acknowledgements.clone();
         warp::post()
             .and(path!("raw" / "1.0").or(path!("raw")))
             .and(self.authorization())
-            .and(
-                warp::header::optional::<String>("x-splunk-request-channel").and_then(
-                    |channel: Option<String>| async {
-                        if let Some(channel) = channel {
-                            Ok(channel)
-                        } else {
-                            Err(Rejection::from(ApiError::MissingChannel))
-                        }
-                    },
-                ),
-            )
+            .and(Self::channel().and_then(|channel: Option<String>| async {
+                if let Some(channel) = channel {
+                    Ok(channel)
+                } else {
+                    Err(Rejection::from(ApiError::MissingChannel))
+                }
+            }))
             .and(warp::header::optional::<String>("host"))
             .and(self.gzip())
             .and(warp::body::bytes())
             .and_then(
                 move |_, _, channel: String, host: Option<String>, gzip: bool, body: Bytes| {
                     let out = out.clone();
+                    let indexer_acknowledgements = indexer_acknowledgements.clone();
                     async move {
                         // Construct event parser
-                        futures01::stream::once(raw_event(body, gzip, channel, host))
-                            .forward(out.clone().sink_map_err(|_| ApiError::ServerShutdown))
-                            .map(|_| ())
-                            .compat()
-                            .await
+                        let events =
+                            futures01::stream::once(raw_event(body, gzip, channel.clone(), host));
+                        send_events(
+                            events,
+                            out,
+                            acknowledgements,
+                            indexer_acknowledgements,
+                            Some(channel),
+                        )
+                        .await
                     }
                 },
             )
@@ -264,13 +312,38 @@ impl SplunkSource {
             .boxed()
     }
 
+    fn ack_service(&self) -> BoxedFilter<(Response,)> {
+        let indexer_acknowledgements = self.indexer_acknowledgements.clone();
+        warp::post()
+            .and(path!("ack"))
+            .and(self.authorization())
+            .and(Self::channel())
+            .and(warp::body::bytes())
+            .and_then(move |_, channel: Option<String>, body: Bytes| {
+                let indexer_acknowledgements = indexer_acknowledgements.clone();
+                async move {
+                    let acks = indexer_acknowledgements.ok_or(ApiError::AckIsDisabled)?;
+                    let channel = channel.ok_or(ApiError::MissingChannel)?;
+                    let request = serde_json::from_slice::<AckRequest>(&body)
+                        .map_err(|_| ApiError::BadRequest)?;
+
+                    let response = AckResponse {
+                        acks: acks.query(&channel, &request.acks),
+                    };
+                    Ok::<_, Rejection>(response_json(StatusCode::OK, response))
+                }
+            })
+            .boxed()
+    }
+
     fn options() -> BoxedFilter<(Response,)> {
         let post = warp::options()
             .and(
                 path!("event")
                     .or(path!("event" / "1.0"))
                     .or(path!("raw" / "1.0"))
-                    .or(path!("raw")),
+                    .or(path!("raw"))
+                    .or(path!("ack")),
             )
             .map(|_| warp::reply::with_header(warp::reply(), "Allow", "POST").into_response());
 
@@ -301,6 +374,18 @@ impl SplunkSource {
             .boxed()
     }
 
+    /// Channel of the request, from either the header or the query string
+    fn channel() -> BoxedFilter<(Option<String>,)> {
+        warp::header::optional::<String>("x-splunk-request-channel")
+            .and(warp::query::<HashMap<String, String>>())
+            .map(
+                |header: Option<String>, mut query: HashMap<String, String>| {
+                    header.or_else(|| query.remove("channel"))
+                },
+            )
+            .boxed()
+    }
+
     /// Is body encoded with gzip
     fn gzip(&self) -> BoxedFilter<(bool,)> {
         warp::header::optional::<String>("Content-Encoding")
@@ -315,6 +400,51 @@ impl SplunkSource {
     }
 }
 
+/// Forwards `events` to `out`, returning the request's ack ID if indexer
+/// acknowledgements are enabled and the request was sent on a channel.
+/// Otherwise, with acknowledgements enabled, this only completes once all of
+/// the events have been delivered.
+async fn send_events<S>(
+    events: S,
+    out: Pipeline,
+    acknowledgements: bool,
+    indexer_acknowledgements: Option<Arc<IndexerAcknowledgements>>,
+    channel: Option<String>,
+) -> Result<Option<u64>, Rejection>
+where
+    S: Stream<Item = Event, Error = Rejection>,
+{
+    let out = out.sink_map_err(|_| ApiError::ServerShutdown);
+    if let (Some(acks), Some(channel)) = (indexer_acknowledgements, channel) {
+        let (id, batch) = acks.create_ack(&channel)?;
+        events
+            .map(move |event| event.with_batch_notifier(&batch))
+            .forward(out)
+            .map(|_| Some(id))
+            .compat()
+            .await
+            .map_err(|error| {
+                acks.forget(&channel, id);
+                error
+            })
+    } else if acknowledgements {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        events
+            .map(move |event| event.with_batch_notifier(&batch))
+            .forward(out)
+            .map(|_| ())
+            .compat()
+            .await?;
+        match receiver.await {
+            BatchStatus::Delivered => Ok(None),
+            BatchStatus::Errored => Err(ApiError::DeliveryFailed.into()),
+            BatchStatus::Failed => Err(ApiError::DeliveryRejected.into()),
+        }
+    } else {
+        events.forward(out).map(|_| None).compat().await
+    }
+}
+
 /// Constructs one ore more events from json-s coming from reader.
 /// If errors, it's done with input.
 struct EventStream<R: Read> {
@@ -625,6 +755,10 @@ pub(crate) enum ApiError {
     NoData,
     InvalidDataFormat { event: usize },
     ServerShutdown,
+    ServerBusy,
+    DeliveryFailed,
+    DeliveryRejected,
+    AckIsDisabled,
     EmptyEventField { event: usize },
     MissingEventField { event: usize },
     BadRequest,
@@ -657,17 +791,29 @@ mod splunk_response {
         pub static ref SUCCESS: Bytes = json_to_bytes(json!({"text":"Success","code":0}));
         pub static ref SERVER_ERROR: Bytes =
             json_to_bytes(json!({"text":"Internal server error","code":8}));
+        pub static ref DATA_REJECTED: Bytes =
+            json_to_bytes(json!({"text":"Data was rejected","code":6}));
         pub static ref SERVER_SHUTDOWN: Bytes =
             json_to_bytes(json!({"text":"Server is shutting down","code":9}));
         pub static ref UNSUPPORTED_MEDIA_TYPE: Bytes =
             json_to_bytes(json!({"text":"unsupported content encoding"}));
         pub static ref NO_CHANNEL: Bytes =
             json_to_bytes(json!({"text":"Data channel is missing","code":10}));
+        pub static ref SERVER_BUSY: Bytes =
+            json_to_bytes(json!({"text":"Server is busy","code":9}));
+        pub static ref ACK_IS_DISABLED: Bytes =
+            json_to_bytes(json!({"text":"ACK is disabled","code":14}));
     }
 }
 
-fn finish_ok(_: ()) -> Response {
-    response_json(StatusCode::OK, splunk_response::SUCCESS.as_ref())
+fn finish_ok(ack_id: Option<u64>) -> Response {
+    match ack_id {
+        Some(id) => response_json(
+            StatusCode::OK,
+            json!({"text":"Success","code":0,"ackId":id}),
+        ),
+        None => response_json(StatusCode::OK, splunk_response::SUCCESS.as_ref()),
+    }
 }
 
 async fn finish_err(rejection: Rejection) -> Result<(Response,), Rejection> {
@@ -697,6 +843,22 @@ async fn finish_err(rejection: Rejection) -> Result<(Response,), Rejection> {
                 StatusCode::SERVICE_UNAVAILABLE,
                 splunk_response::SERVER_SHUTDOWN.as_ref(),
             ),
+            ApiError::ServerBusy => response_json(
+                StatusCode::SERVICE_UNAVAILABLE,
+                splunk_response::SERVER_BUSY.as_ref(),
+            ),
+            ApiError::DeliveryFailed => response_json(
+                StatusCode::SERVICE_UNAVAILABLE,
+                splunk_response::SERVER_ERROR.as_ref(),
+            ),
+            ApiError::DeliveryRejected => response_json(
+                StatusCode::BAD_REQUEST,
+                splunk_response::DATA_REJECTED.as_ref(),
+            ),
+            ApiError::AckIsDisabled => response_json(
+                StatusCode::BAD_REQUEST,
+                splunk_response::ACK_IS_DISABLED.as_ref(),
+            ),
             ApiError::InvalidDataFormat { event } => event_error("Invalid data format", 6, event),
             ApiError::EmptyEventField { event } => {
                 event_error("Event field cannot be blank", 13, event)
@@ -746,10 +908,10 @@ fn event_error(text: &str, code: u16, event: usize) -> Response {
 #[cfg(feature = "sinks-splunk_hec")]
 #[cfg(test)]
 mod tests {
-    use super::{parse_timestamp, SplunkConfig};
+    use super::{parse_timestamp, IndexerAcknowledgementsConfig, SplunkConfig};
     use crate::{
         config::{log_schema, GlobalOptions, SinkConfig, SinkContext, SourceConfig},
-        event::Event,
+        event::{finalization::EventStatus, Event},
         shutdown::ShutdownSignal,
         sinks::{
             splunk_hec::{Encoding, HecSinkConfig},
@@ -760,8 +922,9 @@ mod tests {
         Pipeline,
     };
     use chrono::{TimeZone, Utc};
-    use futures::{compat::Future01CompatExt, future, stream, StreamExt};
+    use futures::{compat::Future01CompatExt, future, stream, FutureExt, StreamExt};
     use futures01::sync::mpsc;
+    use serde_json::{json, Value as JsonValue};
     use std::net::SocketAddr;
 
     #[test]
@@ -773,29 +936,37 @@ mod tests {
     const TOKEN: &str = "token";
 
     async fn source() -> (mpsc::Receiver<Event>, SocketAddr) {
-        source_with(Some(TOKEN.to_owned())).await
+        source_with(Some(TOKEN.to_owned()), false).await
     }
 
-    async fn source_with(token: Option<String>) -> (mpsc::Receiver<Event>, SocketAddr) {
+    async fn source_with(
+        token: Option<String>,
+        acknowledgements: bool,
+    ) -> (mpsc::Receiver<Event>, SocketAddr) {
+        source_from(SplunkConfig {
+            token,
+            acknowledgements,
+            ..SplunkConfig::default()
+        })
+        .await
+    }
+
+    async fn source_from(config: SplunkConfig) -> (mpsc::Receiver<Event>, SocketAddr) {
         let (sender, recv) = Pipeline::new_test();
         let address = next_addr();
         tokio::spawn(async move {
-            SplunkConfig {
-                address,
-                token,
-                tls: None,
-            }
-            .build(
-                "default",
-                &GlobalOptions::default(),
-                ShutdownSignal::noop(),
-                sender,
-            )
-            .await
-            .unwrap()
-            .compat()
-            .await
-            .unwrap()
+            SplunkConfig { address, ..config }
+                .build(
+                    "default",
+                    &GlobalOptions::default(),
+                    ShutdownSignal::noop(),
+                    sender,
+                )
+                .await
+                .unwrap()
+                .compat()
+                .await
+                .unwrap()
         });
         wait_for_tcp(address).await;
         (recv, address)
@@ -1039,7 +1210,7 @@ mod tests {
         trace_init();
 
         let message = "no_authorization";
-        let (source, address) = source_with(None).await;
+        let (source, address) = source_with(None, false).await;
         let (sink, health) = sink(address, Encoding::Text, Compression::gzip_default()).await;
         assert!(health.await.is_ok());
 
@@ -1069,6 +1240,159 @@ mod tests {
         );
     }
 
+    async fn acknowledged_post(status: EventStatus) -> u16 {
+        let (source, address) = source_with(Some(TOKEN.to_owned()), true).await;
+
+        let mut request = tokio::spawn(post(address, "services/collector/raw", "message"));
+        let mut event = collect_n(source, 1).await.unwrap().remove(0);
+        assert!((&mut request).now_or_never().is_none());
+
+        event.take_finalizers().update_status(status);
+        drop(event);
+        request.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn acknowledged_delivery() {
+        trace_init();
+
+        assert_eq!(200, acknowledged_post(EventStatus::Delivered).await);
+    }
+
+    #[tokio::test]
+    async fn acknowledged_error() {
+        trace_init();
+
+        assert_eq!(503, acknowledged_post(EventStatus::Errored).await);
+    }
+
+    #[tokio::test]
+    async fn acknowledged_failure() {
+        trace_init();
+
+        assert_eq!(400, acknowledged_post(EventStatus::Failed).await);
+    }
+
+    async fn indexer_source() -> (mpsc::Receiver<Event>, SocketAddr) {
+        source_from(SplunkConfig {
+            token: Some(TOKEN.to_owned()),
+            indexer_acknowledgements: IndexerAcknowledgementsConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..SplunkConfig::default()
+        })
+        .await
+    }
+
+    /// Sends a request on `channel`, returning the status and json body
+    async fn send_on(
+        address: SocketAddr,
+        api: &str,
+        channel: Option<&str>,
+        body: &str,
+    ) -> (u16, JsonValue) {
+        let mut request = reqwest::Client::new()
+            .post(&format!("http://{}/{}", address, api))
+            .header("Authorization", format!("Splunk {}", TOKEN))
+            .body(body.to_owned());
+        if let Some(channel) = channel {
+            request = request.header("x-splunk-request-channel", channel);
+        }
+        let response = request.send().await.unwrap();
+        let status = response.status().as_u16();
+        (status, response.json().await.unwrap())
+    }
+
+    async fn query_acks(address: SocketAddr, channel: &str, acks: &[u64]) -> JsonValue {
+        let body = json!({ "acks": acks }).to_string();
+        let (status, body) = send_on(address, "services/collector/ack", Some(channel), &body).await;
+        assert_eq!(200, status);
+        body
+    }
+
+    #[tokio::test]
+    async fn indexer_acknowledgements() {
+        trace_init();
+
+        let (source, address) = indexer_source().await;
+
+        let (status, body) =
+            send_on(address, "services/collector/raw", Some("guid"), "first").await;
+        assert_eq!(200, status);
+        assert_eq!(body, json!({"text":"Success","code":0,"ackId":0}));
+
+        let api = "services/collector/event?channel=guid";
+        let (status, body) = send_on(address, api, None, r#"{"event":"second"}"#).await;
+        assert_eq!(200, status);
+        assert_eq!(body["ackId"], json!(1));
+
+        let mut events = collect_n(source, 2).await.unwrap();
+        assert_eq!(
+            query_acks(address, "guid", &[0, 1]).await,
+            json!({"acks":{"0":false,"1":false}})
+        );
+
+        for event in &mut events {
+            event
+                .take_finalizers()
+                .update_status(EventStatus::Delivered);
+        }
+        drop(events);
+        tokio::time::delay_for(std::time::Duration::from_millis(50)).await;
+
+        assert_eq!(
+            query_acks(address, "guid", &[0, 1, 2]).await,
+            json!({"acks":{"0":true,"1":true,"2":false}})
+        );
+        assert_eq!(
+            query_acks(address, "guid", &[0]).await,
+            json!({"acks":{"0":false}})
+        );
+        assert_eq!(
+            query_acks(address, "other", &[0]).await,
+            json!({"acks":{"0":false}})
+        );
+    }
+
+    #[tokio::test]
+    async fn indexer_acknowledgements_without_channel() {
+        trace_init();
+
+        let (source, address) = indexer_source().await;
+
+        let (status, body) = send_on(
+            address,
+            "services/collector/event",
+            None,
+            r#"{"event":"first"}"#,
+        )
+        .await;
+        assert_eq!(200, status);
+        assert_eq!(body, json!({"text":"Success","code":0}));
+        let event = collect_n(source, 1).await.unwrap().remove(0);
+        assert_eq!(event.as_log()[log_schema().message_key()], "first".into());
+
+        let (status, _) = send_on(address, "services/collector/ack", None, r#"{"acks":[0]}"#).await;
+        assert_eq!(400, status);
+    }
+
+    #[tokio::test]
+    async fn indexer_acknowledgements_disabled() {
+        trace_init();
+
+        let (_source, address) = source().await;
+
+        let (status, _) = send_on(
+            address,
+            "services/collector/ack",
+            Some("guid"),
+            r#"{"acks":[0]}"#,
+        )
+        .await;
+        assert_eq!(400, status);
+    }
+
     #[tokio::test]
     async fn default() {
         trace_init();
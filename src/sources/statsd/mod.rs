@@ -1,6 +1,8 @@
 use crate::{
-    config::{self, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
-    internal_events::{StatsdEventReceived, StatsdInvalidRecord, StatsdSocketError},
+    config::{self, log_schema, GenerateConfig, GlobalOptions, SourceConfig, SourceDescription},
+    internal_events::{
+        StatsdEventReceived, StatsdInvalidRecord, StatsdLogEventDiscarded, StatsdSocketError,
+    },
     shutdown::ShutdownSignal,
     sources::util::{SocketListenAddr, TcpSource},
     tls::{MaybeTlsSettings, TlsConfig},
@@ -18,9 +20,9 @@ pub mod parser;
 #[cfg(unix)]
 mod unix;
 
-use parser::parse;
+use parser::parse_line;
 #[cfg(unix)]
-use unix::{statsd_unix, UnixConfig};
+use unix::{statsd_unix, statsd_unix_datagram, UnixConfig};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "mode", rename_all = "snake_case")]
@@ -29,11 +31,15 @@ enum StatsdConfig {
     Udp(UdpConfig),
     #[cfg(unix)]
     Unix(UnixConfig),
+    #[cfg(unix)]
+    UnixDatagram(UnixConfig),
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct UdpConfig {
     pub address: SocketAddr,
+    #[serde(default)]
+    pub dogstatsd_events: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -43,6 +49,8 @@ struct TcpConfig {
     tls: Option<TlsConfig>,
     #[serde(default = "default_shutdown_timeout_secs")]
     pub shutdown_timeout_secs: u64,
+    #[serde(default)]
+    dogstatsd_events: bool,
 }
 
 fn default_shutdown_timeout_secs() -> u64 {
@@ -57,6 +65,7 @@ impl GenerateConfig for StatsdConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self::Udp(UdpConfig {
             address: SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8125)),
+            dogstatsd_events: false,
         }))
         .unwrap()
     }
@@ -78,7 +87,10 @@ impl SourceConfig for StatsdConfig {
             )),
             StatsdConfig::Tcp(config) => {
                 let tls = MaybeTlsSettings::from_config(&config.tls, true)?;
-                StatsdTcpSource.run(
+                let source = StatsdTcpSource {
+                    dogstatsd_events: config.dogstatsd_events,
+                };
+                source.run(
                     config.address,
                     config.shutdown_timeout_secs,
                     tls,
@@ -88,11 +100,22 @@ impl SourceConfig for StatsdConfig {
             }
             #[cfg(unix)]
             StatsdConfig::Unix(config) => Ok(statsd_unix(config.clone(), shutdown, out)),
+            #[cfg(unix)]
+            StatsdConfig::UnixDatagram(config) => Ok(Box::new(
+                statsd_unix_datagram(config.clone(), shutdown, out)
+                    .boxed()
+                    .compat(),
+            )),
         }
     }
 
     fn output_type(&self) -> crate::config::DataType {
-        config::DataType::Metric
+        // DogStatsD events and service checks are turned into log events.
+        if self.dogstatsd_events() {
+            config::DataType::Any
+        } else {
+            config::DataType::Metric
+        }
     }
 
     fn source_type(&self) -> &'static str {
@@ -100,13 +123,35 @@ impl SourceConfig for StatsdConfig {
     }
 }
 
-pub(self) fn parse_event(line: &str) -> Option<Event> {
-    match parse(line) {
-        Ok(metric) => {
+impl StatsdConfig {
+    fn dogstatsd_events(&self) -> bool {
+        match self {
+            StatsdConfig::Tcp(config) => config.dogstatsd_events,
+            StatsdConfig::Udp(config) => config.dogstatsd_events,
+            #[cfg(unix)]
+            StatsdConfig::Unix(config) | StatsdConfig::UnixDatagram(config) => {
+                config.dogstatsd_events
+            }
+        }
+    }
+}
+
+/// Parses a line into an event. DogStatsD events and service checks are
+/// discarded unless `dogstatsd_events` is set, since they are log events.
+pub(self) fn parse_event(line: &str, dogstatsd_events: bool) -> Option<Event> {
+    match parse_line(line) {
+        Ok(Event::Log(_)) if !dogstatsd_events => {
+            emit!(StatsdLogEventDiscarded { text: line });
+            None
+        }
+        Ok(mut event) => {
             emit!(StatsdEventReceived {
                 byte_size: line.len()
             });
-            Some(Event::Metric(metric))
+            if let Event::Log(log) = &mut event {
+                log.insert(log_schema().source_type_key(), Bytes::from("statsd"));
+            }
+            Some(event)
         }
         Err(error) => {
             emit!(StatsdInvalidRecord { error, text: line });
@@ -132,7 +177,10 @@ async fn statsd_udp(config: UdpConfig, shutdown: ShutdownSignal, out: Pipeline)
         match frame {
             Ok((bytes, _sock)) => {
                 let packet = String::from_utf8_lossy(bytes.as_ref());
-                let metrics = packet.lines().filter_map(parse_event).map(Ok);
+                let metrics = packet
+                    .lines()
+                    .filter_map(|line| parse_event(line, config.dogstatsd_events))
+                    .map(Ok);
 
                 // Need `boxed` to resolve a lifetime issue
                 // https://github.com/rust-lang/rust/issues/64552#issuecomment-669728225
@@ -152,7 +200,9 @@ async fn statsd_udp(config: UdpConfig, shutdown: ShutdownSignal, out: Pipeline)
 }
 
 #[derive(Clone)]
-struct StatsdTcpSource;
+struct StatsdTcpSource {
+    dogstatsd_events: bool,
+}
 
 impl TcpSource for StatsdTcpSource {
     type Error = std::io::Error;
@@ -164,7 +214,7 @@ impl TcpSource for StatsdTcpSource {
 
     fn build_event(&self, line: Bytes, _host: Bytes) -> Option<Event> {
         let line = String::from_utf8_lossy(line.as_ref());
-        parse_event(&line)
+        parse_event(&line, self.dogstatsd_events)
     }
 }
 
@@ -201,7 +251,10 @@ mod test {
     #[tokio::test]
     async fn test_statsd_udp() {
         let in_addr = next_addr();
-        let config = StatsdConfig::Udp(UdpConfig { address: in_addr });
+        let config = StatsdConfig::Udp(UdpConfig {
+            address: in_addr,
+            dogstatsd_events: false,
+        });
         let sender = {
             let (sender, mut receiver) = mpsc::channel(200);
             let addr = in_addr;
@@ -225,6 +278,7 @@ mod test {
             address: in_addr.into(),
             tls: None,
             shutdown_timeout_secs: 30,
+            dogstatsd_events: false,
         });
         let sender = {
             let (sender, mut receiver) = mpsc::channel(200);
@@ -250,6 +304,7 @@ mod test {
         let in_path = tempfile::tempdir().unwrap().into_path().join("unix_test");
         let config = StatsdConfig::Unix(UnixConfig {
             path: in_path.clone(),
+            dogstatsd_events: false,
         });
         let sender = {
             let (sender, mut receiver) = mpsc::channel(200);
@@ -269,6 +324,89 @@ mod test {
         test_statsd(config, sender).await;
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_statsd_unix_datagram() {
+        let in_path = tempfile::tempdir()
+            .unwrap()
+            .into_path()
+            .join("unix_datagram_test");
+        let config = StatsdConfig::UnixDatagram(UnixConfig {
+            path: in_path.clone(),
+            dogstatsd_events: false,
+        });
+        let sender = {
+            let (sender, mut receiver) = mpsc::channel(200);
+            let path = in_path;
+            tokio::spawn(async move {
+                let mut socket = tokio::net::UnixDatagram::unbound().unwrap();
+                while let Some(bytes) = receiver.recv().await {
+                    socket.send_to(bytes, &path).await.unwrap();
+                }
+            });
+            sender
+        };
+        test_statsd(config, sender).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_statsd_unix_datagram_replaces_stale_socket() {
+        let in_path = tempfile::tempdir()
+            .unwrap()
+            .into_path()
+            .join("unix_datagram_stale_test");
+        // A socket left behind by an earlier run.
+        drop(std::os::unix::net::UnixDatagram::bind(&in_path).unwrap());
+
+        let config = StatsdConfig::UnixDatagram(UnixConfig {
+            path: in_path.clone(),
+            dogstatsd_events: false,
+        });
+        let sender = {
+            let (sender, mut receiver) = mpsc::channel(200);
+            let path = in_path;
+            tokio::spawn(async move {
+                let mut socket = tokio::net::UnixDatagram::unbound().unwrap();
+                while let Some(bytes) = receiver.recv().await {
+                    socket.send_to(bytes, &path).await.unwrap();
+                }
+            });
+            sender
+        };
+        test_statsd(config, sender).await;
+    }
+
+    #[test]
+    fn dogstatsd_events_are_opt_in() {
+        let line = "_e{6,4}:Deploy|done";
+        assert!(parse_event(line, false).is_none());
+        assert!(parse_event(line, true)
+            .unwrap()
+            .as_log()
+            .get("title")
+            .is_some());
+
+        let config: StatsdConfig = toml::from_str(
+            r#"
+            mode = "udp"
+            address = "127.0.0.1:8125"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.output_type(), config::DataType::Metric);
+
+        let config: StatsdConfig = toml::from_str(
+            r#"
+            mode = "udp"
+            address = "127.0.0.1:8125"
+            dogstatsd_events = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.output_type(), config::DataType::Any);
+    }
+
     async fn test_statsd(
         statsd_config: StatsdConfig,
         // could use unbounded channel,
@@ -1,4 +1,11 @@
-use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        Event, LogEvent, Value,
+    },
+};
+use chrono::{DateTime, TimeZone, Utc};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::{
@@ -12,6 +19,18 @@ lazy_static! {
     static ref NONALPHANUM: Regex = Regex::new(r"[^a-zA-Z_\-0-9\.]").unwrap();
 }
 
+/// Parses a DogStatsD line, which holds either a metric, an event or a
+/// service check. Events and service checks are turned into log events.
+pub fn parse_line(line: &str) -> Result<Event, ParseError> {
+    if line.starts_with("_e{") {
+        parse_event(line).map(Event::Log)
+    } else if line.starts_with("_sc|") {
+        parse_service_check(line).map(Event::Log)
+    } else {
+        parse(line).map(Event::Metric)
+    }
+}
+
 pub fn parse(packet: &str) -> Result<Metric, ParseError> {
     // https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/#datagram-format
     let key_and_body = packet.splitn(2, ':').collect::<Vec<_>>();
@@ -32,102 +51,219 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
     let name = sanitize_key(key);
     let metric_type = parts[1];
 
-    // sampling part is optional and comes after metric type part
-    let sampling = parts.get(2).filter(|s| s.starts_with('@'));
-    let sample_rate = if let Some(s) = sampling {
-        1.0 / sanitize_sampling(parse_sampling(s)?)
-    } else {
-        1.0
-    };
+    // the optional parts following the metric type may come in any order,
+    // and those we don't know about are ignored
+    let mut sample_rate = 1.0;
+    let mut tags = None;
+    let mut timestamp = None;
+    for part in &parts[2..] {
+        if part.starts_with('@') {
+            sample_rate = 1.0 / sanitize_sampling(parse_sampling(part)?);
+        } else if part.starts_with('#') {
+            let parsed = parse_tags(part)?;
+            tags.get_or_insert_with(BTreeMap::new).extend(parsed);
+        } else if let Some(seconds) = part.strip_prefix('T') {
+            timestamp = Some(parse_timestamp(seconds)?);
+        } else if let Some(container_id) = part.strip_prefix("c:") {
+            tags.get_or_insert_with(BTreeMap::new)
+                .insert("container_id".to_owned(), container_id.to_owned());
+        }
+    }
 
-    // tags are optional and could be found either after sampling of after metric type part
-    let tags = if sampling.is_none() {
-        parts.get(2)
-    } else {
-        parts.get(3)
-    };
-    let tags = tags.filter(|s| s.starts_with('#'));
-    let tags = if let Some(t) = tags {
-        Some(parse_tags(t)?)
-    } else {
-        None
-    };
+    // clients may pack several values of a metric into a single line
+    let values = parts[0].split(':').collect::<Vec<_>>();
 
-    let metric = match metric_type {
+    let (kind, value) = match metric_type {
         "c" => {
-            let val: f64 = parts[0].parse()?;
-            Metric {
-                name,
-                namespace: None,
-                timestamp: None,
-                tags,
-                kind: MetricKind::Incremental,
-                value: MetricValue::Counter {
-                    value: val * sample_rate,
-                },
+            let mut sum = 0.0;
+            for value in values {
+                sum += value.parse::<f64>()?;
             }
+            (
+                MetricKind::Incremental,
+                MetricValue::Counter {
+                    value: sum * sample_rate,
+                },
+            )
         }
         unit @ "h" | unit @ "ms" | unit @ "d" => {
-            let val: f64 = parts[0].parse()?;
-            Metric {
-                name,
-                namespace: None,
-                timestamp: None,
-                tags,
-                kind: MetricKind::Incremental,
-                value: MetricValue::Distribution {
-                    values: vec![convert_to_base_units(unit, val)],
-                    sample_rates: vec![sample_rate as u32],
+            let values = values
+                .into_iter()
+                .map(|value| {
+                    value
+                        .parse()
+                        .map(|value| convert_to_base_units(unit, value))
+                })
+                .collect::<Result<Vec<f64>, _>>()?;
+            (
+                MetricKind::Incremental,
+                MetricValue::Distribution {
+                    sample_rates: vec![sample_rate as u32; values.len()],
+                    values,
                     statistic: convert_to_statistic(unit),
                 },
-            }
+            )
         }
         "g" => {
-            let value = if parts[0]
-                .chars()
-                .next()
-                .map(|c| c.is_ascii_digit())
-                .ok_or_else(|| ParseError::Malformed("empty first body component"))?
-            {
-                parts[0].parse()?
-            } else {
-                parts[0][1..].parse()?
-            };
-
-            match parse_direction(parts[0])? {
-                None => Metric {
-                    name,
-                    namespace: None,
-                    timestamp: None,
-                    tags,
-                    kind: MetricKind::Absolute,
-                    value: MetricValue::Gauge { value },
-                },
-                Some(sign) => Metric {
-                    name,
-                    namespace: None,
-                    timestamp: None,
-                    tags,
-                    kind: MetricKind::Incremental,
-                    value: MetricValue::Gauge {
-                        value: value * sign,
-                    },
-                },
+            // a gauge is only incremental if all of its values are signed
+            let mut kind = MetricKind::Incremental;
+            let mut total = 0.0;
+            for value in values {
+                let magnitude: f64 = if value
+                    .chars()
+                    .next()
+                    .map(|c| c.is_ascii_digit())
+                    .ok_or_else(|| ParseError::Malformed("empty first body component"))?
+                {
+                    value.parse()?
+                } else {
+                    value[1..].parse()?
+                };
+
+                match parse_direction(value)? {
+                    None => {
+                        kind = MetricKind::Absolute;
+                        total = magnitude;
+                    }
+                    Some(sign) => total += magnitude * sign,
+                }
             }
+            (kind, MetricValue::Gauge { value: total })
         }
-        "s" => Metric {
-            name,
-            namespace: None,
-            timestamp: None,
-            tags,
-            kind: MetricKind::Incremental,
-            value: MetricValue::Set {
-                values: vec![parts[0].into()].into_iter().collect(),
+        "s" => (
+            MetricKind::Incremental,
+            MetricValue::Set {
+                values: values.into_iter().map(Into::into).collect(),
             },
-        },
+        ),
         other => return Err(ParseError::UnknownMetricType(other.into())),
     };
-    Ok(metric)
+
+    Ok(Metric {
+        name,
+        namespace: None,
+        timestamp,
+        tags,
+        kind,
+        value,
+    })
+}
+
+fn parse_event(line: &str) -> Result<LogEvent, ParseError> {
+    // https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/#events
+    let header_end = line.find("}:").ok_or(ParseError::Malformed(
+        "event should start with '_e{<TITLE_LENGTH>,<TEXT_LENGTH>}:'",
+    ))?;
+    let lengths = line[3..header_end].splitn(2, ',').collect::<Vec<_>>();
+    if lengths.len() != 2 {
+        return Err(ParseError::Malformed(
+            "event header should have title and text lengths with ',' separator",
+        ));
+    }
+    let title_length: usize = lengths[0].parse()?;
+    let text_length: usize = lengths[1].parse()?;
+
+    // the lengths are in bytes, and cut the title and the text out of the body
+    let body = &line[header_end + 2..];
+    let title = body.get(..title_length).ok_or(ParseError::Malformed(
+        "event title should be as long as its header says",
+    ))?;
+    let text = body[title_length..]
+        .strip_prefix('|')
+        .and_then(|rest| rest.get(..text_length))
+        .ok_or(ParseError::Malformed(
+            "event text should be as long as its header says",
+        ))?;
+    let fields = &body[title_length + 1 + text_length..];
+
+    let mut log = LogEvent::from(BTreeMap::new());
+    log.insert(log_schema().message_key(), text.replace("\\n", "\n"));
+    log.insert("title", title.replace("\\n", "\n"));
+
+    let mut timestamp = None;
+    for field in fields.split('|').skip(1) {
+        if let Some(seconds) = field.strip_prefix("d:") {
+            timestamp = Some(parse_timestamp(seconds)?);
+        } else if let Some(host) = field.strip_prefix("h:") {
+            log.insert(log_schema().host_key(), host);
+        } else if let Some(aggregation_key) = field.strip_prefix("k:") {
+            log.insert("aggregation_key", aggregation_key);
+        } else if let Some(priority) = field.strip_prefix("p:") {
+            log.insert("priority", priority);
+        } else if let Some(source_type_name) = field.strip_prefix("s:") {
+            log.insert("source_type_name", source_type_name);
+        } else if let Some(alert_type) = field.strip_prefix("t:") {
+            log.insert("alert_type", alert_type);
+        } else if field.starts_with('#') {
+            log.insert("tags", tags_value(parse_tags(field)?));
+        }
+    }
+    log.insert(
+        log_schema().timestamp_key(),
+        timestamp.unwrap_or_else(Utc::now),
+    );
+
+    Ok(log)
+}
+
+fn parse_service_check(line: &str) -> Result<LogEvent, ParseError> {
+    // https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/#service-checks
+    // the message comes last, and may contain anything
+    let (head, message) = match line.find("|m:") {
+        Some(index) => (&line[..index], Some(&line[index + 3..])),
+        None => (line, None),
+    };
+
+    let parts = head.split('|').collect::<Vec<_>>();
+    if parts.len() < 3 || parts[1].is_empty() {
+        return Err(ParseError::Malformed(
+            "service check should have a name and a status",
+        ));
+    }
+    let status: i64 = parts[2].parse()?;
+    if !(0..=3).contains(&status) {
+        return Err(ParseError::Malformed(
+            "service check status should be between 0 and 3",
+        ));
+    }
+
+    let mut log = LogEvent::from(BTreeMap::new());
+    log.insert("check", parts[1]);
+    log.insert("status", status);
+    if let Some(message) = message {
+        log.insert(log_schema().message_key(), message.replace("\\n", "\n"));
+    }
+
+    let mut timestamp = None;
+    for part in &parts[3..] {
+        if let Some(seconds) = part.strip_prefix("d:") {
+            timestamp = Some(parse_timestamp(seconds)?);
+        } else if let Some(host) = part.strip_prefix("h:") {
+            log.insert(log_schema().host_key(), host);
+        } else if part.starts_with('#') {
+            log.insert("tags", tags_value(parse_tags(part)?));
+        }
+    }
+    log.insert(
+        log_schema().timestamp_key(),
+        timestamp.unwrap_or_else(Utc::now),
+    );
+
+    Ok(log)
+}
+
+fn parse_timestamp(seconds: &str) -> Result<DateTime<Utc>, ParseError> {
+    Utc.timestamp_opt(seconds.parse()?, 0)
+        .single()
+        .ok_or(ParseError::Malformed("timestamp is out of range"))
+}
+
+fn tags_value(tags: BTreeMap<String, String>) -> Value {
+    Value::Map(
+        tags.into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect(),
+    )
 }
 
 fn parse_sampling(input: &str) -> Result<f64, ParseError> {
@@ -240,8 +376,12 @@ impl From<ParseFloatError> for ParseError {
 
 #[cfg(test)]
 mod test {
-    use super::{parse, sanitize_key, sanitize_sampling};
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use super::{parse, parse_line, sanitize_key, sanitize_sampling, ParseError};
+    use crate::{
+        config::log_schema,
+        event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
+    };
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn basic_counter() {
@@ -454,4 +594,110 @@ mod test {
         assert_eq!(2.5, sanitize_sampling(2.5));
         assert_eq!(-5.0, sanitize_sampling(-5.0));
     }
+
+    #[test]
+    fn dogstatsd_fields_in_any_order() {
+        assert_eq!(
+            parse("page.views:1|c|T1656581400|c:83c0a99c0a54|#env:prod|@0.5"),
+            Ok(Metric {
+                name: "page.views".into(),
+                namespace: None,
+                timestamp: Some(Utc.timestamp(1656581400, 0)),
+                tags: Some(
+                    vec![
+                        ("container_id".to_owned(), "83c0a99c0a54".to_owned()),
+                        ("env".to_owned(), "prod".to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                kind: MetricKind::Incremental,
+                value: MetricValue::Counter { value: 2.0 },
+            }),
+        );
+    }
+
+    #[test]
+    fn packed_values() {
+        assert_eq!(
+            parse("latency:10:20:30|ms|#env:prod").map(|metric| metric.value),
+            Ok(MetricValue::Distribution {
+                values: vec![0.01, 0.02, 0.03],
+                sample_rates: vec![1, 1, 1],
+                statistic: StatisticKind::Histogram,
+            }),
+        );
+        assert_eq!(
+            parse("requests:1:2:3|c").map(|metric| metric.value),
+            Ok(MetricValue::Counter { value: 6.0 }),
+        );
+
+        let gauge = parse("queue:+4:-1|g").unwrap();
+        assert_eq!(gauge.kind, MetricKind::Incremental);
+        assert_eq!(gauge.value, MetricValue::Gauge { value: 3.0 });
+
+        let gauge = parse("queue:+4:10:-1|g").unwrap();
+        assert_eq!(gauge.kind, MetricKind::Absolute);
+        assert_eq!(gauge.value, MetricValue::Gauge { value: 9.0 });
+    }
+
+    #[test]
+    fn events() {
+        let event = parse_line(
+            "_e{11,23}:Deploy done|Version 1.2\\nrolled out|d:1656581400|h:web-1|p:low|t:success|#env:prod,canary",
+        )
+        .unwrap();
+        let log = event.as_log();
+
+        assert_eq!(
+            log[log_schema().message_key()],
+            "Version 1.2\nrolled out".into()
+        );
+        assert_eq!(log["title"], "Deploy done".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1656581400, 0).into()
+        );
+        assert_eq!(log[log_schema().host_key()], "web-1".into());
+        assert_eq!(log["priority"], "low".into());
+        assert_eq!(log["alert_type"], "success".into());
+        assert_eq!(log["tags.env"], "prod".into());
+        assert_eq!(log["tags.canary"], "true".into());
+    }
+
+    #[test]
+    fn invalid_events() {
+        assert_eq!(
+            parse_line("_e{50,4}:Deploy|done").map(|_| ()),
+            Err(ParseError::Malformed(
+                "event title should be as long as its header says"
+            )),
+        );
+        assert!(parse_line("_e{6,x}:Deploy|done").is_err());
+    }
+
+    #[test]
+    fn service_checks() {
+        let event = parse_line(
+            "_sc|db.replication|2|d:1656581400|h:db-1|#env:prod|m:Lagging by 30s | check replica",
+        )
+        .unwrap();
+        let log = event.as_log();
+
+        assert_eq!(log["check"], "db.replication".into());
+        assert_eq!(log["status"], 2.into());
+        assert_eq!(
+            log[log_schema().message_key()],
+            "Lagging by 30s | check replica".into()
+        );
+        assert_eq!(log[log_schema().host_key()], "db-1".into());
+        assert_eq!(log["tags.env"], "prod".into());
+
+        assert_eq!(
+            parse_line("_sc|db.replication|7").map(|_| ()),
+            Err(ParseError::Malformed(
+                "service check status should be between 0 and 3"
+            )),
+        );
+    }
 }
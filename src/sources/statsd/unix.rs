@@ -1,27 +1,98 @@
 use crate::{
-    shutdown::ShutdownSignal, sources::util::build_unix_source, sources::Source, Event, Pipeline,
+    internal_events::StatsdSocketError, shutdown::ShutdownSignal, sources::util::build_unix_source,
+    sources::Source, Event, Pipeline,
 };
 use bytes::Bytes;
+use futures::{compat::Sink01CompatExt, stream, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{
+    io,
+    os::unix::fs::FileTypeExt,
+    path::{Path, PathBuf},
+};
+use tokio::net::UnixDatagram;
 use tokio_util::codec::LinesCodec;
 
+/// The largest datagram a unix socket will hand us.
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct UnixConfig {
     pub path: PathBuf,
-}
-
-fn build_event(_: &str, _: Option<Bytes>, line: &str) -> Option<Event> {
-    super::parse_event(line)
+    #[serde(default)]
+    pub dogstatsd_events: bool,
 }
 
 pub fn statsd_unix(config: UnixConfig, shutdown: ShutdownSignal, out: Pipeline) -> Source {
+    let dogstatsd_events = config.dogstatsd_events;
     build_unix_source(
         config.path,
         LinesCodec::new(),
         String::new(),
         shutdown,
         out,
-        build_event,
+        move |_: &str, _: Option<Bytes>, line: &str| -> Option<Event> {
+            super::parse_event(line, dogstatsd_events)
+        },
     )
 }
+
+/// Removes a socket left behind at `path`, for example by a previous run that
+/// did not shut down cleanly, as binding to it would fail otherwise.
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path),
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// Listens on a unix datagram socket, which is what DogStatsD clients use
+/// by default when given a socket path.
+pub async fn statsd_unix_datagram(
+    config: UnixConfig,
+    mut shutdown: ShutdownSignal,
+    out: Pipeline,
+) -> Result<(), ()> {
+    remove_stale_socket(&config.path).map_err(|error| emit!(StatsdSocketError::bind(error)))?;
+    let mut socket =
+        UnixDatagram::bind(&config.path).map_err(|error| emit!(StatsdSocketError::bind(error)))?;
+
+    info!(
+        message = "Listening.",
+        path = ?config.path,
+        r#type = "unix_datagram"
+    );
+
+    let mut out = out.sink_compat();
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let received = tokio::select! {
+            received = socket.recv(&mut buffer) => received,
+            _ = &mut shutdown => break,
+        };
+
+        match received {
+            Ok(size) => {
+                let packet = String::from_utf8_lossy(&buffer[..size]);
+                let metrics = packet
+                    .lines()
+                    .filter_map(|line| super::parse_event(line, config.dogstatsd_events))
+                    .map(Ok);
+
+                // Need `boxed` to resolve a lifetime issue
+                // https://github.com/rust-lang/rust/issues/64552#issuecomment-669728225
+                let mut metrics = stream::iter(metrics).boxed();
+                if let Err(error) = out.send_all(&mut metrics).await {
+                    error!(message = "Error sending metric.", %error);
+                    break;
+                }
+            }
+            Err(error) => {
+                emit!(StatsdSocketError::read(error));
+            }
+        }
+    }
+
+    Ok(())
+}
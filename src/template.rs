@@ -1,4 +1,8 @@
-use crate::{config::log_schema, event::Value, Event};
+use crate::{
+    config::log_schema,
+    event::{Metric, Value},
+    Event,
+};
 use bytes::Bytes;
 use chrono::{
     format::{strftime::StrftimeItems, Item},
@@ -149,12 +153,14 @@ fn render_fields(src: &str, event: &Event) -> Result<String, Vec<String>> {
                 .get(1)
                 .map(|s| s.as_str().trim())
                 .expect("src should match regex");
-            if let Some(val) = event.as_log().get(&key) {
-                val.to_string_lossy()
-            } else {
+            let value = match event {
+                Event::Log(log) => log.get(&key).map(Value::to_string_lossy),
+                Event::Metric(metric) => render_metric_field(key, metric),
+            };
+            value.unwrap_or_else(|| {
                 missing_fields.push(key.to_owned());
                 String::new()
-            }
+            })
         })
         .into_owned();
     if missing_fields.is_empty() {
@@ -164,12 +170,26 @@ fn render_fields(src: &str, event: &Event) -> Result<String, Vec<String>> {
     }
 }
 
+/// Metrics have no fields, so their templates may only refer to their name,
+/// namespace and tags, as `tags.<tag>`.
+fn render_metric_field(key: &str, metric: &Metric) -> Option<String> {
+    match key {
+        "name" => Some(metric.name.clone()),
+        "namespace" => metric.namespace.clone(),
+        _ if key.starts_with("tags.") => metric
+            .tags
+            .as_ref()
+            .and_then(|tags| tags.get(&key["tags.".len()..]).cloned()),
+        _ => None,
+    }
+}
+
 fn render_timestamp(src: &str, event: &Event) -> String {
     let timestamp = match event {
         Event::Log(log) => log
             .get(log_schema().timestamp_key())
             .and_then(Value::as_timestamp),
-        _ => None,
+        Event::Metric(metric) => metric.timestamp.as_ref(),
     };
     if let Some(ts) = timestamp {
         ts.format(src).to_string()
@@ -218,6 +238,7 @@ impl Serialize for Template {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::{MetricKind, MetricValue};
     use chrono::TimeZone;
 
     #[test]
@@ -309,6 +330,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn render_metric() {
+        let event = Event::Metric(Metric {
+            name: "requests".into(),
+            namespace: Some("app".into()),
+            timestamp: Some(Utc.ymd(2001, 2, 3).and_hms(4, 5, 6)),
+            tags: Some(
+                vec![("host".to_owned(), "web-1".to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        });
+
+        let template = Template::try_from("{{ namespace }}.{{ name }}-{{ tags.host }}-%F").unwrap();
+        assert_eq!(
+            Ok(Bytes::from("app.requests-web-1-2001-02-03")),
+            template.render(&event)
+        );
+
+        let template = Template::try_from("{{ message }}-{{ tags.region }}").unwrap();
+        assert_eq!(
+            Err(vec!["message".to_string(), "tags.region".to_string()]),
+            template.render(&event)
+        );
+    }
+
     #[test]
     fn render_dynamic_multiple_keys() {
         let mut event = Event::from("hello world");
@@ -6,7 +6,7 @@ use super::{
 use super::{Handshake, MaybeTls};
 use bytes::{Buf, BufMut};
 use futures::{future::BoxFuture, stream, FutureExt, Stream};
-use openssl::ssl::{SslAcceptor, SslMethod};
+use openssl::ssl::{self, AlpnError, SslAcceptor, SslMethod};
 use snafu::ResultExt;
 use std::{
     future::Future,
@@ -21,6 +21,9 @@ use tokio::{
 };
 use tokio_openssl::{HandshakeError, SslStream};
 
+/// Application protocols accepted over TLS, in wire format and order of preference.
+const ALPN_PROTOCOLS: &[u8] = b"\x02h2\x08http/1.1";
+
 impl TlsSettings {
     pub(crate) fn acceptor(&self) -> crate::tls::Result<SslAcceptor> {
         match self.identity {
@@ -29,6 +32,11 @@ impl TlsSettings {
                 let mut acceptor =
                     SslAcceptor::mozilla_intermediate(SslMethod::tls()).context(CreateAcceptor)?;
                 self.apply_context(&mut acceptor)?;
+                if self.http2_alpn {
+                    acceptor.set_alpn_select_callback(|_, client| {
+                        ssl::select_next_proto(ALPN_PROTOCOLS, client).ok_or(AlpnError::NOACK)
+                    });
+                }
                 Ok(acceptor.build())
             }
         }
@@ -36,6 +44,15 @@ impl TlsSettings {
 }
 
 impl MaybeTlsSettings {
+    /// Negotiates HTTP/2 with clients through ALPN, which gRPC clients insist
+    /// on. Other listeners leave ALPN alone, as their clients don't ask for it.
+    pub(crate) fn with_http2_alpn(mut self) -> Self {
+        if let Self::Tls(tls) = &mut self {
+            tls.http2_alpn = true;
+        }
+        self
+    }
+
     pub(crate) async fn bind(&self, addr: &SocketAddr) -> crate::tls::Result<MaybeTlsListener> {
         let listener = TcpListener::bind(addr).await.context(TcpBind)?;
 
@@ -5,10 +5,13 @@ use super::{
 };
 use crate::{
     buffers,
-    config::{DataType, SinkContext},
-    event::Event,
+    config::{dead_letter_output, named_output, DataType, SinkContext},
+    event::{
+        finalization::{EventFinalizer, EventFinalizers},
+        Event,
+    },
     shutdown::SourceShutdownCoordinator,
-    transforms::Transform,
+    transforms::{NamedOutputs, Transform},
     Pipeline,
 };
 use futures::{
@@ -16,12 +19,14 @@ use futures::{
     future, FutureExt, StreamExt, TryFutureExt,
 };
 use futures01::{sync::mpsc, Future as Future01, Stream as Stream01};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use tokio::time::{timeout, Duration};
 
 pub struct Pieces {
     pub inputs: HashMap<String, (buffers::BufferInputCloner, Vec<String>)>,
     pub outputs: HashMap<String, fanout::ControlChannel>,
+    /// The named outputs of each component, as they appear in `outputs`.
+    pub named_outputs: HashMap<String, Vec<String>>,
     pub tasks: HashMap<String, Task>,
     pub source_tasks: HashMap<String, Task>,
     pub healthchecks: HashMap<String, Task>,
@@ -35,6 +40,7 @@ pub async fn build_pieces(
 ) -> Result<Pieces, Vec<String>> {
     let mut inputs = HashMap::new();
     let mut outputs = HashMap::new();
+    let mut named_outputs = HashMap::new();
     let mut tasks = HashMap::new();
     let mut source_tasks = HashMap::new();
     let mut healthchecks = HashMap::new();
@@ -100,6 +106,7 @@ pub async fn build_pieces(
         let typetag = transform.inner.transform_type();
 
         let input_type = transform.inner.input_type();
+        let transform_outputs = transform.inner.named_outputs();
         let transform = match transform.inner.build().await {
             Err(error) => {
                 errors.push(format!("Transform \"{}\": {}", name, error));
@@ -113,16 +120,33 @@ pub async fn build_pieces(
 
         let (output, control) = Fanout::new();
 
+        let mut named = HashMap::new();
+        let mut names = Vec::new();
+        for transform_output in transform_outputs {
+            let (output, control) = Fanout::new();
+            let output_name = named_output(name, &transform_output);
+            outputs.insert(output_name.clone(), control);
+            names.push(output_name);
+            named.insert(transform_output, output);
+        }
+        let output = fanout::Outputs::new(output, named);
+
         let transform = match transform {
             Transform::Function(mut t) => {
                 let filtered = filter_event_type(input_rx, input_type);
+                let mut named = NamedOutputs::default();
                 #[allow(deprecated)]
                 // `boxed()` here is deprecated, but the replacement won't work until we adopt futures 0.3 here.
                 let transformed = filtered
                     .map(move |v| {
                         let mut buf = Vec::with_capacity(1);
-                        t.transform(&mut buf, v);
-                        futures01::stream::iter_ok(buf.into_iter())
+                        t.transform_with_outputs(&mut buf, &mut named, v);
+                        let routed = named.drain().collect::<Vec<_>>();
+                        let events = buf
+                            .into_iter()
+                            .map(|event| (None, event))
+                            .chain(routed.into_iter().map(|(name, event)| (Some(name), event)));
+                        futures01::stream::iter_ok(events)
                     })
                     .flatten()
                     .boxed();
@@ -131,7 +155,7 @@ pub async fn build_pieces(
             Transform::Task(t) => {
                 let filtered = filter_event_type(input_rx, input_type);
                 let transformed: Box<dyn futures01::Stream<Item = _, Error = _> + Send> =
-                    t.transform(filtered);
+                    t.transform_with_outputs(filtered);
                 transformed.forward(output)
             }
         }
@@ -141,6 +165,7 @@ pub async fn build_pieces(
 
         inputs.insert(name.clone(), (input_tx, trans_inputs.clone()));
         outputs.insert(name.clone(), control);
+        named_outputs.insert(name.clone(), names);
         tasks.insert(name.clone(), task);
     }
 
@@ -165,6 +190,20 @@ pub async fn build_pieces(
             Ok(buffer) => buffer,
         };
 
+        // Events the sink rejects are passed on to its dead-letter output,
+        // as long as something consumes it.
+        let dead_letter = if config.dead_letter_consumed(name) {
+            let (dead_letter_tx, dead_letter_rx) = mpsc::unbounded();
+            let (output, control) = Fanout::new();
+            outputs.insert(dead_letter_output(name), control);
+            named_outputs.insert(name.clone(), vec![dead_letter_output(name)]);
+
+            let pump = dead_letter_rx.forward(output).map(|_| ()).compat();
+            let dead_letter = Arc::new(buffers::DeadLetter::new(name, dead_letter_tx));
+            Some((dead_letter, pump))
+        } else {
+            None
+        };
         let cx = SinkContext { acker };
 
         let (sink, healthcheck) = match sink.inner.build(cx).await {
@@ -175,14 +214,40 @@ pub async fn build_pieces(
             Ok((sink, healthcheck)) => (sink, healthcheck),
         };
 
+        let (dead_letter, pump) = match dead_letter {
+            Some((dead_letter, pump)) => (Some(dead_letter), Some(pump)),
+            None => (None, None),
+        };
         let sink = sink
             .run(
                 filter_event_type(rx, input_type)
                     .compat()
                     .take_while(|e| future::ready(e.is_ok()))
-                    .map(|x| x.unwrap()),
+                    .map(move |x| {
+                        let mut event = x.unwrap();
+                        // The finalizer holds on to a copy of the event until
+                        // the sink is done with it, and only hands it to the
+                        // dead-letter output if the event is rejected. This
+                        // doubles the memory held by the sink's events, so it
+                        // is only paid while the output is consumed. Metrics
+                        // carry no finalizers and never reach the output.
+                        if let (Some(dead_letter), Event::Log(log)) = (&dead_letter, &mut event) {
+                            let mut copy = log.clone();
+                            copy.take_finalizers();
+                            let finalizer =
+                                EventFinalizer::dead_letter(copy, Arc::clone(dead_letter));
+                            log.merge_finalizers(EventFinalizers::new(finalizer));
+                        }
+                        event
+                    }),
             )
             .inspect(|_| debug!("Finished."));
+        // The dead-letter output is done once the sink and the events it
+        // received have let go of it.
+        let sink = match pump {
+            Some(pump) => future::join(sink, pump).map(|(result, _)| result).boxed(),
+            None => sink.boxed(),
+        };
         let task = Task::new(name, typetag, sink);
 
         let healthcheck_task = async move {
@@ -220,6 +285,7 @@ pub async fn build_pieces(
         let pieces = Pieces {
             inputs,
             outputs,
+            named_outputs,
             tasks,
             source_tasks,
             healthchecks,
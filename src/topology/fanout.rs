@@ -1,6 +1,7 @@
-use crate::Event;
+use crate::{transforms::RoutedEvent, Event};
 use futures::compat::Future01CompatExt;
 use futures01::{future, sync::mpsc, Async, AsyncSink, Poll, Sink, StartSend, Stream};
+use std::collections::HashMap;
 
 type RouterSink = Box<dyn Sink<SinkItem = Event, SinkError = ()> + 'static + Send>;
 
@@ -150,11 +151,80 @@ impl Sink for Fanout {
     }
 }
 
+/// Sends each event of a transform to the fanout of the output it was routed to.
+pub struct Outputs {
+    default: Fanout,
+    named: HashMap<String, Fanout>,
+}
+
+impl Outputs {
+    pub fn new(default: Fanout, named: HashMap<String, Fanout>) -> Self {
+        Self { default, named }
+    }
+
+    fn poll_outputs(&mut self, close: bool) -> Poll<(), ()> {
+        let mut poll_result = Async::Ready(());
+
+        for fanout in std::iter::once(&mut self.default).chain(self.named.values_mut()) {
+            let result = if close {
+                fanout.close()
+            } else {
+                fanout.poll_complete()
+            };
+            if result?.is_not_ready() {
+                poll_result = Async::NotReady;
+            }
+        }
+
+        Ok(poll_result)
+    }
+}
+
+impl Sink for Outputs {
+    type SinkItem = RoutedEvent;
+    type SinkError = ();
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        let (output, event) = item;
+        let fanout = match &output {
+            None => &mut self.default,
+            Some(name) => match self.named.get_mut(name) {
+                Some(fanout) => fanout,
+                None => {
+                    error!(
+                        message = "Transform sent an event to an output it doesn't have; dropping event.",
+                        output = %name,
+                        rate_limit_secs = 30
+                    );
+                    return Ok(AsyncSink::Ready);
+                }
+            },
+        };
+
+        Ok(match fanout.start_send(event)? {
+            AsyncSink::Ready => AsyncSink::Ready,
+            AsyncSink::NotReady(event) => AsyncSink::NotReady((output, event)),
+        })
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.poll_outputs(false)
+    }
+
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        self.poll_outputs(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ControlMessage, Fanout};
-    use crate::{test_util::collect_ready, Event};
-    use futures::compat::Future01CompatExt;
+    use super::{ControlMessage, Fanout, Outputs};
+    use crate::{
+        event::finalization::{BatchNotifier, BatchStatus, EventStatus},
+        test_util::collect_ready,
+        Event,
+    };
+    use futures::{compat::Future01CompatExt, FutureExt};
     use futures01::{stream, sync::mpsc, Future, Sink, Stream};
     use tokio::time::{delay_for, Duration};
 
@@ -183,6 +253,73 @@ mod tests {
         assert_eq!(collect_ready(rx_b).await.unwrap(), vec![rec1, rec2]);
     }
 
+    #[tokio::test]
+    async fn outputs_route_events_by_name() {
+        let (tx_default, rx_default) = mpsc::unbounded();
+        let tx_default = Box::new(tx_default.sink_map_err(|_| unreachable!()));
+        let (tx_errors, rx_errors) = mpsc::unbounded();
+        let tx_errors = Box::new(tx_errors.sink_map_err(|_| unreachable!()));
+
+        let mut default = Fanout::new().0;
+        default.add("a".to_string(), tx_default);
+        let mut errors = Fanout::new().0;
+        errors.add("b".to_string(), tx_errors);
+        let outputs = Outputs::new(
+            default,
+            vec![("errors".to_string(), errors)].into_iter().collect(),
+        );
+
+        let rec1 = Event::from("line 1".to_string());
+        let rec2 = Event::from("line 2".to_string());
+        let rec3 = Event::from("line 3".to_string());
+
+        let _outputs = outputs
+            .send_all(stream::iter_ok(vec![
+                (None, rec1.clone()),
+                (Some("errors".to_string()), rec2.clone()),
+                (Some("unknown".to_string()), rec3),
+            ]))
+            .compat()
+            .await
+            .unwrap();
+
+        assert_eq!(collect_ready(rx_default).await.unwrap(), vec![rec1]);
+        assert_eq!(collect_ready(rx_errors).await.unwrap(), vec![rec2]);
+    }
+
+    #[tokio::test]
+    async fn fanout_finalizes_after_all_sinks() {
+        let (tx_a, rx_a) = mpsc::unbounded();
+        let tx_a = Box::new(tx_a.sink_map_err(|_| unreachable!()));
+        let (tx_b, rx_b) = mpsc::unbounded();
+        let tx_b = Box::new(tx_b.sink_map_err(|_| unreachable!()));
+
+        let mut fanout = Fanout::new().0;
+
+        fanout.add("a".to_string(), tx_a);
+        fanout.add("b".to_string(), tx_b);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let rec = Event::from("line 1".to_string()).with_batch_notifier(&batch);
+        drop(batch);
+
+        let _fanout = fanout.send(rec).compat().await.unwrap();
+
+        let mut recs_a = collect_ready(rx_a).await.unwrap();
+        recs_a[0]
+            .take_finalizers()
+            .update_status(EventStatus::Delivered);
+        drop(recs_a);
+        assert!((&mut receiver).now_or_never().is_none());
+
+        let mut recs_b = collect_ready(rx_b).await.unwrap();
+        recs_b[0]
+            .take_finalizers()
+            .update_status(EventStatus::Delivered);
+        drop(recs_b);
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+    }
+
     #[tokio::test]
     async fn fanout_notready() {
         let (tx_a, rx_a) = mpsc::channel(1);
@@ -32,6 +32,7 @@ type TaskHandle = tokio::task::JoinHandle<Result<(), ()>>;
 pub struct RunningTopology {
     inputs: HashMap<String, buffers::BufferInputCloner>,
     outputs: HashMap<String, fanout::ControlChannel>,
+    named_outputs: HashMap<String, Vec<String>>,
     source_tasks: HashMap<String, TaskHandle>,
     tasks: HashMap<String, TaskHandle>,
     shutdown_coordinator: SourceShutdownCoordinator,
@@ -50,6 +51,7 @@ pub async fn start_validated(
     let mut running_topology = RunningTopology {
         inputs: HashMap::new(),
         outputs: HashMap::new(),
+        named_outputs: HashMap::new(),
         config,
         shutdown_coordinator: SourceShutdownCoordinator::default(),
         source_tasks: HashMap::new(),
@@ -377,6 +379,10 @@ impl RunningTopology {
             self.remove_outputs(&name);
         }
 
+        for name in diff.transforms.removed_and_changed() {
+            self.remove_named_outputs(name);
+        }
+
         // Sinks
         // First pass to detach sinks
         for name in &diff.sinks.to_remove {
@@ -384,6 +390,10 @@ impl RunningTopology {
             self.remove_inputs(&name);
         }
 
+        for name in diff.sinks.removed_and_changed() {
+            self.remove_named_outputs(name);
+        }
+
         // Second pass for final cleanup
         for name in &diff.sinks.to_remove {
             let previous = self.tasks.remove(name).unwrap();
@@ -414,6 +424,15 @@ impl RunningTopology {
             self.setup_outputs(&name, new_pieces);
         }
 
+        // Named outputs of transforms, and the dead-letter outputs of sinks
+        for name in diff
+            .transforms
+            .changed_and_added()
+            .chain(diff.sinks.changed_and_added())
+        {
+            self.setup_named_outputs(name, new_pieces);
+        }
+
         for name in &diff.transforms.to_change {
             self.replace_inputs(&name, new_pieces);
         }
@@ -537,6 +556,12 @@ impl RunningTopology {
         self.outputs.remove(name);
     }
 
+    fn remove_named_outputs(&mut self, name: &str) {
+        for output in self.named_outputs.remove(name).unwrap_or_default() {
+            self.remove_outputs(&output);
+        }
+    }
+
     fn remove_inputs(&mut self, name: &str) {
         self.inputs.remove(name);
 
@@ -585,6 +610,16 @@ impl RunningTopology {
         self.outputs.insert(name.to_string(), output);
     }
 
+    fn setup_named_outputs(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
+        // Sinks only have a dead-letter output while something consumes it.
+        if let Some(outputs) = new_pieces.named_outputs.remove(name) {
+            for output in &outputs {
+                self.setup_outputs(output, new_pieces);
+            }
+            self.named_outputs.insert(name.to_string(), outputs);
+        }
+    }
+
     fn setup_inputs(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
         let (tx, inputs) = new_pieces.inputs.remove(name).unwrap();
 
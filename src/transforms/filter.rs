@@ -18,11 +18,7 @@ inventory::submit! {
 
 impl GenerateConfig for FilterConfig {
     fn generate_config() -> toml::Value {
-        toml::from_str(
-            r#"condition.type = "check_fields"
-            condition."message.eq" = "value""#,
-        )
-        .unwrap()
+        toml::from_str(r#"condition = '.message == "value"'"#).unwrap()
     }
 }
 
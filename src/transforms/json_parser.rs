@@ -2,7 +2,7 @@ use crate::{
     config::{log_schema, DataType, TransformConfig, TransformDescription},
     event::Event,
     internal_events::{JsonParserEventProcessed, JsonParserFailedParse, JsonParserTargetExists},
-    transforms::{FunctionTransform, Transform},
+    transforms::{FunctionTransform, NamedOutputs, Transform, ERRORS_OUTPUT},
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -43,6 +43,10 @@ impl TransformConfig for JsonParserConfig {
     fn transform_type(&self) -> &'static str {
         "json_parser"
     }
+
+    fn named_outputs(&self) -> Vec<String> {
+        vec![ERRORS_OUTPUT.to_owned()]
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -71,7 +75,16 @@ impl From<JsonParserConfig> for JsonParser {
 }
 
 impl FunctionTransform for JsonParser {
-    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        self.transform_with_outputs(output, &mut NamedOutputs::default(), event)
+    }
+
+    fn transform_with_outputs(
+        &mut self,
+        output: &mut Vec<Event>,
+        named: &mut NamedOutputs,
+        mut event: Event,
+    ) {
         let log = event.as_mut_log();
         let value = log.get(&self.field);
 
@@ -123,7 +136,10 @@ impl FunctionTransform for JsonParser {
                     }
                 }
             }
-        } else if self.drop_invalid {
+        } else {
+            if !self.drop_invalid {
+                named.push(ERRORS_OUTPUT, event);
+            }
             return;
         }
 
@@ -137,6 +153,15 @@ mod test {
     use crate::{config::log_schema, event::Event};
     use serde_json::json;
 
+    /// Returns the event sent to the errors output, if any.
+    fn transform_errors(parser: &mut JsonParser, event: Event) -> Option<Event> {
+        let mut output = Vec::new();
+        let mut named = NamedOutputs::default();
+        parser.transform_with_outputs(&mut output, &mut named, event);
+        assert!(output.is_empty());
+        named.drain().map(|(_, event)| event).next()
+    }
+
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<JsonParserConfig>();
@@ -303,7 +328,7 @@ mod test {
 
         let event = Event::from(invalid);
 
-        let parsed = parser.transform_one(event.clone()).unwrap();
+        let parsed = transform_errors(&mut parser, event.clone()).unwrap();
 
         assert_eq!(event, parsed);
         assert_eq!(event.as_log()[log_schema().message_key()], invalid.into());
@@ -318,12 +343,45 @@ mod test {
         let mut event = Event::from("message");
         event.as_mut_log().insert("data", invalid);
 
-        let event = parser.transform_one(event).unwrap();
+        let event = transform_errors(&mut parser, event).unwrap();
 
         assert_eq!(event.as_log()["data"], invalid.into());
         assert!(event.as_log().get("greeting").is_none());
     }
 
+    #[test]
+    fn json_parser_sends_invalid_to_errors_output() {
+        let invalid = r#"{"greeting": "hello","#;
+
+        for &drop_invalid in &[false, true] {
+            let mut parser = JsonParser::from(JsonParserConfig {
+                drop_invalid,
+                ..Default::default()
+            });
+            let mut output = Vec::new();
+            let mut named = NamedOutputs::default();
+
+            let event = Event::from(r#"{"greeting": "hello"}"#);
+            parser.transform_with_outputs(&mut output, &mut named, event);
+            assert_eq!(named.drain().count(), 0);
+
+            let event = Event::from(invalid);
+            parser.transform_with_outputs(&mut output, &mut named, event);
+            let errors = named.drain().collect::<Vec<_>>();
+            if drop_invalid {
+                assert!(errors.is_empty());
+            } else {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].0, ERRORS_OUTPUT);
+                assert_eq!(
+                    errors[0].1.as_log()[log_schema().message_key()],
+                    invalid.into()
+                );
+            }
+            assert_eq!(output.len(), 1);
+        }
+    }
+
     #[test]
     fn json_parser_drop_invalid() {
         let valid = r#"{"greeting": "hello", "name": "bob"}"#;
@@ -456,7 +514,7 @@ mod test {
 
         let event = Event::from(r#"invalid json"#);
 
-        let event = parser.transform_one(event).unwrap();
+        let event = transform_errors(&mut parser, event).unwrap();
 
         assert_eq!(event.as_log()["message"], "invalid json".into());
     }
@@ -152,6 +152,19 @@ impl Transform {
 pub trait FunctionTransform: Send + dyn_clone::DynClone {
     fn transform(&mut self, output: &mut Vec<Event>, event: Event);
 
+    /// Like `transform`, but can also send events to the named outputs declared by the
+    /// transform's config.
+    ///
+    /// Only transforms with named outputs need to implement this, the topology always calls it.
+    fn transform_with_outputs(
+        &mut self,
+        output: &mut Vec<Event>,
+        _named: &mut NamedOutputs,
+        event: Event,
+    ) {
+        self.transform(output, event)
+    }
+
     /// A handy test function that inputs and outputs only one event.
     ///
     /// In a prior time, Vector primarily used this API to handle events.
@@ -184,6 +197,47 @@ pub trait TaskTransform: Send {
     ) -> Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>
     where
         Self: 'static;
+
+    /// Like `transform`, but each event is paired with the named output it goes to, or `None`
+    /// for the default output.
+    ///
+    /// Only transforms with named outputs need to implement this, the topology always calls it.
+    fn transform_with_outputs(
+        self: Box<Self>,
+        task: Box<dyn futures01::Stream<Item = Event, Error = ()> + Send>,
+    ) -> Box<dyn futures01::Stream<Item = RoutedEvent, Error = ()> + Send>
+    where
+        Self: 'static,
+    {
+        Box::new(futures01::Stream::map(self.transform(task), |event| {
+            (None, event)
+        }))
+    }
+}
+
+/// The named output that transforms send events they failed to process to.
+pub const ERRORS_OUTPUT: &str = "errors";
+
+/// An event along with the named output it was sent to, or `None` for the default output.
+pub type RoutedEvent = (Option<String>, Event);
+
+/// Events a function transform sends to its named outputs, declared with
+/// [`TransformConfig::named_outputs`](crate::config::TransformConfig::named_outputs).
+#[derive(Debug, Default)]
+pub struct NamedOutputs {
+    events: Vec<(String, Event)>,
+}
+
+impl NamedOutputs {
+    /// Sends `event` to the named output `output`.
+    pub fn push(&mut self, output: &str, event: Event) {
+        self.events.push((output.to_owned(), event));
+    }
+
+    /// Takes the events sent so far, in the order they were sent.
+    pub fn drain(&mut self) -> impl Iterator<Item = (String, Event)> + '_ {
+        self.events.drain(..)
+    }
 }
 
 #[derive(Debug, Snafu)]
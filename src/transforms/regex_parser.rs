@@ -5,7 +5,7 @@ use crate::{
         RegexParserConversionFailed, RegexParserEventProcessed, RegexParserFailedMatch,
         RegexParserMissingField, RegexParserTargetExists,
     },
-    transforms::{FunctionTransform, Transform},
+    transforms::{FunctionTransform, NamedOutputs, Transform, ERRORS_OUTPUT},
     types::{parse_check_conversion_map, Conversion},
 };
 use bytes::Bytes;
@@ -58,6 +58,10 @@ impl TransformConfig for RegexParserConfig {
     fn transform_type(&self) -> &'static str {
         "regex_parser"
     }
+
+    fn named_outputs(&self) -> Vec<String> {
+        vec![ERRORS_OUTPUT.to_owned()]
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -234,10 +238,27 @@ impl RegexParser {
             overwrite_target,
         }
     }
+
+    /// Sends an event that didn't match to the errors output, unless failed
+    /// events are dropped.
+    fn failed(&self, named: &mut NamedOutputs, event: Event) {
+        if !self.drop_failed {
+            named.push(ERRORS_OUTPUT, event);
+        }
+    }
 }
 
 impl FunctionTransform for RegexParser {
-    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        self.transform_with_outputs(output, &mut NamedOutputs::default(), event)
+    }
+
+    fn transform_with_outputs(
+        &mut self,
+        output: &mut Vec<Event>,
+        named: &mut NamedOutputs,
+        mut event: Event,
+    ) {
         let log = event.as_mut_log();
         let value = log.get(&self.field).map(|s| s.as_bytes());
         emit!(RegexParserEventProcessed);
@@ -248,12 +269,8 @@ impl FunctionTransform for RegexParser {
                 Some(id) => id,
                 None => {
                     emit!(RegexParserFailedMatch { value });
-                    if self.drop_failed {
-                        return;
-                    } else {
-                        output.push(event);
-                        return;
-                    };
+                    self.failed(named, event);
+                    return;
                 }
             };
 
@@ -294,9 +311,7 @@ impl FunctionTransform for RegexParser {
             emit!(RegexParserMissingField { field: &self.field });
         }
 
-        if !self.drop_failed {
-            output.push(event);
-        }
+        self.failed(named, event);
     }
 }
 
@@ -304,6 +319,7 @@ impl FunctionTransform for RegexParser {
 mod tests {
     use super::RegexParserConfig;
     use crate::event::{LogEvent, Value};
+    use crate::transforms::{NamedOutputs, ERRORS_OUTPUT};
     use crate::{config::TransformConfig, Event};
 
     #[test]
@@ -312,6 +328,23 @@ mod tests {
     }
 
     async fn do_transform(event: &str, patterns: &str, config: &str) -> Option<LogEvent> {
+        let (mut output, _errors) = do_transform_with_outputs(event, patterns, config).await;
+        output.pop().map(|event| event.into_log())
+    }
+
+    /// Returns the event sent to the errors output, checking that nothing was
+    /// sent to the default output.
+    async fn do_transform_failed(event: &str, patterns: &str, config: &str) -> Option<LogEvent> {
+        let (output, mut errors) = do_transform_with_outputs(event, patterns, config).await;
+        assert!(output.is_empty());
+        errors.pop().map(|event| event.into_log())
+    }
+
+    async fn do_transform_with_outputs(
+        event: &str,
+        patterns: &str,
+        config: &str,
+    ) -> (Vec<Event>, Vec<Event>) {
         let event = Event::from(event);
         let mut parser = toml::from_str::<RegexParserConfig>(&format!(
             r#"
@@ -326,7 +359,17 @@ mod tests {
         .unwrap();
         let parser = parser.as_function();
 
-        parser.transform_one(event).map(|event| event.into_log())
+        let mut output = Vec::new();
+        let mut named = NamedOutputs::default();
+        parser.transform_with_outputs(&mut output, &mut named, event);
+        let errors = named
+            .drain()
+            .map(|(name, event)| {
+                assert_eq!(name, ERRORS_OUTPUT);
+                event
+            })
+            .collect();
+        (output, errors)
     }
 
     #[tokio::test]
@@ -346,7 +389,7 @@ mod tests {
 
     #[tokio::test]
     async fn doesnt_do_anything_if_no_match() {
-        let log = do_transform(
+        let log = do_transform_failed(
             "asdf1234",
             r#"['status=(?P<status>\d+)']"#,
             "drop_field = false",
@@ -389,7 +432,7 @@ mod tests {
 
     #[tokio::test]
     async fn does_not_drop_field_if_no_match() {
-        let log = do_transform(
+        let log = do_transform_failed(
             "asdf1234",
             r#"['status=(?P<message>\S+)']"#,
             r#"field = "message""#,
@@ -480,6 +523,33 @@ mod tests {
     async fn does_drop_event_if_no_match() {
         let log = do_transform("asdf1234", r#"['something']"#, "drop_failed = true").await;
         assert!(log.is_none());
+        let log = do_transform_failed("asdf1234", r#"['something']"#, "drop_failed = true").await;
+        assert!(log.is_none());
+    }
+
+    #[tokio::test]
+    async fn sends_event_to_errors_output_if_no_match() {
+        let mut parser = toml::from_str::<RegexParserConfig>(
+            r#"
+                patterns = ['something']
+            "#,
+        )
+        .unwrap()
+        .build()
+        .await
+        .unwrap();
+        let parser = parser.as_function();
+
+        let mut output = Vec::new();
+        let mut named = NamedOutputs::default();
+        parser.transform_with_outputs(&mut output, &mut named, Event::from("asdf1234"));
+        parser.transform_with_outputs(&mut output, &mut named, Event::from("something"));
+
+        let errors = named.drain().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, ERRORS_OUTPUT);
+        assert_eq!(errors[0].1.as_log()["message"], "asdf1234".into());
+        assert_eq!(output.len(), 1);
     }
 
     #[tokio::test]
@@ -1,7 +1,7 @@
 use crate::{
     config::{DataType, TransformConfig, TransformDescription},
     event::Event,
-    internal_events::{RemapEventProcessed, RemapFailedMapping},
+    internal_events::{RemapEventAborted, RemapEventProcessed, RemapFailedMapping},
     transforms::{FunctionTransform, Transform},
     Result,
 };
@@ -14,6 +14,8 @@ use serde::{Deserialize, Serialize};
 pub struct RemapConfig {
     pub source: String,
     pub drop_on_err: bool,
+    #[derivative(Default(value = "true"))]
+    pub drop_on_abort: bool,
 }
 
 inventory::submit! {
@@ -30,11 +32,11 @@ impl TransformConfig for RemapConfig {
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Log
+        DataType::Any
     }
 
     fn output_type(&self) -> DataType {
-        DataType::Log
+        DataType::Any
     }
 
     fn transform_type(&self) -> &'static str {
@@ -46,6 +48,7 @@ impl TransformConfig for RemapConfig {
 pub struct Remap {
     program: Program,
     drop_on_err: bool,
+    drop_on_abort: bool,
 }
 
 impl Remap {
@@ -53,6 +56,7 @@ impl Remap {
         Ok(Remap {
             program: Program::new(&config.source, &crate::remap::FUNCTIONS_MUT)?,
             drop_on_err: config.drop_on_err,
+            drop_on_abort: config.drop_on_abort,
         })
     }
 }
@@ -63,14 +67,26 @@ impl FunctionTransform for Remap {
 
         let mut runtime = Runtime::default();
 
-        if let Err(error) = runtime.execute(&mut event, &self.program) {
-            emit!(RemapFailedMapping {
-                event_dropped: self.drop_on_err,
-                error: error.to_string(),
-            });
+        match runtime.execute(&mut event, &self.program) {
+            Ok(_) => {}
+            Err(remap::Error::Abort) => {
+                emit!(RemapEventAborted {
+                    event_dropped: self.drop_on_abort,
+                });
 
-            if self.drop_on_err {
-                return;
+                if self.drop_on_abort {
+                    return;
+                }
+            }
+            Err(error) => {
+                emit!(RemapFailedMapping {
+                    event_dropped: self.drop_on_err,
+                    error: error.to_string(),
+                });
+
+                if self.drop_on_err {
+                    return;
+                }
             }
         }
 
@@ -81,6 +97,7 @@ impl FunctionTransform for Remap {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::{Metric, MetricKind, MetricValue};
 
     #[test]
     fn generate_config() {
@@ -106,6 +123,7 @@ mod tests {
 "#
             .to_string(),
             drop_on_err: true,
+            drop_on_abort: true,
         };
         let mut tform = Remap::new(conf).unwrap();
 
@@ -116,4 +134,117 @@ mod tests {
         assert_eq!(get_field_string(&result, "bar"), "baz");
         assert_eq!(get_field_string(&result, "copy"), "buz");
     }
+
+    #[test]
+    fn check_remap_error_handling() {
+        let conf = RemapConfig {
+            source: r#".ts = parse_timestamp(.t, "%s") ?? "none"
+.parsed, $err = parse_json(.message)
+.err = $err
+"#
+            .to_string(),
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf).unwrap();
+
+        let result = tform.transform_one(Event::from("not json")).unwrap();
+        assert_eq!(get_field_string(&result, "ts"), "none");
+        assert_eq!(get_field_string(&result, "parsed"), "<null>");
+        assert!(get_field_string(&result, "err").starts_with("function call error"));
+    }
+
+    #[test]
+    fn check_remap_abort() {
+        let source = r#"if .message == "drop me" { abort }
+.foo = "bar"
+"#;
+        let mut tform = Remap::new(RemapConfig {
+            source: source.to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(tform.transform_one(Event::from("drop me")).is_none());
+        let result = tform.transform_one(Event::from("keep me")).unwrap();
+        assert_eq!(get_field_string(&result, "foo"), "bar");
+
+        let mut tform = Remap::new(RemapConfig {
+            source: source.to_string(),
+            drop_on_abort: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let result = tform.transform_one(Event::from("drop me")).unwrap();
+        assert!(result.as_log().get("foo").is_none());
+    }
+
+    #[test]
+    fn check_remap_type_error() {
+        let conf = RemapConfig {
+            source: ".count = 1\n.foo = upcase(.count)".to_string(),
+            ..Default::default()
+        };
+
+        let error = Remap::new(conf).unwrap_err().to_string();
+        assert!(
+            error.contains(r#"argument "value" of function "upcase" expects string, got integer"#)
+        );
+    }
+
+    #[test]
+    fn check_remap_metric() {
+        let conf = RemapConfig {
+            source: r#"if .tags.env == "test" { abort }
+.name = "http_" + .name
+.namespace = "vector"
+.tags.host = downcase(.tags.host)
+.tags.count = to_string(.value)
+del(".tags.env")
+"#
+            .to_string(),
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf).unwrap();
+
+        let metric = |env: &str| {
+            Event::Metric(Metric {
+                name: "requests".into(),
+                namespace: None,
+                timestamp: None,
+                tags: Some(
+                    vec![
+                        ("env".to_owned(), env.to_owned()),
+                        ("host".to_owned(), "LOCALHOST".to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                kind: MetricKind::Incremental,
+                value: MetricValue::Counter { value: 2.0 },
+            })
+        };
+
+        assert!(tform.transform_one(metric("test")).is_none());
+
+        let result = tform.transform_one(metric("prod")).unwrap();
+        assert_eq!(
+            result.into_metric(),
+            Metric {
+                name: "http_requests".into(),
+                namespace: Some("vector".into()),
+                timestamp: None,
+                tags: Some(
+                    vec![
+                        ("count".to_owned(), "2".to_owned()),
+                        ("host".to_owned(), "localhost".to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                kind: MetricKind::Incremental,
+                value: MetricValue::Counter { value: 2.0 },
+            }
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::config;
+use crate::config::{self, ConfigPath, Format};
 use colored::*;
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -6,21 +6,53 @@ use structopt::StructOpt;
 #[derive(StructOpt, Debug)]
 #[structopt(rename_all = "kebab-case")]
 pub struct Opts {
-    /// Any number of Vector config files to test. If none are specified the
-    /// default config path `/etc/vector/vector.toml` will be targeted.
+    /// Any number of Vector config files to test.
+    /// Format is detected from the file name.
+    /// If none are specified the default config path `/etc/vector/vector.toml`
+    /// will be targeted.
     paths: Vec<PathBuf>,
+
+    /// Vector config files in TOML format to test.
+    #[structopt(name = "config-toml", long)]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in YAML format to test.
+    #[structopt(name = "config-yaml", long)]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format to test.
+    #[structopt(name = "config-json", long)]
+    paths_json: Vec<PathBuf>,
+
+    /// Directories of Vector config files to test, see `vector --config-dir`.
+    #[structopt(name = "config-dir", short = "C", long)]
+    config_dirs: Vec<PathBuf>,
+}
+
+impl Opts {
+    fn paths_with_formats(&self) -> Vec<ConfigPath> {
+        config::merge_path_lists(vec![
+            (&self.paths[..], None),
+            (&self.paths_toml[..], Some(Format::Toml)),
+            (&self.paths_yaml[..], Some(Format::Yaml)),
+            (&self.paths_json[..], Some(Format::Json)),
+        ])
+        .into_iter()
+        .chain(self.config_dirs.iter().cloned().map(ConfigPath::Dir))
+        .collect()
+    }
 }
 
 pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
     let mut failed_files: Vec<(String, Vec<(String, Vec<String>)>)> = Vec::new();
     let mut inspected_files: Vec<(String, Vec<(String, Vec<String>)>)> = Vec::new();
 
-    let paths = config::process_paths(&opts.paths).unwrap_or_else(|| {
+    let paths = config::process_paths(&opts.paths_with_formats()).unwrap_or_else(|| {
         std::process::exit(exitcode::CONFIG);
     });
 
     for (i, path) in paths.iter().enumerate() {
-        let path_str = path.to_str().unwrap_or("");
+        let path_str = path.as_path().to_str().unwrap_or("");
         if i > 0 {
             println!();
         }
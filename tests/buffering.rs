@@ -1,5 +1,3 @@
-#![cfg(feature = "leveldb")]
-
 use futures::{
     compat::{Future01CompatExt, Sink01CompatExt},
     SinkExt, StreamExt,
@@ -24,8 +22,26 @@ fn terminate_abruptly(rt: Runtime, topology: topology::RunningTopology) {
     drop(topology);
 }
 
+#[cfg(feature = "leveldb")]
 #[test]
 fn test_buffering() {
+    test_buffering_with(|max_size| BufferConfig::Disk {
+        max_size,
+        when_full: Default::default(),
+    });
+}
+
+#[test]
+fn test_buffering_disk_v2() {
+    test_buffering_with(|max_size| BufferConfig::DiskV2 {
+        max_size,
+        max_segment_size: 1000,
+        sync: Default::default(),
+        when_full: Default::default(),
+    });
+}
+
+fn test_buffering_with(buffer: fn(usize) -> BufferConfig) {
     trace_init();
 
     let data_dir = tempdir().unwrap();
@@ -51,10 +67,7 @@ fn test_buffering() {
         let mut config = config::Config::builder();
         config.add_source("in", source_config);
         config.add_sink("out", &["in"], sink_config);
-        config.sinks["out"].buffer = BufferConfig::Disk {
-            max_size,
-            when_full: Default::default(),
-        };
+        config.sinks["out"].buffer = buffer(max_size);
         config.global.data_dir = Some(data_dir.clone());
         config.build().unwrap()
     };
@@ -101,10 +114,7 @@ fn test_buffering() {
         let mut config = config::Config::builder();
         config.add_source("in", source_config);
         config.add_sink("out", &["in"], sink_config);
-        config.sinks["out"].buffer = BufferConfig::Disk {
-            max_size,
-            when_full: Default::default(),
-        };
+        config.sinks["out"].buffer = buffer(max_size);
         config.global.data_dir = Some(data_dir);
         config.build().unwrap()
     };
@@ -4,7 +4,7 @@ use vector::{
 };
 
 async fn load(config: &str) -> Result<Vec<String>, Vec<String>> {
-    match config::load_from_str(config) {
+    match config::load_from_str(config, Some(config::Format::Toml)) {
         Ok(c) => {
             let diff = ConfigDiff::initial(&c);
             match (
@@ -23,7 +23,7 @@ use std::{
 };
 use tracing::{error, info};
 use vector::{
-    buffers::Acker,
+    buffers::{Acker, Rejection},
     config::{DataType, GlobalOptions, SinkConfig, SinkContext, SourceConfig, TransformConfig},
     event::{metric::MetricValue, Value},
     shutdown::ShutdownSignal,
@@ -370,6 +370,47 @@ where
     }
 }
 
+/// A sink whose destination rejects every event.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RejectingSinkConfig;
+
+#[async_trait]
+#[typetag::serde(name = "rejecting")]
+impl SinkConfig for RejectingSinkConfig {
+    async fn build(&self, cx: SinkContext) -> Result<(VectorSink, Healthcheck), vector::Error> {
+        let sink = RejectingSink { acker: cx.acker() };
+        Ok((VectorSink::Stream(Box::new(sink)), future::ok(()).boxed()))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "rejecting"
+    }
+}
+
+struct RejectingSink {
+    acker: Acker,
+}
+
+#[async_trait]
+impl StreamSink for RejectingSink {
+    async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let rejection = Rejection {
+            reason: "rejected by mock".into(),
+            status: Some(400),
+        };
+        while let Some(mut event) = input.next().await {
+            event.take_finalizers().reject(&rejection);
+            self.acker.ack(1);
+        }
+
+        Ok(())
+    }
+}
+
 /// Represents a sink that's never ready.
 /// Useful to simulate an upstream sink server that is down.
 #[derive(Debug, Clone)]
@@ -1,6 +1,8 @@
 mod support;
 
-use crate::support::{sink, sink_failing_healthcheck, source, transform, MockSourceConfig};
+use crate::support::{
+    sink, sink_failing_healthcheck, source, transform, MockSourceConfig, RejectingSinkConfig,
+};
 use futures::compat::Future01CompatExt;
 use futures01::{
     future, future::Future, sink::Sink, stream::iter_ok, stream::Stream, sync::mpsc::SendError,
@@ -198,6 +200,32 @@ async fn topology_transform_chain() {
     assert_eq!(vec!["this first second"], res);
 }
 
+#[tokio::test]
+async fn topology_dead_letter() {
+    let (in1, source1) = source();
+    let (out1, sink1) = sink(10);
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_sink("rejects", &["in1"], RejectingSinkConfig);
+    config.add_sink("out1", &["rejects.dead_letter"], sink1);
+
+    let (topology, _crash) = start_topology(config.build().unwrap(), false).await;
+
+    let event = Event::from("this");
+    in1.send(event).compat().await.unwrap();
+
+    topology.stop().compat().await.unwrap();
+
+    let res = out1.collect().compat().await.unwrap();
+    assert_eq!(res.len(), 1);
+    let log = res[0].as_log();
+    assert_eq!(into_message(res[0].clone()), "this");
+    assert_eq!(log["dead_letter.sink"], "rejects".into());
+    assert_eq!(log["dead_letter.reason"], "rejected by mock".into());
+    assert_eq!(log["dead_letter.status"], 400.into());
+}
+
 #[tokio::test]
 async fn topology_remove_one_source() {
     let (in1, source1) = source();